@@ -5,3 +5,4 @@
 pub mod entrypoint;
 pub mod processor;
 pub mod state;
+pub mod utils;
@@ -1,19 +1,79 @@
 mod process_add_index_components;
+mod process_begin_mint;
+mod process_begin_redeem;
+mod process_cancel_composition_change;
+mod process_cancel_mint;
 mod process_create_index;
+mod process_create_index_with_seed;
+mod process_create_multisig;
+mod process_deposit_components;
+mod process_execute_composition_change;
+mod process_finalize_mint;
+mod process_flash_borrow;
+mod process_flash_mint;
+mod process_flash_repay;
+mod process_freeze_holder;
+mod process_grant_controller_role;
 mod process_init_controller;
 mod process_init_controller_global_config;
+mod process_init_controller_with_nonce;
 mod process_init_module;
 mod process_init_protocol;
 mod process_instruction;
 mod process_mint;
+mod process_queue_composition_change;
 mod process_redeem;
+mod process_redeem_underlying;
+mod process_revoke_controller_role;
+mod process_set_asset_registry_entry;
+mod process_set_composition_delay;
+mod process_set_controller_config;
+mod process_set_controller_creator;
+mod process_set_flash_loan_config;
+mod process_set_transfer_allowlist;
+mod process_settle_flash_mint;
+mod process_thaw_holder;
+mod process_transfer_ownership;
+mod process_update_controller_global_config;
+mod process_withdraw_components;
+mod process_withdraw_treasury;
 
 pub use process_add_index_components::*;
+pub use process_begin_mint::*;
+pub use process_begin_redeem::*;
+pub use process_cancel_composition_change::*;
+pub use process_cancel_mint::*;
 pub use process_create_index::*;
+pub use process_create_index_with_seed::*;
+pub use process_create_multisig::*;
+pub use process_deposit_components::*;
+pub use process_execute_composition_change::*;
+pub use process_finalize_mint::*;
+pub use process_flash_borrow::*;
+pub use process_flash_mint::*;
+pub use process_flash_repay::*;
+pub use process_freeze_holder::*;
+pub use process_grant_controller_role::*;
 pub use process_init_controller::*;
 pub use process_init_controller_global_config::*;
+pub use process_init_controller_with_nonce::*;
 pub use process_init_module::*;
 pub use process_init_protocol::*;
 pub use process_instruction::*;
 pub use process_mint::*;
+pub use process_queue_composition_change::*;
 pub use process_redeem::*;
+pub use process_redeem_underlying::*;
+pub use process_revoke_controller_role::*;
+pub use process_set_asset_registry_entry::*;
+pub use process_set_composition_delay::*;
+pub use process_set_controller_config::*;
+pub use process_set_controller_creator::*;
+pub use process_set_flash_loan_config::*;
+pub use process_set_transfer_allowlist::*;
+pub use process_settle_flash_mint::*;
+pub use process_thaw_holder::*;
+pub use process_transfer_ownership::*;
+pub use process_update_controller_global_config::*;
+pub use process_withdraw_components::*;
+pub use process_withdraw_treasury::*;
@@ -1,13 +1,19 @@
 //! Program state processor
 
 use crate::state::{Component, Controller, ControllerGlobalConfig, Index, IndexMints};
+use crate::utils::{
+    check_asset_registry_entry, check_component_recipe, check_controller_role,
+    check_underlying_index, create_pda_account, load_controller_config, load_index, realloc_account,
+};
 use borsh::{BorshDeserialize, BorshSerialize};
 use openindex_sdk::{
     openindex::{
         error::ProtocolError,
+        instruction::ControllerRole,
         pda::{
-            create_index_address, find_component_address, find_component_vault_address,
-             find_index_mints_data_address,
+            create_index_address_from_seed, create_index_mint_address_from_seed,
+            create_index_mints_data_address_from_seed, find_component_address,
+            find_component_vault_address,
         },
         seeds::{COMPONENT_SEED, COMPONENT_VAULT_SEED, INDEX_MINTS_DATA_SEED},
     },
@@ -21,7 +27,6 @@ use solana_program::{
     program_pack::IsInitialized,
     pubkey::Pubkey,
     rent::Rent,
-    system_instruction,
     sysvar::Sysvar,
 };
 use spl_associated_token_account::instruction::create_associated_token_account;
@@ -32,18 +37,23 @@ pub fn process_add_index_components(
     accounts: &[AccountInfo],
     mints: Vec<Pubkey>,
     amounts: Vec<u64>,
+    nested_components: Vec<bool>,
 ) -> ProgramResult {
     let accounts_iter = &mut accounts.iter();
     let signer = next_account_info(accounts_iter)?;
+    let payer = next_account_info(accounts_iter)?;
     let index_account = next_account_info(accounts_iter)?;
     let index_mints_account = next_account_info(accounts_iter)?;
     let controller_account = next_account_info(accounts_iter)?;
     let controller_global_config_account = next_account_info(accounts_iter)?;
+    let controller_config_account = next_account_info(accounts_iter)?;
+    let controller_roles_account = next_account_info(accounts_iter)?;
     let system_program_account = next_account_info(accounts_iter)?;
     let associated_token_program_account = next_account_info(accounts_iter)?;
     let token_program_account = next_account_info(accounts_iter)?;
 
     require!(signer.is_signer, ProgramError::MissingRequiredSignature);
+    require!(payer.is_signer, ProgramError::MissingRequiredSignature);
 
     require!(
         index_account.owner == program_id,
@@ -61,25 +71,60 @@ pub fn process_add_index_components(
     );
 
     let controller = Controller::try_from_slice(&controller_account.data.borrow())?;
-    require!(
-        controller.owner == *signer.key,
-        ProtocolError::OnlyControllerOwner.into()
-    );
 
-    let controller_global_config =
-        ControllerGlobalConfig::try_from_slice(&controller_global_config_account.data.borrow())?;
+    let mut controller_global_config =
+        ControllerGlobalConfig::unpack(&controller_global_config_account.data.borrow())?;
     require!(
         controller_global_config.is_initialized(),
         ProtocolError::ControllerGlobalConfigNotInitialized.into()
     );
 
-    let index_data = Index::try_from_slice(&index_account.data.borrow())?;
-    let index_id = index_data.id;
+    let controller_config =
+        load_controller_config(program_id, controller_account, controller_config_account)?;
+    let max_index_components = controller_config.as_ref().map_or(
+        controller_global_config.max_index_components,
+        |config| config.max_index_components(controller_global_config.max_index_components),
+    );
+    let check_asset_registry = controller_global_config.asset_registry_required
+        && !controller_config
+            .as_ref()
+            .is_some_and(|config| config.override_asset_checks);
+
+    // the four accounts of every component are followed by one asset registry
+    // account per component while registry checks apply, then by the
+    // underlying controller and index of every nested component; multisig
+    // approvals come last
+    let nested_count = nested_components.iter().filter(|nested| **nested).count();
+    let asset_registry_accounts = accounts_iter
+        .as_slice()
+        .get(mints.len() * 4..)
+        .unwrap_or_default();
+    let asset_registry_count = if check_asset_registry { mints.len() } else { 0 };
+    let underlying_accounts = asset_registry_accounts
+        .get(asset_registry_count..)
+        .unwrap_or_default();
+    let authority_accounts = underlying_accounts
+        .get(nested_count * 2..)
+        .unwrap_or_default();
+    let mut asset_registry_accounts = asset_registry_accounts.iter();
+    let mut underlying_accounts = underlying_accounts.iter();
+    check_controller_role(
+        program_id,
+        signer,
+        &controller,
+        controller_account,
+        controller_roles_account,
+        authority_accounts,
+        ControllerRole::ComponentManager,
+    )?;
 
-    let index_pda = create_index_address(
+    let mut index_data = load_index(program_id, controller_account.key, index_account)?;
+    let index_seed = index_data.address_seed();
+
+    let index_pda = create_index_address_from_seed(
         program_id,
         controller_account.key,
-        index_id,
+        &index_seed,
         index_data.bump,
     )?;
 
@@ -88,39 +133,42 @@ pub fn process_add_index_components(
         ProtocolError::IncorrectIndexAccount.into()
     );
 
-    let index_data: Index = Index::try_from_slice(&index_account.data.borrow())?;
-
     require!(
         index_data.is_initialized(),
         ProtocolError::IndexNotInitialized.into()
     );
 
-    let (index_mints_pda, index_mints_bump) =
-        find_index_mints_data_address(program_id, controller_account.key, index_id);
+    let index_mints_bump = index_data.index_mints_bump;
+    let index_mints_pda = create_index_mints_data_address_from_seed(
+        program_id,
+        controller_account.key,
+        &index_seed,
+        index_mints_bump,
+    )?;
 
     require!(
         *index_mints_account.key == index_mints_pda,
         ProtocolError::IncorrectIndexMintsAccount.into()
     );
 
+    let index_mint_pda = create_index_mint_address_from_seed(
+        program_id,
+        controller_account.key,
+        &index_seed,
+        index_data.mint_bump,
+    )?;
+    check_component_recipe(
+        &mints,
+        &amounts,
+        &nested_components,
+        &index_mint_pda,
+        max_index_components,
+        controller_config.as_ref(),
+    )?;
     let mints_len = mints.len();
-    require!(mints_len > 0, ProtocolError::NoMintsProvided.into());
-
-    require!(
-        mints_len <= controller_global_config.max_index_components as usize,
-        ProtocolError::MaxIndexComponentsExceeded.into()
-    );
-
-    require!(
-        mints_len == amounts.len(),
-        ProtocolError::MintsAmountsLenMismatch.into()
-    );
-
-
 
     // creates components
     let rent = Rent::get()?;
-    let component_lamports = rent.minimum_balance(Component::LEN);
     for (index, mint) in mints.iter().enumerate() {
         let mint_account = next_account_info(accounts_iter)?;
         let component_account = next_account_info(accounts_iter)?;
@@ -137,6 +185,11 @@ pub fn process_add_index_components(
             ProtocolError::InvalidMintAccount.into()
         );
 
+        if check_asset_registry {
+            let asset_registry_account = next_account_info(&mut asset_registry_accounts)?;
+            check_asset_registry_entry(program_id, mint_account, asset_registry_account)?;
+        }
+
         let amount = amounts
             .get(index)
             .ok_or(ProtocolError::ComponentAmountError)?;
@@ -167,45 +220,51 @@ pub fn process_add_index_components(
         );
 
         // create component account
-        invoke_signed(
-            &system_instruction::create_account(
-                &signer.key,
-                &component_account.key,
-                component_lamports,
-                Component::LEN as u64,
-                program_id,
-            ),
+        create_pda_account(
+            payer,
+            component_account,
+            system_program_account,
+            program_id,
+            Component::LEN,
+            &rent,
             &[
-                signer.clone(),
-                component_account.clone(),
-                system_program_account.clone(),
-            ],
-            &[&[
                 COMPONENT_SEED,
                 index_account.key.as_ref(),
                 mint_account.key.as_ref(),
                 &[component_bump],
-            ]],
+            ],
         )?;
 
         // Initialize component data
-        let component = Component::new(
+        let mut component = Component::new(
             *amount,
             mint_account.key.clone(),
             component_bump,
             vault_bump,
         );
+        if nested_components.get(index).copied().unwrap_or(false) {
+            let underlying_controller_account = next_account_info(&mut underlying_accounts)?;
+            let underlying_index_account = next_account_info(&mut underlying_accounts)?;
+            check_underlying_index(
+                program_id,
+                index_account,
+                underlying_controller_account,
+                underlying_index_account,
+                mint,
+            )?;
+            component.underlying_index = *underlying_index_account.key;
+        }
         component.serialize(&mut &mut component_account.data.borrow_mut()[..])?;
         // create vault associated token account
         invoke_signed(
             &create_associated_token_account(
-                signer.key,
+                payer.key,
                 vault_pda.key,
                 mint_account.key,
                 &spl_token::ID,
             ),
             &[
-                signer.clone(),
+                payer.clone(),
                 vault_ata.clone(),
                 vault_pda.clone(),
                 mint_account.clone(),
@@ -224,31 +283,47 @@ pub fn process_add_index_components(
     
     //creates index mints account
     let space = IndexMints::calc_len(mints_len);
-    let lamports = rent.minimum_balance(space);
-
-    invoke_signed(
-        &system_instruction::create_account(
-            &signer.key,
-            &index_mints_account.key,
-            lamports,
-            space as u64,
-            program_id,
-        ),
+
+    create_pda_account(
+        payer,
+        index_mints_account,
+        system_program_account,
+        program_id,
+        space,
+        &rent,
         &[
-            signer.clone(),
-            index_mints_account.clone(),
-            system_program_account.clone(),
-        ],
-        &[&[
             INDEX_MINTS_DATA_SEED,
             controller_account.key.as_ref(),
-            &index_id.to_le_bytes(),
+            &index_seed,
             &[index_mints_bump],
-        ]],
+        ],
     )?;
 
     let index_mints = IndexMints::new(mints, index_mints_bump);
     index_mints.serialize(&mut &mut index_mints_account.data.borrow_mut()[..])?;
 
+    index_data.composition_version = index_data
+        .composition_version
+        .checked_add(1)
+        .ok_or(ProgramError::ArithmeticOverflow)?;
+    realloc_account(
+        payer,
+        index_account,
+        system_program_account,
+        Index::LEN,
+        &rent,
+    )?;
+    index_data.serialize(&mut &mut index_account.data.borrow_mut()[..])?;
+
+    // accounts still on the legacy layout have no room for the counter until
+    // `UpdateControllerGlobalConfig` reallocates them
+    if mints_len as u32 > controller_global_config.largest_index_components
+        && controller_global_config_account.data_len() >= ControllerGlobalConfig::LEN
+    {
+        controller_global_config.largest_index_components = mints_len as u32;
+        controller_global_config
+            .serialize(&mut &mut controller_global_config_account.data.borrow_mut()[..])?;
+    }
+
     Ok(())
 }
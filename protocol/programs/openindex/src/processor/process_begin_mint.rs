@@ -0,0 +1,142 @@
+//! Program state processor
+
+use crate::state::{IndexMints, MintRequest};
+use crate::utils::{create_pda_account, load_index};
+use borsh::{BorshDeserialize, BorshSerialize};
+use openindex_sdk::{
+    openindex::{
+        error::ProtocolError,
+        pda::{
+            create_index_address_from_seed, create_index_mints_data_address_from_seed,
+            find_mint_request_address,
+        },
+        seeds::MINT_REQUEST_SEED,
+    },
+    require,
+};
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    program_error::ProgramError,
+    program_pack::IsInitialized,
+    pubkey::Pubkey,
+    rent::Rent,
+    sysvar::Sysvar,
+};
+
+/// instruction to process opening a staged mint request
+pub fn process_begin_mint(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    index_id: u64,
+    amount: u64,
+) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let signer = next_account_info(accounts_iter)?;
+    let controller_account = next_account_info(accounts_iter)?;
+    let index_account = next_account_info(accounts_iter)?;
+    let index_mints_account = next_account_info(accounts_iter)?;
+    let mint_request_account = next_account_info(accounts_iter)?;
+    let system_program_account = next_account_info(accounts_iter)?;
+
+    require!(signer.is_signer, ProgramError::MissingRequiredSignature);
+
+    require!(
+        amount > 0,
+        ProtocolError::AmountMustBeGreaterThanZero.into()
+    );
+
+    require!(
+        controller_account.owner == program_id,
+        ProtocolError::UnknownControllerAccount.into()
+    );
+
+    require!(
+        index_account.owner == program_id,
+        ProtocolError::UnknownIndexAccount.into()
+    );
+
+    require!(
+        mint_request_account.data_is_empty(),
+        ProgramError::AccountAlreadyInitialized
+    );
+
+    let index = load_index(program_id, controller_account.key, index_account)?;
+
+    require!(
+        index.id == index_id,
+        ProtocolError::IncorrectIndexAccount.into()
+    );
+
+    let index_seed = index.address_seed();
+
+    require!(
+        index.is_initialized(),
+        ProtocolError::IndexNotInitialized.into()
+    );
+
+    let index_pda = create_index_address_from_seed(
+        program_id,
+        controller_account.key,
+        &index_seed,
+        index.bump,
+    )?;
+
+    require!(
+        *index_account.key == index_pda,
+        ProtocolError::IncorrectIndexAccount.into()
+    );
+
+    let index_mints_pda = create_index_mints_data_address_from_seed(
+        program_id,
+        controller_account.key,
+        &index_seed,
+        index.index_mints_bump,
+    )?;
+
+    require!(
+        *index_mints_account.key == index_mints_pda,
+        ProtocolError::IncorrectIndexMintsAccount.into()
+    );
+
+    let index_mints_data = IndexMints::try_from_slice(&index_mints_account.data.borrow())
+        .map_err(|_| ProtocolError::InvalidIndexMintsAccountData)?;
+
+    let (mint_request_pda, mint_request_bump) =
+        find_mint_request_address(program_id, index_account.key, signer.key);
+
+    require!(
+        *mint_request_account.key == mint_request_pda,
+        ProtocolError::IncorrectMintRequestAccount.into()
+    );
+
+    let space = MintRequest::calc_len(index_mints_data.mints.len());
+    let rent = Rent::get()?;
+
+    create_pda_account(
+        signer,
+        mint_request_account,
+        system_program_account,
+        program_id,
+        space,
+        &rent,
+        &[
+            MINT_REQUEST_SEED,
+            index_account.key.as_ref(),
+            signer.key.as_ref(),
+            &[mint_request_bump],
+        ],
+    )?;
+
+    let mint_request = MintRequest::new(
+        *signer.key,
+        *index_account.key,
+        amount,
+        index.composition_version,
+        index_mints_data.mints,
+        mint_request_bump,
+    );
+    mint_request.serialize(&mut &mut mint_request_account.data.borrow_mut()[..])?;
+
+    Ok(())
+}
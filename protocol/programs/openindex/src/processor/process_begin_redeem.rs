@@ -0,0 +1,207 @@
+//! Program state processor
+
+use crate::state::{Component, IndexMints, RedeemRequest};
+use crate::utils::{check_index_token_program, create_pda_account, load_index};
+use borsh::{BorshDeserialize, BorshSerialize};
+use openindex_sdk::{
+    openindex::{
+        error::ProtocolError,
+        pda::{
+            create_component_address, create_index_address_from_seed,
+            create_index_mint_address_from_seed, create_index_mints_data_address_from_seed,
+            find_redeem_request_address,
+        },
+        seeds::REDEEM_REQUEST_SEED,
+    },
+    require,
+};
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    program::invoke,
+    program_error::ProgramError,
+    program_pack::IsInitialized,
+    pubkey::Pubkey,
+    rent::Rent,
+    sysvar::Sysvar,
+};
+use spl_token_2022::instruction::burn;
+
+/// instruction to process burning index tokens into a staged redeem request
+pub fn process_begin_redeem(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    index_id: u64,
+    amount: u64,
+) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let signer = next_account_info(accounts_iter)?;
+    let controller_account = next_account_info(accounts_iter)?;
+    let mint_account = next_account_info(accounts_iter)?;
+    let index_account = next_account_info(accounts_iter)?;
+    let index_mints_account = next_account_info(accounts_iter)?;
+    let redeem_request_account = next_account_info(accounts_iter)?;
+    let token_account = next_account_info(accounts_iter)?;
+    let token_program_account = next_account_info(accounts_iter)?;
+    let system_program_account = next_account_info(accounts_iter)?;
+
+    require!(signer.is_signer, ProgramError::MissingRequiredSignature);
+
+    require!(
+        amount > 0,
+        ProtocolError::AmountMustBeGreaterThanZero.into()
+    );
+
+    require!(
+        controller_account.owner == program_id,
+        ProtocolError::UnknownControllerAccount.into()
+    );
+
+    require!(
+        index_account.owner == program_id,
+        ProtocolError::UnknownIndexAccount.into()
+    );
+
+    require!(
+        redeem_request_account.data_is_empty(),
+        ProgramError::AccountAlreadyInitialized
+    );
+
+    let index = load_index(program_id, controller_account.key, index_account)?;
+
+    require!(
+        index.id == index_id,
+        ProtocolError::IncorrectIndexAccount.into()
+    );
+
+    let index_seed = index.address_seed();
+
+    require!(
+        index.is_initialized(),
+        ProtocolError::IndexNotInitialized.into()
+    );
+
+    let index_pda = create_index_address_from_seed(
+        program_id,
+        controller_account.key,
+        &index_seed,
+        index.bump,
+    )?;
+
+    require!(
+        *index_account.key == index_pda,
+        ProtocolError::IncorrectIndexAccount.into()
+    );
+
+    let mint_pda = create_index_mint_address_from_seed(
+        program_id,
+        controller_account.key,
+        &index_seed,
+        index.mint_bump,
+    )?;
+
+    require!(
+        *mint_account.key == mint_pda,
+        ProtocolError::IncorrectMintAccount.into()
+    );
+
+    check_index_token_program(mint_account, token_program_account)?;
+
+    let index_mints_pda = create_index_mints_data_address_from_seed(
+        program_id,
+        controller_account.key,
+        &index_seed,
+        index.index_mints_bump,
+    )?;
+
+    require!(
+        *index_mints_account.key == index_mints_pda,
+        ProtocolError::IncorrectIndexMintsAccount.into()
+    );
+
+    let index_mints_data = IndexMints::try_from_slice(&index_mints_account.data.borrow())
+        .map_err(|_| ProtocolError::InvalidIndexMintsAccountData)?;
+
+    let (redeem_request_pda, redeem_request_bump) =
+        find_redeem_request_address(program_id, index_account.key, signer.key);
+
+    require!(
+        *redeem_request_account.key == redeem_request_pda,
+        ProtocolError::IncorrectRedeemRequestAccount.into()
+    );
+
+    // the amount owed for each component is fixed at the current units
+    let mut owed = Vec::with_capacity(index_mints_data.mints.len());
+    for mint in index_mints_data.mints.iter() {
+        let component_account = next_account_info(accounts_iter)?;
+
+        require!(
+            component_account.owner == program_id,
+            ProtocolError::IncorrectComponentAccount.into()
+        );
+
+        let component = Component::unpack(&component_account.data.borrow())
+            .map_err(|_| ProtocolError::InvalidComponentData)?;
+
+        let component_pda =
+            create_component_address(program_id, index_account.key, mint, component.bump)?;
+
+        require!(
+            *component_account.key == component_pda,
+            ProtocolError::IncorrectComponentAccount.into()
+        );
+
+        owed.push(
+            amount
+                .checked_mul(component.uints)
+                .ok_or(ProgramError::ArithmeticOverflow)?,
+        );
+    }
+
+    invoke(
+        &burn(
+            token_program_account.key,
+            token_account.key,
+            mint_account.key,
+            signer.key,
+            &[],
+            amount,
+        )?,
+        &[
+            token_program_account.clone(),
+            token_account.clone(),
+            mint_account.clone(),
+            signer.clone(),
+        ],
+    )?;
+
+    let space = RedeemRequest::calc_len(index_mints_data.mints.len());
+    let rent = Rent::get()?;
+
+    create_pda_account(
+        signer,
+        redeem_request_account,
+        system_program_account,
+        program_id,
+        space,
+        &rent,
+        &[
+            REDEEM_REQUEST_SEED,
+            index_account.key.as_ref(),
+            signer.key.as_ref(),
+            &[redeem_request_bump],
+        ],
+    )?;
+
+    let redeem_request = RedeemRequest::new(
+        *signer.key,
+        *index_account.key,
+        amount,
+        index_mints_data.mints,
+        owed,
+        redeem_request_bump,
+    );
+    redeem_request.serialize(&mut &mut redeem_request_account.data.borrow_mut()[..])?;
+
+    Ok(())
+}
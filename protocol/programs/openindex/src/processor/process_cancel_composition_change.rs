@@ -0,0 +1,86 @@
+//! Program state processor
+
+use crate::utils::{check_owner, load_index, load_pending_composition_change};
+use openindex_sdk::{
+    openindex::{error::ProtocolError, pda::create_index_address_from_seed},
+    require,
+};
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+};
+
+/// instruction to process cancelling a queued composition change
+pub fn process_cancel_composition_change(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let signer = next_account_info(accounts_iter)?;
+    let index_account = next_account_info(accounts_iter)?;
+    let controller_account = next_account_info(accounts_iter)?;
+    let pending_change_account = next_account_info(accounts_iter)?;
+    let rent_receiver = next_account_info(accounts_iter)?;
+
+    require!(signer.is_signer, ProgramError::MissingRequiredSignature);
+
+    require!(
+        index_account.owner == program_id,
+        ProtocolError::UnknownIndexAccount.into()
+    );
+
+    let index = load_index(program_id, controller_account.key, index_account)?;
+
+    let index_pda = create_index_address_from_seed(
+        program_id,
+        controller_account.key,
+        &index.address_seed(),
+        index.bump,
+    )?;
+
+    require!(
+        *index_account.key == index_pda,
+        ProtocolError::IncorrectIndexAccount.into()
+    );
+
+    // the signer acts for the owner when it is the owner or names the owner
+    // as the multisig it approves for, and for the manager otherwise
+    let authority_accounts = accounts_iter.as_slice();
+    let acts_for_owner = *signer.key != index.manager
+        && (*signer.key == index.owner
+            || authority_accounts
+                .first()
+                .is_some_and(|account| *account.key == index.owner));
+    let authority = if acts_for_owner {
+        &index.owner
+    } else {
+        &index.manager
+    };
+    check_owner(
+        program_id,
+        authority,
+        signer,
+        authority_accounts,
+        ProtocolError::OnlyIndexManager,
+    )?;
+
+    let pending_change =
+        load_pending_composition_change(program_id, index_account, pending_change_account)?;
+
+    require!(
+        *rent_receiver.key == pending_change.manager,
+        ProtocolError::IncorrectPendingCompositionChangeAccount.into()
+    );
+
+    // close the pending change and return its rent to the manager that queued it
+    let rent_receiver_lamports = rent_receiver.lamports();
+    **rent_receiver.lamports.borrow_mut() = rent_receiver_lamports
+        .checked_add(pending_change_account.lamports())
+        .ok_or(ProgramError::ArithmeticOverflow)?;
+    **pending_change_account.lamports.borrow_mut() = 0;
+    pending_change_account.data.borrow_mut().fill(0);
+
+    Ok(())
+}
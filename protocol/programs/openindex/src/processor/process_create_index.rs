@@ -1,19 +1,28 @@
 //! Program state processor
 
 use crate::state::{Controller, ControllerGlobalConfig, Index};
+use crate::utils::{
+    check_controller_role, check_treasury_account, create_pda_account, load_controller_config,
+    pay_fee,
+};
 use borsh::{BorshDeserialize, BorshSerialize};
 use openindex_sdk::{
     openindex::{
         error::ProtocolError,
-        pda::{find_index_address, find_index_mint_address},
+        instruction::ControllerRole,
+        pda::{
+            find_index_address_from_seed, find_index_mint_address_from_seed,
+            find_index_mint_authority_address_from_seed, find_index_mints_data_address_from_seed,
+        },
         seeds::{INDEX_MINT_AUTHORITY_SEED, INDEX_MINT_SEED, INDEX_SEED},
+        transfer_hook::{transfer_allowlist_extra_account_metas, TRANSFER_HOOK_PROGRAM_ID},
     },
     require,
 };
 use solana_program::{
     account_info::{next_account_info, AccountInfo},
     entrypoint::ProgramResult,
-    program::invoke_signed,
+    program::{invoke, invoke_signed},
     program_error::ProgramError,
     program_pack::{IsInitialized, Pack},
     pubkey::Pubkey,
@@ -21,28 +30,86 @@ use solana_program::{
     system_instruction, system_program,
     sysvar::Sysvar,
 };
-use spl_token::{instruction::initialize_mint2, state::Mint};
+use spl_tlv_account_resolution::state::ExtraAccountMetaList;
+use spl_token::state::Mint;
+use spl_token_2022::{
+    extension::{transfer_hook, ExtensionType},
+    instruction::initialize_mint2,
+};
+use spl_transfer_hook_interface::{
+    get_extra_account_metas_address, instruction::initialize_extra_account_meta_list,
+};
+
+/// Decimals of index mints when the controller config does not override them.
+const INDEX_MINT_DECIMALS: u8 = 9;
 
 /// instruction to process creating an index
-pub fn process_create_index(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+pub fn process_create_index(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    drop_freeze_authority: bool,
+    transfer_hook_program: Option<Pubkey>,
+) -> ProgramResult {
+    create_index(
+        program_id,
+        accounts,
+        None,
+        drop_freeze_authority,
+        transfer_hook_program,
+    )
+}
+
+/// Creates an index whose PDAs are derived from `seed` when given, otherwise
+/// from the next sequential index id of the controller. The mint-authority
+/// PDA also becomes the freeze authority unless `drop_freeze_authority`.
+/// With `transfer_hook_program` the mint is a Token-2022 mint whose
+/// transfers that program checks against the index transfer allowlist.
+pub(crate) fn create_index(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    seed: Option<[u8; 32]>,
+    drop_freeze_authority: bool,
+    transfer_hook_program: Option<Pubkey>,
+) -> ProgramResult {
     let accounts_iter = &mut accounts.iter();
     let signer = next_account_info(accounts_iter)?;
+    let payer = next_account_info(accounts_iter)?;
     let manager = next_account_info(accounts_iter)?;
     let index_account = next_account_info(accounts_iter)?;
     let mint_account = next_account_info(accounts_iter)?;
     let controller_account = next_account_info(accounts_iter)?;
     let controller_global_config_account = next_account_info(accounts_iter)?;
+    let controller_config_account = next_account_info(accounts_iter)?;
     let system_program_account = next_account_info(accounts_iter)?;
     let token_program_account = next_account_info(accounts_iter)?;
+    let treasury_account = next_account_info(accounts_iter)?;
+    let controller_roles_account = next_account_info(accounts_iter)?;
+    // the transfer hook accounts precede the multisig accounts of the signer
+    let transfer_hook_accounts = match transfer_hook_program {
+        Some(_) => Some((
+            next_account_info(accounts_iter)?,
+            next_account_info(accounts_iter)?,
+            next_account_info(accounts_iter)?,
+        )),
+        None => None,
+    };
 
     require!(signer.is_signer, ProgramError::MissingRequiredSignature);
+    require!(payer.is_signer, ProgramError::MissingRequiredSignature);
+
+    // the hook is registered with the mint authority seeds, so no other
+    // program may receive them
+    require!(
+        transfer_hook_program.is_none_or(|program| program == TRANSFER_HOOK_PROGRAM_ID),
+        ProtocolError::UnsupportedTransferHookProgram.into()
+    );
     require!(
-        index_account.lamports() == 0,
+        index_account.data_is_empty(),
         ProgramError::AccountAlreadyInitialized
     );
 
     require!(
-        mint_account.lamports() == 0,
+        mint_account.data_is_empty(),
         ProgramError::AccountAlreadyInitialized
     );
 
@@ -57,21 +124,41 @@ pub fn process_create_index(program_id: &Pubkey, accounts: &[AccountInfo]) -> Pr
     );
 
     let mut controller = Controller::try_from_slice(&controller_account.data.borrow())?;
-    require!(
-        controller.owner == *signer.key,
-        ProtocolError::OnlyControllerOwner.into()
-    );
+    check_controller_role(
+        program_id,
+        signer,
+        &controller,
+        controller_account,
+        controller_roles_account,
+        accounts_iter.as_slice(),
+        ControllerRole::IndexCreator,
+    )?;
 
     let controller_global_config =
-        ControllerGlobalConfig::try_from_slice(&controller_global_config_account.data.borrow())?;
+        ControllerGlobalConfig::unpack(&controller_global_config_account.data.borrow())?;
     require!(
         controller_global_config.is_initialized(),
         ProtocolError::ControllerGlobalConfigNotInitialized.into()
     );
 
+    let controller_config =
+        load_controller_config(program_id, controller_account, controller_config_account)?;
+    let decimals = controller_config
+        .and_then(|config| config.default_decimals)
+        .unwrap_or(INDEX_MINT_DECIMALS);
+
     let index_id = controller.get_next_index_id();
 
-    let (index_pda, index_bump) = find_index_address(program_id, controller_account.key, index_id);
+    let index_seed = match seed {
+        Some(seed) => {
+            require!(seed != [0; 32], ProtocolError::InvalidIndexSeed.into());
+            seed.to_vec()
+        }
+        None => index_id.to_le_bytes().to_vec(),
+    };
+
+    let (index_pda, index_bump) =
+        find_index_address_from_seed(program_id, controller_account.key, &index_seed);
 
     require!(
         *index_account.key == index_pda,
@@ -79,7 +166,7 @@ pub fn process_create_index(program_id: &Pubkey, accounts: &[AccountInfo]) -> Pr
     );
 
     let (mint_pda, mint_bump) =
-        find_index_mint_address(program_id, controller_account.key, index_id);
+        find_index_mint_address_from_seed(program_id, controller_account.key, &index_seed);
 
     require!(
         *mint_account.key == mint_pda,
@@ -91,86 +178,202 @@ pub fn process_create_index(program_id: &Pubkey, accounts: &[AccountInfo]) -> Pr
         ProgramError::IncorrectProgramId
     );
 
+    // transfer hooks are a Token-2022 extension
+    let token_program_id = match transfer_hook_program {
+        Some(_) => spl_token_2022::ID,
+        None => spl_token::ID,
+    };
+    require!(
+        *token_program_account.key == token_program_id,
+        ProgramError::IncorrectProgramId
+    );
+
+    let (mint_authority_pda, mint_authority_bump) = find_index_mint_authority_address_from_seed(
+        program_id,
+        controller_account.key,
+        &index_seed,
+    );
+
+    if let Some((
+        transfer_hook_program_account,
+        extra_account_metas_account,
+        mint_authority_account,
+    )) = transfer_hook_accounts
+    {
+        require!(
+            Some(*transfer_hook_program_account.key) == transfer_hook_program,
+            ProgramError::IncorrectProgramId
+        );
+
+        require!(
+            *extra_account_metas_account.key
+                == get_extra_account_metas_address(
+                    mint_account.key,
+                    transfer_hook_program_account.key
+                ),
+            ProtocolError::IncorrectExtraAccountMetasAccount.into()
+        );
+
+        require!(
+            *mint_authority_account.key == mint_authority_pda,
+            ProtocolError::IncorrectMintAuthority.into()
+        );
+    }
+
     let rent = Rent::get()?;
 
     // Create Index
     let space = Index::LEN;
-    let lamports = rent.minimum_balance(space);
-    invoke_signed(
-        &system_instruction::create_account(
-            &signer.key,
-            &index_account.key,
-            lamports,
-            space as u64,
-            program_id,
-        ),
+    create_pda_account(
+        payer,
+        index_account,
+        system_program_account,
+        program_id,
+        space,
+        &rent,
         &[
-            signer.clone(),
-            index_account.clone(),
-            system_program_account.clone(),
-        ],
-        &[&[
             INDEX_SEED,
             controller_account.key.as_ref(),
-            &index_id.to_le_bytes(),
+            &index_seed,
             &[index_bump],
-        ]],
+        ],
     )?;
 
     // Create Token Mint
-    let mint_space = Mint::LEN;
-    let mint_lamports = rent.minimum_balance(mint_space);
-    invoke_signed(
-        &system_instruction::create_account(
-            signer.key,
-            mint_account.key,
-            mint_lamports,
-            mint_space as u64,
-            token_program_account.key,
-        ),
+    let mint_space = match transfer_hook_program {
+        Some(_) => ExtensionType::try_calculate_account_len::<spl_token_2022::state::Mint>(&[
+            ExtensionType::TransferHook,
+        ])?,
+        None => Mint::LEN,
+    };
+    create_pda_account(
+        payer,
+        mint_account,
+        system_program_account,
+        token_program_account.key,
+        mint_space,
+        &rent,
         &[
-            signer.clone(),
-            mint_account.clone(),
-            system_program_account.clone(),
-        ],
-        &[&[
             INDEX_MINT_SEED,
             controller_account.key.as_ref(),
-            &index_id.to_le_bytes(),
+            &index_seed,
             &[mint_bump],
-        ]],
+        ],
     )?;
 
-    // Initialize Mint
-    let (mint_authority_pda, mint_authority_bump) = Pubkey::find_program_address(
-        &[
-            INDEX_MINT_AUTHORITY_SEED,
-            controller_account.key.as_ref(),
-            &index_id.to_le_bytes(),
-        ],
-        program_id,
-    );
+    // extensions are initialized before the mint itself
+    if transfer_hook_program.is_some() {
+        invoke(
+            &transfer_hook::instruction::initialize(
+                token_program_account.key,
+                mint_account.key,
+                None,
+                transfer_hook_program,
+            )?,
+            &[mint_account.clone(), token_program_account.clone()],
+        )?;
+    }
 
+    // Initialize Mint
     invoke_signed(
         &initialize_mint2(
             &token_program_account.key,
             mint_account.key,
             &mint_authority_pda,
-            Some(&mint_authority_pda),
-            9,
+            (!drop_freeze_authority).then_some(&mint_authority_pda),
+            decimals,
         )?,
         &[mint_account.clone(), token_program_account.clone()],
         &[&[
             INDEX_MINT_AUTHORITY_SEED,
             controller_account.key.as_ref(),
-            &index_id.to_le_bytes(),
+            &index_seed,
             &[mint_authority_bump],
         ]],
     )?;
 
-    let index = Index::new(index_id, signer.key.clone(), manager.key.clone(), index_bump);
+    if let Some((
+        transfer_hook_program_account,
+        extra_account_metas_account,
+        mint_authority_account,
+    )) = transfer_hook_accounts
+    {
+        let extra_account_metas = transfer_allowlist_extra_account_metas(program_id)?;
+
+        // the hook allocates the account, but it cannot pay for it
+        let required_lamports =
+            rent.minimum_balance(ExtraAccountMetaList::size_of(extra_account_metas.len())?);
+        let missing_lamports =
+            required_lamports.saturating_sub(extra_account_metas_account.lamports());
+        if missing_lamports > 0 {
+            invoke(
+                &system_instruction::transfer(
+                    payer.key,
+                    extra_account_metas_account.key,
+                    missing_lamports,
+                ),
+                &[
+                    payer.clone(),
+                    extra_account_metas_account.clone(),
+                    system_program_account.clone(),
+                ],
+            )?;
+        }
+
+        invoke_signed(
+            &initialize_extra_account_meta_list(
+                transfer_hook_program_account.key,
+                extra_account_metas_account.key,
+                mint_account.key,
+                &mint_authority_pda,
+                &extra_account_metas,
+            ),
+            &[
+                extra_account_metas_account.clone(),
+                mint_account.clone(),
+                mint_authority_account.clone(),
+                system_program_account.clone(),
+                transfer_hook_program_account.clone(),
+            ],
+            &[&[
+                INDEX_MINT_AUTHORITY_SEED,
+                controller_account.key.as_ref(),
+                &index_seed,
+                &[mint_authority_bump],
+            ]],
+        )?;
+    }
+
+    // Only the bump is needed here; the account is created by `AddIndexComponents`.
+    let (_, index_mints_bump) = find_index_mints_data_address_from_seed(
+        program_id,
+        controller_account.key,
+        &index_seed,
+    );
+
+    let mut index = Index::new(
+        index_id,
+        controller.owner,
+        manager.key.clone(),
+        index_bump,
+        mint_bump,
+        mint_authority_bump,
+        index_mints_bump,
+    );
+    index.seed = seed.unwrap_or_default();
+    index.transfer_hook_program = transfer_hook_program.unwrap_or_default();
     index.serialize(&mut &mut index_account.data.borrow_mut()[..])?;
 
+    if controller_global_config.index_creation_fee > 0 {
+        check_treasury_account(program_id, treasury_account)?;
+        pay_fee(
+            payer,
+            treasury_account,
+            system_program_account,
+            controller_global_config.index_creation_fee,
+        )?;
+    }
+
     controller.generate_next_index_id();
     controller.serialize(&mut &mut controller_account.data.borrow_mut()[..])?;
 
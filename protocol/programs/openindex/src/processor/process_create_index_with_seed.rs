@@ -0,0 +1,22 @@
+//! Program state processor
+
+use solana_program::{account_info::AccountInfo, entrypoint::ProgramResult, pubkey::Pubkey};
+
+use crate::processor::create_index;
+
+/// instruction to process creating an index addressed by a creator-chosen seed
+pub fn process_create_index_with_seed(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    seed: [u8; 32],
+    drop_freeze_authority: bool,
+    transfer_hook_program: Option<Pubkey>,
+) -> ProgramResult {
+    create_index(
+        program_id,
+        accounts,
+        Some(seed),
+        drop_freeze_authority,
+        transfer_hook_program,
+    )
+}
@@ -0,0 +1,76 @@
+//! Program state processor
+
+use crate::state::Multisig;
+use crate::utils::create_pda_account;
+use borsh::BorshSerialize;
+use openindex_sdk::{
+    openindex::{error::ProtocolError, pda::find_multisig_address, seeds::MULTISIG_SEED},
+    require,
+};
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    rent::Rent,
+    sysvar::Sysvar,
+};
+
+/// instruction to process creating a multisig authority
+pub fn process_create_multisig(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    members: Vec<Pubkey>,
+    threshold: u8,
+) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let signer = next_account_info(accounts_iter)?;
+    let create_key = next_account_info(accounts_iter)?;
+    let multisig_account = next_account_info(accounts_iter)?;
+    let system_program_account = next_account_info(accounts_iter)?;
+
+    require!(
+        signer.is_signer && create_key.is_signer,
+        ProgramError::MissingRequiredSignature
+    );
+
+    require!(
+        !members.is_empty() && members.len() <= Multisig::MAX_MEMBERS,
+        ProtocolError::InvalidMultisigMembers.into()
+    );
+
+    for (i, member) in members.iter().enumerate() {
+        require!(
+            !members[..i].contains(member),
+            ProtocolError::InvalidMultisigMembers.into()
+        );
+    }
+
+    require!(
+        threshold > 0 && threshold as usize <= members.len(),
+        ProtocolError::InvalidMultisigThreshold.into()
+    );
+
+    let (multisig_pda, multisig_bump) = find_multisig_address(program_id, create_key.key);
+    require!(
+        *multisig_account.key == multisig_pda,
+        ProtocolError::IncorrectMultisigAccount.into()
+    );
+
+    let multisig = Multisig::new(threshold, members, multisig_bump);
+
+    let rent = Rent::get()?;
+    create_pda_account(
+        signer,
+        multisig_account,
+        system_program_account,
+        program_id,
+        multisig.packed_len(),
+        &rent,
+        &[MULTISIG_SEED, create_key.key.as_ref(), &[multisig_bump]],
+    )?;
+
+    multisig.serialize(&mut &mut multisig_account.data.borrow_mut()[..])?;
+
+    Ok(())
+}
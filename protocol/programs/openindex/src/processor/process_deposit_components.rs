@@ -0,0 +1,214 @@
+//! Program state processor
+
+use crate::state::{Component, MintRequest};
+use crate::utils::load_index;
+use borsh::{BorshDeserialize, BorshSerialize};
+use openindex_sdk::{
+    openindex::{
+        error::ProtocolError,
+        pda::{
+            create_component_address, create_component_vault_address,
+            create_index_address_from_seed, create_mint_request_address,
+        },
+    },
+    require,
+};
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    program::invoke,
+    program_error::ProgramError,
+    program_pack::IsInitialized,
+    pubkey::Pubkey,
+};
+use spl_token::instruction::transfer;
+
+/// instruction to process depositing a subset of components into a mint request
+pub fn process_deposit_components(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    index_id: u64,
+) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let signer = next_account_info(accounts_iter)?;
+    let controller_account = next_account_info(accounts_iter)?;
+    let index_account = next_account_info(accounts_iter)?;
+    let mint_request_account = next_account_info(accounts_iter)?;
+    let token_program_account = next_account_info(accounts_iter)?;
+
+    require!(signer.is_signer, ProgramError::MissingRequiredSignature);
+
+    require!(
+        controller_account.owner == program_id,
+        ProtocolError::UnknownControllerAccount.into()
+    );
+
+    require!(
+        index_account.owner == program_id,
+        ProtocolError::UnknownIndexAccount.into()
+    );
+
+    require!(
+        *token_program_account.key == spl_token::ID,
+        ProgramError::IncorrectProgramId
+    );
+
+    let index = load_index(program_id, controller_account.key, index_account)?;
+
+    require!(
+        index.id == index_id,
+        ProtocolError::IncorrectIndexAccount.into()
+    );
+
+    let index_seed = index.address_seed();
+
+    let index_pda = create_index_address_from_seed(
+        program_id,
+        controller_account.key,
+        &index_seed,
+        index.bump,
+    )?;
+
+    require!(
+        *index_account.key == index_pda,
+        ProtocolError::IncorrectIndexAccount.into()
+    );
+
+    let mut mint_request = MintRequest::try_from_slice(&mint_request_account.data.borrow())
+        .map_err(|_| ProtocolError::InvalidMintRequestAccountData)?;
+
+    require!(
+        mint_request.is_initialized(),
+        ProtocolError::InvalidMintRequestAccountData.into()
+    );
+
+    let mint_request_pda = create_mint_request_address(
+        program_id,
+        index_account.key,
+        signer.key,
+        mint_request.bump,
+    )?;
+
+    require!(
+        *mint_request_account.key == mint_request_pda,
+        ProtocolError::IncorrectMintRequestAccount.into()
+    );
+
+    require!(
+        mint_request.owner == *signer.key,
+        ProtocolError::OnlyMintRequestOwner.into()
+    );
+
+    require!(
+        index.composition_version == mint_request.composition_version,
+        ProtocolError::CompositionVersionMismatch.into()
+    );
+
+    require!(
+        accounts_iter.len() > 0,
+        ProtocolError::NoMintsProvided.into()
+    );
+
+    while accounts_iter.len() > 0 {
+        let component_mint_account = next_account_info(accounts_iter)?;
+        let component_account = next_account_info(accounts_iter)?;
+        let vault_pda = next_account_info(accounts_iter)?;
+        let vault_ata = next_account_info(accounts_iter)?;
+        let component_token_account = next_account_info(accounts_iter)?;
+
+        require!(
+            component_token_account.owner == token_program_account.key,
+            ProgramError::InvalidAccountOwner
+        );
+
+        require!(
+            component_mint_account.owner == token_program_account.key,
+            ProgramError::IncorrectProgramId
+        );
+
+        let position = mint_request
+            .mints
+            .iter()
+            .position(|mint| mint == component_mint_account.key)
+            .ok_or(ProtocolError::InvalidMintAccount)?;
+
+        let deposited = mint_request
+            .deposited
+            .get_mut(position)
+            .ok_or(ProtocolError::InvalidMintRequestAccountData)?;
+
+        require!(
+            *deposited == 0,
+            ProtocolError::ComponentAlreadyDeposited.into()
+        );
+
+        let component = Component::unpack(&component_account.data.borrow())
+            .map_err(|_| ProtocolError::InvalidComponentData)?;
+
+        let component_pda = create_component_address(
+            program_id,
+            index_account.key,
+            component_mint_account.key,
+            component.bump,
+        )?;
+
+        require!(
+            *component_account.key == component_pda,
+            ProtocolError::IncorrectComponentAccount.into()
+        );
+
+        require!(
+            component.is_initialized(),
+            ProtocolError::ComponentNotInitialized.into()
+        );
+
+        let expected_vault_pda = create_component_vault_address(
+            program_id,
+            index_account.key,
+            component_mint_account.key,
+            component.vault_bump,
+        )?;
+
+        require!(
+            *vault_pda.key == expected_vault_pda,
+            ProtocolError::IncorrectVaultAccount.into()
+        );
+
+        let expected_vault_ata = spl_associated_token_account::get_associated_token_address(
+            vault_pda.key,
+            component_mint_account.key,
+        );
+        require!(
+            *vault_ata.key == expected_vault_ata,
+            ProtocolError::IncorrectVaultATA.into()
+        );
+
+        let component_amount = mint_request
+            .amount
+            .checked_mul(component.uints)
+            .ok_or(ProgramError::ArithmeticOverflow)?;
+
+        invoke(
+            &transfer(
+                token_program_account.key,
+                component_token_account.key,
+                vault_ata.key,
+                signer.key,
+                &[],
+                component_amount,
+            )?,
+            &[
+                signer.clone(),
+                component_token_account.clone(),
+                vault_ata.clone(),
+                token_program_account.clone(),
+            ],
+        )?;
+
+        *deposited = component_amount;
+    }
+
+    mint_request.serialize(&mut &mut mint_request_account.data.borrow_mut()[..])?;
+
+    Ok(())
+}
@@ -0,0 +1,435 @@
+//! Program state processor
+
+use crate::state::{Component, ControllerGlobalConfig, Index, IndexMints};
+use crate::utils::{
+    check_asset_registry_entry, check_component_recipe, check_underlying_index, create_pda_account,
+    load_controller_config, load_index, load_pending_composition_change, realloc_account,
+};
+use borsh::{BorshDeserialize, BorshSerialize};
+use openindex_sdk::{
+    openindex::{
+        error::ProtocolError,
+        pda::{
+            create_index_address_from_seed, create_index_mint_address_from_seed,
+            create_index_mints_data_address_from_seed, find_component_address,
+            find_component_vault_address,
+        },
+        seeds::{COMPONENT_SEED, COMPONENT_VAULT_SEED},
+    },
+    require,
+};
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    clock::Clock,
+    entrypoint::ProgramResult,
+    program::invoke_signed,
+    program_error::ProgramError,
+    program_pack::IsInitialized,
+    pubkey::Pubkey,
+    rent::Rent,
+    sysvar::Sysvar,
+};
+use spl_associated_token_account::instruction::create_associated_token_account_idempotent;
+use spl_token_2022::{
+    extension::StateWithExtensions,
+    state::{Account, Mint},
+};
+
+/// instruction to process executing a queued composition change
+pub fn process_execute_composition_change(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let signer = next_account_info(accounts_iter)?;
+    let index_account = next_account_info(accounts_iter)?;
+    let index_mints_account = next_account_info(accounts_iter)?;
+    let mint_account = next_account_info(accounts_iter)?;
+    let controller_account = next_account_info(accounts_iter)?;
+    let controller_global_config_account = next_account_info(accounts_iter)?;
+    let controller_config_account = next_account_info(accounts_iter)?;
+    let pending_change_account = next_account_info(accounts_iter)?;
+    let rent_receiver = next_account_info(accounts_iter)?;
+    let system_program_account = next_account_info(accounts_iter)?;
+    let associated_token_program_account = next_account_info(accounts_iter)?;
+    let token_program_account = next_account_info(accounts_iter)?;
+
+    require!(signer.is_signer, ProgramError::MissingRequiredSignature);
+
+    require!(
+        index_account.owner == program_id,
+        ProtocolError::UnknownIndexAccount.into()
+    );
+
+    require!(
+        controller_global_config_account.owner == program_id,
+        ProtocolError::UnknownControllerGlobalConfigAccount.into()
+    );
+
+    let mut index = load_index(program_id, controller_account.key, index_account)?;
+    let index_seed = index.address_seed();
+
+    require!(
+        index.is_initialized(),
+        ProtocolError::IndexNotInitialized.into()
+    );
+
+    let index_pda = create_index_address_from_seed(
+        program_id,
+        controller_account.key,
+        &index_seed,
+        index.bump,
+    )?;
+
+    require!(
+        *index_account.key == index_pda,
+        ProtocolError::IncorrectIndexAccount.into()
+    );
+
+    let index_mints_pda = create_index_mints_data_address_from_seed(
+        program_id,
+        controller_account.key,
+        &index_seed,
+        index.index_mints_bump,
+    )?;
+
+    require!(
+        *index_mints_account.key == index_mints_pda,
+        ProtocolError::IncorrectIndexMintsAccount.into()
+    );
+
+    let mut index_mints = IndexMints::try_from_slice(&index_mints_account.data.borrow())
+        .map_err(|_| ProtocolError::InvalidIndexMintsAccountData)?;
+
+    let index_mint_pda = create_index_mint_address_from_seed(
+        program_id,
+        controller_account.key,
+        &index_seed,
+        index.mint_bump,
+    )?;
+
+    require!(
+        *mint_account.key == index_mint_pda,
+        ProtocolError::IncorrectMintAccount.into()
+    );
+
+    require!(
+        *mint_account.owner == spl_token::ID || *mint_account.owner == spl_token_2022::ID,
+        ProgramError::IncorrectProgramId
+    );
+
+    // outstanding tokens are redeemed against the recipe in force, so while
+    // any are in circulation every vault of the new recipe has to back the
+    // supply and no component may lose the units it already backs
+    let supply = StateWithExtensions::<Mint>::unpack(&mint_account.data.borrow())?
+        .base
+        .supply;
+
+    let pending_change =
+        load_pending_composition_change(program_id, index_account, pending_change_account)?;
+
+    require!(
+        Clock::get()?.unix_timestamp >= pending_change.executable_at,
+        ProtocolError::CompositionChangeTimelocked.into()
+    );
+
+    require!(
+        *rent_receiver.key == pending_change.manager,
+        ProtocolError::IncorrectPendingCompositionChangeAccount.into()
+    );
+
+    let mut controller_global_config =
+        ControllerGlobalConfig::unpack(&controller_global_config_account.data.borrow())?;
+    require!(
+        controller_global_config.is_initialized(),
+        ProtocolError::ControllerGlobalConfigNotInitialized.into()
+    );
+
+    // the controller config and the asset registry may have changed while
+    // the change was queued, so the recipe is checked again
+    let controller_config =
+        load_controller_config(program_id, controller_account, controller_config_account)?;
+    let max_index_components = controller_config.as_ref().map_or(
+        controller_global_config.max_index_components,
+        |config| config.max_index_components(controller_global_config.max_index_components),
+    );
+    check_component_recipe(
+        &pending_change.mints,
+        &pending_change.amounts,
+        &pending_change.nested_components,
+        &index_mint_pda,
+        max_index_components,
+        controller_config.as_ref(),
+    )?;
+
+    // components the change drops stay at the end of the recipe until the
+    // supply they back has been redeemed
+    let current_mints = std::mem::take(&mut index_mints.mints);
+    let dropped_mints: Vec<Pubkey> = if supply > 0 {
+        current_mints
+            .iter()
+            .filter(|mint| !pending_change.mints.contains(mint))
+            .copied()
+            .collect()
+    } else {
+        Vec::new()
+    };
+    let mints_len = pending_change.mints.len() + dropped_mints.len();
+    require!(
+        mints_len <= max_index_components as usize,
+        ProtocolError::MaxIndexComponentsExceeded.into()
+    );
+
+    let check_asset_registry = controller_global_config.asset_registry_required
+        && !controller_config
+            .as_ref()
+            .is_some_and(|config| config.override_asset_checks);
+
+    // as in `AddIndexComponents`, the four accounts of every component are
+    // followed by the asset registry accounts while registry checks apply,
+    // then by the underlying controller and index of every nested component
+    let asset_registry_accounts = accounts_iter
+        .as_slice()
+        .get(pending_change.mints.len() * 4..)
+        .unwrap_or_default();
+    let asset_registry_count = if check_asset_registry {
+        pending_change.mints.len()
+    } else {
+        0
+    };
+    let underlying_accounts = asset_registry_accounts
+        .get(asset_registry_count..)
+        .unwrap_or_default();
+    let mut asset_registry_accounts = asset_registry_accounts.iter();
+    let mut underlying_accounts = underlying_accounts.iter();
+
+    let rent = Rent::get()?;
+    for (position, (mint, amount)) in pending_change
+        .mints
+        .iter()
+        .zip(&pending_change.amounts)
+        .enumerate()
+    {
+        let mint_account = next_account_info(accounts_iter)?;
+        let component_account = next_account_info(accounts_iter)?;
+        let vault_pda = next_account_info(accounts_iter)?;
+        let vault_ata = next_account_info(accounts_iter)?;
+
+        require!(
+            mint_account.owner == token_program_account.key,
+            ProgramError::IncorrectProgramId
+        );
+
+        require!(
+            mint_account.key == mint,
+            ProtocolError::InvalidMintAccount.into()
+        );
+
+        if check_asset_registry {
+            let asset_registry_account = next_account_info(&mut asset_registry_accounts)?;
+            check_asset_registry_entry(program_id, mint_account, asset_registry_account)?;
+        }
+
+        let underlying_index =
+            if pending_change.nested_components.get(position).copied().unwrap_or(false) {
+                let underlying_controller_account = next_account_info(&mut underlying_accounts)?;
+                let underlying_index_account = next_account_info(&mut underlying_accounts)?;
+                check_underlying_index(
+                    program_id,
+                    index_account,
+                    underlying_controller_account,
+                    underlying_index_account,
+                    mint,
+                )?;
+                *underlying_index_account.key
+            } else {
+                Pubkey::default()
+            };
+
+        let (component_pda, component_bump) =
+            find_component_address(program_id, index_account.key, mint_account.key);
+
+        require!(
+            *component_account.key == component_pda,
+            ProtocolError::IncorrectComponentAccount.into()
+        );
+
+        let (expected_vault_pda, vault_bump) =
+            find_component_vault_address(program_id, index_account.key, mint_account.key);
+
+        require!(
+            *vault_pda.key == expected_vault_pda,
+            ProtocolError::IncorrectVaultAccount.into()
+        );
+
+        let expected_vault_ata = spl_associated_token_account::get_associated_token_address(
+            vault_pda.key,
+            mint_account.key,
+        );
+        require!(
+            *vault_ata.key == expected_vault_ata,
+            ProtocolError::IncorrectVaultATA.into()
+        );
+
+        // components kept from the current recipe, or dropped by an earlier
+        // change and now re-added, only get their units updated
+        if !component_account.data_is_empty() {
+            require!(
+                component_account.owner == program_id,
+                ProtocolError::IncorrectComponentAccount.into()
+            );
+
+            let mut component = Component::unpack(&component_account.data.borrow())
+                .map_err(|_| ProtocolError::InvalidComponentData)?;
+            let backed = current_mints.contains(mint);
+            let units = if backed && supply > 0 {
+                component.uints.max(*amount)
+            } else {
+                *amount
+            };
+            if !backed || units > component.uints {
+                check_vault_backing(vault_ata, units, supply)?;
+            }
+
+            component.uints = units;
+            component.underlying_index = underlying_index;
+            realloc_account(
+                signer,
+                component_account,
+                system_program_account,
+                Component::LEN,
+                &rent,
+            )?;
+            component.serialize(&mut &mut component_account.data.borrow_mut()[..])?;
+            continue;
+        }
+
+        create_pda_account(
+            signer,
+            component_account,
+            system_program_account,
+            program_id,
+            Component::LEN,
+            &rent,
+            &[
+                COMPONENT_SEED,
+                index_account.key.as_ref(),
+                mint_account.key.as_ref(),
+                &[component_bump],
+            ],
+        )?;
+
+        let mut component =
+            Component::new(*amount, *mint_account.key, component_bump, vault_bump);
+        component.underlying_index = underlying_index;
+        component.serialize(&mut &mut component_account.data.borrow_mut()[..])?;
+
+        // the manager may already have created and funded the vault
+        invoke_signed(
+            &create_associated_token_account_idempotent(
+                signer.key,
+                vault_pda.key,
+                mint_account.key,
+                &spl_token::ID,
+            ),
+            &[
+                signer.clone(),
+                vault_ata.clone(),
+                vault_pda.clone(),
+                mint_account.clone(),
+                system_program_account.clone(),
+                token_program_account.clone(),
+                associated_token_program_account.clone(),
+            ],
+            &[&[
+                COMPONENT_VAULT_SEED,
+                index_account.key.as_ref(),
+                mint_account.key.as_ref(),
+                &[vault_bump],
+            ]],
+        )?;
+
+        check_vault_backing(vault_ata, *amount, supply)?;
+    }
+
+    // resize the mint list to the new recipe; rent freed by a shorter list
+    // goes back to the manager together with the pending change rent
+    index_mints.mints = pending_change.mints;
+    index_mints.mints.extend(dropped_mints);
+    let space = index_mints.len();
+
+    if space > index_mints_account.data_len() {
+        realloc_account(
+            signer,
+            index_mints_account,
+            system_program_account,
+            space,
+            &rent,
+        )?;
+    } else if space < index_mints_account.data_len() {
+        index_mints_account.realloc(space, false)?;
+
+        let surplus = index_mints_account
+            .lamports()
+            .saturating_sub(rent.minimum_balance(space));
+        **index_mints_account.lamports.borrow_mut() -= surplus;
+        let rent_receiver_lamports = rent_receiver.lamports();
+        **rent_receiver.lamports.borrow_mut() = rent_receiver_lamports
+            .checked_add(surplus)
+            .ok_or(ProgramError::ArithmeticOverflow)?;
+    }
+
+    index_mints.serialize(&mut &mut index_mints_account.data.borrow_mut()[..])?;
+
+    index.composition_version = index
+        .composition_version
+        .checked_add(1)
+        .ok_or(ProgramError::ArithmeticOverflow)?;
+    realloc_account(
+        signer,
+        index_account,
+        system_program_account,
+        Index::LEN,
+        &rent,
+    )?;
+    index.serialize(&mut &mut index_account.data.borrow_mut()[..])?;
+
+    if mints_len as u32 > controller_global_config.largest_index_components
+        && controller_global_config_account.data_len() >= ControllerGlobalConfig::LEN
+    {
+        controller_global_config.largest_index_components = mints_len as u32;
+        controller_global_config
+            .serialize(&mut &mut controller_global_config_account.data.borrow_mut()[..])?;
+    }
+
+    // close the pending change and return its rent to the manager that queued it
+    let rent_receiver_lamports = rent_receiver.lamports();
+    **rent_receiver.lamports.borrow_mut() = rent_receiver_lamports
+        .checked_add(pending_change_account.lamports())
+        .ok_or(ProgramError::ArithmeticOverflow)?;
+    **pending_change_account.lamports.borrow_mut() = 0;
+    pending_change_account.data.borrow_mut().fill(0);
+
+    Ok(())
+}
+
+/// checks that `vault_ata` holds `units` for every outstanding index token
+fn check_vault_backing(vault_ata: &AccountInfo, units: u64, supply: u64) -> ProgramResult {
+    if supply == 0 {
+        return Ok(());
+    }
+
+    let required = units
+        .checked_mul(supply)
+        .ok_or(ProgramError::ArithmeticOverflow)?;
+    let balance = StateWithExtensions::<Account>::unpack(&vault_ata.data.borrow())
+        .map_err(|_| ProtocolError::ComponentVaultUnderfunded)?
+        .base
+        .amount;
+    require!(
+        balance >= required,
+        ProtocolError::ComponentVaultUnderfunded.into()
+    );
+
+    Ok(())
+}
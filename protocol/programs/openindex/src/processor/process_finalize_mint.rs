@@ -0,0 +1,176 @@
+//! Program state processor
+
+use crate::state::MintRequest;
+use crate::utils::{check_index_token_program, load_index};
+use borsh::BorshDeserialize;
+use openindex_sdk::{
+    openindex::{
+        error::ProtocolError,
+        pda::{
+            create_index_address_from_seed, create_index_mint_address_from_seed,
+            create_index_mint_authority_address_from_seed, create_mint_request_address,
+        },
+        seeds::INDEX_MINT_AUTHORITY_SEED,
+    },
+    require,
+};
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    program::invoke_signed,
+    program_error::ProgramError,
+    program_pack::IsInitialized,
+    pubkey::Pubkey,
+};
+use spl_token_2022::{extension::StateWithExtensions, instruction::mint_to, state::Account};
+
+/// instruction to process minting index tokens for a fully deposited mint request
+pub fn process_finalize_mint(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    index_id: u64,
+) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let signer = next_account_info(accounts_iter)?;
+    let controller_account = next_account_info(accounts_iter)?;
+    let mint_account = next_account_info(accounts_iter)?;
+    let mint_authority_account = next_account_info(accounts_iter)?;
+    let index_account = next_account_info(accounts_iter)?;
+    let mint_request_account = next_account_info(accounts_iter)?;
+    let token_account = next_account_info(accounts_iter)?;
+    let token_program_account = next_account_info(accounts_iter)?;
+
+    require!(signer.is_signer, ProgramError::MissingRequiredSignature);
+
+    require!(
+        controller_account.owner == program_id,
+        ProtocolError::UnknownControllerAccount.into()
+    );
+
+    require!(
+        index_account.owner == program_id,
+        ProtocolError::UnknownIndexAccount.into()
+    );
+
+    let index = load_index(program_id, controller_account.key, index_account)?;
+
+    require!(
+        index.id == index_id,
+        ProtocolError::IncorrectIndexAccount.into()
+    );
+
+    let index_seed = index.address_seed();
+
+    let index_pda = create_index_address_from_seed(
+        program_id,
+        controller_account.key,
+        &index_seed,
+        index.bump,
+    )?;
+
+    require!(
+        *index_account.key == index_pda,
+        ProtocolError::IncorrectIndexAccount.into()
+    );
+
+    let mint_pda = create_index_mint_address_from_seed(
+        program_id,
+        controller_account.key,
+        &index_seed,
+        index.mint_bump,
+    )?;
+
+    require!(
+        *mint_account.key == mint_pda,
+        ProtocolError::IncorrectMintAccount.into()
+    );
+
+    check_index_token_program(mint_account, token_program_account)?;
+
+    let mint_authority_pda = create_index_mint_authority_address_from_seed(
+        program_id,
+        controller_account.key,
+        &index_seed,
+        index.mint_authority_bump,
+    )?;
+
+    require!(
+        *mint_authority_account.key == mint_authority_pda,
+        ProtocolError::IncorrectMintAuthority.into()
+    );
+
+    let mint_request = MintRequest::try_from_slice(&mint_request_account.data.borrow())
+        .map_err(|_| ProtocolError::InvalidMintRequestAccountData)?;
+
+    require!(
+        mint_request.is_initialized(),
+        ProtocolError::InvalidMintRequestAccountData.into()
+    );
+
+    let mint_request_pda = create_mint_request_address(
+        program_id,
+        index_account.key,
+        signer.key,
+        mint_request.bump,
+    )?;
+
+    require!(
+        *mint_request_account.key == mint_request_pda,
+        ProtocolError::IncorrectMintRequestAccount.into()
+    );
+
+    require!(
+        mint_request.owner == *signer.key,
+        ProtocolError::OnlyMintRequestOwner.into()
+    );
+
+    require!(
+        index.composition_version == mint_request.composition_version,
+        ProtocolError::CompositionVersionMismatch.into()
+    );
+
+    require!(
+        mint_request.is_complete(),
+        ProtocolError::MintRequestIncomplete.into()
+    );
+
+    let token_account_mint =
+        StateWithExtensions::<Account>::unpack(&token_account.data.borrow())?.base.mint;
+    require!(
+        *mint_account.key == token_account_mint,
+        ProtocolError::InvalidMintAccount.into()
+    );
+
+    invoke_signed(
+        &mint_to(
+            token_program_account.key,
+            mint_account.key,
+            token_account.key,
+            &mint_authority_pda,
+            &[],
+            mint_request.amount,
+        )?,
+        &[
+            token_program_account.clone(),
+            mint_account.clone(),
+            token_account.clone(),
+            mint_authority_account.clone(),
+        ],
+        &[&[
+            INDEX_MINT_AUTHORITY_SEED,
+            controller_account.key.as_ref(),
+            &index_seed,
+            &[index.mint_authority_bump],
+        ]],
+    )?;
+
+    // close the mint request and return its rent to the owner
+    let signer_lamports = signer.lamports();
+    **signer.lamports.borrow_mut() = signer_lamports
+        .checked_add(mint_request_account.lamports())
+        .ok_or(ProgramError::ArithmeticOverflow)?;
+    **mint_request_account.lamports.borrow_mut() = 0;
+    mint_request_account.data.borrow_mut().fill(0);
+
+    Ok(())
+}
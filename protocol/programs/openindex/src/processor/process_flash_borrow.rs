@@ -0,0 +1,225 @@
+//! Program state processor
+
+use crate::state::{Component, FlashLoan};
+use crate::utils::{create_pda_account, is_followed_by, load_index};
+use borsh::BorshSerialize;
+use openindex_sdk::{
+    openindex::{
+        error::ProtocolError,
+        instruction::ProtocolInstruction,
+        pda::{
+            create_component_address, create_component_vault_address,
+            create_index_address_from_seed, find_flash_loan_address,
+        },
+        seeds::{COMPONENT_VAULT_SEED, FLASH_LOAN_SEED},
+    },
+    require,
+};
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    program::invoke_signed,
+    program_error::ProgramError,
+    program_pack::{IsInitialized, Pack},
+    pubkey::Pubkey,
+    rent::Rent,
+    sysvar::Sysvar,
+};
+use spl_token::instruction::transfer;
+
+/// Position of `flash_loan_account` in the accounts of `FlashRepay`.
+const REPAYMENT_FLASH_LOAN_POSITION: usize = 3;
+
+/// instruction to process lending a component vault balance within a transaction
+pub fn process_flash_borrow(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    index_id: u64,
+    amount: u64,
+) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let signer = next_account_info(accounts_iter)?;
+    let controller_account = next_account_info(accounts_iter)?;
+    let index_account = next_account_info(accounts_iter)?;
+    let mint_account = next_account_info(accounts_iter)?;
+    let component_account = next_account_info(accounts_iter)?;
+    let vault_pda = next_account_info(accounts_iter)?;
+    let vault_ata = next_account_info(accounts_iter)?;
+    let flash_loan_account = next_account_info(accounts_iter)?;
+    let token_account = next_account_info(accounts_iter)?;
+    let system_program_account = next_account_info(accounts_iter)?;
+    let token_program_account = next_account_info(accounts_iter)?;
+    let instructions_sysvar_account = next_account_info(accounts_iter)?;
+
+    require!(signer.is_signer, ProgramError::MissingRequiredSignature);
+
+    require!(
+        amount > 0,
+        ProtocolError::AmountMustBeGreaterThanZero.into()
+    );
+
+    require!(
+        controller_account.owner == program_id,
+        ProtocolError::UnknownControllerAccount.into()
+    );
+
+    require!(
+        index_account.owner == program_id,
+        ProtocolError::UnknownIndexAccount.into()
+    );
+
+    require!(
+        *token_program_account.key == spl_token::ID,
+        ProgramError::IncorrectProgramId
+    );
+
+    let index = load_index(program_id, controller_account.key, index_account)?;
+
+    require!(
+        index.id == index_id,
+        ProtocolError::IncorrectIndexAccount.into()
+    );
+
+    require!(
+        index.is_initialized(),
+        ProtocolError::IndexNotInitialized.into()
+    );
+
+    let index_pda = create_index_address_from_seed(
+        program_id,
+        controller_account.key,
+        &index.address_seed(),
+        index.bump,
+    )?;
+
+    require!(
+        *index_account.key == index_pda,
+        ProtocolError::IncorrectIndexAccount.into()
+    );
+
+    require!(
+        index.flash_loans_enabled,
+        ProtocolError::FlashLoansDisabled.into()
+    );
+
+    require!(
+        component_account.owner == program_id,
+        ProtocolError::IncorrectComponentAccount.into()
+    );
+
+    let component = Component::unpack(&component_account.data.borrow())
+        .map_err(|_| ProtocolError::InvalidComponentData)?;
+
+    let component_pda = create_component_address(
+        program_id,
+        index_account.key,
+        mint_account.key,
+        component.bump,
+    )?;
+
+    require!(
+        *component_account.key == component_pda,
+        ProtocolError::IncorrectComponentAccount.into()
+    );
+
+    let expected_vault_pda = create_component_vault_address(
+        program_id,
+        index_account.key,
+        mint_account.key,
+        component.vault_bump,
+    )?;
+
+    require!(
+        *vault_pda.key == expected_vault_pda,
+        ProtocolError::IncorrectVaultAccount.into()
+    );
+
+    let expected_vault_ata =
+        spl_associated_token_account::get_associated_token_address(vault_pda.key, mint_account.key);
+    require!(
+        *vault_ata.key == expected_vault_ata,
+        ProtocolError::IncorrectVaultATA.into()
+    );
+
+    let (flash_loan_pda, flash_loan_bump) =
+        find_flash_loan_address(program_id, component_account.key, signer.key);
+
+    require!(
+        *flash_loan_account.key == flash_loan_pda,
+        ProtocolError::IncorrectFlashLoanAccount.into()
+    );
+
+    require!(
+        flash_loan_account.data_is_empty(),
+        ProgramError::AccountAlreadyInitialized
+    );
+
+    // the vault may only be lent if this very transaction pays it back
+    let is_repaid = is_followed_by(
+        program_id,
+        instructions_sysvar_account,
+        REPAYMENT_FLASH_LOAN_POSITION,
+        &flash_loan_pda,
+        |instruction| matches!(instruction, ProtocolInstruction::FlashRepay),
+    )?;
+    require!(is_repaid, ProtocolError::MissingFlashRepay.into());
+
+    let token_account_data = spl_token::state::Account::unpack(&token_account.data.borrow())?;
+    require!(
+        *mint_account.key == token_account_data.mint,
+        ProtocolError::InvalidTokenMint.into()
+    );
+
+    let flash_loan = FlashLoan::new(
+        *signer.key,
+        *component_account.key,
+        *vault_ata.key,
+        amount,
+        index.flash_loan_fee_bps,
+        flash_loan_bump,
+    )
+    .ok_or(ProgramError::ArithmeticOverflow)?;
+
+    let rent = Rent::get()?;
+    create_pda_account(
+        signer,
+        flash_loan_account,
+        system_program_account,
+        program_id,
+        FlashLoan::LEN,
+        &rent,
+        &[
+            FLASH_LOAN_SEED,
+            component_account.key.as_ref(),
+            signer.key.as_ref(),
+            &[flash_loan_bump],
+        ],
+    )?;
+
+    flash_loan.serialize(&mut &mut flash_loan_account.data.borrow_mut()[..])?;
+
+    invoke_signed(
+        &transfer(
+            token_program_account.key,
+            vault_ata.key,
+            token_account.key,
+            vault_pda.key,
+            &[],
+            amount,
+        )?,
+        &[
+            token_program_account.clone(),
+            vault_ata.clone(),
+            token_account.clone(),
+            vault_pda.clone(),
+        ],
+        &[&[
+            COMPONENT_VAULT_SEED,
+            index_account.key.as_ref(),
+            mint_account.key.as_ref(),
+            &[component.vault_bump],
+        ]],
+    )?;
+
+    Ok(())
+}
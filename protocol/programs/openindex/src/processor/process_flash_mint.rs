@@ -0,0 +1,233 @@
+//! Program state processor
+
+use crate::state::{ControllerGlobalConfig, FlashMint};
+use crate::utils::{
+    check_index_token_program, create_pda_account, is_followed_by, load_controller_config,
+    load_index,
+};
+use borsh::BorshSerialize;
+use openindex_sdk::{
+    openindex::{
+        error::ProtocolError,
+        instruction::ProtocolInstruction,
+        pda::{
+            create_controller_global_config_address, create_index_address_from_seed,
+            create_index_mint_address_from_seed, create_index_mint_authority_address_from_seed,
+            find_flash_mint_address,
+        },
+        seeds::{FLASH_MINT_SEED, INDEX_MINT_AUTHORITY_SEED},
+    },
+    require,
+};
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    program::invoke_signed,
+    program_error::ProgramError,
+    program_pack::IsInitialized,
+    pubkey::Pubkey,
+    rent::Rent,
+    sysvar::Sysvar,
+};
+use spl_token_2022::{extension::StateWithExtensions, instruction::mint_to, state::Account};
+
+/// Position of `flash_mint_account` in the accounts of `SettleFlashMint`.
+const SETTLEMENT_FLASH_MINT_POSITION: usize = 4;
+
+/// instruction to process minting index tokens before their components are deposited
+pub fn process_flash_mint(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    index_id: u64,
+    amount: u64,
+) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let signer = next_account_info(accounts_iter)?;
+    let controller_account = next_account_info(accounts_iter)?;
+    let mint_account = next_account_info(accounts_iter)?;
+    let mint_authority_account = next_account_info(accounts_iter)?;
+    let index_account = next_account_info(accounts_iter)?;
+    let controller_global_config_account = next_account_info(accounts_iter)?;
+    let controller_config_account = next_account_info(accounts_iter)?;
+    let flash_mint_account = next_account_info(accounts_iter)?;
+    let token_account = next_account_info(accounts_iter)?;
+    let system_program_account = next_account_info(accounts_iter)?;
+    let token_program_account = next_account_info(accounts_iter)?;
+    let instructions_sysvar_account = next_account_info(accounts_iter)?;
+
+    require!(signer.is_signer, ProgramError::MissingRequiredSignature);
+
+    require!(
+        amount > 0,
+        ProtocolError::AmountMustBeGreaterThanZero.into()
+    );
+
+    require!(
+        controller_account.owner == program_id,
+        ProtocolError::UnknownControllerAccount.into()
+    );
+
+    require!(
+        index_account.owner == program_id,
+        ProtocolError::UnknownIndexAccount.into()
+    );
+
+    require!(
+        controller_global_config_account.owner == program_id,
+        ProtocolError::UnknownControllerGlobalConfigAccount.into()
+    );
+
+    let index = load_index(program_id, controller_account.key, index_account)?;
+
+    require!(
+        index.id == index_id,
+        ProtocolError::IncorrectIndexAccount.into()
+    );
+
+    require!(
+        index.is_initialized(),
+        ProtocolError::IndexNotInitialized.into()
+    );
+
+    let index_seed = index.address_seed();
+
+    let index_pda = create_index_address_from_seed(
+        program_id,
+        controller_account.key,
+        &index_seed,
+        index.bump,
+    )?;
+
+    require!(
+        *index_account.key == index_pda,
+        ProtocolError::IncorrectIndexAccount.into()
+    );
+
+    let mint_pda = create_index_mint_address_from_seed(
+        program_id,
+        controller_account.key,
+        &index_seed,
+        index.mint_bump,
+    )?;
+
+    require!(
+        *mint_account.key == mint_pda,
+        ProtocolError::IncorrectMintAccount.into()
+    );
+
+    check_index_token_program(mint_account, token_program_account)?;
+
+    let mint_authority_pda = create_index_mint_authority_address_from_seed(
+        program_id,
+        controller_account.key,
+        &index_seed,
+        index.mint_authority_bump,
+    )?;
+
+    require!(
+        *mint_authority_account.key == mint_authority_pda,
+        ProtocolError::IncorrectMintAuthority.into()
+    );
+
+    let controller_global_config =
+        ControllerGlobalConfig::unpack(&controller_global_config_account.data.borrow())?;
+
+    let controller_global_config_pda =
+        create_controller_global_config_address(program_id, controller_global_config.bump)?;
+
+    require!(
+        *controller_global_config_account.key == controller_global_config_pda,
+        ProtocolError::IncorrectControllerGlobalConfigAccount.into()
+    );
+
+    let controller_config =
+        load_controller_config(program_id, controller_account, controller_config_account)?;
+    let fee_bps = controller_config.as_ref().map_or(
+        controller_global_config.flash_mint_fee_bps,
+        |config| config.flash_mint_fee_bps(controller_global_config.flash_mint_fee_bps),
+    );
+
+    let (flash_mint_pda, flash_mint_bump) =
+        find_flash_mint_address(program_id, index_account.key, signer.key);
+
+    require!(
+        *flash_mint_account.key == flash_mint_pda,
+        ProtocolError::IncorrectFlashMintAccount.into()
+    );
+
+    require!(
+        flash_mint_account.data_is_empty(),
+        ProgramError::AccountAlreadyInitialized
+    );
+
+    // the obligation is only safe to open if this very transaction closes it
+    let is_settled = is_followed_by(
+        program_id,
+        instructions_sysvar_account,
+        SETTLEMENT_FLASH_MINT_POSITION,
+        &flash_mint_pda,
+        |instruction| matches!(instruction, ProtocolInstruction::SettleFlashMint { .. }),
+    )?;
+    require!(
+        is_settled,
+        ProtocolError::MissingFlashMintSettlement.into()
+    );
+
+    let token_account_data =
+        StateWithExtensions::<Account>::unpack(&token_account.data.borrow())?.base;
+    require!(
+        *mint_account.key == token_account_data.mint,
+        ProtocolError::InvalidMintAccount.into()
+    );
+
+    let rent = Rent::get()?;
+    create_pda_account(
+        signer,
+        flash_mint_account,
+        system_program_account,
+        program_id,
+        FlashMint::LEN,
+        &rent,
+        &[
+            FLASH_MINT_SEED,
+            index_account.key.as_ref(),
+            signer.key.as_ref(),
+            &[flash_mint_bump],
+        ],
+    )?;
+
+    let flash_mint = FlashMint::new(
+        *signer.key,
+        *index_account.key,
+        amount,
+        fee_bps,
+        index.composition_version,
+        flash_mint_bump,
+    );
+    flash_mint.serialize(&mut &mut flash_mint_account.data.borrow_mut()[..])?;
+
+    invoke_signed(
+        &mint_to(
+            token_program_account.key,
+            mint_account.key,
+            token_account.key,
+            &mint_authority_pda,
+            &[],
+            amount,
+        )?,
+        &[
+            token_program_account.clone(),
+            mint_account.clone(),
+            token_account.clone(),
+            mint_authority_account.clone(),
+        ],
+        &[&[
+            INDEX_MINT_AUTHORITY_SEED,
+            controller_account.key.as_ref(),
+            &index_seed,
+            &[index.mint_authority_bump],
+        ]],
+    )?;
+
+    Ok(())
+}
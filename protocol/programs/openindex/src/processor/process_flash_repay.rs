@@ -0,0 +1,108 @@
+//! Program state processor
+
+use crate::state::FlashLoan;
+use crate::utils::check_token_account_authority;
+use borsh::BorshDeserialize;
+use openindex_sdk::{
+    openindex::{error::ProtocolError, pda::create_flash_loan_address},
+    require,
+};
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    program::invoke,
+    program_error::ProgramError,
+    program_pack::IsInitialized,
+    pubkey::Pubkey,
+};
+use spl_token::instruction::transfer;
+use spl_token_2022::{extension::StateWithExtensions, state::Account};
+
+/// instruction to process repaying a flash loan with its fee
+pub fn process_flash_repay(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let signer = next_account_info(accounts_iter)?;
+    let component_account = next_account_info(accounts_iter)?;
+    let vault_ata = next_account_info(accounts_iter)?;
+    let flash_loan_account = next_account_info(accounts_iter)?;
+    let token_account = next_account_info(accounts_iter)?;
+    let token_program_account = next_account_info(accounts_iter)?;
+
+    require!(signer.is_signer, ProgramError::MissingRequiredSignature);
+
+    require!(
+        flash_loan_account.owner == program_id,
+        ProtocolError::IncorrectFlashLoanAccount.into()
+    );
+
+    require!(
+        *token_program_account.key == spl_token::ID,
+        ProgramError::IncorrectProgramId
+    );
+
+    let flash_loan = FlashLoan::try_from_slice(&flash_loan_account.data.borrow())
+        .map_err(|_| ProtocolError::InvalidFlashLoanAccountData)?;
+
+    require!(
+        flash_loan.is_initialized() && flash_loan.component == *component_account.key,
+        ProtocolError::InvalidFlashLoanAccountData.into()
+    );
+
+    // the bump is only valid for the borrower's own loan
+    let flash_loan_pda = create_flash_loan_address(
+        program_id,
+        component_account.key,
+        signer.key,
+        flash_loan.bump,
+    )?;
+
+    require!(
+        *flash_loan_account.key == flash_loan_pda,
+        ProtocolError::IncorrectFlashLoanAccount.into()
+    );
+
+    require!(
+        *vault_ata.key == flash_loan.vault_ata,
+        ProtocolError::IncorrectVaultATA.into()
+    );
+
+    let amount_due = flash_loan
+        .amount_due()
+        .ok_or(ProgramError::ArithmeticOverflow)?;
+
+    require!(
+        token_account.owner == token_program_account.key,
+        ProgramError::InvalidAccountOwner
+    );
+
+    let token_account_data =
+        StateWithExtensions::<Account>::unpack(&token_account.data.borrow())?.base;
+    check_token_account_authority(&token_account_data, signer.key, amount_due)?;
+
+    invoke(
+        &transfer(
+            token_program_account.key,
+            token_account.key,
+            vault_ata.key,
+            signer.key,
+            &[],
+            amount_due,
+        )?,
+        &[
+            signer.clone(),
+            token_account.clone(),
+            vault_ata.clone(),
+            token_program_account.clone(),
+        ],
+    )?;
+
+    // close the loan and return its rent to the borrower
+    let signer_lamports = signer.lamports();
+    **signer.lamports.borrow_mut() = signer_lamports
+        .checked_add(flash_loan_account.lamports())
+        .ok_or(ProgramError::ArithmeticOverflow)?;
+    **flash_loan_account.lamports.borrow_mut() = 0;
+    flash_loan_account.data.borrow_mut().fill(0);
+
+    Ok(())
+}
@@ -0,0 +1,178 @@
+//! Program state processor
+
+use crate::utils::{check_index_compliance_authority, check_index_token_program, load_index};
+use openindex_sdk::{
+    openindex::{
+        error::ProtocolError,
+        pda::{
+            create_index_address_from_seed, create_index_mint_address_from_seed,
+            create_index_mint_authority_address_from_seed,
+        },
+        seeds::INDEX_MINT_AUTHORITY_SEED,
+    },
+    require,
+};
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    program::invoke_signed,
+    program_error::ProgramError,
+    program_pack::IsInitialized,
+    pubkey::Pubkey,
+};
+use spl_token_2022::{
+    extension::StateWithExtensions,
+    instruction::{freeze_account, thaw_account},
+    state::{Account, Mint},
+};
+
+/// instruction to process freezing a holder's index token account
+pub fn process_freeze_holder(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    index_id: u64,
+) -> ProgramResult {
+    set_holder_frozen(program_id, accounts, index_id, true)
+}
+
+/// Freezes or thaws `token_account` with the index mint freeze authority.
+/// Allowed for the index owner, and for the controller owner or a
+/// `ComplianceOfficer` of the controller.
+pub(crate) fn set_holder_frozen(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    index_id: u64,
+    freeze: bool,
+) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let signer = next_account_info(accounts_iter)?;
+    let controller_account = next_account_info(accounts_iter)?;
+    let index_account = next_account_info(accounts_iter)?;
+    let mint_account = next_account_info(accounts_iter)?;
+    let mint_authority_account = next_account_info(accounts_iter)?;
+    let token_account = next_account_info(accounts_iter)?;
+    let token_program_account = next_account_info(accounts_iter)?;
+    let controller_roles_account = next_account_info(accounts_iter)?;
+
+    require!(signer.is_signer, ProgramError::MissingRequiredSignature);
+
+    require!(
+        controller_account.owner == program_id,
+        ProtocolError::UnknownControllerAccount.into()
+    );
+
+    require!(
+        index_account.owner == program_id,
+        ProtocolError::UnknownIndexAccount.into()
+    );
+
+    let index = load_index(program_id, controller_account.key, index_account)?;
+
+    require!(
+        index.id == index_id,
+        ProtocolError::IncorrectIndexAccount.into()
+    );
+
+    require!(
+        index.is_initialized(),
+        ProtocolError::IndexNotInitialized.into()
+    );
+
+    let index_seed = index.address_seed();
+
+    let index_pda = create_index_address_from_seed(
+        program_id,
+        controller_account.key,
+        &index_seed,
+        index.bump,
+    )?;
+
+    require!(
+        *index_account.key == index_pda,
+        ProtocolError::IncorrectIndexAccount.into()
+    );
+
+    check_index_compliance_authority(
+        program_id,
+        signer,
+        &index,
+        controller_account,
+        controller_roles_account,
+        accounts_iter.as_slice(),
+    )?;
+
+    let mint_pda = create_index_mint_address_from_seed(
+        program_id,
+        controller_account.key,
+        &index_seed,
+        index.mint_bump,
+    )?;
+
+    require!(
+        *mint_account.key == mint_pda,
+        ProtocolError::IncorrectMintAccount.into()
+    );
+
+    check_index_token_program(mint_account, token_program_account)?;
+
+    let mint_authority_pda = create_index_mint_authority_address_from_seed(
+        program_id,
+        controller_account.key,
+        &index_seed,
+        index.mint_authority_bump,
+    )?;
+
+    require!(
+        *mint_authority_account.key == mint_authority_pda,
+        ProtocolError::IncorrectMintAuthority.into()
+    );
+
+    let mint = StateWithExtensions::<Mint>::unpack(&mint_account.data.borrow())?.base;
+    require!(
+        mint.freeze_authority.contains(&mint_authority_pda),
+        ProtocolError::IndexNotFreezable.into()
+    );
+
+    let token_account_data =
+        StateWithExtensions::<Account>::unpack(&token_account.data.borrow())?.base;
+    require!(
+        token_account_data.mint == *mint_account.key,
+        ProtocolError::InvalidMintAccount.into()
+    );
+
+    let instruction = if freeze {
+        freeze_account(
+            token_program_account.key,
+            token_account.key,
+            mint_account.key,
+            &mint_authority_pda,
+            &[],
+        )?
+    } else {
+        thaw_account(
+            token_program_account.key,
+            token_account.key,
+            mint_account.key,
+            &mint_authority_pda,
+            &[],
+        )?
+    };
+
+    invoke_signed(
+        &instruction,
+        &[
+            token_program_account.clone(),
+            token_account.clone(),
+            mint_account.clone(),
+            mint_authority_account.clone(),
+        ],
+        &[&[
+            INDEX_MINT_AUTHORITY_SEED,
+            controller_account.key.as_ref(),
+            &index_seed,
+            &[index.mint_authority_bump],
+        ]],
+    )?;
+
+    Ok(())
+}
@@ -0,0 +1,98 @@
+//! Program state processor
+
+use crate::state::{Controller, ControllerRoles};
+use crate::utils::{check_owner, create_pda_account, load_controller_roles, realloc_account};
+use borsh::{BorshDeserialize, BorshSerialize};
+use openindex_sdk::{
+    openindex::{
+        error::ProtocolError, instruction::ControllerRole, pda::find_controller_roles_address,
+        seeds::CONTROLLER_ROLES_SEED,
+    },
+    require,
+};
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    rent::Rent,
+    sysvar::Sysvar,
+};
+
+/// instruction to process granting a controller role
+pub fn process_grant_controller_role(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    role: ControllerRole,
+) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let signer = next_account_info(accounts_iter)?;
+    let controller_account = next_account_info(accounts_iter)?;
+    let holder = next_account_info(accounts_iter)?;
+    let controller_roles_account = next_account_info(accounts_iter)?;
+    let system_program_account = next_account_info(accounts_iter)?;
+
+    require!(signer.is_signer, ProgramError::MissingRequiredSignature);
+
+    require!(
+        controller_account.owner == program_id,
+        ProtocolError::UnknownControllerAccount.into()
+    );
+
+    let controller = Controller::try_from_slice(&controller_account.data.borrow())?;
+    check_owner(
+        program_id,
+        &controller.owner,
+        signer,
+        accounts_iter.as_slice(),
+        ProtocolError::OnlyControllerOwner,
+    )?;
+
+    let existing_roles =
+        load_controller_roles(program_id, controller_account, controller_roles_account)?;
+    let is_created = existing_roles.is_some();
+
+    let mut controller_roles = match existing_roles {
+        Some(controller_roles) => controller_roles,
+        None => ControllerRoles::new(
+            *controller_account.key,
+            find_controller_roles_address(program_id, controller_account.key).1,
+        ),
+    };
+
+    require!(
+        controller_roles.grant(*holder.key, role),
+        ProtocolError::ControllerRoleAlreadyGranted.into()
+    );
+
+    let rent = Rent::get()?;
+    let space = controller_roles.packed_len();
+
+    if is_created {
+        realloc_account(
+            signer,
+            controller_roles_account,
+            system_program_account,
+            space,
+            &rent,
+        )?;
+    } else {
+        create_pda_account(
+            signer,
+            controller_roles_account,
+            system_program_account,
+            program_id,
+            space,
+            &rent,
+            &[
+                CONTROLLER_ROLES_SEED,
+                controller_account.key.as_ref(),
+                &[controller_roles.bump],
+            ],
+        )?;
+    }
+
+    controller_roles.serialize(&mut &mut controller_roles_account.data.borrow_mut()[..])?;
+
+    Ok(())
+}
@@ -4,20 +4,23 @@ use borsh::{BorshDeserialize, BorshSerialize};
 use solana_program::{
     account_info::{next_account_info, AccountInfo},
     entrypoint::ProgramResult,
-    program::invoke_signed,
     program_error::ProgramError,
     program_pack::IsInitialized,
     pubkey::Pubkey,
     rent::Rent,
-    system_instruction,
     sysvar::Sysvar,
 };
 
-use crate::state::{Controller, Protocol};
+use crate::state::{Controller, ControllerCreator, ControllerGlobalConfig, Protocol};
+use crate::utils::{check_treasury_account, create_pda_account, pay_fee};
 use openindex_sdk::{
     openindex::{
         error::ProtocolError,
-        pda::{create_protocol_address, find_controller_address},
+        instruction::ControllerCreationPolicy,
+        pda::{
+            create_controller_creator_address, create_protocol_address, find_controller_address,
+            find_controller_global_config_address,
+        },
         seeds::CONTROLLER_SEED,
     },
     require,
@@ -27,11 +30,16 @@ use openindex_sdk::{
 pub fn process_init_controller(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
     let accounts_iter = &mut accounts.iter();
     let signer = next_account_info(accounts_iter)?;
+    let payer = next_account_info(accounts_iter)?;
     let protocol_account = next_account_info(accounts_iter)?;
     let controller_account = next_account_info(accounts_iter)?;
     let system_program = next_account_info(accounts_iter)?;
+    let controller_global_config_account = next_account_info(accounts_iter)?;
+    let controller_creator_account = next_account_info(accounts_iter)?;
+    let treasury_account = next_account_info(accounts_iter)?;
 
     require!(signer.is_signer, ProgramError::MissingRequiredSignature);
+    require!(payer.is_signer, ProgramError::MissingRequiredSignature);
 
     require!(
         protocol_account.owner == program_id,
@@ -39,7 +47,7 @@ pub fn process_init_controller(program_id: &Pubkey, accounts: &[AccountInfo]) ->
     );
 
     require!(
-        controller_account.lamports() == 0,
+        controller_account.data_is_empty(),
         ProgramError::AccountAlreadyInitialized
     );
 
@@ -58,6 +66,16 @@ pub fn process_init_controller(program_id: &Pubkey, accounts: &[AccountInfo]) ->
         ProtocolError::IncorrectProtocolAccount.into()
     );
 
+    check_controller_creation_policy(
+        program_id,
+        signer,
+        payer,
+        controller_global_config_account,
+        controller_creator_account,
+        treasury_account,
+        system_program,
+    )?;
+
     let controller_id = protocol.get_next_controller_id();
 
     let (controller_pda, controller_bump) = find_controller_address(program_id, controller_id);
@@ -68,26 +86,19 @@ pub fn process_init_controller(program_id: &Pubkey, accounts: &[AccountInfo]) ->
     );
 
     let rent = Rent::get()?;
-    let lamports = rent.minimum_balance(Controller::LEN);
-
-    invoke_signed(
-        &system_instruction::create_account(
-            &signer.key,
-            &controller_account.key,
-            lamports,
-            Controller::LEN as u64,
-            program_id,
-        ),
+
+    create_pda_account(
+        payer,
+        controller_account,
+        system_program,
+        program_id,
+        Controller::LEN,
+        &rent,
         &[
-            signer.clone(),
-            controller_account.clone(),
-            system_program.clone(),
-        ],
-        &[&[
             CONTROLLER_SEED,
             &controller_id.to_le_bytes(),
             &[controller_bump],
-        ]],
+        ],
     )?;
 
     let controller = Controller::new(controller_id, signer.key.clone(), controller_bump);
@@ -98,3 +109,78 @@ pub fn process_init_controller(program_id: &Pubkey, accounts: &[AccountInfo]) ->
 
     Ok(())
 }
+
+/// Enforces `ControllerGlobalConfig::controller_creation_policy` for
+/// `signer`, charging the creation fee to `payer` when the `Fee` policy is
+/// active.
+pub(crate) fn check_controller_creation_policy<'a>(
+    program_id: &Pubkey,
+    signer: &AccountInfo<'a>,
+    payer: &AccountInfo<'a>,
+    controller_global_config_account: &AccountInfo<'a>,
+    controller_creator_account: &AccountInfo<'a>,
+    treasury_account: &AccountInfo<'a>,
+    system_program: &AccountInfo<'a>,
+) -> ProgramResult {
+    require!(
+        *controller_global_config_account.key
+            == find_controller_global_config_address(program_id).0,
+        ProtocolError::IncorrectControllerGlobalConfigAccount.into()
+    );
+
+    // without a global config nobody has restricted controller creation yet
+    if controller_global_config_account.owner != program_id {
+        return Ok(());
+    }
+
+    let controller_global_config =
+        ControllerGlobalConfig::unpack(&controller_global_config_account.data.borrow())?;
+
+    match controller_global_config.controller_creation_policy {
+        ControllerCreationPolicy::Open => Ok(()),
+        ControllerCreationPolicy::Allowlist => {
+            check_controller_creator(program_id, signer.key, controller_creator_account)
+        }
+        ControllerCreationPolicy::Fee => {
+            check_treasury_account(program_id, treasury_account)?;
+            pay_fee(
+                payer,
+                treasury_account,
+                system_program,
+                controller_global_config.controller_creation_fee,
+            )
+        }
+    }
+}
+
+/// Checks that `controller_creator_account` is the allowlist entry of `creator`.
+fn check_controller_creator(
+    program_id: &Pubkey,
+    creator: &Pubkey,
+    controller_creator_account: &AccountInfo,
+) -> ProgramResult {
+    require!(
+        controller_creator_account.owner == program_id,
+        ProtocolError::ControllerCreatorNotAllowed.into()
+    );
+
+    let controller_creator =
+        ControllerCreator::try_from_slice(&controller_creator_account.data.borrow())
+            .map_err(|_| ProtocolError::IncorrectControllerCreatorAccount)?;
+
+    let controller_creator_pda =
+        create_controller_creator_address(program_id, creator, controller_creator.bump)?;
+
+    require!(
+        *controller_creator_account.key == controller_creator_pda
+            && controller_creator.creator == *creator,
+        ProtocolError::ControllerCreatorNotAllowed.into()
+    );
+
+    require!(
+        controller_creator.is_initialized(),
+        ProtocolError::ControllerCreatorNotAllowed.into()
+    );
+
+    Ok(())
+}
@@ -1,6 +1,7 @@
 //! Program state processor
 
 use crate::state::{ControllerGlobalConfig, Protocol};
+use crate::utils::{check_owner, create_pda_account};
 use borsh::{BorshDeserialize, BorshSerialize};
 use openindex_sdk::{
     openindex::{
@@ -13,12 +14,10 @@ use openindex_sdk::{
 use solana_program::{
     account_info::{next_account_info, AccountInfo},
     entrypoint::ProgramResult,
-    program::invoke_signed,
     program_error::ProgramError,
     program_pack::IsInitialized,
     pubkey::Pubkey,
     rent::Rent,
-    system_instruction,
     sysvar::Sysvar,
 };
 
@@ -41,7 +40,7 @@ pub fn process_init_controller_global_config(
     require!(signer.is_signer, ProgramError::MissingRequiredSignature);
 
     require!(
-        controller_global_config_account.lamports() == 0,
+        controller_global_config_account.data_is_empty(),
         ProgramError::AccountAlreadyInitialized
     );
 
@@ -65,10 +64,14 @@ pub fn process_init_controller_global_config(
         ProtocolError::IncorrectProtocolAccount.into()
     );
 
-    require!(
-        *signer.key == protocol.owner,
-        ProtocolError::OnlyProtocolOwner.into()
-    );
+    // `InitProtocol` only lets the upgrade authority become the protocol owner
+    check_owner(
+        program_id,
+        &protocol.owner,
+        signer,
+        accounts_iter.as_slice(),
+        ProtocolError::OnlyProtocolOwner,
+    )?;
 
     let (controller_global_config_pda, controller_global_conifg_bump) =
         find_controller_global_config_address(program_id);
@@ -79,25 +82,18 @@ pub fn process_init_controller_global_config(
     );
 
     let rent = Rent::get()?;
-    let lamports = rent.minimum_balance(ControllerGlobalConfig::LEN);
-
-    invoke_signed(
-        &system_instruction::create_account(
-            &signer.key,
-            &controller_global_config_account.key,
-            lamports,
-            ControllerGlobalConfig::LEN as u64,
-            &program_id,
-        ),
+
+    create_pda_account(
+        signer,
+        controller_global_config_account,
+        system_program_account,
+        program_id,
+        ControllerGlobalConfig::LEN,
+        &rent,
         &[
-            signer.clone(),
-            controller_global_config_account.clone(),
-            system_program_account.clone(),
-        ],
-        &[&[
             CONTROLLER_GLOBAL_CONFIG_SEED,
             &[controller_global_conifg_bump],
-        ]],
+        ],
     )?;
 
     let controller_global_conifg =
@@ -0,0 +1,152 @@
+//! Program state processor
+
+use borsh::{BorshDeserialize, BorshSerialize};
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    program_error::ProgramError,
+    program_pack::IsInitialized,
+    pubkey::Pubkey,
+    rent::Rent,
+    sysvar::Sysvar,
+};
+
+use crate::processor::check_controller_creation_policy;
+use crate::state::{Controller, ControllerCounter, Protocol};
+use crate::utils::create_pda_account;
+use openindex_sdk::{
+    openindex::{
+        error::ProtocolError,
+        pda::{
+            controller_counter_shard, create_protocol_address, find_controller_counter_address,
+            find_creator_controller_address,
+        },
+        seeds::{CONTROLLER_COUNTER_SEED, CREATOR_CONTROLLER_SEED},
+    },
+    require,
+};
+
+/// Controller id stored by controllers that are not derived from
+/// `Protocol.next_controller_id`.
+const NONCE_CONTROLLER_ID: u64 = 0;
+
+/// instruction to process initializing a controller derived from its creator and a nonce
+pub fn process_init_controller_with_nonce(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    nonce: u64,
+) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let signer = next_account_info(accounts_iter)?;
+    let payer = next_account_info(accounts_iter)?;
+    let protocol_account = next_account_info(accounts_iter)?;
+    let controller_account = next_account_info(accounts_iter)?;
+    let system_program = next_account_info(accounts_iter)?;
+    let controller_global_config_account = next_account_info(accounts_iter)?;
+    let controller_creator_account = next_account_info(accounts_iter)?;
+    let treasury_account = next_account_info(accounts_iter)?;
+    let controller_counter_account = next_account_info(accounts_iter)?;
+
+    require!(signer.is_signer, ProgramError::MissingRequiredSignature);
+    require!(payer.is_signer, ProgramError::MissingRequiredSignature);
+
+    require!(
+        protocol_account.owner == program_id,
+        ProtocolError::UnknownProtocolAccount.into()
+    );
+
+    require!(
+        controller_account.data_is_empty(),
+        ProgramError::AccountAlreadyInitialized
+    );
+
+    let protocol: Protocol = Protocol::try_from_slice(&protocol_account.data.borrow())
+        .map_err(|_| ProtocolError::InvalidProtocolAccountData)?;
+
+    require!(
+        protocol.is_initialized(),
+        ProtocolError::ProtocolNotInitialized.into()
+    );
+
+    let protocol_pda = create_protocol_address(program_id, protocol.bump)?;
+
+    require!(
+        *protocol_account.key == protocol_pda,
+        ProtocolError::IncorrectProtocolAccount.into()
+    );
+
+    check_controller_creation_policy(
+        program_id,
+        signer,
+        payer,
+        controller_global_config_account,
+        controller_creator_account,
+        treasury_account,
+        system_program,
+    )?;
+
+    let (controller_pda, controller_bump) =
+        find_creator_controller_address(program_id, signer.key, nonce);
+
+    require!(
+        *controller_account.key == controller_pda,
+        ProtocolError::IncorrectControllerAccount.into()
+    );
+
+    let shard = controller_counter_shard(signer.key);
+    let (controller_counter_pda, controller_counter_bump) =
+        find_controller_counter_address(program_id, shard);
+
+    require!(
+        *controller_counter_account.key == controller_counter_pda,
+        ProtocolError::IncorrectControllerCounterAccount.into()
+    );
+
+    let rent = Rent::get()?;
+
+    create_pda_account(
+        payer,
+        controller_account,
+        system_program,
+        program_id,
+        Controller::LEN,
+        &rent,
+        &[
+            CREATOR_CONTROLLER_SEED,
+            signer.key.as_ref(),
+            &nonce.to_le_bytes(),
+            &[controller_bump],
+        ],
+    )?;
+
+    let controller = Controller::new(NONCE_CONTROLLER_ID, *signer.key, controller_bump);
+    controller.serialize(&mut &mut controller_account.data.borrow_mut()[..])?;
+
+    let mut controller_counter = if controller_counter_account.owner == program_id {
+        ControllerCounter::try_from_slice(&controller_counter_account.data.borrow())
+            .map_err(|_| ProtocolError::IncorrectControllerCounterAccount)?
+    } else {
+        create_pda_account(
+            payer,
+            controller_counter_account,
+            system_program,
+            program_id,
+            ControllerCounter::LEN,
+            &rent,
+            &[
+                CONTROLLER_COUNTER_SEED,
+                &[shard],
+                &[controller_counter_bump],
+            ],
+        )?;
+        ControllerCounter::new(shard, controller_counter_bump)
+    };
+
+    controller_counter.count = controller_counter
+        .count
+        .checked_add(1)
+        .ok_or(ProgramError::ArithmeticOverflow)?;
+    controller_counter.serialize(&mut &mut controller_counter_account.data.borrow_mut()[..])?;
+
+    Ok(())
+}
@@ -4,16 +4,15 @@ use borsh::{BorshDeserialize, BorshSerialize};
 use solana_program::{
     account_info::{next_account_info, AccountInfo},
     entrypoint::ProgramResult,
-    program::invoke_signed,
     program_error::ProgramError,
     program_pack::IsInitialized,
     pubkey::Pubkey,
     rent::Rent,
-    system_instruction,
     sysvar::Sysvar,
 };
 
 use crate::state::{Module, Protocol};
+use crate::utils::{check_owner, create_pda_account};
 use openindex_sdk::{
     openindex::{
         error::ProtocolError,
@@ -28,12 +27,14 @@ pub fn process_init_module(program_id: &Pubkey, accounts: &[AccountInfo]) -> Pro
     let accounts_iter = &mut accounts.iter();
 
     let signer = next_account_info(accounts_iter)?;
+    let payer = next_account_info(accounts_iter)?;
     let protocol_account = next_account_info(accounts_iter)?;
     let module_signer_account = next_account_info(accounts_iter)?;
     let registered_module_account = next_account_info(accounts_iter)?;
     let system_program = next_account_info(accounts_iter)?;
 
     require!(signer.is_signer, ProgramError::MissingRequiredSignature);
+    require!(payer.is_signer, ProgramError::MissingRequiredSignature);
 
     require!(
         protocol_account.owner == program_id,
@@ -55,10 +56,13 @@ pub fn process_init_module(program_id: &Pubkey, accounts: &[AccountInfo]) -> Pro
         ProtocolError::IncorrectProtocolAccount.into()
     );
 
-    require!(
-        protocol.owner == *signer.key,
-        ProtocolError::OnlyProtocolOwner.into()
-    );
+    check_owner(
+        program_id,
+        &protocol.owner,
+        signer,
+        accounts_iter.as_slice(),
+        ProtocolError::OnlyProtocolOwner,
+    )?;
 
     let (registered_module_pda, registered_module_bump) =
         find_registered_module_address(program_id, module_signer_account.key);
@@ -69,26 +73,19 @@ pub fn process_init_module(program_id: &Pubkey, accounts: &[AccountInfo]) -> Pro
     );
 
     let rent = Rent::get()?;
-    let lamports = rent.minimum_balance(Module::LEN);
-
-    invoke_signed(
-        &system_instruction::create_account(
-            &signer.key,
-            &registered_module_account.key,
-            lamports,
-            Module::LEN as u64,
-            program_id,
-        ),
+
+    create_pda_account(
+        payer,
+        registered_module_account,
+        system_program,
+        program_id,
+        Module::LEN,
+        &rent,
         &[
-            signer.clone(),
-            registered_module_account.clone(),
-            system_program.clone(),
-        ],
-        &[&[
             MODULE_SEED,
             &module_signer_account.key.as_ref(),
             &[registered_module_bump],
-        ]],
+        ],
     )?;
 
     let module = Module::new(true, registered_module_bump);
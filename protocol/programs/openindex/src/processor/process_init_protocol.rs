@@ -1,6 +1,7 @@
 //! Program state processor
 
 use crate::state::Protocol;
+use crate::utils::{check_upgrade_authority, create_pda_account};
 use borsh::BorshSerialize;
 use openindex_sdk::{
     openindex::{error::ProtocolError, pda::find_protocol_address, seeds::PROTOCOL_SEED},
@@ -9,11 +10,9 @@ use openindex_sdk::{
 use solana_program::{
     account_info::{next_account_info, AccountInfo},
     entrypoint::ProgramResult,
-    program::invoke_signed,
     program_error::ProgramError,
     pubkey::Pubkey,
     rent::Rent,
-    system_instruction,
     sysvar::Sysvar,
 };
 
@@ -23,11 +22,16 @@ pub fn process_init_protocol(program_id: Pubkey, accounts: &[AccountInfo]) -> Pr
     let signer = next_account_info(accounts_iter)?;
     let protocol_account = next_account_info(accounts_iter)?;
     let system_program_account = next_account_info(accounts_iter)?;
+    let program_data_account = next_account_info(accounts_iter)?;
 
     require!(signer.is_signer, ProgramError::MissingRequiredSignature);
 
+    // whoever initializes the protocol becomes its owner, so only the
+    // deployer may do it
+    check_upgrade_authority(&program_id, program_data_account, signer.key)?;
+
     require!(
-        protocol_account.lamports() == 0,
+        protocol_account.data_is_empty(),
         ProgramError::AccountAlreadyInitialized
     );
 
@@ -39,22 +43,15 @@ pub fn process_init_protocol(program_id: Pubkey, accounts: &[AccountInfo]) -> Pr
     );
 
     let rent = Rent::get()?;
-    let lamports = rent.minimum_balance(Protocol::LEN);
 
-    invoke_signed(
-        &system_instruction::create_account(
-            &signer.key,
-            &protocol_account.key,
-            lamports,
-            Protocol::LEN as u64,
-            &program_id,
-        ),
-        &[
-            signer.clone(),
-            protocol_account.clone(),
-            system_program_account.clone(),
-        ],
-        &[&[PROTOCOL_SEED, &[protocol_bump]]],
+    create_pda_account(
+        signer,
+        protocol_account,
+        system_program_account,
+        &program_id,
+        Protocol::LEN,
+        &rent,
+        &[PROTOCOL_SEED, &[protocol_bump]],
     )?;
 
     let protocol = Protocol::new(signer.key.clone(), protocol_bump);
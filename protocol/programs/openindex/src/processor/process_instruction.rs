@@ -1,7 +1,19 @@
 use crate::processor::{
-    process_add_index_components, process_create_index, process_init_controller,
-    process_init_controller_global_config, process_init_module, process_init_protocol,
-    process_mint, process_redeem,
+    process_add_index_components, process_begin_mint, process_begin_redeem,
+    process_cancel_composition_change, process_cancel_mint,
+    process_create_index, process_create_index_with_seed, process_create_multisig,
+    process_deposit_components,
+    process_execute_composition_change, process_finalize_mint, process_flash_borrow,
+    process_flash_mint, process_flash_repay, process_freeze_holder, process_grant_controller_role,
+    process_init_controller, process_init_controller_global_config,
+    process_init_controller_with_nonce, process_init_module,
+    process_init_protocol, process_mint, process_queue_composition_change, process_redeem,
+    process_redeem_underlying,
+    process_revoke_controller_role, process_set_asset_registry_entry, process_set_composition_delay, process_set_controller_config,
+    process_set_controller_creator, process_set_flash_loan_config, process_set_transfer_allowlist,
+    process_settle_flash_mint, process_thaw_holder, process_transfer_ownership,
+    process_update_controller_global_config,
+    process_withdraw_components, process_withdraw_treasury,
 };
 use borsh::BorshDeserialize;
 use openindex_sdk::openindex::instruction::ProtocolInstruction as Instruction;
@@ -27,17 +39,199 @@ pub fn process_instruction(
             max_index_components,
         } => process_init_controller_global_config(program_id, accounts, max_index_components)?,
 
-        Instruction::CreateIndex => process_create_index(program_id, accounts)?,
-        Instruction::AddIndexComponents { amounts, mints } => {
-            process_add_index_components(program_id, accounts, mints, amounts)?
+        Instruction::CreateIndex {
+            drop_freeze_authority,
+            transfer_hook_program,
+        } => process_create_index(
+            program_id,
+            accounts,
+            drop_freeze_authority,
+            transfer_hook_program,
+        )?,
+        Instruction::AddIndexComponents {
+            amounts,
+            mints,
+            nested_components,
+        } => process_add_index_components(program_id, accounts, mints, amounts, nested_components)?,
+        Instruction::Mint {
+            index_id,
+            amount,
+            expected_version,
+            max_amounts_in,
+            recipient,
+        } => process_mint(
+            program_id,
+            accounts,
+            index_id,
+            amount,
+            expected_version,
+            max_amounts_in,
+            recipient,
+        )?,
+        Instruction::Redeem {
+            index_id,
+            amount,
+            expected_version,
+            min_amounts_out,
+            recipient,
+            create_destination_accounts,
+            unwrap_native,
+        } => process_redeem(
+            program_id,
+            accounts,
+            index_id,
+            amount,
+            expected_version,
+            min_amounts_out,
+            recipient,
+            create_destination_accounts,
+            unwrap_native,
+        )?,
+        Instruction::InitModule => process_init_module(program_id, accounts)?,
+        Instruction::BeginMint { index_id, amount } => {
+            process_begin_mint(program_id, accounts, index_id, amount)?
         }
-        Instruction::Mint { index_id, amount } => {
-            process_mint(program_id, accounts, index_id, amount)?
+        Instruction::DepositComponents { index_id } => {
+            process_deposit_components(program_id, accounts, index_id)?
         }
-        Instruction::Redeem { index_id, amount } => {
-            process_redeem(program_id, accounts, index_id, amount)?
+        Instruction::FinalizeMint { index_id } => {
+            process_finalize_mint(program_id, accounts, index_id)?
         }
-        Instruction::InitModule => process_init_module(program_id, accounts)?,
+        Instruction::CancelMint { index_id } => {
+            process_cancel_mint(program_id, accounts, index_id)?
+        }
+        Instruction::BeginRedeem { index_id, amount } => {
+            process_begin_redeem(program_id, accounts, index_id, amount)?
+        }
+        Instruction::WithdrawComponents { index_id } => {
+            process_withdraw_components(program_id, accounts, index_id)?
+        }
+        Instruction::UpdateControllerGlobalConfig {
+            max_index_components,
+            check_existing_indexes,
+            controller_creation_policy,
+            controller_creation_fee,
+            index_creation_fee,
+            flash_mint_fee_bps,
+            asset_registry_required,
+        } => process_update_controller_global_config(
+            program_id,
+            accounts,
+            max_index_components,
+            check_existing_indexes,
+            controller_creation_policy,
+            controller_creation_fee,
+            index_creation_fee,
+            flash_mint_fee_bps,
+            asset_registry_required,
+        )?,
+        Instruction::SetControllerConfig {
+            max_index_components,
+            max_mint_fee_bps,
+            max_redeem_fee_bps,
+            default_decimals,
+            allowed_mints,
+            override_asset_checks,
+        } => process_set_controller_config(
+            program_id,
+            accounts,
+            max_index_components,
+            max_mint_fee_bps,
+            max_redeem_fee_bps,
+            default_decimals,
+            allowed_mints,
+            override_asset_checks,
+        )?,
+        Instruction::SetControllerCreator { allowed } => {
+            process_set_controller_creator(program_id, accounts, allowed)?
+        }
+        Instruction::WithdrawTreasury { amount } => {
+            process_withdraw_treasury(program_id, accounts, amount)?
+        }
+        Instruction::InitControllerWithNonce { nonce } => {
+            process_init_controller_with_nonce(program_id, accounts, nonce)?
+        }
+        Instruction::CreateIndexWithSeed {
+            seed,
+            drop_freeze_authority,
+            transfer_hook_program,
+        } => process_create_index_with_seed(
+            program_id,
+            accounts,
+            seed,
+            drop_freeze_authority,
+            transfer_hook_program,
+        )?,
+        Instruction::GrantControllerRole { role } => {
+            process_grant_controller_role(program_id, accounts, role)?
+        }
+        Instruction::RevokeControllerRole { role } => {
+            process_revoke_controller_role(program_id, accounts, role)?
+        }
+        Instruction::CreateMultisig { members, threshold } => {
+            process_create_multisig(program_id, accounts, members, threshold)?
+        }
+        Instruction::TransferOwnership { new_owner } => {
+            process_transfer_ownership(program_id, accounts, new_owner)?
+        }
+        Instruction::SetCompositionDelay { delay } => {
+            process_set_composition_delay(program_id, accounts, delay)?
+        }
+        Instruction::QueueCompositionChange {
+            mints,
+            amounts,
+            nested_components,
+        } => process_queue_composition_change(
+            program_id,
+            accounts,
+            mints,
+            amounts,
+            nested_components,
+        )?,
+        Instruction::ExecuteCompositionChange => {
+            process_execute_composition_change(program_id, accounts)?
+        }
+        Instruction::CancelCompositionChange => {
+            process_cancel_composition_change(program_id, accounts)?
+        }
+        Instruction::RedeemUnderlying { index_id, amount } => {
+            process_redeem_underlying(program_id, accounts, index_id, amount)?
+        }
+        Instruction::FlashMint { index_id, amount } => {
+            process_flash_mint(program_id, accounts, index_id, amount)?
+        }
+        Instruction::SettleFlashMint { index_id } => {
+            process_settle_flash_mint(program_id, accounts, index_id)?
+        }
+        Instruction::SetFlashLoanConfig { enabled, fee_bps } => {
+            process_set_flash_loan_config(program_id, accounts, enabled, fee_bps)?
+        }
+        Instruction::FlashBorrow { index_id, amount } => {
+            process_flash_borrow(program_id, accounts, index_id, amount)?
+        }
+        Instruction::FlashRepay => process_flash_repay(program_id, accounts)?,
+        Instruction::FreezeHolder { index_id } => {
+            process_freeze_holder(program_id, accounts, index_id)?
+        }
+        Instruction::ThawHolder { index_id } => {
+            process_thaw_holder(program_id, accounts, index_id)?
+        }
+        Instruction::SetTransferAllowlist {
+            index_id,
+            wallet,
+            allowed,
+        } => process_set_transfer_allowlist(program_id, accounts, index_id, wallet, allowed)?,
+        Instruction::SetAssetRegistryEntry {
+            approved,
+            risk_tier,
+            accepted_risks,
+        } => process_set_asset_registry_entry(
+            program_id,
+            accounts,
+            approved,
+            risk_tier,
+            accepted_risks,
+        )?,
     }
 
     Ok(())
@@ -1,13 +1,16 @@
 //! Program state processor
 
 use crate::state::{Component, IndexMints};
+use crate::utils::{check_token_account_authority, find_index_token_program, load_index};
 use borsh::BorshDeserialize;
 use openindex_sdk::{
     openindex::{
         error::ProtocolError,
         pda::{
             create_component_address, create_component_vault_address,
-            create_index_mints_data_address, find_index_mint_authority_address,
+            create_index_address_from_seed, create_index_mint_address_from_seed,
+            create_index_mint_authority_address_from_seed,
+            create_index_mints_data_address_from_seed,
         },
         seeds::INDEX_MINT_AUTHORITY_SEED,
     },
@@ -18,10 +21,12 @@ use solana_program::{
     entrypoint::ProgramResult,
     program::{invoke, invoke_signed},
     program_error::ProgramError,
-    program_pack::{IsInitialized, Pack},
+    program_pack::IsInitialized,
     pubkey::Pubkey,
+    system_instruction, system_program,
 };
-use spl_token::instruction::{mint_to, transfer};
+use spl_token::instruction::{sync_native, transfer};
+use spl_token_2022::{extension::StateWithExtensions, instruction::mint_to, state::Account};
 
 //// instruction to process minting an index
 pub fn process_mint(
@@ -29,6 +34,9 @@ pub fn process_mint(
     accounts: &[AccountInfo],
     index_id: u64,
     amount: u64,
+    expected_version: Option<u64>,
+    max_amounts_in: Option<Vec<u64>>,
+    recipient: Option<Pubkey>,
 ) -> ProgramResult {
     let accounts_iter = &mut accounts.iter();
     let signer = next_account_info(accounts_iter)?;
@@ -60,13 +68,61 @@ pub fn process_mint(
         ProtocolError::UnknownIndexAccount.into()
     );
 
+    let index = load_index(program_id, controller_account.key, index_account)?;
+
+    require!(
+        index.id == index_id,
+        ProtocolError::IncorrectIndexAccount.into()
+    );
+
+    if let Some(expected_version) = expected_version {
+        require!(
+            index.composition_version == expected_version,
+            ProtocolError::CompositionVersionMismatch.into()
+        );
+    }
+
+    let index_seed = index.address_seed();
+
+    require!(
+        index.is_initialized(),
+        ProtocolError::IndexNotInitialized.into()
+    );
+
+    let index_pda = create_index_address_from_seed(
+        program_id,
+        controller_account.key,
+        &index_seed,
+        index.bump,
+    )?;
+
+    require!(
+        *index_account.key == index_pda,
+        ProtocolError::IncorrectIndexAccount.into()
+    );
+
+    let mint_pda = create_index_mint_address_from_seed(
+        program_id,
+        controller_account.key,
+        &index_seed,
+        index.mint_bump,
+    )?;
+
+    require!(
+        *mint_account.key == mint_pda,
+        ProtocolError::IncorrectMintAccount.into()
+    );
+
+    let index_token_program_account =
+        find_index_token_program(mint_account, token_program_account, accounts)?;
+
     let index_mints_data = IndexMints::try_from_slice(&index_mints_account.data.borrow_mut()[..])
         .map_err(|_| ProtocolError::InvalidIndexMintsAccountData)?;
 
-    let index_mints_pda = create_index_mints_data_address(
+    let index_mints_pda = create_index_mints_data_address_from_seed(
         program_id,
         controller_account.key,
-        index_id,
+        &index_seed,
         index_mints_data.bump,
     )?;
 
@@ -77,15 +133,31 @@ pub fn process_mint(
 
     let mints = index_mints_data.mints;
 
-    let (mint_authority_pda, mint_authority_bump) =
-        find_index_mint_authority_address(program_id, controller_account.key, index_id);
+    if let Some(max_amounts_in) = max_amounts_in.as_ref() {
+        require!(
+            max_amounts_in.len() == mints.len(),
+            ProtocolError::MintsAmountsLenMismatch.into()
+        );
+    }
+
+    let mint_authority_bump = index.mint_authority_bump;
+    let mint_authority_pda = create_index_mint_authority_address_from_seed(
+        program_id,
+        controller_account.key,
+        &index_seed,
+        mint_authority_bump,
+    )?;
 
     require!(
         *mint_authority_account.key == mint_authority_pda,
         ProtocolError::IncorrectMintAuthority.into()
     );
 
-    for _ in mints.iter() {
+    // the system program follows the five accounts of every component and is
+    // only needed to pay a wrapped SOL component in lamports
+    let system_program_account = accounts_iter.as_slice().get(mints.len() * 5);
+
+    for (position, mint) in mints.iter().enumerate() {
         let component_mint_account = next_account_info(accounts_iter)?;
         let component_account = next_account_info(accounts_iter)?;
         let vault_pda = next_account_info(accounts_iter)?;
@@ -93,16 +165,18 @@ pub fn process_mint(
         let component_token_account = next_account_info(accounts_iter)?;
 
         require!(
-            component_token_account.owner == token_program_account.key,
-            ProgramError::InvalidAccountOwner
+            component_mint_account.owner == token_program_account.key,
+            ProgramError::IncorrectProgramId
         );
 
+        // components dropped by a composition change keep their accounts,
+        // so each bundle must match the current recipe
         require!(
-            component_mint_account.owner == token_program_account.key,
-            ProgramError::IncorrectProgramId
+            component_mint_account.key == mint,
+            ProtocolError::InvalidMintAccount.into()
         );
 
-        let component = Component::try_from_slice(&component_account.data.borrow_mut()[..])
+        let component = Component::unpack(&component_account.data.borrow_mut()[..])
             .map_err(|_| ProtocolError::InvalidComponentData)?;
 
         let component_pda = create_component_address(
@@ -126,6 +200,13 @@ pub fn process_mint(
             .checked_mul(component.uints)
             .ok_or(ProgramError::ArithmeticOverflow)?;
 
+        if let Some(max_amounts_in) = max_amounts_in.as_ref() {
+            require!(
+                component_amount <= max_amounts_in[position],
+                ProtocolError::MaxAmountInExceeded.into()
+            );
+        }
+
         let expected_vault_pda = create_component_vault_address(
             program_id,
             index_account.key,
@@ -148,6 +229,46 @@ pub fn process_mint(
             ProtocolError::IncorrectVaultATA.into()
         );
 
+        if *mint == spl_token::native_mint::ID && component_token_account.key == signer.key {
+            let system_program_account =
+                system_program_account.ok_or(ProgramError::NotEnoughAccountKeys)?;
+            require!(
+                *system_program_account.key == system_program::ID,
+                ProgramError::IncorrectProgramId
+            );
+
+            invoke(
+                &system_instruction::transfer(signer.key, vault_ata.key, component_amount),
+                &[
+                    signer.clone(),
+                    vault_ata.clone(),
+                    system_program_account.clone(),
+                ],
+            )?;
+            invoke(
+                &sync_native(token_program_account.key, vault_ata.key)?,
+                &[vault_ata.clone(), token_program_account.clone()],
+            )?;
+            continue;
+        }
+
+        require!(
+            component_token_account.owner == token_program_account.key,
+            ProgramError::InvalidAccountOwner
+        );
+
+        let component_token_account_data =
+            StateWithExtensions::<Account>::unpack(&component_token_account.data.borrow())?.base;
+        require!(
+            component_token_account_data.mint == *mint,
+            ProtocolError::InvalidTokenMint.into()
+        );
+        check_token_account_authority(
+            &component_token_account_data,
+            signer.key,
+            component_amount,
+        )?;
+
         invoke(
             &transfer(
                 token_program_account.key,
@@ -165,15 +286,20 @@ pub fn process_mint(
             ],
         )?;
     }
-    let token_account_data = spl_token::state::Account::unpack(&token_account.data.borrow())?;
+    let token_account_data =
+        StateWithExtensions::<Account>::unpack(&token_account.data.borrow())?.base;
     require!(
         *mint_account.key == token_account_data.mint,
         ProtocolError::InvalidMintAccount.into()
     );
+    require!(
+        token_account_data.owner == recipient.unwrap_or(*signer.key),
+        ProtocolError::IncorrectRecipient.into()
+    );
 
     invoke_signed(
         &mint_to(
-            token_program_account.key,
+            index_token_program_account.key,
             mint_account.key,
             token_account.key,
             &mint_authority_pda,
@@ -181,7 +307,7 @@ pub fn process_mint(
             amount,
         )?,
         &[
-            token_program_account.clone(),
+            index_token_program_account.clone(),
             mint_account.clone(),
             token_account.clone(),
             mint_authority_account.clone(),
@@ -189,7 +315,7 @@ pub fn process_mint(
         &[&[
             INDEX_MINT_AUTHORITY_SEED,
             controller_account.key.as_ref(),
-            &index_id.to_le_bytes(),
+            &index_seed,
             &[mint_authority_bump],
         ]],
     )?;
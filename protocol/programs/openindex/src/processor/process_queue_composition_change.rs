@@ -0,0 +1,170 @@
+//! Program state processor
+
+use crate::state::{ControllerGlobalConfig, PendingCompositionChange};
+use crate::utils::{
+    check_component_recipe, check_owner, create_pda_account, load_controller_config, load_index,
+};
+use borsh::BorshSerialize;
+use openindex_sdk::{
+    openindex::{
+        error::ProtocolError,
+        pda::{
+            create_index_address_from_seed, create_index_mint_address_from_seed,
+            find_pending_composition_change_address,
+        },
+        seeds::PENDING_COMPOSITION_CHANGE_SEED,
+    },
+    require,
+};
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    clock::Clock,
+    entrypoint::ProgramResult,
+    program_error::ProgramError,
+    program_pack::IsInitialized,
+    pubkey::Pubkey,
+    rent::Rent,
+    sysvar::Sysvar,
+};
+
+/// instruction to process queueing a timelocked composition change
+pub fn process_queue_composition_change(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    mints: Vec<Pubkey>,
+    amounts: Vec<u64>,
+    nested_components: Vec<bool>,
+) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let signer = next_account_info(accounts_iter)?;
+    let index_account = next_account_info(accounts_iter)?;
+    let controller_account = next_account_info(accounts_iter)?;
+    let controller_global_config_account = next_account_info(accounts_iter)?;
+    let controller_config_account = next_account_info(accounts_iter)?;
+    let pending_change_account = next_account_info(accounts_iter)?;
+    let system_program_account = next_account_info(accounts_iter)?;
+
+    require!(signer.is_signer, ProgramError::MissingRequiredSignature);
+
+    require!(
+        index_account.owner == program_id,
+        ProtocolError::UnknownIndexAccount.into()
+    );
+
+    require!(
+        controller_account.owner == program_id,
+        ProtocolError::UnknownControllerAccount.into()
+    );
+
+    require!(
+        controller_global_config_account.owner == program_id,
+        ProtocolError::UnknownControllerGlobalConfigAccount.into()
+    );
+
+    let index = load_index(program_id, controller_account.key, index_account)?;
+
+    require!(
+        index.is_initialized(),
+        ProtocolError::IndexNotInitialized.into()
+    );
+
+    let index_seed = index.address_seed();
+    let index_pda = create_index_address_from_seed(
+        program_id,
+        controller_account.key,
+        &index_seed,
+        index.bump,
+    )?;
+
+    require!(
+        *index_account.key == index_pda,
+        ProtocolError::IncorrectIndexAccount.into()
+    );
+
+    check_owner(
+        program_id,
+        &index.manager,
+        signer,
+        accounts_iter.as_slice(),
+        ProtocolError::OnlyIndexManager,
+    )?;
+
+    let controller_global_config =
+        ControllerGlobalConfig::unpack(&controller_global_config_account.data.borrow())?;
+    require!(
+        controller_global_config.is_initialized(),
+        ProtocolError::ControllerGlobalConfigNotInitialized.into()
+    );
+
+    let controller_config =
+        load_controller_config(program_id, controller_account, controller_config_account)?;
+    let max_index_components = controller_config.as_ref().map_or(
+        controller_global_config.max_index_components,
+        |config| config.max_index_components(controller_global_config.max_index_components),
+    );
+
+    // the asset registry and the underlying indexes are checked when the
+    // change is executed, against the accounts of every component
+    let index_mint_pda = create_index_mint_address_from_seed(
+        program_id,
+        controller_account.key,
+        &index_seed,
+        index.mint_bump,
+    )?;
+    check_component_recipe(
+        &mints,
+        &amounts,
+        &nested_components,
+        &index_mint_pda,
+        max_index_components,
+        controller_config.as_ref(),
+    )?;
+
+    let (pending_change_pda, pending_change_bump) =
+        find_pending_composition_change_address(program_id, index_account.key);
+
+    require!(
+        *pending_change_account.key == pending_change_pda,
+        ProtocolError::IncorrectPendingCompositionChangeAccount.into()
+    );
+
+    require!(
+        pending_change_account.data_is_empty(),
+        ProtocolError::CompositionChangeAlreadyQueued.into()
+    );
+
+    let queued_at = Clock::get()?.unix_timestamp;
+    let executable_at = queued_at
+        .checked_add(index.composition_delay)
+        .ok_or(ProgramError::ArithmeticOverflow)?;
+
+    let pending_change = PendingCompositionChange::new(
+        *index_account.key,
+        *signer.key,
+        mints,
+        amounts,
+        nested_components,
+        queued_at,
+        executable_at,
+        pending_change_bump,
+    );
+
+    let rent = Rent::get()?;
+    create_pda_account(
+        signer,
+        pending_change_account,
+        system_program_account,
+        program_id,
+        pending_change.packed_len(),
+        &rent,
+        &[
+            PENDING_COMPOSITION_CHANGE_SEED,
+            index_account.key.as_ref(),
+            &[pending_change_bump],
+        ],
+    )?;
+
+    pending_change.serialize(&mut &mut pending_change_account.data.borrow_mut()[..])?;
+
+    Ok(())
+}
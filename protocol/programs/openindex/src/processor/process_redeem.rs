@@ -6,7 +6,9 @@ use openindex_sdk::{
         error::ProtocolError,
         pda::{
             create_component_address, create_component_vault_address,
-            create_index_mints_data_address, find_index_mint_authority_address,
+            create_index_address_from_seed, create_index_mint_address_from_seed,
+            create_index_mint_authority_address_from_seed,
+            create_index_mints_data_address_from_seed,
         },
         seeds::COMPONENT_VAULT_SEED,
     },
@@ -17,17 +19,28 @@ use solana_program::{
     entrypoint::ProgramResult,
     program::{invoke, invoke_signed},
     program_error::ProgramError,
+    program_pack::{IsInitialized, Pack},
     pubkey::Pubkey,
+    system_program,
 };
-use spl_token::instruction::{burn, transfer};
+use spl_associated_token_account::instruction::create_associated_token_account_idempotent;
+use spl_token::instruction::{close_account, transfer};
+use spl_token_2022::{extension::StateWithExtensions, instruction::burn, state::Account};
 use crate::state::{Component, IndexMints};
+use crate::utils::{check_token_account_authority, find_index_token_program, load_index};
 
 /// instruction to process minting an index
+#[allow(clippy::too_many_arguments)]
 pub fn process_redeem(
     program_id: &Pubkey,
     accounts: &[AccountInfo],
     index_id: u64,
     amount: u64,
+    expected_version: Option<u64>,
+    min_amounts_out: Option<Vec<u64>>,
+    recipient: Option<Pubkey>,
+    create_destination_accounts: bool,
+    unwrap_native: bool,
 ) -> ProgramResult {
     let accounts_iter = &mut accounts.iter();
     let signer = next_account_info(accounts_iter)?;
@@ -59,13 +72,61 @@ pub fn process_redeem(
         ProtocolError::UnknownIndexAccount.into()
     );
 
+    let index = load_index(program_id, controller_account.key, index_account)?;
+
+    require!(
+        index.id == index_id,
+        ProtocolError::IncorrectIndexAccount.into()
+    );
+
+    if let Some(expected_version) = expected_version {
+        require!(
+            index.composition_version == expected_version,
+            ProtocolError::CompositionVersionMismatch.into()
+        );
+    }
+
+    let index_seed = index.address_seed();
+
+    require!(
+        index.is_initialized(),
+        ProtocolError::IndexNotInitialized.into()
+    );
+
+    let index_pda = create_index_address_from_seed(
+        program_id,
+        controller_account.key,
+        &index_seed,
+        index.bump,
+    )?;
+
+    require!(
+        *index_account.key == index_pda,
+        ProtocolError::IncorrectIndexAccount.into()
+    );
+
+    let mint_pda = create_index_mint_address_from_seed(
+        program_id,
+        controller_account.key,
+        &index_seed,
+        index.mint_bump,
+    )?;
+
+    require!(
+        *mint_account.key == mint_pda,
+        ProtocolError::IncorrectMintAccount.into()
+    );
+
+    let index_token_program_account =
+        find_index_token_program(mint_account, token_program_account, accounts)?;
+
     let index_mints_data = IndexMints::try_from_slice(&index_mints_account.data.borrow()[..])
         .map_err(|_| ProtocolError::InvalidIndexMintsAccountData)?;
 
-    let index_mints_pda = create_index_mints_data_address(
+    let index_mints_pda = create_index_mints_data_address_from_seed(
         program_id,
         controller_account.key,
-        index_id,
+        &index_seed,
         index_mints_data.bump,
     )?;
 
@@ -76,26 +137,82 @@ pub fn process_redeem(
 
     let mints = index_mints_data.mints;
 
-    let (mint_authority_pda, _) =
-        find_index_mint_authority_address(program_id, controller_account.key, index_id);
+    if let Some(min_amounts_out) = min_amounts_out.as_ref() {
+        require!(
+            min_amounts_out.len() == mints.len(),
+            ProtocolError::MintsAmountsLenMismatch.into()
+        );
+    }
+
+    let recipient = recipient.unwrap_or(*signer.key);
+
+    // unwrapping closes the destination, which only its owner can sign for
+    require!(
+        !unwrap_native || recipient == *signer.key,
+        ProtocolError::InvalidUnwrapRecipient.into()
+    );
+
+    // accounts for creating destinations follow the five accounts of every component
+    let creation_accounts = if create_destination_accounts {
+        let creation_accounts = accounts_iter
+            .as_slice()
+            .get(mints.len() * 5..)
+            .unwrap_or_default();
+        let [recipient_account, system_program_account, associated_token_program_account, ..] =
+            creation_accounts
+        else {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        };
+
+        require!(
+            *recipient_account.key == recipient,
+            ProtocolError::IncorrectRecipient.into()
+        );
+        require!(
+            *system_program_account.key == system_program::ID,
+            ProgramError::IncorrectProgramId
+        );
+        require!(
+            *associated_token_program_account.key == spl_associated_token_account::ID,
+            ProgramError::IncorrectProgramId
+        );
+
+        Some((
+            recipient_account,
+            system_program_account,
+            associated_token_program_account,
+        ))
+    } else {
+        None
+    };
+
+    let mint_authority_pda = create_index_mint_authority_address_from_seed(
+        program_id,
+        controller_account.key,
+        &index_seed,
+        index.mint_authority_bump,
+    )?;
 
     require!(
         *mint_authority_account.key == mint_authority_pda,
         ProtocolError::IncorrectMintAuthority.into()
     );
 
-    for  mint in mints.iter() {
+    let token_account_data =
+        StateWithExtensions::<Account>::unpack(&token_account.data.borrow())?.base;
+    require!(
+        *mint_account.key == token_account_data.mint,
+        ProtocolError::InvalidMintAccount.into()
+    );
+    check_token_account_authority(&token_account_data, signer.key, amount)?;
+
+    for (position, mint) in mints.iter().enumerate() {
         let component_mint_account = next_account_info(accounts_iter)?;
         let component_account = next_account_info(accounts_iter)?;
         let vault_pda = next_account_info(accounts_iter)?;
         let vault_ata = next_account_info(accounts_iter)?;
         let token_account = next_account_info(accounts_iter)?;
 
-        require!(
-            token_account.owner == token_program_account.key,
-            ProgramError::InvalidAccountOwner
-        );
-
         require!(
             component_mint_account.owner == token_program_account.key,
             ProgramError::IncorrectProgramId
@@ -106,7 +223,50 @@ pub fn process_redeem(
             ProtocolError::InvalidMintAccount.into()
         );
 
-        let component = Component::try_from_slice(&component_account.data.borrow_mut()[..])
+        let expected_token_account =
+            spl_associated_token_account::get_associated_token_address(&recipient, mint);
+        require!(
+            *token_account.key == expected_token_account,
+            ProtocolError::IncorrectDestinationAccount.into()
+        );
+
+        if let Some((
+            recipient_account,
+            system_program_account,
+            associated_token_program_account,
+        )) = creation_accounts
+        {
+            invoke(
+                &create_associated_token_account_idempotent(
+                    signer.key,
+                    &recipient,
+                    mint,
+                    token_program_account.key,
+                ),
+                &[
+                    signer.clone(),
+                    token_account.clone(),
+                    recipient_account.clone(),
+                    component_mint_account.clone(),
+                    system_program_account.clone(),
+                    token_program_account.clone(),
+                    associated_token_program_account.clone(),
+                ],
+            )?;
+        }
+
+        require!(
+            token_account.owner == token_program_account.key,
+            ProgramError::InvalidAccountOwner
+        );
+
+        let token_account_data = spl_token::state::Account::unpack(&token_account.data.borrow())?;
+        require!(
+            token_account_data.mint == *mint,
+            ProtocolError::InvalidTokenMint.into()
+        );
+
+        let component = Component::unpack(&component_account.data.borrow_mut()[..])
             .map_err(|_| ProtocolError::InvalidComponentData)?;
 
         let component_pda = create_component_address(
@@ -146,6 +306,13 @@ pub fn process_redeem(
             .checked_mul(component.uints)
             .ok_or(ProgramError::ArithmeticOverflow)?;
 
+        if let Some(min_amounts_out) = min_amounts_out.as_ref() {
+            require!(
+                component_amount >= min_amounts_out[position],
+                ProtocolError::MinAmountOutNotMet.into()
+            );
+        }
+
         invoke_signed(
             &transfer(
                 token_program_account.key,
@@ -168,11 +335,28 @@ pub fn process_redeem(
                 &[component.vault_bump],
             ]],
         )?;
+
+        if unwrap_native && *mint == spl_token::native_mint::ID {
+            invoke(
+                &close_account(
+                    token_program_account.key,
+                    token_account.key,
+                    signer.key,
+                    signer.key,
+                    &[],
+                )?,
+                &[
+                    token_account.clone(),
+                    signer.clone(),
+                    token_program_account.clone(),
+                ],
+            )?;
+        }
     }
 
     invoke(
         &burn(
-            token_program_account.key,
+            index_token_program_account.key,
             token_account.key,
             mint_account.key,
             &signer.key,
@@ -180,7 +364,7 @@ pub fn process_redeem(
             amount,
         )?,
         &[
-            token_program_account.clone(),
+            index_token_program_account.clone(),
             token_account.clone(),
             mint_account.clone(),
             signer.clone(),
@@ -0,0 +1,117 @@
+//! Program state processor
+
+use borsh::BorshDeserialize;
+use openindex_sdk::{openindex::error::ProtocolError, require};
+use solana_program::{
+    account_info::AccountInfo, entrypoint::ProgramResult, program_error::ProgramError,
+    pubkey::Pubkey,
+};
+
+use crate::processor::process_redeem;
+use crate::state::{Component, Index, IndexMints};
+
+/// Levels of nested indexes a single `RedeemUnderlying` unwinds.
+pub const MAX_NESTING_DEPTH: u8 = 4;
+
+/// instruction to process redeeming an index down to its base assets
+pub fn process_redeem_underlying(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    index_id: u64,
+    amount: u64,
+) -> ProgramResult {
+    redeem_underlying(program_id, accounts, index_id, amount, 0).map(|_| ())
+}
+
+/// Redeems `amount` index tokens and then, for every nested component in
+/// `mints` order, the underlying index tokens just received. Returns the
+/// number of accounts used, so the caller can find the next underlying
+/// index in its trailing accounts.
+fn redeem_underlying<'a>(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo<'a>],
+    index_id: u64,
+    amount: u64,
+    depth: u8,
+) -> Result<usize, ProgramError> {
+    require!(
+        depth < MAX_NESTING_DEPTH,
+        ProtocolError::MaxNestingDepthExceeded.into()
+    );
+
+    let [signer, _, _, _, _, index_mints_account, _, token_program_account, ..] = accounts else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+
+    let mints_len = IndexMints::try_from_slice(&index_mints_account.data.borrow()[..])
+        .map_err(|_| ProtocolError::InvalidIndexMintsAccountData)?
+        .mints
+        .len();
+    let redeem_accounts_len = 8 + mints_len * 5;
+    let redeem_accounts = accounts
+        .get(..redeem_accounts_len)
+        .ok_or(ProgramError::NotEnoughAccountKeys)?;
+
+    // validates every account of this level, including the index mints
+    // account read above
+    process_redeem(
+        program_id,
+        redeem_accounts,
+        index_id,
+        amount,
+        None,
+        None,
+        None,
+        false,
+        false,
+    )?;
+
+    let mut used = redeem_accounts_len;
+    for bundle in redeem_accounts[8..].chunks(5) {
+        let component = Component::unpack(&bundle[1].data.borrow())
+            .map_err(|_| ProtocolError::InvalidComponentData)?;
+        if !component.is_nested() {
+            continue;
+        }
+
+        // the underlying index follows with its Redeem accounts minus the
+        // signer, token account and token program, which are shared
+        let trailing_accounts = accounts.get(used..).unwrap_or_default();
+        let underlying_index_accounts = trailing_accounts
+            .get(..5)
+            .ok_or(ProgramError::NotEnoughAccountKeys)?;
+        let underlying_index_account = &underlying_index_accounts[3];
+
+        require!(
+            *underlying_index_account.key == component.underlying_index,
+            ProtocolError::IncorrectUnderlyingIndexAccount.into()
+        );
+        require!(
+            underlying_index_account.owner == program_id,
+            ProtocolError::UnknownIndexAccount.into()
+        );
+        let underlying_index_id = Index::unpack(&underlying_index_account.data.borrow())?.id;
+
+        let underlying_amount = amount
+            .checked_mul(component.uints)
+            .ok_or(ProgramError::ArithmeticOverflow)?;
+
+        // the destination of the nested component is the signer's token
+        // account for the underlying index mint
+        let mut underlying_accounts = vec![signer.clone()];
+        underlying_accounts.extend_from_slice(underlying_index_accounts);
+        underlying_accounts.extend([bundle[4].clone(), token_program_account.clone()]);
+        underlying_accounts.extend_from_slice(&trailing_accounts[5..]);
+
+        let underlying_used = redeem_underlying(
+            program_id,
+            &underlying_accounts,
+            underlying_index_id,
+            underlying_amount,
+            depth + 1,
+        )?;
+        used += underlying_used - 3;
+    }
+
+    Ok(used)
+}
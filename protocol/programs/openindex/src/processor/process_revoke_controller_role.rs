@@ -0,0 +1,74 @@
+//! Program state processor
+
+use crate::state::Controller;
+use crate::utils::{check_owner, load_controller_roles};
+use borsh::{BorshDeserialize, BorshSerialize};
+use openindex_sdk::{
+    openindex::{error::ProtocolError, instruction::ControllerRole},
+    require,
+};
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    rent::Rent,
+    sysvar::Sysvar,
+};
+
+/// instruction to process revoking a controller role
+pub fn process_revoke_controller_role(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    role: ControllerRole,
+) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let signer = next_account_info(accounts_iter)?;
+    let controller_account = next_account_info(accounts_iter)?;
+    let holder = next_account_info(accounts_iter)?;
+    let controller_roles_account = next_account_info(accounts_iter)?;
+
+    require!(signer.is_signer, ProgramError::MissingRequiredSignature);
+
+    require!(
+        controller_account.owner == program_id,
+        ProtocolError::UnknownControllerAccount.into()
+    );
+
+    let controller = Controller::try_from_slice(&controller_account.data.borrow())?;
+    check_owner(
+        program_id,
+        &controller.owner,
+        signer,
+        accounts_iter.as_slice(),
+        ProtocolError::OnlyControllerOwner,
+    )?;
+
+    let mut controller_roles =
+        load_controller_roles(program_id, controller_account, controller_roles_account)?
+            .ok_or(ProtocolError::ControllerRoleNotGranted)?;
+
+    require!(
+        controller_roles.revoke(holder.key, role),
+        ProtocolError::ControllerRoleNotGranted.into()
+    );
+
+    // shrink the registry and return the rent it no longer needs
+    let space = controller_roles.packed_len();
+    controller_roles_account.realloc(space, false)?;
+
+    let surplus_lamports = controller_roles_account
+        .lamports()
+        .saturating_sub(Rent::get()?.minimum_balance(space));
+    if surplus_lamports > 0 {
+        **controller_roles_account.lamports.borrow_mut() -= surplus_lamports;
+        let signer_lamports = signer.lamports();
+        **signer.lamports.borrow_mut() = signer_lamports
+            .checked_add(surplus_lamports)
+            .ok_or(ProgramError::ArithmeticOverflow)?;
+    }
+
+    controller_roles.serialize(&mut &mut controller_roles_account.data.borrow_mut()[..])?;
+
+    Ok(())
+}
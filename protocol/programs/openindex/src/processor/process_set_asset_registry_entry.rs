@@ -0,0 +1,125 @@
+//! Program state processor
+
+use crate::state::{AssetRegistryEntry, Protocol};
+use crate::utils::{check_owner, create_pda_account};
+use borsh::{BorshDeserialize, BorshSerialize};
+use openindex_sdk::{
+    openindex::{
+        error::ProtocolError,
+        instruction::ASSET_RISK_ALL,
+        pda::{create_protocol_address, find_asset_registry_address},
+        seeds::ASSET_REGISTRY_SEED,
+    },
+    require,
+};
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    program_error::ProgramError,
+    program_pack::IsInitialized,
+    pubkey::Pubkey,
+    rent::Rent,
+    sysvar::Sysvar,
+};
+
+/// instruction to process approving, updating or removing an asset registry entry
+pub fn process_set_asset_registry_entry(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    approved: bool,
+    risk_tier: u8,
+    accepted_risks: u8,
+) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let signer = next_account_info(accounts_iter)?;
+    let protocol_account = next_account_info(accounts_iter)?;
+    let mint_account = next_account_info(accounts_iter)?;
+    let asset_registry_account = next_account_info(accounts_iter)?;
+    let system_program_account = next_account_info(accounts_iter)?;
+
+    require!(signer.is_signer, ProgramError::MissingRequiredSignature);
+
+    require!(
+        accepted_risks & !ASSET_RISK_ALL == 0,
+        ProtocolError::InvalidAssetRisks.into()
+    );
+
+    require!(
+        protocol_account.owner == program_id,
+        ProtocolError::UnknownProtocolAccount.into()
+    );
+
+    let protocol: Protocol = Protocol::try_from_slice(&protocol_account.data.borrow())
+        .map_err(|_| ProtocolError::InvalidProtocolAccountData)?;
+
+    require!(
+        protocol.is_initialized(),
+        ProtocolError::ProtocolNotInitialized.into()
+    );
+
+    let protocol_pda = create_protocol_address(program_id, protocol.bump)?;
+
+    require!(
+        *protocol_account.key == protocol_pda,
+        ProtocolError::IncorrectProtocolAccount.into()
+    );
+
+    check_owner(
+        program_id,
+        &protocol.owner,
+        signer,
+        accounts_iter.as_slice(),
+        ProtocolError::OnlyProtocolOwner,
+    )?;
+
+    let (asset_registry_pda, asset_registry_bump) =
+        find_asset_registry_address(program_id, mint_account.key);
+
+    require!(
+        *asset_registry_account.key == asset_registry_pda,
+        ProtocolError::IncorrectAssetRegistryAccount.into()
+    );
+
+    let is_registered =
+        asset_registry_account.owner == program_id && !asset_registry_account.data_is_empty();
+
+    if approved {
+        if !is_registered {
+            let rent = Rent::get()?;
+
+            create_pda_account(
+                signer,
+                asset_registry_account,
+                system_program_account,
+                program_id,
+                AssetRegistryEntry::LEN,
+                &rent,
+                &[
+                    ASSET_REGISTRY_SEED,
+                    mint_account.key.as_ref(),
+                    &[asset_registry_bump],
+                ],
+            )?;
+        }
+
+        let asset_registry_entry = AssetRegistryEntry::new(
+            *mint_account.key,
+            risk_tier,
+            accepted_risks,
+            asset_registry_bump,
+        );
+        asset_registry_entry.serialize(&mut &mut asset_registry_account.data.borrow_mut()[..])?;
+    } else {
+        require!(is_registered, ProgramError::UninitializedAccount);
+
+        // close the registry entry and return its rent to the signer
+        let signer_lamports = signer.lamports();
+        **signer.lamports.borrow_mut() = signer_lamports
+            .checked_add(asset_registry_account.lamports())
+            .ok_or(ProgramError::ArithmeticOverflow)?;
+        **asset_registry_account.lamports.borrow_mut() = 0;
+        asset_registry_account.data.borrow_mut().fill(0);
+    }
+
+    Ok(())
+}
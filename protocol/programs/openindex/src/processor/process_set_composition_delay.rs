@@ -0,0 +1,87 @@
+//! Program state processor
+
+use crate::state::Index;
+use crate::utils::{check_owner, load_index, realloc_account};
+use borsh::BorshSerialize;
+use openindex_sdk::{
+    openindex::{error::ProtocolError, pda::create_index_address_from_seed},
+    require,
+};
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    program_error::ProgramError,
+    program_pack::IsInitialized,
+    pubkey::Pubkey,
+    rent::Rent,
+    sysvar::Sysvar,
+};
+
+/// instruction to process setting the composition change delay of an index
+pub fn process_set_composition_delay(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    delay: i64,
+) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let signer = next_account_info(accounts_iter)?;
+    let index_account = next_account_info(accounts_iter)?;
+    let controller_account = next_account_info(accounts_iter)?;
+    let system_program_account = next_account_info(accounts_iter)?;
+
+    require!(signer.is_signer, ProgramError::MissingRequiredSignature);
+
+    require!(
+        index_account.owner == program_id,
+        ProtocolError::UnknownIndexAccount.into()
+    );
+
+    let mut index = load_index(program_id, controller_account.key, index_account)?;
+
+    require!(
+        index.is_initialized(),
+        ProtocolError::IndexNotInitialized.into()
+    );
+
+    let index_pda = create_index_address_from_seed(
+        program_id,
+        controller_account.key,
+        &index.address_seed(),
+        index.bump,
+    )?;
+
+    require!(
+        *index_account.key == index_pda,
+        ProtocolError::IncorrectIndexAccount.into()
+    );
+
+    check_owner(
+        program_id,
+        &index.owner,
+        signer,
+        accounts_iter.as_slice(),
+        ProtocolError::OnlyIndexOwner,
+    )?;
+
+    require!(delay >= 0, ProtocolError::InvalidCompositionDelay.into());
+
+    require!(
+        delay >= index.composition_delay,
+        ProtocolError::CompositionDelayDecreased.into()
+    );
+
+    index.composition_delay = delay;
+
+    let rent = Rent::get()?;
+    realloc_account(
+        signer,
+        index_account,
+        system_program_account,
+        Index::LEN,
+        &rent,
+    )?;
+
+    index.serialize(&mut &mut index_account.data.borrow_mut()[..])?;
+
+    Ok(())
+}
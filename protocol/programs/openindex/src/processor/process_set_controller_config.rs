@@ -0,0 +1,119 @@
+//! Program state processor
+
+use crate::state::{Controller, ControllerConfig};
+use crate::utils::{
+    check_owner, create_pda_account, load_controller_config, realloc_account, MAX_FEE_BPS,
+};
+use borsh::{BorshDeserialize, BorshSerialize};
+use openindex_sdk::{
+    openindex::{
+        error::ProtocolError, pda::find_controller_config_address, seeds::CONTROLLER_CONFIG_SEED,
+    },
+    require,
+};
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    rent::Rent,
+    sysvar::Sysvar,
+};
+
+/// instruction to process creating or updating a controller config
+#[allow(clippy::too_many_arguments)]
+pub fn process_set_controller_config(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    max_index_components: Option<u32>,
+    max_mint_fee_bps: u16,
+    max_redeem_fee_bps: u16,
+    default_decimals: Option<u8>,
+    allowed_mints: Vec<Pubkey>,
+    override_asset_checks: bool,
+) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let signer = next_account_info(accounts_iter)?;
+    let controller_account = next_account_info(accounts_iter)?;
+    let controller_config_account = next_account_info(accounts_iter)?;
+    let system_program_account = next_account_info(accounts_iter)?;
+
+    require!(signer.is_signer, ProgramError::MissingRequiredSignature);
+
+    require!(
+        max_index_components != Some(0),
+        ProtocolError::InvalidMaxIndexComponents.into()
+    );
+
+    require!(
+        max_mint_fee_bps <= MAX_FEE_BPS && max_redeem_fee_bps <= MAX_FEE_BPS,
+        ProtocolError::InvalidFeeBps.into()
+    );
+
+    require!(
+        controller_account.owner == program_id,
+        ProtocolError::UnknownControllerAccount.into()
+    );
+
+    let controller = Controller::try_from_slice(&controller_account.data.borrow())?;
+    check_owner(
+        program_id,
+        &controller.owner,
+        signer,
+        accounts_iter.as_slice(),
+        ProtocolError::OnlyControllerOwner,
+    )?;
+
+    let existing_config =
+        load_controller_config(program_id, controller_account, controller_config_account)?;
+
+    let bump = match existing_config.as_ref() {
+        Some(existing_config) => existing_config.bump,
+        None => find_controller_config_address(program_id, controller_account.key).1,
+    };
+
+    let controller_config = ControllerConfig::new(
+        max_index_components,
+        max_mint_fee_bps,
+        max_redeem_fee_bps,
+        default_decimals,
+        allowed_mints,
+        override_asset_checks,
+        bump,
+    );
+
+    let rent = Rent::get()?;
+    let space = controller_config.packed_len();
+
+    if existing_config.is_some() {
+        realloc_account(
+            signer,
+            controller_config_account,
+            system_program_account,
+            space,
+            &rent,
+        )?;
+        // a smaller config leaves trailing bytes borsh would reject
+        if controller_config_account.data_len() > space {
+            controller_config_account.realloc(space, false)?;
+        }
+    } else {
+        create_pda_account(
+            signer,
+            controller_config_account,
+            system_program_account,
+            program_id,
+            space,
+            &rent,
+            &[
+                CONTROLLER_CONFIG_SEED,
+                controller_account.key.as_ref(),
+                &[bump],
+            ],
+        )?;
+    }
+
+    controller_config.serialize(&mut &mut controller_config_account.data.borrow_mut()[..])?;
+
+    Ok(())
+}
@@ -0,0 +1,110 @@
+//! Program state processor
+
+use crate::state::{ControllerCreator, Protocol};
+use crate::utils::{check_owner, create_pda_account};
+use borsh::{BorshDeserialize, BorshSerialize};
+use openindex_sdk::{
+    openindex::{
+        error::ProtocolError,
+        pda::{create_protocol_address, find_controller_creator_address},
+        seeds::CONTROLLER_CREATOR_SEED,
+    },
+    require,
+};
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    program_error::ProgramError,
+    program_pack::IsInitialized,
+    pubkey::Pubkey,
+    rent::Rent,
+    sysvar::Sysvar,
+};
+
+/// instruction to process approving or revoking a controller creator
+pub fn process_set_controller_creator(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    allowed: bool,
+) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let signer = next_account_info(accounts_iter)?;
+    let protocol_account = next_account_info(accounts_iter)?;
+    let creator = next_account_info(accounts_iter)?;
+    let controller_creator_account = next_account_info(accounts_iter)?;
+    let system_program_account = next_account_info(accounts_iter)?;
+
+    require!(signer.is_signer, ProgramError::MissingRequiredSignature);
+
+    require!(
+        protocol_account.owner == program_id,
+        ProtocolError::UnknownProtocolAccount.into()
+    );
+
+    let protocol: Protocol = Protocol::try_from_slice(&protocol_account.data.borrow())
+        .map_err(|_| ProtocolError::InvalidProtocolAccountData)?;
+
+    require!(
+        protocol.is_initialized(),
+        ProtocolError::ProtocolNotInitialized.into()
+    );
+
+    let protocol_pda = create_protocol_address(program_id, protocol.bump)?;
+
+    require!(
+        *protocol_account.key == protocol_pda,
+        ProtocolError::IncorrectProtocolAccount.into()
+    );
+
+    check_owner(
+        program_id,
+        &protocol.owner,
+        signer,
+        accounts_iter.as_slice(),
+        ProtocolError::OnlyProtocolOwner,
+    )?;
+
+    let (controller_creator_pda, controller_creator_bump) =
+        find_controller_creator_address(program_id, creator.key);
+
+    require!(
+        *controller_creator_account.key == controller_creator_pda,
+        ProtocolError::IncorrectControllerCreatorAccount.into()
+    );
+
+    if allowed {
+        let rent = Rent::get()?;
+
+        create_pda_account(
+            signer,
+            controller_creator_account,
+            system_program_account,
+            program_id,
+            ControllerCreator::LEN,
+            &rent,
+            &[
+                CONTROLLER_CREATOR_SEED,
+                creator.key.as_ref(),
+                &[controller_creator_bump],
+            ],
+        )?;
+
+        let controller_creator = ControllerCreator::new(*creator.key, controller_creator_bump);
+        controller_creator.serialize(&mut &mut controller_creator_account.data.borrow_mut()[..])?;
+    } else {
+        require!(
+            controller_creator_account.owner == program_id,
+            ProgramError::UninitializedAccount
+        );
+
+        // close the allowlist entry and return its rent to the signer
+        let signer_lamports = signer.lamports();
+        **signer.lamports.borrow_mut() = signer_lamports
+            .checked_add(controller_creator_account.lamports())
+            .ok_or(ProgramError::ArithmeticOverflow)?;
+        **controller_creator_account.lamports.borrow_mut() = 0;
+        controller_creator_account.data.borrow_mut().fill(0);
+    }
+
+    Ok(())
+}
@@ -0,0 +1,152 @@
+//! Program state processor
+
+use crate::state::TransferAllowlist;
+use crate::utils::{check_index_compliance_authority, create_pda_account, load_index};
+use borsh::BorshSerialize;
+use openindex_sdk::{
+    openindex::{
+        error::ProtocolError,
+        pda::{
+            create_index_address_from_seed, create_index_mint_address_from_seed,
+            find_transfer_allowlist_address,
+        },
+        seeds::TRANSFER_ALLOWLIST_SEED,
+    },
+    require,
+};
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    program_error::ProgramError,
+    program_pack::IsInitialized,
+    pubkey::Pubkey,
+    rent::Rent,
+    sysvar::Sysvar,
+};
+
+/// instruction to process adding a wallet to, or removing it from, the transfer allowlist
+pub fn process_set_transfer_allowlist(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    index_id: u64,
+    wallet: Pubkey,
+    allowed: bool,
+) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let signer = next_account_info(accounts_iter)?;
+    let controller_account = next_account_info(accounts_iter)?;
+    let index_account = next_account_info(accounts_iter)?;
+    let mint_account = next_account_info(accounts_iter)?;
+    let transfer_allowlist_account = next_account_info(accounts_iter)?;
+    let system_program_account = next_account_info(accounts_iter)?;
+    let controller_roles_account = next_account_info(accounts_iter)?;
+
+    require!(signer.is_signer, ProgramError::MissingRequiredSignature);
+
+    require!(
+        controller_account.owner == program_id,
+        ProtocolError::UnknownControllerAccount.into()
+    );
+
+    require!(
+        index_account.owner == program_id,
+        ProtocolError::UnknownIndexAccount.into()
+    );
+
+    let index = load_index(program_id, controller_account.key, index_account)?;
+
+    require!(
+        index.id == index_id,
+        ProtocolError::IncorrectIndexAccount.into()
+    );
+
+    require!(
+        index.is_initialized(),
+        ProtocolError::IndexNotInitialized.into()
+    );
+
+    let index_seed = index.address_seed();
+
+    let index_pda = create_index_address_from_seed(
+        program_id,
+        controller_account.key,
+        &index_seed,
+        index.bump,
+    )?;
+
+    require!(
+        *index_account.key == index_pda,
+        ProtocolError::IncorrectIndexAccount.into()
+    );
+
+    check_index_compliance_authority(
+        program_id,
+        signer,
+        &index,
+        controller_account,
+        controller_roles_account,
+        accounts_iter.as_slice(),
+    )?;
+
+    require!(
+        index.has_transfer_hook(),
+        ProtocolError::TransferHookNotEnabled.into()
+    );
+
+    let mint_pda = create_index_mint_address_from_seed(
+        program_id,
+        controller_account.key,
+        &index_seed,
+        index.mint_bump,
+    )?;
+
+    require!(
+        *mint_account.key == mint_pda,
+        ProtocolError::IncorrectMintAccount.into()
+    );
+
+    // the transfer hook derives the entry from the mint and the token
+    // account owner
+    let (transfer_allowlist_pda, transfer_allowlist_bump) =
+        find_transfer_allowlist_address(program_id, mint_account.key, &wallet);
+
+    require!(
+        *transfer_allowlist_account.key == transfer_allowlist_pda,
+        ProtocolError::IncorrectTransferAllowlistAccount.into()
+    );
+
+    let is_allowed =
+        transfer_allowlist_account.owner == program_id && !transfer_allowlist_account.data_is_empty();
+
+    if allowed && !is_allowed {
+        let rent = Rent::get()?;
+        create_pda_account(
+            signer,
+            transfer_allowlist_account,
+            system_program_account,
+            program_id,
+            TransferAllowlist::LEN,
+            &rent,
+            &[
+                TRANSFER_ALLOWLIST_SEED,
+                mint_account.key.as_ref(),
+                wallet.as_ref(),
+                &[transfer_allowlist_bump],
+            ],
+        )?;
+
+        let transfer_allowlist =
+            TransferAllowlist::new(*index_account.key, wallet, transfer_allowlist_bump);
+        transfer_allowlist.serialize(&mut &mut transfer_allowlist_account.data.borrow_mut()[..])?;
+    } else if !allowed && is_allowed {
+        // close the entry and return its rent to the signer
+        let signer_lamports = signer.lamports();
+        **signer.lamports.borrow_mut() = signer_lamports
+            .checked_add(transfer_allowlist_account.lamports())
+            .ok_or(ProgramError::ArithmeticOverflow)?;
+        **transfer_allowlist_account.lamports.borrow_mut() = 0;
+        transfer_allowlist_account.data.borrow_mut().fill(0);
+    }
+
+    Ok(())
+}
@@ -0,0 +1,218 @@
+//! Program state processor
+
+use crate::state::{Component, FlashMint, IndexMints};
+use crate::utils::{check_token_account_authority, load_index};
+use borsh::BorshDeserialize;
+use openindex_sdk::{
+    openindex::{
+        error::ProtocolError,
+        pda::{
+            create_component_address, create_component_vault_address, create_flash_mint_address,
+            create_index_address_from_seed, create_index_mints_data_address_from_seed,
+        },
+    },
+    require,
+};
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    program::invoke,
+    program_error::ProgramError,
+    program_pack::IsInitialized,
+    pubkey::Pubkey,
+};
+use spl_token::instruction::transfer;
+use spl_token_2022::{extension::StateWithExtensions, state::Account};
+
+/// instruction to process depositing the components owed for a flash mint
+pub fn process_settle_flash_mint(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    index_id: u64,
+) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let signer = next_account_info(accounts_iter)?;
+    let controller_account = next_account_info(accounts_iter)?;
+    let index_account = next_account_info(accounts_iter)?;
+    let index_mints_account = next_account_info(accounts_iter)?;
+    let flash_mint_account = next_account_info(accounts_iter)?;
+    let token_program_account = next_account_info(accounts_iter)?;
+
+    require!(signer.is_signer, ProgramError::MissingRequiredSignature);
+
+    require!(
+        controller_account.owner == program_id,
+        ProtocolError::UnknownControllerAccount.into()
+    );
+
+    require!(
+        index_account.owner == program_id,
+        ProtocolError::UnknownIndexAccount.into()
+    );
+
+    require!(
+        flash_mint_account.owner == program_id,
+        ProtocolError::IncorrectFlashMintAccount.into()
+    );
+
+    require!(
+        *token_program_account.key == spl_token::ID,
+        ProgramError::IncorrectProgramId
+    );
+
+    let index = load_index(program_id, controller_account.key, index_account)?;
+
+    require!(
+        index.id == index_id,
+        ProtocolError::IncorrectIndexAccount.into()
+    );
+
+    let index_seed = index.address_seed();
+
+    let index_pda = create_index_address_from_seed(
+        program_id,
+        controller_account.key,
+        &index_seed,
+        index.bump,
+    )?;
+
+    require!(
+        *index_account.key == index_pda,
+        ProtocolError::IncorrectIndexAccount.into()
+    );
+
+    let index_mints_pda = create_index_mints_data_address_from_seed(
+        program_id,
+        controller_account.key,
+        &index_seed,
+        index.index_mints_bump,
+    )?;
+
+    require!(
+        *index_mints_account.key == index_mints_pda,
+        ProtocolError::IncorrectIndexMintsAccount.into()
+    );
+
+    let index_mints_data = IndexMints::try_from_slice(&index_mints_account.data.borrow())
+        .map_err(|_| ProtocolError::InvalidIndexMintsAccountData)?;
+
+    require!(
+        !index_mints_data.mints.is_empty(),
+        ProtocolError::NoMintsProvided.into()
+    );
+
+    let flash_mint = FlashMint::try_from_slice(&flash_mint_account.data.borrow())
+        .map_err(|_| ProtocolError::InvalidFlashMintAccountData)?;
+
+    require!(
+        flash_mint.is_initialized() && flash_mint.index == *index_account.key,
+        ProtocolError::InvalidFlashMintAccountData.into()
+    );
+
+    // the bump is only valid for the flash minter's own obligation
+    let flash_mint_pda =
+        create_flash_mint_address(program_id, index_account.key, signer.key, flash_mint.bump)?;
+
+    require!(
+        *flash_mint_account.key == flash_mint_pda,
+        ProtocolError::IncorrectFlashMintAccount.into()
+    );
+
+    require!(
+        index.composition_version == flash_mint.composition_version,
+        ProtocolError::CompositionVersionMismatch.into()
+    );
+
+    for mint in index_mints_data.mints.iter() {
+        let component_mint_account = next_account_info(accounts_iter)?;
+        let component_account = next_account_info(accounts_iter)?;
+        let vault_pda = next_account_info(accounts_iter)?;
+        let vault_ata = next_account_info(accounts_iter)?;
+        let component_token_account = next_account_info(accounts_iter)?;
+
+        require!(
+            component_mint_account.key == mint,
+            ProtocolError::InvalidMintAccount.into()
+        );
+
+        let component = Component::unpack(&component_account.data.borrow())
+            .map_err(|_| ProtocolError::InvalidComponentData)?;
+
+        let component_pda = create_component_address(
+            program_id,
+            index_account.key,
+            component_mint_account.key,
+            component.bump,
+        )?;
+
+        require!(
+            *component_account.key == component_pda,
+            ProtocolError::IncorrectComponentAccount.into()
+        );
+
+        let expected_vault_pda = create_component_vault_address(
+            program_id,
+            index_account.key,
+            component_mint_account.key,
+            component.vault_bump,
+        )?;
+
+        require!(
+            *vault_pda.key == expected_vault_pda,
+            ProtocolError::IncorrectVaultAccount.into()
+        );
+
+        let expected_vault_ata = spl_associated_token_account::get_associated_token_address(
+            vault_pda.key,
+            component_mint_account.key,
+        );
+        require!(
+            *vault_ata.key == expected_vault_ata,
+            ProtocolError::IncorrectVaultATA.into()
+        );
+
+        let amount_due = flash_mint
+            .amount_due(component.uints)
+            .ok_or(ProgramError::ArithmeticOverflow)?;
+
+        require!(
+            component_token_account.owner == token_program_account.key,
+            ProgramError::InvalidAccountOwner
+        );
+
+        let component_token_account_data =
+            StateWithExtensions::<Account>::unpack(&component_token_account.data.borrow())?.base;
+        require!(
+            component_token_account_data.mint == *mint,
+            ProtocolError::InvalidTokenMint.into()
+        );
+        check_token_account_authority(&component_token_account_data, signer.key, amount_due)?;
+
+        invoke(
+            &transfer(
+                token_program_account.key,
+                component_token_account.key,
+                vault_ata.key,
+                signer.key,
+                &[],
+                amount_due,
+            )?,
+            &[
+                signer.clone(),
+                component_token_account.clone(),
+                vault_ata.clone(),
+                token_program_account.clone(),
+            ],
+        )?;
+    }
+
+    // close the obligation and return its rent to the flash minter
+    let signer_lamports = signer.lamports();
+    **signer.lamports.borrow_mut() = signer_lamports
+        .checked_add(flash_mint_account.lamports())
+        .ok_or(ProgramError::ArithmeticOverflow)?;
+    **flash_mint_account.lamports.borrow_mut() = 0;
+    flash_mint_account.data.borrow_mut().fill(0);
+
+    Ok(())
+}
@@ -0,0 +1,14 @@
+//! Program state processor
+
+use solana_program::{account_info::AccountInfo, entrypoint::ProgramResult, pubkey::Pubkey};
+
+use crate::processor::set_holder_frozen;
+
+/// instruction to process thawing a holder's index token account
+pub fn process_thaw_holder(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    index_id: u64,
+) -> ProgramResult {
+    set_holder_frozen(program_id, accounts, index_id, false)
+}
@@ -0,0 +1,87 @@
+//! Program state processor
+
+use crate::state::{AccountType, Controller, Index, Protocol};
+use crate::utils::check_owner;
+use borsh::{BorshDeserialize, BorshSerialize};
+use openindex_sdk::{openindex::error::ProtocolError, require};
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    program_error::ProgramError,
+    program_pack::IsInitialized,
+    pubkey::Pubkey,
+};
+
+/// instruction to process transferring ownership of a protocol, controller or index
+pub fn process_transfer_ownership(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    new_owner: Pubkey,
+) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let signer = next_account_info(accounts_iter)?;
+    let target_account = next_account_info(accounts_iter)?;
+    let authority_accounts = accounts_iter.as_slice();
+
+    require!(signer.is_signer, ProgramError::MissingRequiredSignature);
+
+    require!(
+        target_account.owner == program_id,
+        ProtocolError::UnsupportedOwnershipTransfer.into()
+    );
+
+    let account_type = AccountType::deserialize(&mut &target_account.data.borrow()[..])
+        .map_err(|_| ProtocolError::UnsupportedOwnershipTransfer)?;
+
+    match account_type {
+        AccountType::Protocol => {
+            let mut protocol = Protocol::try_from_slice(&target_account.data.borrow())
+                .map_err(|_| ProtocolError::InvalidProtocolAccountData)?;
+            require!(
+                protocol.is_initialized(),
+                ProtocolError::ProtocolNotInitialized.into()
+            );
+            check_owner(
+                program_id,
+                &protocol.owner,
+                signer,
+                authority_accounts,
+                ProtocolError::OnlyProtocolOwner,
+            )?;
+            protocol.owner = new_owner;
+            protocol.serialize(&mut &mut target_account.data.borrow_mut()[..])?;
+        }
+        AccountType::Controller => {
+            let mut controller = Controller::try_from_slice(&target_account.data.borrow())?;
+            check_owner(
+                program_id,
+                &controller.owner,
+                signer,
+                authority_accounts,
+                ProtocolError::OnlyControllerOwner,
+            )?;
+            controller.owner = new_owner;
+            controller.serialize(&mut &mut target_account.data.borrow_mut()[..])?;
+        }
+        AccountType::Index => {
+            let mut index = Index::unpack(&target_account.data.borrow())?;
+            check_owner(
+                program_id,
+                &index.owner,
+                signer,
+                authority_accounts,
+                ProtocolError::OnlyIndexOwner,
+            )?;
+            index.owner = new_owner;
+
+            // legacy index accounts are shorter and lack the zeroed `seed`
+            let data = borsh::to_vec(&index)?;
+            let mut target_data = target_account.data.borrow_mut();
+            let len = target_data.len().min(data.len());
+            target_data[..len].copy_from_slice(&data[..len]);
+        }
+        _ => return Err(ProtocolError::UnsupportedOwnershipTransfer.into()),
+    }
+
+    Ok(())
+}
@@ -0,0 +1,167 @@
+//! Program state processor
+
+use crate::state::{ControllerGlobalConfig, Protocol};
+use crate::utils::{
+    check_owner, check_treasury_account, create_pda_account, realloc_account, MAX_FEE_BPS,
+};
+use borsh::{BorshDeserialize, BorshSerialize};
+use openindex_sdk::{
+    openindex::{
+        error::ProtocolError,
+        instruction::ControllerCreationPolicy,
+        pda::{create_controller_global_config_address, create_protocol_address},
+        seeds::TREASURY_SEED,
+    },
+    require,
+};
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    program_error::ProgramError,
+    program_pack::IsInitialized,
+    pubkey::Pubkey,
+    rent::Rent,
+    sysvar::Sysvar,
+};
+
+/// instruction to process updating the controller global config
+#[allow(clippy::too_many_arguments)]
+pub fn process_update_controller_global_config(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    max_index_components: Option<u32>,
+    check_existing_indexes: bool,
+    controller_creation_policy: Option<ControllerCreationPolicy>,
+    controller_creation_fee: Option<u64>,
+    index_creation_fee: Option<u64>,
+    flash_mint_fee_bps: Option<u16>,
+    asset_registry_required: Option<bool>,
+) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let signer = next_account_info(accounts_iter)?;
+    let protocol_account = next_account_info(accounts_iter)?;
+    let controller_global_config_account = next_account_info(accounts_iter)?;
+    let system_program_account = next_account_info(accounts_iter)?;
+    let treasury_account = next_account_info(accounts_iter)?;
+
+    require!(signer.is_signer, ProgramError::MissingRequiredSignature);
+
+    require!(
+        protocol_account.owner == program_id,
+        ProtocolError::UnknownProtocolAccount.into()
+    );
+
+    let protocol: Protocol = Protocol::try_from_slice(&protocol_account.data.borrow())
+        .map_err(|_| ProtocolError::InvalidProtocolAccountData)?;
+
+    require!(
+        protocol.is_initialized(),
+        ProtocolError::ProtocolNotInitialized.into()
+    );
+
+    let protocol_pda = create_protocol_address(program_id, protocol.bump)?;
+
+    require!(
+        *protocol_account.key == protocol_pda,
+        ProtocolError::IncorrectProtocolAccount.into()
+    );
+
+    check_owner(
+        program_id,
+        &protocol.owner,
+        signer,
+        accounts_iter.as_slice(),
+        ProtocolError::OnlyProtocolOwner,
+    )?;
+
+    require!(
+        controller_global_config_account.owner == program_id,
+        ProtocolError::UnknownControllerGlobalConfigAccount.into()
+    );
+
+    let mut controller_global_config =
+        ControllerGlobalConfig::unpack(&controller_global_config_account.data.borrow())?;
+
+    require!(
+        controller_global_config.is_initialized(),
+        ProtocolError::ControllerGlobalConfigNotInitialized.into()
+    );
+
+    let controller_global_config_pda =
+        create_controller_global_config_address(program_id, controller_global_config.bump)?;
+
+    require!(
+        *controller_global_config_account.key == controller_global_config_pda,
+        ProtocolError::IncorrectControllerGlobalConfigAccount.into()
+    );
+
+    if let Some(max_index_components) = max_index_components {
+        require!(
+            max_index_components > 0,
+            ProtocolError::InvalidMaxIndexComponents.into()
+        );
+
+        if check_existing_indexes {
+            require!(
+                max_index_components >= controller_global_config.largest_index_components,
+                ProtocolError::MaxIndexComponentsBelowLargestIndex.into()
+            );
+        }
+
+        controller_global_config.max_index_components = max_index_components;
+    }
+
+    if let Some(controller_creation_policy) = controller_creation_policy {
+        controller_global_config.controller_creation_policy = controller_creation_policy;
+    }
+
+    if let Some(controller_creation_fee) = controller_creation_fee {
+        controller_global_config.controller_creation_fee = controller_creation_fee;
+    }
+
+    if let Some(index_creation_fee) = index_creation_fee {
+        controller_global_config.index_creation_fee = index_creation_fee;
+    }
+
+    if let Some(flash_mint_fee_bps) = flash_mint_fee_bps {
+        require!(
+            flash_mint_fee_bps <= MAX_FEE_BPS,
+            ProtocolError::InvalidFeeBps.into()
+        );
+        controller_global_config.flash_mint_fee_bps = flash_mint_fee_bps;
+    }
+
+    if let Some(asset_registry_required) = asset_registry_required {
+        controller_global_config.asset_registry_required = asset_registry_required;
+    }
+
+    // accounts created before new fields were appended are grown in place
+    let rent = Rent::get()?;
+
+    // the treasury must exist before any fee can be paid into it
+    let treasury_bump = check_treasury_account(program_id, treasury_account)?;
+    if treasury_account.owner != program_id {
+        create_pda_account(
+            signer,
+            treasury_account,
+            system_program_account,
+            program_id,
+            0,
+            &rent,
+            &[TREASURY_SEED, &[treasury_bump]],
+        )?;
+    }
+
+    realloc_account(
+        signer,
+        controller_global_config_account,
+        system_program_account,
+        ControllerGlobalConfig::LEN,
+        &rent,
+    )?;
+
+    controller_global_config
+        .serialize(&mut &mut controller_global_config_account.data.borrow_mut()[..])?;
+
+    Ok(())
+}
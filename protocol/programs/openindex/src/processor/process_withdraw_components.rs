@@ -0,0 +1,210 @@
+//! Program state processor
+
+use crate::state::{Component, RedeemRequest};
+use crate::utils::load_index;
+use borsh::{BorshDeserialize, BorshSerialize};
+use openindex_sdk::{
+    openindex::{
+        error::ProtocolError,
+        pda::{
+            create_component_address, create_component_vault_address,
+            create_index_address_from_seed, create_redeem_request_address,
+        },
+        seeds::COMPONENT_VAULT_SEED,
+    },
+    require,
+};
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    program::invoke_signed,
+    program_error::ProgramError,
+    program_pack::IsInitialized,
+    pubkey::Pubkey,
+};
+use spl_token::instruction::transfer;
+
+/// instruction to process releasing a subset of components for a redeem request
+pub fn process_withdraw_components(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    index_id: u64,
+) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let signer = next_account_info(accounts_iter)?;
+    let controller_account = next_account_info(accounts_iter)?;
+    let index_account = next_account_info(accounts_iter)?;
+    let redeem_request_account = next_account_info(accounts_iter)?;
+    let token_program_account = next_account_info(accounts_iter)?;
+
+    require!(signer.is_signer, ProgramError::MissingRequiredSignature);
+
+    require!(
+        controller_account.owner == program_id,
+        ProtocolError::UnknownControllerAccount.into()
+    );
+
+    require!(
+        index_account.owner == program_id,
+        ProtocolError::UnknownIndexAccount.into()
+    );
+
+    require!(
+        *token_program_account.key == spl_token::ID,
+        ProgramError::IncorrectProgramId
+    );
+
+    let index = load_index(program_id, controller_account.key, index_account)?;
+
+    require!(
+        index.id == index_id,
+        ProtocolError::IncorrectIndexAccount.into()
+    );
+
+    let index_seed = index.address_seed();
+
+    let index_pda = create_index_address_from_seed(
+        program_id,
+        controller_account.key,
+        &index_seed,
+        index.bump,
+    )?;
+
+    require!(
+        *index_account.key == index_pda,
+        ProtocolError::IncorrectIndexAccount.into()
+    );
+
+    let mut redeem_request = RedeemRequest::try_from_slice(&redeem_request_account.data.borrow())
+        .map_err(|_| ProtocolError::InvalidRedeemRequestAccountData)?;
+
+    require!(
+        redeem_request.is_initialized(),
+        ProtocolError::InvalidRedeemRequestAccountData.into()
+    );
+
+    let redeem_request_pda = create_redeem_request_address(
+        program_id,
+        index_account.key,
+        signer.key,
+        redeem_request.bump,
+    )?;
+
+    require!(
+        *redeem_request_account.key == redeem_request_pda,
+        ProtocolError::IncorrectRedeemRequestAccount.into()
+    );
+
+    require!(
+        redeem_request.owner == *signer.key,
+        ProtocolError::OnlyRedeemRequestOwner.into()
+    );
+
+    require!(
+        accounts_iter.len() > 0,
+        ProtocolError::NoMintsProvided.into()
+    );
+
+    while accounts_iter.len() > 0 {
+        let component_mint_account = next_account_info(accounts_iter)?;
+        let component_account = next_account_info(accounts_iter)?;
+        let vault_pda = next_account_info(accounts_iter)?;
+        let vault_ata = next_account_info(accounts_iter)?;
+        let component_token_account = next_account_info(accounts_iter)?;
+
+        require!(
+            component_token_account.owner == token_program_account.key,
+            ProgramError::InvalidAccountOwner
+        );
+
+        let position = redeem_request
+            .mints
+            .iter()
+            .position(|mint| mint == component_mint_account.key)
+            .ok_or(ProtocolError::InvalidMintAccount)?;
+
+        let owed = redeem_request
+            .owed
+            .get_mut(position)
+            .ok_or(ProtocolError::InvalidRedeemRequestAccountData)?;
+
+        require!(*owed > 0, ProtocolError::ComponentAlreadyWithdrawn.into());
+
+        let component = Component::unpack(&component_account.data.borrow())
+            .map_err(|_| ProtocolError::InvalidComponentData)?;
+
+        let component_pda = create_component_address(
+            program_id,
+            index_account.key,
+            component_mint_account.key,
+            component.bump,
+        )?;
+
+        require!(
+            *component_account.key == component_pda,
+            ProtocolError::IncorrectComponentAccount.into()
+        );
+
+        let expected_vault_pda = create_component_vault_address(
+            program_id,
+            index_account.key,
+            component_mint_account.key,
+            component.vault_bump,
+        )?;
+
+        require!(
+            *vault_pda.key == expected_vault_pda,
+            ProtocolError::IncorrectVaultAccount.into()
+        );
+
+        let expected_vault_ata = spl_associated_token_account::get_associated_token_address(
+            vault_pda.key,
+            component_mint_account.key,
+        );
+        require!(
+            *vault_ata.key == expected_vault_ata,
+            ProtocolError::IncorrectVaultATA.into()
+        );
+
+        // pay out what was owed when the tokens were burned
+        invoke_signed(
+            &transfer(
+                token_program_account.key,
+                vault_ata.key,
+                component_token_account.key,
+                vault_pda.key,
+                &[],
+                *owed,
+            )?,
+            &[
+                token_program_account.clone(),
+                vault_ata.clone(),
+                component_token_account.clone(),
+                vault_pda.clone(),
+            ],
+            &[&[
+                COMPONENT_VAULT_SEED,
+                index_account.key.as_ref(),
+                component_mint_account.key.as_ref(),
+                &[component.vault_bump],
+            ]],
+        )?;
+
+        *owed = 0;
+    }
+
+    if !redeem_request.is_settled() {
+        redeem_request.serialize(&mut &mut redeem_request_account.data.borrow_mut()[..])?;
+        return Ok(());
+    }
+
+    // every component was released: close the redeem request and return its rent
+    let signer_lamports = signer.lamports();
+    **signer.lamports.borrow_mut() = signer_lamports
+        .checked_add(redeem_request_account.lamports())
+        .ok_or(ProgramError::ArithmeticOverflow)?;
+    **redeem_request_account.lamports.borrow_mut() = 0;
+    redeem_request_account.data.borrow_mut().fill(0);
+
+    Ok(())
+}
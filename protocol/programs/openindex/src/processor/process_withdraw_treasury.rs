@@ -0,0 +1,88 @@
+//! Program state processor
+
+use crate::state::Protocol;
+use crate::utils::{check_owner, check_treasury_account};
+use borsh::BorshDeserialize;
+use openindex_sdk::{
+    openindex::{error::ProtocolError, pda::create_protocol_address},
+    require,
+};
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    program_error::ProgramError,
+    program_pack::IsInitialized,
+    pubkey::Pubkey,
+    rent::Rent,
+    sysvar::Sysvar,
+};
+
+/// instruction to process withdrawing collected fees from the treasury
+pub fn process_withdraw_treasury(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    amount: u64,
+) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let signer = next_account_info(accounts_iter)?;
+    let protocol_account = next_account_info(accounts_iter)?;
+    let treasury_account = next_account_info(accounts_iter)?;
+    let destination = next_account_info(accounts_iter)?;
+
+    require!(signer.is_signer, ProgramError::MissingRequiredSignature);
+
+    require!(
+        protocol_account.owner == program_id,
+        ProtocolError::UnknownProtocolAccount.into()
+    );
+
+    let protocol: Protocol = Protocol::try_from_slice(&protocol_account.data.borrow())
+        .map_err(|_| ProtocolError::InvalidProtocolAccountData)?;
+
+    require!(
+        protocol.is_initialized(),
+        ProtocolError::ProtocolNotInitialized.into()
+    );
+
+    let protocol_pda = create_protocol_address(program_id, protocol.bump)?;
+
+    require!(
+        *protocol_account.key == protocol_pda,
+        ProtocolError::IncorrectProtocolAccount.into()
+    );
+
+    check_owner(
+        program_id,
+        &protocol.owner,
+        signer,
+        accounts_iter.as_slice(),
+        ProtocolError::OnlyProtocolOwner,
+    )?;
+
+    check_treasury_account(program_id, treasury_account)?;
+
+    require!(
+        treasury_account.owner == program_id,
+        ProtocolError::IncorrectTreasuryAccount.into()
+    );
+
+    let rent = Rent::get()?;
+    let available = treasury_account
+        .lamports()
+        .saturating_sub(rent.minimum_balance(treasury_account.data_len()));
+
+    require!(
+        amount <= available,
+        ProtocolError::InsufficientTreasuryBalance.into()
+    );
+
+    let treasury_lamports = treasury_account.lamports();
+    **treasury_account.lamports.borrow_mut() = treasury_lamports - amount;
+
+    let destination_lamports = destination.lamports();
+    **destination.lamports.borrow_mut() = destination_lamports
+        .checked_add(amount)
+        .ok_or(ProgramError::ArithmeticOverflow)?;
+
+    Ok(())
+}
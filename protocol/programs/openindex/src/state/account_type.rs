@@ -21,4 +21,28 @@ pub enum AccountType {
     IndexMints, 
     /// Module account -  Determines if an external program is a registered module
     Module, 
+    /// Mint request account - tracks a staged, multi-transaction mint
+    MintRequest,
+    /// Redeem request account - tracks a staged, multi-transaction redemption
+    RedeemRequest,
+    /// Controller config account - per-controller overrides of the global config
+    ControllerConfig,
+    /// Controller creator account - allowlist entry for controller creation
+    ControllerCreator,
+    /// Controller counter account - one shard of the nonce-based controller count
+    ControllerCounter,
+    /// Controller roles account - role registry of a single controller
+    ControllerRoles,
+    /// Multisig account - m-of-n authority usable as an owner
+    Multisig,
+    /// Pending composition change account - recipe queued behind the index timelock
+    PendingCompositionChange,
+    /// Flash mint account - obligation of a flash mint awaiting settlement
+    FlashMint,
+    /// Flash loan account - component amount lent by `FlashBorrow` awaiting repayment
+    FlashLoan,
+    /// Transfer allowlist account - wallet allowed to transfer a transfer-hooked index mint
+    TransferAllowlist,
+    /// Asset registry entry account - component mint approved by the protocol with its accepted risks
+    AssetRegistryEntry,
 }
\ No newline at end of file
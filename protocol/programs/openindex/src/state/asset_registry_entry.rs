@@ -0,0 +1,98 @@
+use borsh::{BorshDeserialize, BorshSerialize};
+use solana_program::{program_pack::IsInitialized, pubkey::Pubkey};
+
+use super::AccountType;
+
+/// AssetRegistryEntry
+///
+/// Entry of the protocol asset registry approving `mint` as an index
+/// component. Created, updated and closed by `SetAssetRegistryEntry`;
+/// `AddIndexComponents` consults it while
+/// `ControllerGlobalConfig::asset_registry_required` is set.
+#[derive(BorshDeserialize, BorshSerialize, Debug)]
+pub struct AssetRegistryEntry {
+    /// Account type. It can be **Uninitialized** or **AssetRegistryEntry**.
+    pub account_type: AccountType,
+
+    /// Approved component mint.
+    pub mint: Pubkey,
+
+    /// Governance rating of the asset; informational only.
+    pub risk_tier: u8,
+
+    /// `ASSET_RISK_*` flags the mint may carry and still be used as a
+    /// component.
+    pub accepted_risks: u8,
+
+    /// Set to `true` by `SetAssetRegistryEntry`; queried via `IsInitialized`.
+    pub initialized: bool,
+
+    /// PDA bump seed for `asset_registry_account`.
+    pub bump: u8,
+}
+
+impl AssetRegistryEntry {
+    /// Packed size in bytes:
+    /// * 1  – `account_type`
+    /// * 32 – `mint`
+    /// * 1  – `risk_tier`
+    /// * 1  – `accepted_risks`
+    /// * 1  – `initialized`
+    /// * 1  – `bump`
+    pub const LEN: usize = 1 + 32 + 1 + 1 + 1 + 1;
+
+    /// Constructor used by `process_set_asset_registry_entry`.
+    pub fn new(mint: Pubkey, risk_tier: u8, accepted_risks: u8, bump: u8) -> Self {
+        Self {
+            account_type: AccountType::AssetRegistryEntry,
+            mint,
+            risk_tier,
+            accepted_risks,
+            initialized: true,
+            bump,
+        }
+    }
+
+    /// `true` when every flag in `risks` is accepted for the mint.
+    pub fn accepts(&self, risks: u8) -> bool {
+        risks & !self.accepted_risks == 0
+    }
+}
+
+impl IsInitialized for AssetRegistryEntry {
+    fn is_initialized(&self) -> bool {
+        self.initialized
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_new() {
+        let mint = Pubkey::new_unique();
+        let c = AssetRegistryEntry::new(mint, 2, 0b0101, 254);
+        assert_eq!(c.mint, mint);
+        assert_eq!(c.risk_tier, 2);
+        assert_eq!(c.accepted_risks, 0b0101);
+        assert!(c.is_initialized());
+        assert_eq!(c.bump, 254);
+    }
+
+    #[test]
+    fn test_len() {
+        let c = AssetRegistryEntry::new(Pubkey::new_unique(), 2, 0b0101, 254);
+        assert_eq!(borsh::to_vec(&c).unwrap().len(), AssetRegistryEntry::LEN);
+    }
+
+    #[test]
+    fn test_accepts() {
+        let c = AssetRegistryEntry::new(Pubkey::new_unique(), 0, 0b0101, 254);
+        assert!(c.accepts(0));
+        assert!(c.accepts(0b0100));
+        assert!(c.accepts(0b0101));
+        assert!(!c.accepts(0b0010));
+        assert!(!c.accepts(0b0111));
+    }
+}
@@ -1,5 +1,5 @@
 use borsh::{BorshDeserialize, BorshSerialize};
-use solana_program::{program_pack::IsInitialized, pubkey::Pubkey};
+use solana_program::{program_error::ProgramError, program_pack::IsInitialized, pubkey::Pubkey};
 
 use super::AccountType;
 
@@ -30,6 +30,10 @@ pub struct Component {
 
     /// Set to `true` by `AddIndexComponents`; queried via `IsInitialized`.
     initialized: bool,
+
+    /// Index account whose mint is this component, for indexes of indexes.
+    /// All zero for plain SPL components.
+    pub underlying_index: Pubkey,
 }
 
 impl Component {
@@ -40,7 +44,8 @@ impl Component {
     /// * 1  – `bump`
     /// * 1  – `vault_bump`
     /// * 1  – `initialized`
-    pub const LEN: usize = 1 + 8 + 32 + 1 + 1 + 1;
+    /// * 32 – `underlying_index`
+    pub const LEN: usize = 1 + 8 + 32 + 1 + 1 + 1 + 32;
 
    /// Constructor used by `process_add_index_components`.
     pub fn new(uints: u64, mint: Pubkey, bump: u8, vault_bump: u8) -> Self {
@@ -51,7 +56,25 @@ impl Component {
             bump,
             vault_bump,
             initialized: true,
+            underlying_index: Pubkey::default(),
+        }
+    }
+
+    /// Deserializes the account data, zero-filling fields that are missing
+    /// from accounts created with an older, shorter layout.
+    pub fn unpack(data: &[u8]) -> Result<Self, ProgramError> {
+        if data.len() >= Self::LEN {
+            return Self::try_from_slice(data).map_err(Into::into);
         }
+
+        let mut padded = data.to_vec();
+        padded.resize(Self::LEN, 0);
+        Self::try_from_slice(&padded).map_err(Into::into)
+    }
+
+    /// Returns `true` if the component mint is itself an index mint.
+    pub fn is_nested(&self) -> bool {
+        self.underlying_index != Pubkey::default()
     }
 }
 
@@ -74,6 +97,7 @@ mod test {
         assert_eq!(c.bump, 253);
         assert_eq!(c.vault_bump, 252);
         assert_eq!(c.initialized, true);
+        assert!(!c.is_nested());
     }
 
     #[test]
@@ -81,4 +105,18 @@ mod test {
         let c = Component::new(1, Pubkey::new_unique(), 1, 1);
         assert_eq!(borsh::to_vec(&c).unwrap().len(), Component::LEN);
     }
+
+    #[test]
+    fn test_unpack_legacy_layout() {
+        let mint = Pubkey::new_unique();
+        let mut c = Component::new(3, mint, 253, 252);
+        let data = borsh::to_vec(&c).unwrap();
+        let legacy = Component::unpack(&data[..Component::LEN - 32]).unwrap();
+        assert_eq!(legacy.uints, 3);
+        assert_eq!(legacy.mint, mint);
+        assert!(!legacy.is_nested());
+
+        c.underlying_index = Pubkey::new_unique();
+        assert!(c.is_nested());
+    }
 }
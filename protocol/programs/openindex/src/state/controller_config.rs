@@ -0,0 +1,224 @@
+use borsh::{BorshDeserialize, BorshSerialize};
+use solana_program::{program_error::ProgramError, program_pack::IsInitialized, pubkey::Pubkey};
+
+use super::AccountType;
+
+/// ControllerConfig
+///
+/// Optional per-controller PDA that overrides the limits stored in
+/// `ControllerGlobalConfig` for indexes created under one controller, e.g.
+/// to run a retail and an institutional controller side by side.
+/// Created and updated by `SetControllerConfig`.
+///
+/// `CreateIndex` and `AddIndexComponents` consult this account first and
+/// fall back to the global config for every value left unset.
+///
+/// New fields are appended, so accounts written with an older layout stay
+/// readable through [`ControllerConfig::unpack`] until the next
+/// `SetControllerConfig` rewrites them.
+#[derive(BorshDeserialize, BorshSerialize, Debug)]
+pub struct ControllerConfig {
+    /// Account type. It can be **Uninitialized** or **ControllerConfig**.
+    pub account_type: AccountType,
+
+    /// Cap on how many component mints an index may contain; `None` falls
+    /// back to `ControllerGlobalConfig::max_index_components`, which also
+    /// bounds this value.
+    pub max_index_components: Option<u32>,
+
+    /// Upper bound, in basis points, for the mint fee of any index under
+    /// this controller. `FlashMint` charges at most this fee.
+    pub max_mint_fee_bps: u16,
+
+    /// Upper bound, in basis points, for the redeem fee of any index under
+    /// this controller. `SetFlashLoanConfig` refuses a higher flash loan
+    /// fee.
+    pub max_redeem_fee_bps: u16,
+
+    /// Decimals of new index mints; `None` keeps the protocol default of 9.
+    pub default_decimals: Option<u8>,
+
+    /// Component mints indexes may hold; empty allows any mint.
+    pub allowed_mints: Vec<Pubkey>,
+
+    /// Set to `true` by `SetControllerConfig`; queried via `IsInitialized`.
+    pub initialized: bool,
+
+    /// PDA bump seed for `controller_config_account`.
+    pub bump: u8,
+
+    /// Skips the asset registry checks of `AddIndexComponents` for indexes
+    /// under this controller.
+    pub override_asset_checks: bool,
+}
+
+impl ControllerConfig {
+    /// Constructor used by `process_set_controller_config`.
+    pub fn new(
+        max_index_components: Option<u32>,
+        max_mint_fee_bps: u16,
+        max_redeem_fee_bps: u16,
+        default_decimals: Option<u8>,
+        allowed_mints: Vec<Pubkey>,
+        override_asset_checks: bool,
+        bump: u8,
+    ) -> Self {
+        Self {
+            account_type: AccountType::ControllerConfig,
+            max_index_components,
+            max_mint_fee_bps,
+            max_redeem_fee_bps,
+            default_decimals,
+            allowed_mints,
+            initialized: true,
+            bump,
+            override_asset_checks,
+        }
+    }
+
+    /// Deserializes the account data, defaulting the trailing fields that
+    /// are missing from accounts created with an older layout.
+    pub fn unpack(data: &[u8]) -> Result<Self, ProgramError> {
+        if let Ok(controller_config) = Self::try_from_slice(data) {
+            return Ok(controller_config);
+        }
+
+        // the legacy layout ends right before `override_asset_checks`
+        let mut padded = data.to_vec();
+        padded.push(0);
+        Self::try_from_slice(&padded).map_err(Into::into)
+    }
+
+    /// Compute the packed size of this instance.
+    ///
+    /// Layout:
+    /// * 1      – `account_type`
+    /// * 1(+4)  – `max_index_components`
+    /// * 2      – `max_mint_fee_bps`
+    /// * 2      – `max_redeem_fee_bps`
+    /// * 1(+1)  – `default_decimals`
+    /// * 4      – `Vec` length prefix (`u32`)
+    /// * N×32   – each `Pubkey` in `allowed_mints`
+    /// * 1      – `initialized`
+    /// * 1      – `bump`
+    /// * 1      – `override_asset_checks`
+    pub fn packed_len(&self) -> usize {
+        1 + (1 + self.max_index_components.map_or(0, |_| 4))
+            + 2
+            + 2
+            + (1 + self.default_decimals.map_or(0, |_| 1))
+            + 4
+            + (self.allowed_mints.len() * 32)
+            + 1
+            + 1
+            + 1
+    }
+
+    /// Component cap for indexes under this controller; the per-controller
+    /// cap can only tighten `global_max_index_components`.
+    pub fn max_index_components(&self, global_max_index_components: u32) -> u32 {
+        self.max_index_components
+            .map_or(global_max_index_components, |max_index_components| {
+                max_index_components.min(global_max_index_components)
+            })
+    }
+
+    /// Flash mint fee for indexes under this controller; the per-controller
+    /// cap can only lower `global_flash_mint_fee_bps`.
+    pub fn flash_mint_fee_bps(&self, global_flash_mint_fee_bps: u16) -> u16 {
+        self.max_mint_fee_bps.min(global_flash_mint_fee_bps)
+    }
+
+    /// `true` when `mint` may be used as a component.
+    pub fn is_mint_allowed(&self, mint: &Pubkey) -> bool {
+        self.allowed_mints.is_empty() || self.allowed_mints.contains(mint)
+    }
+}
+
+impl IsInitialized for ControllerConfig {
+    fn is_initialized(&self) -> bool {
+        self.initialized
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_new() {
+        let mint = Pubkey::new_unique();
+        let c = ControllerConfig::new(Some(5), 100, 50, Some(6), vec![mint], true, 254);
+        assert_eq!(c.max_index_components, Some(5));
+        assert_eq!(c.max_mint_fee_bps, 100);
+        assert_eq!(c.max_redeem_fee_bps, 50);
+        assert_eq!(c.default_decimals, Some(6));
+        assert_eq!(c.allowed_mints, vec![mint]);
+        assert!(c.override_asset_checks);
+        assert!(c.is_initialized());
+        assert_eq!(c.bump, 254);
+    }
+
+    #[test]
+    fn test_len() {
+        let c = ControllerConfig::new(
+            Some(5),
+            100,
+            50,
+            Some(6),
+            vec![Pubkey::new_unique(), Pubkey::new_unique()],
+            false,
+            254,
+        );
+        assert_eq!(borsh::to_vec(&c).unwrap().len(), c.packed_len());
+
+        let c = ControllerConfig::new(None, 100, 50, None, vec![], false, 254);
+        assert_eq!(borsh::to_vec(&c).unwrap().len(), c.packed_len());
+    }
+
+    #[test]
+    fn test_is_mint_allowed() {
+        let mint = Pubkey::new_unique();
+        let open = ControllerConfig::new(None, 0, 0, None, vec![], false, 254);
+        assert!(open.is_mint_allowed(&mint));
+
+        let restricted = ControllerConfig::new(None, 0, 0, None, vec![mint], false, 254);
+        assert!(restricted.is_mint_allowed(&mint));
+        assert!(!restricted.is_mint_allowed(&Pubkey::new_unique()));
+    }
+
+    #[test]
+    fn test_max_index_components() {
+        let c = ControllerConfig::new(None, 0, 0, None, vec![], false, 254);
+        assert_eq!(c.max_index_components(10), 10);
+
+        let c = ControllerConfig::new(Some(5), 0, 0, None, vec![], false, 254);
+        assert_eq!(c.max_index_components(10), 5);
+
+        let c = ControllerConfig::new(Some(50), 0, 0, None, vec![], false, 254);
+        assert_eq!(c.max_index_components(10), 10);
+    }
+
+    #[test]
+    fn test_flash_mint_fee_bps() {
+        let c = ControllerConfig::new(None, 30, 0, None, vec![], false, 254);
+        assert_eq!(c.flash_mint_fee_bps(10), 10);
+        assert_eq!(c.flash_mint_fee_bps(50), 30);
+    }
+
+    #[test]
+    fn test_unpack_legacy_layout() {
+        let mint = Pubkey::new_unique();
+        let c = ControllerConfig::new(Some(5), 100, 50, Some(6), vec![mint], true, 254);
+        let data = borsh::to_vec(&c).unwrap();
+
+        let legacy = ControllerConfig::unpack(&data[..data.len() - 1]).unwrap();
+        assert_eq!(legacy.max_mint_fee_bps, 100);
+        assert_eq!(legacy.allowed_mints, vec![mint]);
+        assert_eq!(legacy.bump, 254);
+        assert!(!legacy.override_asset_checks);
+
+        let current = ControllerConfig::unpack(&data).unwrap();
+        assert!(current.override_asset_checks);
+    }
+}
@@ -0,0 +1,77 @@
+use borsh::{BorshDeserialize, BorshSerialize};
+use solana_program::program_pack::IsInitialized;
+
+use super::AccountType;
+
+/// ControllerCounter
+///
+/// One shard of the statistics counter for controllers created by
+/// `InitControllerWithNonce`. Creators are spread over
+/// `CONTROLLER_COUNTER_SHARDS` shards so that concurrent creations rarely
+/// write the same account; the total is the sum over all shards.
+///
+/// Created on first use by `InitControllerWithNonce`.
+#[derive(BorshDeserialize, BorshSerialize, Debug)]
+pub struct ControllerCounter {
+    /// Account type. It can be **Uninitialized** or **ControllerCounter**.
+    pub account_type: AccountType,
+
+    /// Shard index, `0..CONTROLLER_COUNTER_SHARDS`.
+    pub shard: u8,
+
+    /// Controllers counted by this shard.
+    pub count: u64,
+
+    /// Set to `true` on creation; queried via `IsInitialized`.
+    pub initialized: bool,
+
+    /// PDA bump seed for `controller_counter_account`.
+    pub bump: u8,
+}
+
+impl ControllerCounter {
+    /// Packed size in bytes:
+    /// * 1 – `account_type`
+    /// * 1 – `shard`
+    /// * 8 – `count`
+    /// * 1 – `initialized`
+    /// * 1 – `bump`
+    pub const LEN: usize = 1 + 1 + 8 + 1 + 1;
+
+    /// Constructor used by `process_init_controller_with_nonce`.
+    pub fn new(shard: u8, bump: u8) -> Self {
+        Self {
+            account_type: AccountType::ControllerCounter,
+            shard,
+            count: 0,
+            initialized: true,
+            bump,
+        }
+    }
+}
+
+impl IsInitialized for ControllerCounter {
+    fn is_initialized(&self) -> bool {
+        self.initialized
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_new() {
+        let c = ControllerCounter::new(3, 254);
+        assert_eq!(c.shard, 3);
+        assert_eq!(c.count, 0);
+        assert!(c.is_initialized());
+        assert_eq!(c.bump, 254);
+    }
+
+    #[test]
+    fn test_len() {
+        let c = ControllerCounter::new(3, 254);
+        assert_eq!(borsh::to_vec(&c).unwrap().len(), ControllerCounter::LEN);
+    }
+}
@@ -0,0 +1,70 @@
+use borsh::{BorshDeserialize, BorshSerialize};
+use solana_program::{program_pack::IsInitialized, pubkey::Pubkey};
+
+use super::AccountType;
+
+/// ControllerCreator
+///
+/// Allowlist entry approving one wallet to create controllers while
+/// `ControllerGlobalConfig::controller_creation_policy` is `Allowlist`.
+/// Created by `SetControllerCreator { allowed: true }` and closed again on
+/// revocation, so an existing account always means "approved".
+#[derive(BorshDeserialize, BorshSerialize, Debug)]
+pub struct ControllerCreator {
+    /// Account type. It can be **Uninitialized** or **ControllerCreator**.
+    pub account_type: AccountType,
+
+    /// Approved wallet.
+    pub creator: Pubkey,
+
+    /// Set to `true` by `SetControllerCreator`; queried via `IsInitialized`.
+    pub initialized: bool,
+
+    /// PDA bump seed for `controller_creator_account`.
+    pub bump: u8,
+}
+
+impl ControllerCreator {
+    /// Packed size in bytes:
+    /// * 1  – `account_type`
+    /// * 32 – `creator`
+    /// * 1  – `initialized`
+    /// * 1  – `bump`
+    pub const LEN: usize = 1 + 32 + 1 + 1;
+
+    /// Constructor used by `process_set_controller_creator`.
+    pub fn new(creator: Pubkey, bump: u8) -> Self {
+        Self {
+            account_type: AccountType::ControllerCreator,
+            creator,
+            initialized: true,
+            bump,
+        }
+    }
+}
+
+impl IsInitialized for ControllerCreator {
+    fn is_initialized(&self) -> bool {
+        self.initialized
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_new() {
+        let creator = Pubkey::new_unique();
+        let c = ControllerCreator::new(creator, 254);
+        assert_eq!(c.creator, creator);
+        assert!(c.is_initialized());
+        assert_eq!(c.bump, 254);
+    }
+
+    #[test]
+    fn test_len() {
+        let c = ControllerCreator::new(Pubkey::new_unique(), 254);
+        assert_eq!(borsh::to_vec(&c).unwrap().len(), ControllerCreator::LEN);
+    }
+}
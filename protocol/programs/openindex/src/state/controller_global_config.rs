@@ -1,5 +1,6 @@
 use borsh::{BorshDeserialize, BorshSerialize};
-use solana_program::program_pack::IsInitialized;
+use openindex_sdk::openindex::instruction::ControllerCreationPolicy;
+use solana_program::{program_error::ProgramError, program_pack::IsInitialized};
 
 use super::AccountType;
 
@@ -8,9 +9,14 @@ use super::AccountType;
 /// Singleton account that stores *controller-wide limits and guards*
 /// enforced uniformly across every controller and index in the protocol.
 /// 
-/// Currently it holds only `max_index_components`, but you can extend it
-/// with fee rates, rebalancing cool-downs, etc.  
-/// Created once by `InitControllerGlobalConfig`.
+/// It holds the component cap, the controller creation policy and the
+/// creation fees, and can be extended with rebalancing cool-downs, etc.  
+/// Created once by `InitControllerGlobalConfig` and changed afterwards by
+/// `UpdateControllerGlobalConfig`.
+///
+/// New fields are always appended, so accounts written with an older layout
+/// stay readable through [`ControllerGlobalConfig::unpack`] until the update
+/// instruction reallocates them.
 /// 
 #[derive(BorshDeserialize, BorshSerialize, Debug)]
 pub struct ControllerGlobalConfig {
@@ -25,6 +31,30 @@ pub struct ControllerGlobalConfig {
 
     /// PDA bump seed for `controller_global_config_account`.
     pub bump: u8,
+
+    /// Best-effort high-water mark of index component counts, raised by
+    /// `AddIndexComponents` and `ExecuteCompositionChange` once the account
+    /// has the current layout. Indexes grown before that are not counted,
+    /// and the value never decreases when a composition change shrinks an
+    /// index.
+    pub largest_index_components: u32,
+
+    /// Who may create controllers through `InitController`.
+    pub controller_creation_policy: ControllerCreationPolicy,
+
+    /// Lamports paid to the treasury per controller under the `Fee` policy.
+    pub controller_creation_fee: u64,
+
+    /// Lamports paid to the treasury per index by `CreateIndex`; 0 disables it.
+    pub index_creation_fee: u64,
+
+    /// Basis points of every component that `SettleFlashMint` charges on
+    /// top of the recipe; the fee stays in the component vaults.
+    pub flash_mint_fee_bps: u16,
+
+    /// Whether `AddIndexComponents` only accepts mints approved in the asset
+    /// registry whose risks are accepted there.
+    pub asset_registry_required: bool,
 }
 
 impl ControllerGlobalConfig {
@@ -33,7 +63,13 @@ impl ControllerGlobalConfig {
     /// * 4 – `max_index_components`
     /// * 1 – `initialized`
     /// * 1 – `bump`
-    pub const LEN: usize = 1 + 4 + 1 + 1;
+    /// * 4 – `largest_index_components`
+    /// * 1 – `controller_creation_policy`
+    /// * 8 – `controller_creation_fee`
+    /// * 8 – `index_creation_fee`
+    /// * 2 – `flash_mint_fee_bps`
+    /// * 1 – `asset_registry_required`
+    pub const LEN: usize = 1 + 4 + 1 + 1 + 4 + 1 + 8 + 8 + 2 + 1;
 
     /// Constructor used by the processor.
     pub fn new(max_index_components: u32, bump: u8) -> Self {
@@ -42,7 +78,25 @@ impl ControllerGlobalConfig {
             max_index_components,
             initialized: true,
             bump,
+            largest_index_components: 0,
+            controller_creation_policy: ControllerCreationPolicy::Open,
+            controller_creation_fee: 0,
+            index_creation_fee: 0,
+            flash_mint_fee_bps: 0,
+            asset_registry_required: false,
+        }
+    }
+
+    /// Deserializes the account data, zero-filling fields that are missing
+    /// from accounts created with an older, shorter layout.
+    pub fn unpack(data: &[u8]) -> Result<Self, ProgramError> {
+        if data.len() >= Self::LEN {
+            return Self::try_from_slice(data).map_err(Into::into);
         }
+
+        let mut padded = data.to_vec();
+        padded.resize(Self::LEN, 0);
+        Self::try_from_slice(&padded).map_err(Into::into)
     }
 }
 
@@ -72,4 +126,23 @@ mod test {
             ControllerGlobalConfig::LEN
         );
     }
+
+    #[test]
+    fn test_unpack_legacy_layout() {
+        let mut c = ControllerGlobalConfig::new(50, 254);
+        c.largest_index_components = 7;
+        let data = borsh::to_vec(&c).unwrap();
+
+        let legacy = ControllerGlobalConfig::unpack(&data[..7]).unwrap();
+        assert_eq!(legacy.max_index_components, 50);
+        assert_eq!(legacy.bump, 254);
+        assert_eq!(legacy.largest_index_components, 0);
+        assert_eq!(legacy.controller_creation_policy, ControllerCreationPolicy::Open);
+        assert_eq!(legacy.index_creation_fee, 0);
+        assert_eq!(legacy.flash_mint_fee_bps, 0);
+        assert!(!legacy.asset_registry_required);
+
+        let current = ControllerGlobalConfig::unpack(&data).unwrap();
+        assert_eq!(current.largest_index_components, 7);
+    }
 }
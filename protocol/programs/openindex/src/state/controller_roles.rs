@@ -0,0 +1,138 @@
+use borsh::{BorshDeserialize, BorshSerialize};
+use openindex_sdk::openindex::instruction::ControllerRole;
+use solana_program::{program_pack::IsInitialized, pubkey::Pubkey};
+
+use super::AccountType;
+
+/// RoleAssignment
+///
+/// A single `role` held by `holder` on the registry's controller.
+#[derive(BorshDeserialize, BorshSerialize, Clone, Debug, PartialEq)]
+pub struct RoleAssignment {
+    /// Wallet the role is granted to.
+    pub holder: Pubkey,
+
+    /// Granted role.
+    pub role: ControllerRole,
+}
+
+/// ControllerRoles
+///
+/// Optional per-controller role registry that lets the controller owner
+/// delegate owner-only actions, e.g. index creation, to other wallets.
+/// Created and updated by `GrantControllerRole` / `RevokeControllerRole`.
+///
+/// The controller owner implicitly holds every role and is never listed.
+#[derive(BorshDeserialize, BorshSerialize, Debug)]
+pub struct ControllerRoles {
+    /// Account type. It can be **Uninitialized** or **ControllerRoles**.
+    pub account_type: AccountType,
+
+    /// Controller the roles apply to.
+    pub controller: Pubkey,
+
+    /// Granted roles, one entry per `(holder, role)` pair.
+    pub assignments: Vec<RoleAssignment>,
+
+    /// Set to `true` by `GrantControllerRole`; queried via `IsInitialized`.
+    pub initialized: bool,
+
+    /// PDA bump seed for `controller_roles_account`.
+    pub bump: u8,
+}
+
+impl ControllerRoles {
+    /// Constructor used by `process_grant_controller_role`.
+    pub fn new(controller: Pubkey, bump: u8) -> Self {
+        Self {
+            account_type: AccountType::ControllerRoles,
+            controller,
+            assignments: vec![],
+            initialized: true,
+            bump,
+        }
+    }
+
+    /// Compute the packed size of this instance.
+    ///
+    /// Layout:
+    /// * 1      – `account_type`
+    /// * 32     – `controller`
+    /// * 4      – `Vec` length prefix (`u32`)
+    /// * N×33   – each `RoleAssignment` in `assignments`
+    /// * 1      – `initialized`
+    /// * 1      – `bump`
+    pub fn packed_len(&self) -> usize {
+        1 + 32 + 4 + (self.assignments.len() * (32 + 1)) + 1 + 1
+    }
+
+    /// `true` when `holder` was granted `role`.
+    pub fn has_role(&self, holder: &Pubkey, role: ControllerRole) -> bool {
+        self.assignments
+            .iter()
+            .any(|assignment| assignment.holder == *holder && assignment.role == role)
+    }
+
+    /// Adds `role` for `holder`; returns `false` if it was already granted.
+    pub fn grant(&mut self, holder: Pubkey, role: ControllerRole) -> bool {
+        if self.has_role(&holder, role) {
+            return false;
+        }
+        self.assignments.push(RoleAssignment { holder, role });
+        true
+    }
+
+    /// Removes `role` from `holder`; returns `false` if it was not granted.
+    pub fn revoke(&mut self, holder: &Pubkey, role: ControllerRole) -> bool {
+        let len = self.assignments.len();
+        self.assignments
+            .retain(|assignment| !(assignment.holder == *holder && assignment.role == role));
+        self.assignments.len() != len
+    }
+}
+
+impl IsInitialized for ControllerRoles {
+    fn is_initialized(&self) -> bool {
+        self.initialized
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_new() {
+        let controller = Pubkey::new_unique();
+        let c = ControllerRoles::new(controller, 254);
+        assert_eq!(c.controller, controller);
+        assert!(c.assignments.is_empty());
+        assert!(c.is_initialized());
+        assert_eq!(c.bump, 254);
+    }
+
+    #[test]
+    fn test_len() {
+        let mut c = ControllerRoles::new(Pubkey::new_unique(), 254);
+        assert_eq!(borsh::to_vec(&c).unwrap().len(), c.packed_len());
+
+        c.grant(Pubkey::new_unique(), ControllerRole::IndexCreator);
+        c.grant(Pubkey::new_unique(), ControllerRole::ComponentManager);
+        assert_eq!(borsh::to_vec(&c).unwrap().len(), c.packed_len());
+    }
+
+    #[test]
+    fn test_grant_and_revoke() {
+        let holder = Pubkey::new_unique();
+        let mut c = ControllerRoles::new(Pubkey::new_unique(), 254);
+
+        assert!(c.grant(holder, ControllerRole::IndexCreator));
+        assert!(!c.grant(holder, ControllerRole::IndexCreator));
+        assert!(c.has_role(&holder, ControllerRole::IndexCreator));
+        assert!(!c.has_role(&holder, ControllerRole::ComponentManager));
+
+        assert!(c.revoke(&holder, ControllerRole::IndexCreator));
+        assert!(!c.revoke(&holder, ControllerRole::IndexCreator));
+        assert!(!c.has_role(&holder, ControllerRole::IndexCreator));
+    }
+}
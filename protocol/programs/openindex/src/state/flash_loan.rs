@@ -0,0 +1,134 @@
+use borsh::{BorshDeserialize, BorshSerialize};
+use solana_program::{program_pack::IsInitialized, pubkey::Pubkey};
+
+use super::AccountType;
+
+/// FlashLoan
+///
+/// Transient per-borrower PDA recording a component amount lent out of an
+/// index vault by `FlashBorrow`. The `FlashRepay` that `FlashBorrow`
+/// requires later in the same transaction returns the amount plus the fee
+/// to the vault and closes it.
+#[derive(BorshDeserialize, BorshSerialize, Debug)]
+pub struct FlashLoan {
+    /// Account type. It can be **Uninitialized** or **FlashLoan**.
+    pub account_type: AccountType,
+
+    /// Wallet that borrowed and must repay.
+    pub borrower: Pubkey,
+
+    /// Component account of the lent vault.
+    pub component: Pubkey,
+
+    /// Vault token account the loan is repaid to.
+    pub vault_ata: Pubkey,
+
+    /// Component amount lent.
+    pub amount: u64,
+
+    /// Fee owed on top of `amount`, computed from `index.flash_loan_fee_bps`
+    /// at borrow time.
+    pub fee: u64,
+
+    /// Set to `true` by `FlashBorrow`; queried via `IsInitialized`.
+    pub initialized: bool,
+
+    /// PDA bump seed for `flash_loan_account`.
+    pub bump: u8,
+}
+
+impl FlashLoan {
+    /// Packed size in bytes:
+    /// * 1  – `account_type`
+    /// * 32 – `borrower`
+    /// * 32 – `component`
+    /// * 32 – `vault_ata`
+    /// * 8  – `amount`
+    /// * 8  – `fee`
+    /// * 1  – `initialized`
+    /// * 1  – `bump`
+    pub const LEN: usize = 1 + 32 + 32 + 32 + 8 + 8 + 1 + 1;
+
+    /// Constructor used by `process_flash_borrow`. The fee is rounded up.
+    /// `None` on overflow.
+    pub fn new(
+        borrower: Pubkey,
+        component: Pubkey,
+        vault_ata: Pubkey,
+        amount: u64,
+        fee_bps: u16,
+        bump: u8,
+    ) -> Option<Self> {
+        let fee = (amount as u128 * fee_bps as u128).div_ceil(10_000);
+        Some(Self {
+            account_type: AccountType::FlashLoan,
+            borrower,
+            component,
+            vault_ata,
+            amount,
+            fee: u64::try_from(fee).ok()?,
+            initialized: true,
+            bump,
+        })
+    }
+
+    /// Amount `FlashRepay` returns to the vault. `None` on overflow.
+    pub fn amount_due(&self) -> Option<u64> {
+        self.amount.checked_add(self.fee)
+    }
+}
+
+impl IsInitialized for FlashLoan {
+    fn is_initialized(&self) -> bool {
+        self.initialized
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn flash_loan(amount: u64, fee_bps: u16) -> Option<FlashLoan> {
+        FlashLoan::new(
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            amount,
+            fee_bps,
+            254,
+        )
+    }
+
+    #[test]
+    fn test_new() {
+        let borrower = Pubkey::new_unique();
+        let component = Pubkey::new_unique();
+        let vault_ata = Pubkey::new_unique();
+        let c = FlashLoan::new(borrower, component, vault_ata, 1_000, 9, 254).unwrap();
+        assert_eq!(c.borrower, borrower);
+        assert_eq!(c.component, component);
+        assert_eq!(c.vault_ata, vault_ata);
+        assert_eq!(c.amount, 1_000);
+        assert!(c.is_initialized());
+        assert_eq!(c.bump, 254);
+    }
+
+    #[test]
+    fn test_len() {
+        let c = flash_loan(1_000, 9).unwrap();
+        assert_eq!(borsh::to_vec(&c).unwrap().len(), FlashLoan::LEN);
+    }
+
+    #[test]
+    fn test_fee() {
+        assert_eq!(flash_loan(1_000, 0).unwrap().amount_due(), Some(1_000));
+
+        // 0.09% of 1 000 rounds up to one unit
+        let c = flash_loan(1_000, 9).unwrap();
+        assert_eq!(c.fee, 1);
+        assert_eq!(c.amount_due(), Some(1_001));
+        assert_eq!(flash_loan(100_000, 9).unwrap().fee, 90);
+
+        assert_eq!(flash_loan(u64::MAX, 10_000).unwrap().amount_due(), None);
+    }
+}
@@ -0,0 +1,125 @@
+use borsh::{BorshDeserialize, BorshSerialize};
+use solana_program::{program_pack::IsInitialized, pubkey::Pubkey};
+
+use super::AccountType;
+
+/// FlashMint
+///
+/// Transient per-user PDA recording the components owed for index tokens
+/// minted by `FlashMint`. It only lives within a single transaction: the
+/// `SettleFlashMint` that `FlashMint` requires later in the same transaction
+/// deposits the components and closes it.
+#[derive(BorshDeserialize, BorshSerialize, Debug)]
+pub struct FlashMint {
+    /// Account type. It can be **Uninitialized** or **FlashMint**.
+    pub account_type: AccountType,
+
+    /// Wallet that flash minted and must settle.
+    pub owner: Pubkey,
+
+    /// Index account the index tokens were minted for.
+    pub index: Pubkey,
+
+    /// Number of index tokens minted up front.
+    pub amount: u64,
+
+    /// Flash fee in basis points of every component, taken from
+    /// `ControllerGlobalConfig` at mint time.
+    pub fee_bps: u16,
+
+    /// `index.composition_version` the obligation was computed for.
+    pub composition_version: u64,
+
+    /// Set to `true` by `FlashMint`; queried via `IsInitialized`.
+    pub initialized: bool,
+
+    /// PDA bump seed for `flash_mint_account`.
+    pub bump: u8,
+}
+
+impl FlashMint {
+    /// Packed size in bytes:
+    /// * 1  – `account_type`
+    /// * 32 – `owner`
+    /// * 32 – `index`
+    /// * 8  – `amount`
+    /// * 2  – `fee_bps`
+    /// * 8  – `composition_version`
+    /// * 1  – `initialized`
+    /// * 1  – `bump`
+    pub const LEN: usize = 1 + 32 + 32 + 8 + 2 + 8 + 1 + 1;
+
+    /// Constructor used by `process_flash_mint`.
+    pub fn new(
+        owner: Pubkey,
+        index: Pubkey,
+        amount: u64,
+        fee_bps: u16,
+        composition_version: u64,
+        bump: u8,
+    ) -> Self {
+        Self {
+            account_type: AccountType::FlashMint,
+            owner,
+            index,
+            amount,
+            fee_bps,
+            composition_version,
+            initialized: true,
+            bump,
+        }
+    }
+
+    /// Component amount owed for a component of `units` per index token,
+    /// with the fee rounded up. `None` on overflow.
+    pub fn amount_due(&self, units: u64) -> Option<u64> {
+        let amount = self.amount.checked_mul(units)?;
+        let fee = (amount as u128 * self.fee_bps as u128).div_ceil(10_000);
+        amount.checked_add(u64::try_from(fee).ok()?)
+    }
+}
+
+impl IsInitialized for FlashMint {
+    fn is_initialized(&self) -> bool {
+        self.initialized
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_new() {
+        let owner = Pubkey::new_unique();
+        let index = Pubkey::new_unique();
+        let c = FlashMint::new(owner, index, 10, 30, 2, 254);
+        assert_eq!(c.owner, owner);
+        assert_eq!(c.index, index);
+        assert_eq!(c.amount, 10);
+        assert_eq!(c.fee_bps, 30);
+        assert_eq!(c.composition_version, 2);
+        assert!(c.is_initialized());
+        assert_eq!(c.bump, 254);
+    }
+
+    #[test]
+    fn test_len() {
+        let c = FlashMint::new(Pubkey::new_unique(), Pubkey::new_unique(), 10, 30, 2, 254);
+        assert_eq!(borsh::to_vec(&c).unwrap().len(), FlashMint::LEN);
+    }
+
+    #[test]
+    fn test_amount_due() {
+        let mut c = FlashMint::new(Pubkey::new_unique(), Pubkey::new_unique(), 10, 0, 0, 254);
+        assert_eq!(c.amount_due(3), Some(30));
+
+        // 0.3% of 30 rounds up to one unit
+        c.fee_bps = 30;
+        assert_eq!(c.amount_due(3), Some(31));
+        assert_eq!(c.amount_due(1_000), Some(10_030));
+
+        c.amount = u64::MAX;
+        assert_eq!(c.amount_due(2), None);
+    }
+}
@@ -1,5 +1,5 @@
 use borsh::{BorshDeserialize, BorshSerialize};
-use solana_program::{program_pack::IsInitialized, pubkey::Pubkey};
+use solana_program::{program_error::ProgramError, program_pack::IsInitialized, pubkey::Pubkey};
 
 use super::AccountType;
 
@@ -35,6 +35,37 @@ pub struct Index {
 
     /// PDA bump seed for `index_account`.
     pub bump: u8,
+
+    /// PDA bump seed for the index `mint_account`.
+    pub mint_bump: u8,
+
+    /// PDA bump seed for `mint_authority_account`.
+    pub mint_authority_bump: u8,
+
+    /// PDA bump seed for `index_mints_account`.
+    pub index_mints_bump: u8,
+
+    /// Creator-chosen seed of indexes created by `CreateIndexWithSeed`.
+    /// All zero for indexes addressed by `id`.
+    pub seed: [u8; 32],
+
+    /// Seconds a queued composition change waits before it can be executed.
+    pub composition_delay: i64,
+
+    /// Incremented every time the components change, so `Mint` and
+    /// `Redeem` callers can detect a recipe that moved under them.
+    pub composition_version: u64,
+
+    /// Set by the index owner to lend vault balances through `FlashBorrow`.
+    pub flash_loans_enabled: bool,
+
+    /// Fee in basis points of the borrowed amount, paid back to the vault
+    /// by `FlashRepay`.
+    pub flash_loan_fee_bps: u16,
+
+    /// Program checking every transfer of the Token-2022 index mint against
+    /// the index transfer allowlist. Default for indexes without one.
+    pub transfer_hook_program: Pubkey,
 }
 
 impl Index {
@@ -45,10 +76,35 @@ impl Index {
     /// * 32 – `manager`
     /// * 1  – `initialized`
     /// * 1  – `bump`
-    pub const LEN: usize = 1 + 8 + 32 + 32 + 1 + 1;
+    /// * 1  – `mint_bump`
+    /// * 1  – `mint_authority_bump`
+    /// * 1  – `index_mints_bump`
+    /// * 32 – `seed`
+    /// * 8  – `composition_delay`
+    /// * 8  – `composition_version`
+    /// * 1  – `flash_loans_enabled`
+    /// * 2  – `flash_loan_fee_bps`
+    /// * 32 – `transfer_hook_program`
+    pub const LEN: usize = 1 + 8 + 32 + 32 + 1 + 1 + 1 + 1 + 1 + 32 + 8 + 8 + 1 + 2 + 32;
+
+    /// Packed size of indexes created before the mint, mint authority and
+    /// index mints bumps were cached. `unpack` reads those bumps as zero.
+    pub const LEGACY_LEN: usize = 1 + 8 + 32 + 32 + 1 + 1;
 
     /// Constructor used by `process_create_index`.
-    pub fn new(id: u64, owner: Pubkey, manager: Pubkey, bump: u8) -> Self {
+    ///
+    /// All PDA bumps are derived once at creation so that the hot paths
+    /// (`Mint`, `Redeem`) can use `create_program_address` instead of
+    /// searching with `find_program_address`.
+    pub fn new(
+        id: u64,
+        owner: Pubkey,
+        manager: Pubkey,
+        bump: u8,
+        mint_bump: u8,
+        mint_authority_bump: u8,
+        index_mints_bump: u8,
+    ) -> Self {
         Self {
             account_type: AccountType::Index,
             id,
@@ -56,6 +112,47 @@ impl Index {
             manager,
             initialized: true,
             bump,
+            mint_bump,
+            mint_authority_bump,
+            index_mints_bump,
+            seed: [0; 32],
+            composition_delay: 0,
+            composition_version: 0,
+            flash_loans_enabled: false,
+            flash_loan_fee_bps: 0,
+            transfer_hook_program: Pubkey::default(),
+        }
+    }
+
+    /// Deserializes the account data, zero-filling fields that are missing
+    /// from accounts created with an older, shorter layout.
+    pub fn unpack(data: &[u8]) -> Result<Self, ProgramError> {
+        if data.len() >= Self::LEN {
+            return Self::try_from_slice(data).map_err(Into::into);
+        }
+
+        let mut padded = data.to_vec();
+        padded.resize(Self::LEN, 0);
+        Self::try_from_slice(&padded).map_err(Into::into)
+    }
+
+    /// Returns `true` if transfers of the index mint are restricted to the
+    /// index transfer allowlist.
+    pub fn has_transfer_hook(&self) -> bool {
+        self.transfer_hook_program != Pubkey::default()
+    }
+
+    /// Returns `true` if the index PDAs are derived from `seed` rather than `id`.
+    pub fn is_seeded(&self) -> bool {
+        self.seed != [0; 32]
+    }
+
+    /// Bytes that identify this index in its PDAs.
+    pub fn address_seed(&self) -> Vec<u8> {
+        if self.is_seeded() {
+            self.seed.to_vec()
+        } else {
+            self.id.to_le_bytes().to_vec()
         }
     }
 }
@@ -74,17 +171,67 @@ mod test {
     fn test_new() {
         let owner = Pubkey::new_unique();
         let manager = Pubkey::new_unique();
-        let c = Index::new(1, owner, manager, 253);
+        let c = Index::new(1, owner, manager, 253, 252, 251, 250);
         assert_eq!(c.id, 1);
         assert_eq!(c.owner, owner);
         assert_eq!(c.manager, manager);
         assert_eq!(c.is_initialized(), true);
         assert_eq!(c.bump, 253);
+        assert_eq!(c.mint_bump, 252);
+        assert_eq!(c.mint_authority_bump, 251);
+        assert_eq!(c.index_mints_bump, 250);
+        assert!(!c.is_seeded());
+        assert_eq!(c.address_seed(), 1u64.to_le_bytes().to_vec());
     }
 
     #[test]
     fn test_len() {
-        let c = Index::new(1, Pubkey::new_unique(), Pubkey::new_unique(), 253);
+        let c = Index::new(
+            1,
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            253,
+            252,
+            251,
+            250,
+        );
         assert_eq!(borsh::to_vec(&c).unwrap().len(), Index::LEN);
     }
+
+    #[test]
+    fn test_unpack_legacy_layout() {
+        let mut c = Index::new(
+            1,
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            253,
+            252,
+            251,
+            250,
+        );
+        let data = borsh::to_vec(&c).unwrap();
+
+        let baseline = Index::unpack(&data[..Index::LEGACY_LEN]).unwrap();
+        assert_eq!(baseline.id, 1);
+        assert!(baseline.is_initialized());
+        assert_eq!(baseline.bump, 253);
+        assert_eq!(baseline.mint_bump, 0);
+        assert_eq!(baseline.mint_authority_bump, 0);
+        assert_eq!(baseline.index_mints_bump, 0);
+        assert!(!baseline.is_seeded());
+
+        let legacy = Index::unpack(&data[..Index::LEN - 32 - 8 - 8 - 1 - 2 - 32]).unwrap();
+        assert_eq!(legacy.mint_bump, 252);
+        assert_eq!(legacy.id, 1);
+        assert!(!legacy.is_seeded());
+        assert_eq!(legacy.composition_delay, 0);
+        assert_eq!(legacy.composition_version, 0);
+        assert!(!legacy.flash_loans_enabled);
+        assert_eq!(legacy.flash_loan_fee_bps, 0);
+        assert!(!legacy.has_transfer_hook());
+
+        c.seed = [7; 32];
+        assert!(c.is_seeded());
+        assert_eq!(c.address_seed(), vec![7; 32]);
+    }
 }
@@ -0,0 +1,144 @@
+use borsh::{BorshDeserialize, BorshSerialize};
+use solana_program::{program_pack::IsInitialized, pubkey::Pubkey};
+
+use super::AccountType;
+
+/// MintRequest
+///
+/// Per-user PDA that tracks a **staged mint** for indexes whose component
+/// list is too large to be deposited in a single transaction.
+/// Created by `BeginMint`, filled by one or more `DepositComponents`
+/// calls and closed by `FinalizeMint` (or `CancelMint`).
+///
+/// The component mints are snapshotted when the request is opened, so
+/// deposits can always be refunded even after the index composition changed;
+/// `deposited[i]` is the amount held for `mints[i]`.
+#[derive(BorshDeserialize, BorshSerialize, Debug)]
+pub struct MintRequest {
+    /// Account type. It can be **Uninitialized** or **MintRequest**.
+    pub account_type: AccountType,
+
+    /// Wallet that opened the request and supplies the components.
+    pub owner: Pubkey,
+
+    /// Index account the request belongs to.
+    pub index: Pubkey,
+
+    /// Number of index tokens minted once every component is deposited.
+    pub amount: u64,
+
+    /// `index.composition_version` the request was opened for.
+    pub composition_version: u64,
+
+    /// Component mints of the index when the request was opened, in
+    /// `IndexMints` order.
+    pub mints: Vec<Pubkey>,
+
+    /// Amount deposited for each mint in `mints`; zero until deposited.
+    pub deposited: Vec<u64>,
+
+    /// Set to `true` by `BeginMint`; queried via `IsInitialized`.
+    pub initialized: bool,
+
+    /// PDA bump seed for `mint_request_account`.
+    pub bump: u8,
+}
+
+impl MintRequest {
+    /// Constructor used by `process_begin_mint`.
+    pub fn new(
+        owner: Pubkey,
+        index: Pubkey,
+        amount: u64,
+        composition_version: u64,
+        mints: Vec<Pubkey>,
+        bump: u8,
+    ) -> Self {
+        Self {
+            account_type: AccountType::MintRequest,
+            owner,
+            index,
+            amount,
+            composition_version,
+            deposited: vec![0; mints.len()],
+            mints,
+            initialized: true,
+            bump,
+        }
+    }
+
+    /// Compute the packed size **before** the account is created.
+    ///
+    /// Layout:
+    /// * 1  – `account_type`
+    /// * 32 – `owner`
+    /// * 32 – `index`
+    /// * 8  – `amount`
+    /// * 8  – `composition_version`
+    /// * 4  – `Vec` length prefix (`u32`)
+    /// * 32 × N – each `Pubkey` in `mints`
+    /// * 4  – `Vec` length prefix (`u32`)
+    /// * 8 × N – each `u64` in `deposited`
+    /// * 1  – `initialized`
+    /// * 1  – `bump`
+    pub fn calc_len(components_len: usize) -> usize {
+        1 + 32 + 32 + 8 + 8 + 4 + 32 * components_len + 4 + 8 * components_len + 1 + 1
+    }
+
+    /// `true` once every component has been deposited.
+    pub fn is_complete(&self) -> bool {
+        self.deposited.iter().all(|deposited| *deposited > 0)
+    }
+
+    /// `true` while at least one component is still held for this request.
+    pub fn has_deposits(&self) -> bool {
+        self.deposited.iter().any(|deposited| *deposited > 0)
+    }
+}
+
+impl IsInitialized for MintRequest {
+    fn is_initialized(&self) -> bool {
+        self.initialized
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_new() {
+        let owner = Pubkey::new_unique();
+        let index = Pubkey::new_unique();
+        let mints = vec![Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique()];
+        let c = MintRequest::new(owner, index, 10, 2, mints.clone(), 254);
+        assert_eq!(c.owner, owner);
+        assert_eq!(c.index, index);
+        assert_eq!(c.amount, 10);
+        assert_eq!(c.composition_version, 2);
+        assert_eq!(c.mints, mints);
+        assert_eq!(c.deposited, vec![0, 0, 0]);
+        assert!(c.is_initialized());
+        assert_eq!(c.bump, 254);
+    }
+
+    #[test]
+    fn test_len() {
+        let mints = vec![Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique()];
+        let c = MintRequest::new(Pubkey::new_unique(), Pubkey::new_unique(), 10, 0, mints, 254);
+        assert_eq!(borsh::to_vec(&c).unwrap().len(), MintRequest::calc_len(3));
+    }
+
+    #[test]
+    fn test_is_complete() {
+        let mints = vec![Pubkey::new_unique(), Pubkey::new_unique()];
+        let mut c = MintRequest::new(Pubkey::new_unique(), Pubkey::new_unique(), 10, 0, mints, 254);
+        assert!(!c.is_complete());
+        assert!(!c.has_deposits());
+        c.deposited[0] = 20;
+        assert!(!c.is_complete());
+        assert!(c.has_deposits());
+        c.deposited[1] = 30;
+        assert!(c.is_complete());
+    }
+}
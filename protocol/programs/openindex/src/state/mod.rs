@@ -1,17 +1,41 @@
+mod asset_registry_entry;
 mod component;
 mod controller;
+mod controller_config;
+mod controller_counter;
+mod controller_creator;
 mod controller_global_config;
+mod controller_roles;
+mod flash_loan;
+mod flash_mint;
 mod index;
 mod index_mints;
+mod mint_request;
 mod module;
+mod multisig;
+mod pending_composition_change;
 mod protocol;
+mod redeem_request;
+mod transfer_allowlist;
 mod account_type;
 
+pub use asset_registry_entry::*;
 pub use component::*;
 pub use controller::*;
+pub use controller_config::*;
+pub use controller_counter::*;
+pub use controller_creator::*;
 pub use controller_global_config::*;
+pub use controller_roles::*;
+pub use flash_loan::*;
+pub use flash_mint::*;
 pub use index::*;
 pub use index_mints::*;
+pub use mint_request::*;
 pub use module::*;
+pub use multisig::*;
+pub use pending_composition_change::*;
 pub use protocol::*;
+pub use redeem_request::*;
+pub use transfer_allowlist::*;
 pub use account_type::*;
@@ -0,0 +1,119 @@
+use borsh::{BorshDeserialize, BorshSerialize};
+use solana_program::{program_pack::IsInitialized, pubkey::Pubkey};
+
+use super::AccountType;
+
+/// Multisig
+///
+/// m-of-n authority created by `CreateMultisig`. Its address can be set as
+/// the owner of the protocol, a controller or an index; owner checks then
+/// require `threshold` distinct members to sign.
+#[derive(BorshDeserialize, BorshSerialize, Debug)]
+pub struct Multisig {
+    /// Account type. It can be **Uninitialized** or **Multisig**.
+    pub account_type: AccountType,
+
+    /// Number of member signatures required.
+    pub threshold: u8,
+
+    /// Distinct member keys, at most `MAX_MEMBERS`.
+    pub members: Vec<Pubkey>,
+
+    /// Set to `true` by `CreateMultisig`; queried via `IsInitialized`.
+    pub initialized: bool,
+
+    /// PDA bump seed for `multisig_account`.
+    pub bump: u8,
+}
+
+impl Multisig {
+    /// Upper bound on `members`, which keeps approvals within the account
+    /// limit of a transaction.
+    pub const MAX_MEMBERS: usize = 11;
+
+    /// Constructor used by `process_create_multisig`.
+    pub fn new(threshold: u8, members: Vec<Pubkey>, bump: u8) -> Self {
+        Self {
+            account_type: AccountType::Multisig,
+            threshold,
+            members,
+            initialized: true,
+            bump,
+        }
+    }
+
+    /// Compute the packed size of this instance.
+    ///
+    /// Layout:
+    /// * 1      – `account_type`
+    /// * 1      – `threshold`
+    /// * 4      – `Vec` length prefix (`u32`)
+    /// * N×32   – each key in `members`
+    /// * 1      – `initialized`
+    /// * 1      – `bump`
+    pub fn packed_len(&self) -> usize {
+        1 + 1 + 4 + (self.members.len() * 32) + 1 + 1
+    }
+
+    /// Counts the distinct members among `signers`.
+    pub fn count_approvals<'a>(&self, signers: impl Iterator<Item = &'a Pubkey>) -> usize {
+        let mut approved = vec![false; self.members.len()];
+        for signer in signers {
+            if let Some(position) = self.members.iter().position(|member| member == signer) {
+                approved[position] = true;
+            }
+        }
+        approved.into_iter().filter(|approved| *approved).count()
+    }
+
+    /// `true` when `signers` contain at least `threshold` distinct members.
+    pub fn is_approved<'a>(&self, signers: impl Iterator<Item = &'a Pubkey>) -> bool {
+        self.count_approvals(signers) >= self.threshold as usize
+    }
+}
+
+impl IsInitialized for Multisig {
+    fn is_initialized(&self) -> bool {
+        self.initialized
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_new() {
+        let members = vec![Pubkey::new_unique(), Pubkey::new_unique()];
+        let m = Multisig::new(2, members.clone(), 254);
+        assert_eq!(m.threshold, 2);
+        assert_eq!(m.members, members);
+        assert!(m.is_initialized());
+        assert_eq!(m.bump, 254);
+    }
+
+    #[test]
+    fn test_len() {
+        let m = Multisig::new(1, vec![], 254);
+        assert_eq!(borsh::to_vec(&m).unwrap().len(), m.packed_len());
+
+        let m = Multisig::new(2, vec![Pubkey::new_unique(); Multisig::MAX_MEMBERS], 254);
+        assert_eq!(borsh::to_vec(&m).unwrap().len(), m.packed_len());
+    }
+
+    #[test]
+    fn test_count_approvals() {
+        let members = vec![
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+        ];
+        let m = Multisig::new(2, members.clone(), 254);
+        let outsider = Pubkey::new_unique();
+
+        assert_eq!(m.count_approvals([members[0], outsider].iter()), 1);
+        assert_eq!(m.count_approvals([members[0], members[0]].iter()), 1);
+        assert!(!m.is_approved([members[1], members[1]].iter()));
+        assert!(m.is_approved([members[2], outsider, members[0]].iter()));
+    }
+}
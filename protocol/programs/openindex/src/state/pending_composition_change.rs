@@ -0,0 +1,188 @@
+use borsh::{BorshDeserialize, BorshSerialize};
+use solana_program::{program_error::ProgramError, program_pack::IsInitialized, pubkey::Pubkey};
+
+use super::AccountType;
+
+/// PendingCompositionChange
+///
+/// Public queue entry holding the next recipe of an index. Created by
+/// `QueueCompositionChange` and closed by `ExecuteCompositionChange` once
+/// `executable_at` has passed, or earlier by `CancelCompositionChange`.
+#[derive(BorshDeserialize, BorshSerialize, Debug)]
+pub struct PendingCompositionChange {
+    /// Account type. It can be **Uninitialized** or **PendingCompositionChange**.
+    pub account_type: AccountType,
+
+    /// Index account the change applies to.
+    pub index: Pubkey,
+
+    /// Signer that queued the change, the manager or one of its multisig
+    /// members, and receives the rent back.
+    pub manager: Pubkey,
+
+    /// New ordered list of component mints.
+    pub mints: Vec<Pubkey>,
+
+    /// New component units per **one** index token, in `mints` order.
+    pub amounts: Vec<u64>,
+
+    /// Unix timestamp the change was queued at.
+    pub queued_at: i64,
+
+    /// Unix timestamp from which the change can be executed.
+    pub executable_at: i64,
+
+    /// Set to `true` by `QueueCompositionChange`; queried via `IsInitialized`.
+    pub initialized: bool,
+
+    /// PDA bump seed for `pending_change_account`.
+    pub bump: u8,
+
+    /// Which `mints` are index mints, in `mints` order; empty when none are.
+    pub nested_components: Vec<bool>,
+}
+
+impl PendingCompositionChange {
+    /// Constructor used by `process_queue_composition_change`.
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        index: Pubkey,
+        manager: Pubkey,
+        mints: Vec<Pubkey>,
+        amounts: Vec<u64>,
+        nested_components: Vec<bool>,
+        queued_at: i64,
+        executable_at: i64,
+        bump: u8,
+    ) -> Self {
+        Self {
+            account_type: AccountType::PendingCompositionChange,
+            index,
+            manager,
+            mints,
+            amounts,
+            queued_at,
+            executable_at,
+            initialized: true,
+            bump,
+            nested_components,
+        }
+    }
+
+    /// Deserializes a pending change, including ones queued before
+    /// `nested_components` was added.
+    pub fn unpack(data: &[u8]) -> Result<Self, ProgramError> {
+        if let Ok(pending_change) = Self::try_from_slice(data) {
+            return Ok(pending_change);
+        }
+
+        let mut padded = data.to_vec();
+        padded.extend_from_slice(&0u32.to_le_bytes());
+        Self::try_from_slice(&padded).map_err(Into::into)
+    }
+
+    /// Compute the packed size of this instance.
+    ///
+    /// Layout:
+    /// * 1      – `account_type`
+    /// * 32     – `index`
+    /// * 32     – `manager`
+    /// * 4      – `Vec` length prefix (`u32`)
+    /// * N×32   – each `Pubkey` in `mints`
+    /// * 4      – `Vec` length prefix (`u32`)
+    /// * N×8    – each `u64` in `amounts`
+    /// * 8      – `queued_at`
+    /// * 8      – `executable_at`
+    /// * 1      – `initialized`
+    /// * 1      – `bump`
+    /// * 4      – `Vec` length prefix (`u32`)
+    /// * N×1    – each `bool` in `nested_components`
+    pub fn packed_len(&self) -> usize {
+        1 + 32
+            + 32
+            + 4
+            + (self.mints.len() * 32)
+            + 4
+            + (self.amounts.len() * 8)
+            + 8
+            + 8
+            + 1
+            + 1
+            + 4
+            + self.nested_components.len()
+    }
+}
+
+impl IsInitialized for PendingCompositionChange {
+    fn is_initialized(&self) -> bool {
+        self.initialized
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_new() {
+        let index = Pubkey::new_unique();
+        let manager = Pubkey::new_unique();
+        let mints = vec![Pubkey::new_unique()];
+        let c = PendingCompositionChange::new(
+            index,
+            manager,
+            mints.clone(),
+            vec![5],
+            vec![true],
+            10,
+            70,
+            254,
+        );
+        assert_eq!(c.index, index);
+        assert_eq!(c.manager, manager);
+        assert_eq!(c.mints, mints);
+        assert_eq!(c.amounts, vec![5]);
+        assert_eq!(c.nested_components, vec![true]);
+        assert_eq!(c.queued_at, 10);
+        assert_eq!(c.executable_at, 70);
+        assert!(c.is_initialized());
+        assert_eq!(c.bump, 254);
+    }
+
+    #[test]
+    fn test_len() {
+        let c = PendingCompositionChange::new(
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            vec![Pubkey::new_unique(), Pubkey::new_unique()],
+            vec![1, 2],
+            vec![false, true],
+            0,
+            0,
+            254,
+        );
+        assert_eq!(borsh::to_vec(&c).unwrap().len(), c.packed_len());
+    }
+
+    #[test]
+    fn test_unpack_legacy_layout() {
+        let c = PendingCompositionChange::new(
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            vec![Pubkey::new_unique()],
+            vec![3],
+            vec![],
+            10,
+            70,
+            254,
+        );
+        let data = borsh::to_vec(&c).unwrap();
+        let legacy = &data[..data.len() - 4];
+        let unpacked = PendingCompositionChange::unpack(legacy).unwrap();
+        assert_eq!(unpacked.mints, c.mints);
+        assert_eq!(unpacked.amounts, vec![3]);
+        assert_eq!(unpacked.executable_at, 70);
+        assert!(unpacked.nested_components.is_empty());
+        assert_eq!(unpacked.bump, 254);
+    }
+}
@@ -0,0 +1,147 @@
+use borsh::{BorshDeserialize, BorshSerialize};
+use solana_program::{program_pack::IsInitialized, pubkey::Pubkey};
+
+use super::AccountType;
+
+/// RedeemRequest
+///
+/// Per-user PDA that tracks a **staged redemption** for indexes whose
+/// component list is too large to be withdrawn in a single transaction.
+/// Created by `BeginRedeem` (which burns the index tokens up front) and
+/// settled by one or more `WithdrawComponents` calls; the account is closed
+/// once every component has been withdrawn.
+///
+/// The amount owed for each component is fixed by `BeginRedeem`, so the
+/// burned tokens are paid out at the units they were burned at even if the
+/// index composition changes; `owed[i]` is the amount still owed for
+/// `mints[i]`.
+#[derive(BorshDeserialize, BorshSerialize, Debug)]
+pub struct RedeemRequest {
+    /// Account type. It can be **Uninitialized** or **RedeemRequest**.
+    pub account_type: AccountType,
+
+    /// Wallet that burned the index tokens and receives the components.
+    pub owner: Pubkey,
+
+    /// Index account the request belongs to.
+    pub index: Pubkey,
+
+    /// Number of index tokens burned by `BeginRedeem`.
+    pub amount: u64,
+
+    /// Component mints of the index when the request was opened, in
+    /// `IndexMints` order.
+    pub mints: Vec<Pubkey>,
+
+    /// Amount still owed for each mint in `mints`; zero once withdrawn.
+    pub owed: Vec<u64>,
+
+    /// Set to `true` by `BeginRedeem`; queried via `IsInitialized`.
+    pub initialized: bool,
+
+    /// PDA bump seed for `redeem_request_account`.
+    pub bump: u8,
+}
+
+impl RedeemRequest {
+    /// Constructor used by `process_begin_redeem`.
+    pub fn new(
+        owner: Pubkey,
+        index: Pubkey,
+        amount: u64,
+        mints: Vec<Pubkey>,
+        owed: Vec<u64>,
+        bump: u8,
+    ) -> Self {
+        Self {
+            account_type: AccountType::RedeemRequest,
+            owner,
+            index,
+            amount,
+            mints,
+            owed,
+            initialized: true,
+            bump,
+        }
+    }
+
+    /// Compute the packed size **before** the account is created.
+    ///
+    /// Layout:
+    /// * 1  – `account_type`
+    /// * 32 – `owner`
+    /// * 32 – `index`
+    /// * 8  – `amount`
+    /// * 4  – `Vec` length prefix (`u32`)
+    /// * 32 × N – each `Pubkey` in `mints`
+    /// * 4  – `Vec` length prefix (`u32`)
+    /// * 8 × N – each `u64` in `owed`
+    /// * 1  – `initialized`
+    /// * 1  – `bump`
+    pub fn calc_len(components_len: usize) -> usize {
+        1 + 32 + 32 + 8 + 4 + 32 * components_len + 4 + 8 * components_len + 1 + 1
+    }
+
+    /// `true` once every component has been withdrawn.
+    pub fn is_settled(&self) -> bool {
+        self.owed.iter().all(|owed| *owed == 0)
+    }
+}
+
+impl IsInitialized for RedeemRequest {
+    fn is_initialized(&self) -> bool {
+        self.initialized
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_new() {
+        let owner = Pubkey::new_unique();
+        let index = Pubkey::new_unique();
+        let mints = vec![Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique()];
+        let c = RedeemRequest::new(owner, index, 10, mints.clone(), vec![10, 20, 30], 254);
+        assert_eq!(c.owner, owner);
+        assert_eq!(c.index, index);
+        assert_eq!(c.amount, 10);
+        assert_eq!(c.mints, mints);
+        assert_eq!(c.owed, vec![10, 20, 30]);
+        assert!(c.is_initialized());
+        assert_eq!(c.bump, 254);
+    }
+
+    #[test]
+    fn test_len() {
+        let mints = vec![Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique()];
+        let c = RedeemRequest::new(
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            10,
+            mints,
+            vec![10, 20, 30],
+            254,
+        );
+        assert_eq!(borsh::to_vec(&c).unwrap().len(), RedeemRequest::calc_len(3));
+    }
+
+    #[test]
+    fn test_is_settled() {
+        let mints = vec![Pubkey::new_unique(), Pubkey::new_unique()];
+        let mut c = RedeemRequest::new(
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            10,
+            mints,
+            vec![10, 20],
+            254,
+        );
+        assert!(!c.is_settled());
+        c.owed[0] = 0;
+        assert!(!c.is_settled());
+        c.owed[1] = 0;
+        assert!(c.is_settled());
+    }
+}
@@ -0,0 +1,77 @@
+use borsh::{BorshDeserialize, BorshSerialize};
+use solana_program::{program_pack::IsInitialized, pubkey::Pubkey};
+
+use super::AccountType;
+
+/// TransferAllowlist
+///
+/// Entry PDA allowing `wallet` to send and receive the index tokens of an
+/// index created with a transfer hook. The hook only checks that the entry
+/// exists, so `SetTransferAllowlist` creates it to allow the wallet and
+/// closes it to disallow it again.
+#[derive(BorshDeserialize, BorshSerialize, Debug)]
+pub struct TransferAllowlist {
+    /// Account type. It can be **Uninitialized** or **TransferAllowlist**.
+    pub account_type: AccountType,
+
+    /// Index account the entry belongs to.
+    pub index: Pubkey,
+
+    /// Owner of the token accounts allowed to transfer.
+    pub wallet: Pubkey,
+
+    /// Set to `true` by `SetTransferAllowlist`; queried via `IsInitialized`.
+    pub initialized: bool,
+
+    /// PDA bump seed for `transfer_allowlist_account`.
+    pub bump: u8,
+}
+
+impl TransferAllowlist {
+    /// Packed size in bytes:
+    /// * 1  – `account_type`
+    /// * 32 – `index`
+    /// * 32 – `wallet`
+    /// * 1  – `initialized`
+    /// * 1  – `bump`
+    pub const LEN: usize = 1 + 32 + 32 + 1 + 1;
+
+    /// Constructor used by `process_set_transfer_allowlist`.
+    pub fn new(index: Pubkey, wallet: Pubkey, bump: u8) -> Self {
+        Self {
+            account_type: AccountType::TransferAllowlist,
+            index,
+            wallet,
+            initialized: true,
+            bump,
+        }
+    }
+}
+
+impl IsInitialized for TransferAllowlist {
+    fn is_initialized(&self) -> bool {
+        self.initialized
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_new() {
+        let index = Pubkey::new_unique();
+        let wallet = Pubkey::new_unique();
+        let c = TransferAllowlist::new(index, wallet, 254);
+        assert_eq!(c.index, index);
+        assert_eq!(c.wallet, wallet);
+        assert!(c.is_initialized());
+        assert_eq!(c.bump, 254);
+    }
+
+    #[test]
+    fn test_len() {
+        let c = TransferAllowlist::new(Pubkey::new_unique(), Pubkey::new_unique(), 254);
+        assert_eq!(borsh::to_vec(&c).unwrap().len(), TransferAllowlist::LEN);
+    }
+}
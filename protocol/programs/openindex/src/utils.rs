@@ -0,0 +1,740 @@
+//! Program helpers
+
+use crate::state::{
+    AccountType, AssetRegistryEntry, Controller, ControllerConfig, ControllerRoles, Index,
+    Multisig, PendingCompositionChange,
+};
+use borsh::BorshDeserialize;
+use openindex_sdk::{
+    openindex::{
+        error::ProtocolError,
+        instruction::{
+            ControllerRole, ProtocolInstruction, ASSET_RISK_CLOSE_AUTHORITY,
+            ASSET_RISK_FREEZE_AUTHORITY, ASSET_RISK_PERMANENT_DELEGATE, ASSET_RISK_TRANSFER_HOOK,
+        },
+        pda::{
+            create_controller_config_address, create_controller_roles_address,
+            create_index_address_from_seed, create_index_mint_address_from_seed,
+            create_pending_composition_change_address, find_asset_registry_address,
+            find_controller_config_address, find_controller_roles_address,
+            find_index_mint_address_from_seed, find_index_mint_authority_address_from_seed,
+            find_index_mints_data_address_from_seed, find_treasury_address,
+        },
+    },
+    require,
+};
+use solana_loader_v3_interface::{get_program_data_address, state::UpgradeableLoaderState};
+use solana_program::{
+    account_info::AccountInfo,
+    entrypoint::ProgramResult,
+    program::{invoke, invoke_signed},
+    program_error::ProgramError,
+    program_option::COption,
+    program_pack::IsInitialized,
+    pubkey::Pubkey,
+    rent::Rent,
+    sysvar::instructions::{load_current_index_checked, load_instruction_at_checked},
+    bpf_loader_upgradeable, system_instruction, system_program,
+};
+use spl_token_2022::extension::{
+    mint_close_authority::MintCloseAuthority, permanent_delegate::PermanentDelegate,
+    transfer_hook, BaseStateWithExtensions, StateWithExtensions,
+};
+
+/// Largest fee that can be expressed in basis points (100%).
+pub const MAX_FEE_BPS: u16 = 10_000;
+
+/// Creates a PDA account owned by `owner` with `space` bytes of data.
+///
+/// PDAs are predictable, so anyone can send lamports to one before it is
+/// created, which makes `system_instruction::create_account` fail. When the
+/// address already holds lamports, the account is topped up to the rent
+/// exempt minimum and then allocated and assigned instead.
+pub fn create_pda_account<'a>(
+    payer: &AccountInfo<'a>,
+    new_account: &AccountInfo<'a>,
+    system_program_account: &AccountInfo<'a>,
+    owner: &Pubkey,
+    space: usize,
+    rent: &Rent,
+    signer_seeds: &[&[u8]],
+) -> ProgramResult {
+    require!(
+        *new_account.owner == system_program::ID && new_account.data_is_empty(),
+        ProgramError::AccountAlreadyInitialized
+    );
+
+    let required_lamports = rent.minimum_balance(space);
+    let current_lamports = new_account.lamports();
+
+    if current_lamports == 0 {
+        return invoke_signed(
+            &system_instruction::create_account(
+                payer.key,
+                new_account.key,
+                required_lamports,
+                space as u64,
+                owner,
+            ),
+            &[
+                payer.clone(),
+                new_account.clone(),
+                system_program_account.clone(),
+            ],
+            &[signer_seeds],
+        );
+    }
+
+    let missing_lamports = required_lamports.saturating_sub(current_lamports);
+    if missing_lamports > 0 {
+        invoke(
+            &system_instruction::transfer(payer.key, new_account.key, missing_lamports),
+            &[
+                payer.clone(),
+                new_account.clone(),
+                system_program_account.clone(),
+            ],
+        )?;
+    }
+
+    invoke_signed(
+        &system_instruction::allocate(new_account.key, space as u64),
+        &[new_account.clone(), system_program_account.clone()],
+        &[signer_seeds],
+    )?;
+
+    invoke_signed(
+        &system_instruction::assign(new_account.key, owner),
+        &[new_account.clone(), system_program_account.clone()],
+        &[signer_seeds],
+    )
+}
+
+/// Grows `account` to `new_len` bytes, topping its lamports up from `payer`
+/// so it stays rent exempt. Accounts that are already large enough are left
+/// untouched.
+pub fn realloc_account<'a>(
+    payer: &AccountInfo<'a>,
+    account: &AccountInfo<'a>,
+    system_program_account: &AccountInfo<'a>,
+    new_len: usize,
+    rent: &Rent,
+) -> ProgramResult {
+    if account.data_len() >= new_len {
+        return Ok(());
+    }
+
+    let missing_lamports = rent
+        .minimum_balance(new_len)
+        .saturating_sub(account.lamports());
+    if missing_lamports > 0 {
+        invoke(
+            &system_instruction::transfer(payer.key, account.key, missing_lamports),
+            &[
+                payer.clone(),
+                account.clone(),
+                system_program_account.clone(),
+            ],
+        )?;
+    }
+
+    account.realloc(new_len, true)
+}
+
+/// Checks that `authority` is the upgrade authority of `program_id`.
+///
+/// `program_data_account` must be the `ProgramData` account the upgradeable
+/// loader keeps for the program. Immutable programs have no upgrade
+/// authority, so the check always fails for them.
+pub fn check_upgrade_authority(
+    program_id: &Pubkey,
+    program_data_account: &AccountInfo,
+    authority: &Pubkey,
+) -> ProgramResult {
+    require!(
+        *program_data_account.key == get_program_data_address(program_id),
+        ProtocolError::IncorrectProgramDataAccount.into()
+    );
+
+    require!(
+        *program_data_account.owner == bpf_loader_upgradeable::ID,
+        ProtocolError::IncorrectProgramDataAccount.into()
+    );
+
+    let data = program_data_account.data.borrow();
+    let metadata = data
+        .get(..UpgradeableLoaderState::size_of_programdata_metadata())
+        .ok_or(ProtocolError::IncorrectProgramDataAccount)?;
+
+    match bincode::deserialize(metadata) {
+        Ok(UpgradeableLoaderState::ProgramData {
+            upgrade_authority_address: Some(upgrade_authority),
+            ..
+        }) if upgrade_authority == *authority => Ok(()),
+        Ok(UpgradeableLoaderState::ProgramData { .. }) => {
+            Err(ProtocolError::OnlyUpgradeAuthority.into())
+        }
+        _ => Err(ProtocolError::IncorrectProgramDataAccount.into()),
+    }
+}
+
+/// Loads the optional per-controller config of `controller_account`.
+///
+/// Returns `None` when the controller never created one, in which case the
+/// caller falls back to `ControllerGlobalConfig`. The address is always
+/// checked, so a config cannot be bypassed by passing another empty account.
+pub fn load_controller_config(
+    program_id: &Pubkey,
+    controller_account: &AccountInfo,
+    controller_config_account: &AccountInfo,
+) -> Result<Option<ControllerConfig>, ProgramError> {
+    if controller_config_account.data_is_empty() {
+        let (controller_config_pda, _) =
+            find_controller_config_address(program_id, controller_account.key);
+        require!(
+            *controller_config_account.key == controller_config_pda,
+            ProtocolError::IncorrectControllerConfigAccount.into()
+        );
+        return Ok(None);
+    }
+
+    require!(
+        controller_config_account.owner == program_id,
+        ProtocolError::IncorrectControllerConfigAccount.into()
+    );
+
+    let controller_config =
+        ControllerConfig::unpack(&controller_config_account.data.borrow())
+            .map_err(|_| ProtocolError::InvalidControllerConfigAccountData)?;
+
+    let controller_config_pda = create_controller_config_address(
+        program_id,
+        controller_account.key,
+        controller_config.bump,
+    )?;
+    require!(
+        *controller_config_account.key == controller_config_pda,
+        ProtocolError::IncorrectControllerConfigAccount.into()
+    );
+
+    Ok(Some(controller_config))
+}
+
+/// Loads the optional role registry of `controller_account`.
+///
+/// Returns `None` when no role was ever granted on the controller. As with
+/// `load_controller_config`, the address is always checked.
+pub fn load_controller_roles(
+    program_id: &Pubkey,
+    controller_account: &AccountInfo,
+    controller_roles_account: &AccountInfo,
+) -> Result<Option<ControllerRoles>, ProgramError> {
+    if controller_roles_account.data_is_empty() {
+        let (controller_roles_pda, _) =
+            find_controller_roles_address(program_id, controller_account.key);
+        require!(
+            *controller_roles_account.key == controller_roles_pda,
+            ProtocolError::IncorrectControllerRolesAccount.into()
+        );
+        return Ok(None);
+    }
+
+    require!(
+        controller_roles_account.owner == program_id,
+        ProtocolError::IncorrectControllerRolesAccount.into()
+    );
+
+    let controller_roles =
+        ControllerRoles::try_from_slice(&controller_roles_account.data.borrow())
+            .map_err(|_| ProtocolError::InvalidControllerRolesAccountData)?;
+
+    let controller_roles_pda = create_controller_roles_address(
+        program_id,
+        controller_account.key,
+        controller_roles.bump,
+    )?;
+    require!(
+        *controller_roles_account.key == controller_roles_pda,
+        ProtocolError::IncorrectControllerRolesAccount.into()
+    );
+
+    Ok(Some(controller_roles))
+}
+
+/// Loads the index in `index_account` of `controller_account`.
+///
+/// Indexes stored in the `Index::LEGACY_LEN` layout lack the cached PDA
+/// bumps, so these are searched for with `find_program_address`. Writing
+/// the index back then stores them.
+pub fn load_index(
+    program_id: &Pubkey,
+    controller_account: &Pubkey,
+    index_account: &AccountInfo,
+) -> Result<Index, ProgramError> {
+    let mut index = Index::unpack(&index_account.data.borrow())?;
+
+    if index_account.data_len() <= Index::LEGACY_LEN {
+        let index_seed = index.address_seed();
+        index.mint_bump =
+            find_index_mint_address_from_seed(program_id, controller_account, &index_seed).1;
+        index.mint_authority_bump =
+            find_index_mint_authority_address_from_seed(program_id, controller_account, &index_seed)
+                .1;
+        index.index_mints_bump =
+            find_index_mints_data_address_from_seed(program_id, controller_account, &index_seed).1;
+    }
+
+    Ok(index)
+}
+
+/// Loads the composition change queued for `index_account`.
+pub fn load_pending_composition_change(
+    program_id: &Pubkey,
+    index_account: &AccountInfo,
+    pending_change_account: &AccountInfo,
+) -> Result<PendingCompositionChange, ProgramError> {
+    require!(
+        pending_change_account.owner == program_id,
+        ProtocolError::InvalidPendingCompositionChangeAccountData.into()
+    );
+
+    let pending_change = PendingCompositionChange::unpack(&pending_change_account.data.borrow())
+        .map_err(|_| ProtocolError::InvalidPendingCompositionChangeAccountData)?;
+    require!(
+        pending_change.account_type == AccountType::PendingCompositionChange
+            && pending_change.is_initialized(),
+        ProtocolError::InvalidPendingCompositionChangeAccountData.into()
+    );
+
+    let pending_change_pda = create_pending_composition_change_address(
+        program_id,
+        index_account.key,
+        pending_change.bump,
+    )?;
+    require!(
+        *pending_change_account.key == pending_change_pda
+            && pending_change.index == *index_account.key,
+        ProtocolError::IncorrectPendingCompositionChangeAccount.into()
+    );
+
+    Ok(pending_change)
+}
+
+/// Checks that `signer` acts for `owner`, failing with `error` otherwise.
+///
+/// `owner` is either a plain wallet, which must be `signer` itself, or a
+/// `Multisig` account. In the latter case `authority_accounts` starts with
+/// the multisig account followed by the approving members, and `signer`
+/// plus the members that signed must reach the multisig threshold.
+pub fn check_owner<'a>(
+    program_id: &Pubkey,
+    owner: &Pubkey,
+    signer: &AccountInfo<'a>,
+    authority_accounts: &[AccountInfo<'a>],
+    error: ProtocolError,
+) -> ProgramResult {
+    if *owner == *signer.key {
+        return Ok(());
+    }
+
+    let (multisig_account, members) = authority_accounts.split_first().ok_or(error.clone())?;
+    require!(
+        *multisig_account.key == *owner && multisig_account.owner == program_id,
+        error.into()
+    );
+
+    let multisig = Multisig::try_from_slice(&multisig_account.data.borrow())
+        .map_err(|_| ProtocolError::InvalidMultisigAccountData)?;
+    require!(
+        multisig.account_type == AccountType::Multisig && multisig.is_initialized(),
+        ProtocolError::InvalidMultisigAccountData.into()
+    );
+
+    let signers = std::iter::once(signer)
+        .chain(members)
+        .filter(|account| account.is_signer)
+        .map(|account| account.key);
+    require!(
+        multisig.is_approved(signers),
+        ProtocolError::MultisigThresholdNotMet.into()
+    );
+
+    Ok(())
+}
+
+/// Checks that `signer` is the controller owner or holds `role` on it.
+///
+/// When `authority_accounts` are passed the caller acts for a multisig
+/// owner and the check is delegated to `check_owner`.
+pub fn check_controller_role<'a>(
+    program_id: &Pubkey,
+    signer: &AccountInfo<'a>,
+    controller: &Controller,
+    controller_account: &AccountInfo<'a>,
+    controller_roles_account: &AccountInfo<'a>,
+    authority_accounts: &[AccountInfo<'a>],
+    role: ControllerRole,
+) -> ProgramResult {
+    if controller.owner == *signer.key || !authority_accounts.is_empty() {
+        return check_owner(
+            program_id,
+            &controller.owner,
+            signer,
+            authority_accounts,
+            ProtocolError::OnlyControllerOwner,
+        );
+    }
+
+    let controller_roles =
+        load_controller_roles(program_id, controller_account, controller_roles_account)?;
+
+    require!(
+        controller_roles.is_some_and(|roles| roles.has_role(signer.key, role)),
+        ProtocolError::MissingControllerRole.into()
+    );
+
+    Ok(())
+}
+
+/// Checks that `signer` is the index owner, possibly a multisig, or the
+/// controller owner or a `ComplianceOfficer` of the controller.
+pub fn check_index_compliance_authority<'a>(
+    program_id: &Pubkey,
+    signer: &AccountInfo<'a>,
+    index: &Index,
+    controller_account: &AccountInfo<'a>,
+    controller_roles_account: &AccountInfo<'a>,
+    authority_accounts: &[AccountInfo<'a>],
+) -> ProgramResult {
+    // the index owner acts on its own; anyone else needs the compliance
+    // role on the controller
+    if index.owner == *signer.key
+        || authority_accounts
+            .first()
+            .is_some_and(|account| *account.key == index.owner)
+    {
+        return check_owner(
+            program_id,
+            &index.owner,
+            signer,
+            authority_accounts,
+            ProtocolError::OnlyIndexOwner,
+        );
+    }
+
+    let controller = Controller::try_from_slice(&controller_account.data.borrow())?;
+    check_controller_role(
+        program_id,
+        signer,
+        &controller,
+        controller_account,
+        controller_roles_account,
+        authority_accounts,
+        ControllerRole::ComplianceOfficer,
+    )
+}
+
+/// Checks that `token_program_account` is the SPL Token or Token-2022
+/// program owning the index `mint_account`. Token-2022 index mints are
+/// created for indexes with a transfer hook.
+pub fn check_index_token_program(
+    mint_account: &AccountInfo,
+    token_program_account: &AccountInfo,
+) -> ProgramResult {
+    require!(
+        (*token_program_account.key == spl_token::ID
+            || *token_program_account.key == spl_token_2022::ID)
+            && mint_account.owner == token_program_account.key,
+        ProgramError::IncorrectProgramId
+    );
+    Ok(())
+}
+
+/// Returns the token program of the index mint in `mint_account`.
+///
+/// Components always go through `token_program_account`; the Token-2022
+/// program of a Token-2022 index mint is looked up in the remaining
+/// `accounts` of the instruction.
+pub fn find_index_token_program<'a, 'b>(
+    mint_account: &AccountInfo<'a>,
+    token_program_account: &'b AccountInfo<'a>,
+    accounts: &'b [AccountInfo<'a>],
+) -> Result<&'b AccountInfo<'a>, ProgramError> {
+    let index_token_program_account = if mint_account.owner == token_program_account.key {
+        token_program_account
+    } else {
+        accounts
+            .iter()
+            .find(|account| account.key == mint_account.owner)
+            .ok_or(ProgramError::NotEnoughAccountKeys)?
+    };
+    check_index_token_program(mint_account, index_token_program_account)?;
+    Ok(index_token_program_account)
+}
+
+/// Returns the `ASSET_RISK_*` flags of a SPL Token or Token-2022
+/// `mint_account`: authorities and extensions that let a third party
+/// freeze, move or close the tokens held in component vaults.
+pub fn asset_risks(mint_account: &AccountInfo) -> Result<u8, ProgramError> {
+    let mint_data = mint_account.data.borrow();
+    let mint = StateWithExtensions::<spl_token_2022::state::Mint>::unpack(&mint_data)?;
+
+    let mut risks = 0;
+    if mint.base.freeze_authority.is_some() {
+        risks |= ASSET_RISK_FREEZE_AUTHORITY;
+    }
+    if let Ok(permanent_delegate) = mint.get_extension::<PermanentDelegate>() {
+        if Option::<Pubkey>::from(permanent_delegate.delegate).is_some() {
+            risks |= ASSET_RISK_PERMANENT_DELEGATE;
+        }
+    }
+    if let Ok(mint_close_authority) = mint.get_extension::<MintCloseAuthority>() {
+        if Option::<Pubkey>::from(mint_close_authority.close_authority).is_some() {
+            risks |= ASSET_RISK_CLOSE_AUTHORITY;
+        }
+    }
+    if transfer_hook::get_program_id(&mint).is_some() {
+        risks |= ASSET_RISK_TRANSFER_HOOK;
+    }
+
+    Ok(risks)
+}
+
+/// Checks that `mint_account` is approved in the asset registry through
+/// `asset_registry_account` and carries no risk the entry does not accept.
+pub fn check_asset_registry_entry(
+    program_id: &Pubkey,
+    mint_account: &AccountInfo,
+    asset_registry_account: &AccountInfo,
+) -> ProgramResult {
+    let (asset_registry_pda, _) = find_asset_registry_address(program_id, mint_account.key);
+    require!(
+        *asset_registry_account.key == asset_registry_pda,
+        ProtocolError::IncorrectAssetRegistryAccount.into()
+    );
+
+    require!(
+        asset_registry_account.owner == program_id && !asset_registry_account.data_is_empty(),
+        ProtocolError::AssetNotRegistered.into()
+    );
+
+    let asset_registry_entry =
+        AssetRegistryEntry::try_from_slice(&asset_registry_account.data.borrow())?;
+    require!(
+        asset_registry_entry.is_initialized(),
+        ProtocolError::AssetNotRegistered.into()
+    );
+
+    require!(
+        asset_registry_entry.accepts(asset_risks(mint_account)?),
+        ProtocolError::AssetRiskNotAccepted.into()
+    );
+
+    Ok(())
+}
+
+/// Checks a recipe of component `mints` and their `amounts` for an index
+/// whose own mint is `index_mint`, against the component cap and the
+/// controller allow-list. `nested_components` is either empty or flags every
+/// mint.
+pub fn check_component_recipe(
+    mints: &[Pubkey],
+    amounts: &[u64],
+    nested_components: &[bool],
+    index_mint: &Pubkey,
+    max_index_components: u32,
+    controller_config: Option<&ControllerConfig>,
+) -> ProgramResult {
+    require!(!mints.is_empty(), ProtocolError::NoMintsProvided.into());
+
+    require!(
+        mints.len() <= max_index_components as usize,
+        ProtocolError::MaxIndexComponentsExceeded.into()
+    );
+
+    require!(
+        mints.len() == amounts.len(),
+        ProtocolError::MintsAmountsLenMismatch.into()
+    );
+
+    require!(
+        nested_components.is_empty() || nested_components.len() == mints.len(),
+        ProtocolError::MintsAmountsLenMismatch.into()
+    );
+
+    // reject bad recipes before any account is created; a repeated mint would
+    // otherwise only fail inside the system program
+    require!(
+        amounts.iter().all(|amount| *amount > 0),
+        ProtocolError::ZeroComponentUnits.into()
+    );
+
+    for (index, mint) in mints.iter().enumerate() {
+        require!(
+            !mints[..index].contains(mint),
+            ProtocolError::DuplicateComponentMint.into()
+        );
+    }
+
+    require!(
+        !mints.contains(index_mint),
+        ProtocolError::IndexMintAsComponent.into()
+    );
+
+    if let Some(controller_config) = controller_config {
+        require!(
+            mints
+                .iter()
+                .all(|mint| controller_config.is_mint_allowed(mint)),
+            ProtocolError::ComponentMintNotAllowed.into()
+        );
+    }
+
+    Ok(())
+}
+
+/// Checks that `mint` is the index mint of `underlying_index_account`, an
+/// initialized index other than `index_account`.
+pub fn check_underlying_index(
+    program_id: &Pubkey,
+    index_account: &AccountInfo,
+    underlying_controller_account: &AccountInfo,
+    underlying_index_account: &AccountInfo,
+    mint: &Pubkey,
+) -> ProgramResult {
+    require!(
+        underlying_controller_account.owner == program_id,
+        ProtocolError::UnknownControllerAccount.into()
+    );
+    require!(
+        underlying_index_account.owner == program_id,
+        ProtocolError::UnknownIndexAccount.into()
+    );
+    require!(
+        underlying_index_account.key != index_account.key,
+        ProtocolError::InvalidUnderlyingIndex.into()
+    );
+
+    let underlying_index = load_index(
+        program_id,
+        underlying_controller_account.key,
+        underlying_index_account,
+    )?;
+    require!(
+        underlying_index.is_initialized(),
+        ProtocolError::IndexNotInitialized.into()
+    );
+
+    let underlying_seed = underlying_index.address_seed();
+    let underlying_index_pda = create_index_address_from_seed(
+        program_id,
+        underlying_controller_account.key,
+        &underlying_seed,
+        underlying_index.bump,
+    )?;
+    require!(
+        *underlying_index_account.key == underlying_index_pda,
+        ProtocolError::IncorrectIndexAccount.into()
+    );
+
+    let underlying_mint_pda = create_index_mint_address_from_seed(
+        program_id,
+        underlying_controller_account.key,
+        &underlying_seed,
+        underlying_index.mint_bump,
+    )?;
+    require!(
+        *mint == underlying_mint_pda,
+        ProtocolError::InvalidUnderlyingIndex.into()
+    );
+
+    Ok(())
+}
+
+/// Checks that `treasury_account` is the protocol treasury PDA and returns
+/// its bump.
+pub fn check_treasury_account(
+    program_id: &Pubkey,
+    treasury_account: &AccountInfo,
+) -> Result<u8, ProgramError> {
+    let (treasury_pda, treasury_bump) = find_treasury_address(program_id);
+    require!(
+        *treasury_account.key == treasury_pda,
+        ProtocolError::IncorrectTreasuryAccount.into()
+    );
+    Ok(treasury_bump)
+}
+
+/// Transfers a `fee` in lamports from `payer` to the protocol treasury.
+/// Zero fees are skipped without touching the accounts.
+pub fn pay_fee<'a>(
+    payer: &AccountInfo<'a>,
+    treasury_account: &AccountInfo<'a>,
+    system_program_account: &AccountInfo<'a>,
+    fee: u64,
+) -> ProgramResult {
+    if fee == 0 {
+        return Ok(());
+    }
+
+    invoke(
+        &system_instruction::transfer(payer.key, treasury_account.key, fee),
+        &[
+            payer.clone(),
+            treasury_account.clone(),
+            system_program_account.clone(),
+        ],
+    )
+}
+
+/// Checks that `signer` may move `amount` out of `token_account`, either as
+/// its owner or as an SPL delegate with a large enough allowance.
+pub fn check_token_account_authority(
+    token_account: &spl_token_2022::state::Account,
+    signer: &Pubkey,
+    amount: u64,
+) -> ProgramResult {
+    if token_account.owner == *signer {
+        return Ok(());
+    }
+
+    require!(
+        token_account.delegate == COption::Some(*signer),
+        ProtocolError::InvalidTokenAccountAuthority.into()
+    );
+    require!(
+        token_account.delegated_amount >= amount,
+        ProtocolError::InsufficientDelegatedAmount.into()
+    );
+
+    Ok(())
+}
+
+/// Returns `true` if an instruction of this program after the current one
+/// passes `account` at `account_position` and has data accepted by
+/// `is_expected`. Used by flash instructions to require their settlement
+/// later in the same transaction.
+pub fn is_followed_by(
+    program_id: &Pubkey,
+    instructions_sysvar_account: &AccountInfo,
+    account_position: usize,
+    account: &Pubkey,
+    is_expected: impl Fn(&ProtocolInstruction) -> bool,
+) -> Result<bool, ProgramError> {
+    let current_index = load_current_index_checked(instructions_sysvar_account)? as usize;
+    let is_followed = (current_index + 1..)
+        .map_while(|position| {
+            load_instruction_at_checked(position, instructions_sysvar_account).ok()
+        })
+        .any(|instruction| {
+            instruction.program_id == *program_id
+                && instruction
+                    .accounts
+                    .get(account_position)
+                    .is_some_and(|meta| meta.pubkey == *account)
+                && ProtocolInstruction::try_from_slice(&instruction.data)
+                    .is_ok_and(|data| is_expected(&data))
+        });
+
+    Ok(is_followed)
+}
@@ -0,0 +1,35 @@
+use num_derive::FromPrimitive;
+use solana_program::{
+    decode_error::DecodeError,
+    msg,
+    program_error::{PrintProgramError, ProgramError},
+};
+use thiserror::Error;
+
+#[derive(Clone, Debug, Eq, Error, FromPrimitive, PartialEq)]
+pub enum TransferRestrictionError {
+    #[error("Error:Source owner is not on the index transfer allowlist")]
+    SourceNotAllowlisted = 600,
+    #[error("Error:Destination owner is not on the index transfer allowlist")]
+    DestinationNotAllowlisted,
+    #[error("Error:Extra account metas cannot be updated")]
+    ExtraAccountMetasImmutable,
+}
+
+impl From<TransferRestrictionError> for ProgramError {
+    fn from(e: TransferRestrictionError) -> Self {
+        ProgramError::Custom(e as u32)
+    }
+}
+
+impl PrintProgramError for TransferRestrictionError {
+    fn print<E>(&self) {
+        msg!("TRANSFER-HOOK-ERROR: {}", &self.to_string());
+    }
+}
+
+impl<T> DecodeError<T> for TransferRestrictionError {
+    fn type_of() -> &'static str {
+        "Transfer Restriction Error"
+    }
+}
@@ -0,0 +1,8 @@
+//! Transfer hook restricting Token-2022 index mints to the wallets on the
+//! OpenIndex transfer allowlist of the index.
+
+pub mod entrypoint;
+pub mod error;
+pub mod processor;
+
+pub use openindex_sdk::openindex::transfer_hook::TRANSFER_HOOK_PROGRAM_ID as ID;
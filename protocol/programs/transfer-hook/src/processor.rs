@@ -0,0 +1,172 @@
+//! Program state processor
+
+use crate::error::TransferRestrictionError;
+use openindex_sdk::require;
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    program::invoke_signed,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    system_instruction,
+};
+use spl_tlv_account_resolution::{account::ExtraAccountMeta, state::ExtraAccountMetaList};
+use spl_token_2022::{
+    extension::{transfer_hook, StateWithExtensions},
+    state::Mint,
+};
+use spl_transfer_hook_interface::{
+    collect_extra_account_metas_signer_seeds,
+    error::TransferHookError,
+    get_extra_account_metas_address, get_extra_account_metas_address_and_bump_seed,
+    instruction::{ExecuteInstruction, TransferHookInstruction},
+};
+
+pub fn process_instruction(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    input: &[u8],
+) -> ProgramResult {
+    match TransferHookInstruction::unpack(input)? {
+        TransferHookInstruction::Execute { .. } => process_execute(program_id, accounts, input),
+        TransferHookInstruction::InitializeExtraAccountMetaList {
+            extra_account_metas,
+        } => process_initialize_extra_account_meta_list(program_id, accounts, &extra_account_metas),
+        TransferHookInstruction::UpdateExtraAccountMetaList { .. } => {
+            Err(TransferRestrictionError::ExtraAccountMetasImmutable.into())
+        }
+    }
+}
+
+/// instruction to process checking a transfer against the index allowlist
+///
+/// The extra accounts written by OpenIndex at index creation are the
+/// OpenIndex program followed by the allowlist entries of the source and
+/// destination owners.
+fn process_execute(program_id: &Pubkey, accounts: &[AccountInfo], input: &[u8]) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let _source_account = next_account_info(accounts_iter)?;
+    let mint_account = next_account_info(accounts_iter)?;
+    let _destination_account = next_account_info(accounts_iter)?;
+    let _authority = next_account_info(accounts_iter)?;
+    let extra_account_metas_account = next_account_info(accounts_iter)?;
+    let openindex_program_account = next_account_info(accounts_iter)?;
+    let source_entry_account = next_account_info(accounts_iter)?;
+    let destination_entry_account = next_account_info(accounts_iter)?;
+
+    require!(
+        *extra_account_metas_account.key
+            == get_extra_account_metas_address(mint_account.key, program_id),
+        TransferHookError::IncorrectAccount.into()
+    );
+
+    ExtraAccountMetaList::check_account_infos::<ExecuteInstruction>(
+        accounts,
+        input,
+        program_id,
+        &extra_account_metas_account.data.borrow(),
+    )?;
+
+    require!(
+        is_allowlisted(openindex_program_account, source_entry_account),
+        TransferRestrictionError::SourceNotAllowlisted.into()
+    );
+
+    require!(
+        is_allowlisted(openindex_program_account, destination_entry_account),
+        TransferRestrictionError::DestinationNotAllowlisted.into()
+    );
+
+    Ok(())
+}
+
+/// instruction to process writing the extra accounts of `Execute` for a mint
+///
+/// Only the mint authority can write them, and only for mints whose
+/// transfer hook is this program. The account must already hold the
+/// lamports for its rent.
+fn process_initialize_extra_account_meta_list(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    extra_account_metas: &[ExtraAccountMeta],
+) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let extra_account_metas_account = next_account_info(accounts_iter)?;
+    let mint_account = next_account_info(accounts_iter)?;
+    let authority = next_account_info(accounts_iter)?;
+    let system_program_account = next_account_info(accounts_iter)?;
+
+    require!(
+        *mint_account.owner == spl_token_2022::ID,
+        ProgramError::InvalidAccountOwner
+    );
+
+    {
+        let mint_data = mint_account.data.borrow();
+        let mint = StateWithExtensions::<Mint>::unpack(&mint_data)?;
+
+        let mint_authority = Option::<Pubkey>::from(mint.base.mint_authority)
+            .ok_or(TransferHookError::MintHasNoMintAuthority)?;
+        require!(
+            *authority.key == mint_authority,
+            TransferHookError::IncorrectMintAuthority.into()
+        );
+
+        require!(
+            transfer_hook::get_program_id(&mint) == Some(*program_id),
+            TransferHookError::IncorrectAccount.into()
+        );
+    }
+
+    require!(authority.is_signer, ProgramError::MissingRequiredSignature);
+
+    let (extra_account_metas_pda, bump) =
+        get_extra_account_metas_address_and_bump_seed(mint_account.key, program_id);
+
+    require!(
+        *extra_account_metas_account.key == extra_account_metas_pda,
+        TransferHookError::IncorrectAccount.into()
+    );
+
+    require!(
+        extra_account_metas_account.data_is_empty(),
+        ProgramError::AccountAlreadyInitialized
+    );
+
+    let space = ExtraAccountMetaList::size_of(extra_account_metas.len())?;
+    let bump_seed = [bump];
+    let signer_seeds = collect_extra_account_metas_signer_seeds(mint_account.key, &bump_seed);
+
+    invoke_signed(
+        &system_instruction::allocate(extra_account_metas_account.key, space as u64),
+        &[
+            extra_account_metas_account.clone(),
+            system_program_account.clone(),
+        ],
+        &[&signer_seeds],
+    )?;
+
+    invoke_signed(
+        &system_instruction::assign(extra_account_metas_account.key, program_id),
+        &[
+            extra_account_metas_account.clone(),
+            system_program_account.clone(),
+        ],
+        &[&signer_seeds],
+    )?;
+
+    ExtraAccountMetaList::init::<ExecuteInstruction>(
+        &mut extra_account_metas_account.data.borrow_mut(),
+        extra_account_metas,
+    )?;
+
+    Ok(())
+}
+
+/// Allowlist entries are owned by OpenIndex and closed on removal, so a
+/// wallet is allowed exactly while its entry is funded and holds data.
+fn is_allowlisted(openindex_program_account: &AccountInfo, entry_account: &AccountInfo) -> bool {
+    entry_account.owner == openindex_program_account.key
+        && entry_account.lamports() > 0
+        && !entry_account.data_is_empty()
+}
@@ -85,6 +85,154 @@ pub enum ProtocolError {
     ComponentNotInitialized,
     #[error("Error:Index not initialized")]
     IndexNotInitialized,
+    #[error("Error:Incorrect mint request account")]
+    IncorrectMintRequestAccount,
+    #[error("Error:Invalid mint request account data")]
+    InvalidMintRequestAccountData,
+    #[error("Error:Only mint request owner can execute this instruction")]
+    OnlyMintRequestOwner,
+    #[error("Error:Component already deposited")]
+    ComponentAlreadyDeposited,
+    #[error("Error:Component not deposited")]
+    ComponentNotDeposited,
+    #[error("Error:Mint request incomplete")]
+    MintRequestIncomplete,
+    #[error("Error:Incorrect redeem request account")]
+    IncorrectRedeemRequestAccount,
+    #[error("Error:Invalid redeem request account data")]
+    InvalidRedeemRequestAccountData,
+    #[error("Error:Only redeem request owner can execute this instruction")]
+    OnlyRedeemRequestOwner,
+    #[error("Error:Component already withdrawn")]
+    ComponentAlreadyWithdrawn,
+    #[error("Error:Incorrect program data account")]
+    IncorrectProgramDataAccount,
+    #[error("Error:Only the program upgrade authority can execute this instruction")]
+    OnlyUpgradeAuthority,
+    #[error("Error:Max index components below the largest existing index")]
+    MaxIndexComponentsBelowLargestIndex,
+    #[error("Error:Incorrect controller config account")]
+    IncorrectControllerConfigAccount,
+    #[error("Error:Invalid controller config account data")]
+    InvalidControllerConfigAccountData,
+    #[error("Error:Component mint not allowed by the controller config")]
+    ComponentMintNotAllowed,
+    #[error("Error:Fee basis points exceed 10000")]
+    InvalidFeeBps,
+    #[error("Error:Incorrect controller creator account")]
+    IncorrectControllerCreatorAccount,
+    #[error("Error:Signer is not an allowlisted controller creator")]
+    ControllerCreatorNotAllowed,
+    #[error("Error:Incorrect treasury account")]
+    IncorrectTreasuryAccount,
+    #[error("Error:Insufficient treasury balance")]
+    InsufficientTreasuryBalance,
+    #[error("Error:Incorrect controller counter account")]
+    IncorrectControllerCounterAccount,
+    #[error("Error:Invalid index seed")]
+    InvalidIndexSeed,
+    #[error("Error:Incorrect controller roles account")]
+    IncorrectControllerRolesAccount,
+    #[error("Error:Invalid controller roles account data")]
+    InvalidControllerRolesAccountData,
+    #[error("Error:Signer is neither the controller owner nor a holder of the required role")]
+    MissingControllerRole,
+    #[error("Error:Controller role already granted")]
+    ControllerRoleAlreadyGranted,
+    #[error("Error:Controller role not granted")]
+    ControllerRoleNotGranted,
+    #[error("Error:Multisig members must be unique and between 1 and 11")]
+    InvalidMultisigMembers,
+    #[error("Error:Multisig threshold must be between 1 and the number of members")]
+    InvalidMultisigThreshold,
+    #[error("Error:Incorrect multisig account")]
+    IncorrectMultisigAccount,
+    #[error("Error:Invalid multisig account data")]
+    InvalidMultisigAccountData,
+    #[error("Error:Not enough multisig members signed")]
+    MultisigThresholdNotMet,
+    #[error("Error:Only index owner can execute this instruction")]
+    OnlyIndexOwner,
+    #[error("Error:Ownership of this account cannot be transferred")]
+    UnsupportedOwnershipTransfer,
+    #[error("Error:Only index manager can execute this instruction")]
+    OnlyIndexManager,
+    #[error("Error:Composition delay must not be negative")]
+    InvalidCompositionDelay,
+    #[error("Error:Composition delay can only be increased")]
+    CompositionDelayDecreased,
+    #[error("Error:Incorrect pending composition change account")]
+    IncorrectPendingCompositionChangeAccount,
+    #[error("Error:Invalid pending composition change account data")]
+    InvalidPendingCompositionChangeAccountData,
+    #[error("Error:A composition change is already queued for this index")]
+    CompositionChangeAlreadyQueued,
+    #[error("Error:Composition change delay has not elapsed")]
+    CompositionChangeTimelocked,
+    #[error("Error:Index composition changed since the expected version")]
+    CompositionVersionMismatch,
+    #[error("Error:Component amount exceeds the maximum amount in")]
+    MaxAmountInExceeded,
+    #[error("Error:Component amount is below the minimum amount out")]
+    MinAmountOutNotMet,
+    #[error("Error:Index token account is not owned by the recipient")]
+    IncorrectRecipient,
+    #[error("Error:Signer is neither the owner nor the delegate of the token account")]
+    InvalidTokenAccountAuthority,
+    #[error("Error:Delegated amount is below the component amount")]
+    InsufficientDelegatedAmount,
+    #[error("Error:Destination is not the recipient's associated token account")]
+    IncorrectDestinationAccount,
+    #[error("Error:Only the signer's own wrapped SOL account can be unwrapped")]
+    InvalidUnwrapRecipient,
+    #[error("Error:Component mint is not the mint of the given underlying index")]
+    InvalidUnderlyingIndex,
+    #[error("Error:Underlying index account does not match the nested component")]
+    IncorrectUnderlyingIndexAccount,
+    #[error("Error:Indexes are nested deeper than RedeemUnderlying supports")]
+    MaxNestingDepthExceeded,
+    #[error("Error:FlashMint must be settled by a later SettleFlashMint in the same transaction")]
+    MissingFlashMintSettlement,
+    #[error("Error:Incorrect flash mint account")]
+    IncorrectFlashMintAccount,
+    #[error("Error:Invalid flash mint account data")]
+    InvalidFlashMintAccountData,
+    #[error("Error:Flash loans are not enabled for this index")]
+    FlashLoansDisabled,
+    #[error("Error:FlashBorrow must be repaid by a later FlashRepay in the same transaction")]
+    MissingFlashRepay,
+    #[error("Error:Incorrect flash loan account")]
+    IncorrectFlashLoanAccount,
+    #[error("Error:Invalid flash loan account data")]
+    InvalidFlashLoanAccountData,
+    #[error("Error:The index mint has no freeze authority")]
+    IndexNotFreezable,
+    #[error("Error:Incorrect extra account metas account")]
+    IncorrectExtraAccountMetasAccount,
+    #[error("Error:The index mint has no transfer hook")]
+    TransferHookNotEnabled,
+    #[error("Error:Incorrect transfer allowlist account")]
+    IncorrectTransferAllowlistAccount,
+    #[error("Error:Incorrect asset registry account")]
+    IncorrectAssetRegistryAccount,
+    #[error("Error:The component mint is not in the asset registry")]
+    AssetNotRegistered,
+    #[error("Error:The component mint carries a risk its registry entry does not accept")]
+    AssetRiskNotAccepted,
+    #[error("Error:Invalid asset risk flags")]
+    InvalidAssetRisks,
+    #[error("Error:The same component mint is listed more than once")]
+    DuplicateComponentMint,
+    #[error("Error:Component units must be greater than zero")]
+    ZeroComponentUnits,
+    #[error("Error:The index mint cannot be a component of its own index")]
+    IndexMintAsComponent,
+    #[error("Error:A component vault does not back the outstanding index supply")]
+    ComponentVaultUnderfunded,
+    #[error("Error:Only the OpenIndex transfer hook program can be registered")]
+    UnsupportedTransferHookProgram,
+    #[error("Error:The fee exceeds the controller fee cap")]
+    FeeAboveControllerCap,
 }
 
 impl From<ProtocolError> for ProgramError {
@@ -3,10 +3,70 @@ use borsh::BorshSerialize;
 use solana_program::instruction::{AccountMeta, Instruction};
 use solana_program::pubkey::Pubkey;
 use solana_program::system_program;
+use solana_loader_v3_interface::get_program_data_address;
 use spl_associated_token_account::get_associated_token_address_with_program_id;
+use spl_transfer_hook_interface::get_extra_account_metas_address;
 
+use super::pda::controller_counter_shard;
+use super::pda::find_asset_registry_address;
 use super::pda::find_component_address;
 use super::pda::find_component_vault_address;
+use super::pda::find_controller_config_address;
+use super::pda::find_controller_counter_address;
+use super::pda::find_controller_creator_address;
+use super::pda::find_controller_global_config_address;
+use super::pda::find_controller_roles_address;
+use super::pda::find_flash_loan_address;
+use super::pda::find_flash_mint_address;
+use super::pda::find_index_address;
+use super::pda::find_index_mint_address;
+use super::pda::find_index_mint_authority_address;
+use super::pda::find_index_mints_data_address;
+use super::pda::find_mint_request_address;
+use super::pda::find_multisig_address;
+use super::pda::find_pending_composition_change_address;
+use super::pda::find_redeem_request_address;
+use super::pda::find_transfer_allowlist_address;
+use super::pda::find_treasury_address;
+
+/// Who may create controllers, stored in `ControllerGlobalConfig` and set by
+/// `UpdateControllerGlobalConfig`.
+#[derive(BorshDeserialize, BorshSerialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ControllerCreationPolicy {
+    /// Any wallet can create a controller.
+    #[default]
+    Open,
+    /// Only wallets approved through `SetControllerCreator`.
+    Allowlist,
+    /// Any wallet that pays `controller_creation_fee` lamports to the treasury.
+    Fee,
+}
+
+/// Delegated permission on a single controller, granted by the controller
+/// owner through `GrantControllerRole`. The owner implicitly holds every role.
+#[derive(BorshDeserialize, BorshSerialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ControllerRole {
+    /// May call `CreateIndex` and `CreateIndexWithSeed`.
+    IndexCreator,
+    /// May call `AddIndexComponents`.
+    ComponentManager,
+    /// May call `FreezeHolder` and `ThawHolder` on the controller's indexes.
+    ComplianceOfficer,
+}
+
+/// Mint authority set on a component mint; it can freeze the vaults.
+pub const ASSET_RISK_FREEZE_AUTHORITY: u8 = 1 << 0;
+/// Token-2022 permanent delegate; it can move tokens out of the vaults.
+pub const ASSET_RISK_PERMANENT_DELEGATE: u8 = 1 << 1;
+/// Token-2022 mint close authority; it can close the mint once supply is zero.
+pub const ASSET_RISK_CLOSE_AUTHORITY: u8 = 1 << 2;
+/// Token-2022 transfer hook; a third-party program runs on every transfer.
+pub const ASSET_RISK_TRANSFER_HOOK: u8 = 1 << 3;
+/// Every risk flag `SetAssetRegistryEntry` accepts.
+pub const ASSET_RISK_ALL: u8 = ASSET_RISK_FREEZE_AUTHORITY
+    | ASSET_RISK_PERMANENT_DELEGATE
+    | ASSET_RISK_CLOSE_AUTHORITY
+    | ASSET_RISK_TRANSFER_HOOK;
 
 #[derive(BorshDeserialize, BorshSerialize, Debug, PartialEq)]
 pub enum ProtocolInstruction {
@@ -26,16 +86,22 @@ pub enum ProtocolInstruction {
     ///     * `bump`        = PDA bump seed
     ///     * All config fields zero-initialised / default.
     ///
+    /// * Only the program's **upgrade authority** may call it, so the
+    ///   protocol cannot be claimed by front-running the deployer.
+    ///
     /// ### Accounts
-    /// 0. `[signer]`            signer                   – the wallet that pays rent and becomes protocol authority  
+    /// 0. `[signer]`            signer                   – the program upgrade authority; pays rent and becomes protocol authority  
     /// 1. `[writable]`          protocol_account         – PDA derived with seed `b"protocol"`  
-    /// 2. `[]`                  system_program_account   – `solana_program::system_program`
+    /// 2. `[]`                  system_program_account   – `solana_program::system_program`  
+    /// 3. `[]`                  program_data_account     – upgradeable loader `ProgramData` of this program
     ///
     /// ### Instruction data
     /// * _none_ (the call is self-contained)
     ///
     /// ### Fails with
-    /// * `AccountAlreadyInitialized` if `protocol_account` already has lamports  
+    /// * `IncorrectProgramDataAccount` if `program_data_account` is not this program's `ProgramData`  
+    /// * `OnlyUpgradeAuthority`      if signer ≠ program upgrade authority  
+    /// * `AccountAlreadyInitialized` if `protocol_account` was already created  
     /// * `IncorrectProtocolAccount`  if the PDA derivation doesn’t match  
     /// * `MissingRequiredSignature`  if `signer` did not sign
     InitProtocol,
@@ -61,12 +127,20 @@ pub enum ProtocolInstruction {
     /// 
     /// * Persists the updated `protocol_account` (so the next call will get
     ///   the next unique controller ID).
+    /// * Enforces `controller_global_config.controller_creation_policy`:
+    ///   `Allowlist` requires an approved `controller_creator_account`, `Fee`
+    ///   transfers `controller_creation_fee` lamports to the treasury. A
+    ///   missing global config behaves like `Open`.
     ///
     /// ### Accounts
-    /// 0. `[signer]`            signer                   – wallet that pays rent and becomes controller authority  
-    /// 1. `[writable]`          protocol_account         – protocol PDA (must already exist)  
-    /// 2. `[writable]`          controller_account       – PDA derived from (`b"controller"`, controller_id)  
-    /// 3. `[]`                  system_program           – `solana_program::system_program`
+    /// 0. `[signer]`            signer                             – wallet that becomes controller authority  
+    /// 1. `[signer, writable]`  payer                              – pays rent and the creation fee  
+    /// 2. `[writable]`          protocol_account                   – protocol PDA (must already exist)  
+    /// 3. `[writable]`          controller_account                 – PDA derived from (`b"controller"`, controller_id)  
+    /// 4. `[]`                  system_program                     – `solana_program::system_program`  
+    /// 5. `[]`                  controller_global_config_account   – global config PDA; may be uncreated  
+    /// 6. `[]`                  controller_creator_account         – PDA (`b"controller_creator"`, signer); may be uncreated  
+    /// 7. `[writable]`          treasury_account                   – PDA (`b"treasury"`) receiving the creation fee
     ///
     /// ### Instruction data
     /// * _none_ (all data implicit)
@@ -75,7 +149,9 @@ pub enum ProtocolInstruction {
     /// * `ProtocolNotInitialized`        if protocol_account is still zeroed  
     /// * `IncorrectProtocolAccount`      if PDA derivation mismatches provided account  
     /// * `AccountAlreadyInitialized`     if controller_account is non-empty  
-    /// * `MissingRequiredSignature`      if signer did not sign
+    /// * `ControllerCreatorNotAllowed`   if the allowlist policy is active and signer is not approved  
+    /// * `IncorrectTreasuryAccount`      if the treasury PDA mismatches  
+    /// * `MissingRequiredSignature`      if signer or payer did not sign
 
     InitController,
 
@@ -94,7 +170,9 @@ pub enum ProtocolInstruction {
     /// 
     /// * Confirms `protocol_account` is the correct PDA and already
     ///   initialised.  
-    /// * Ensures the caller (`signer`) is exactly `protocol.owner`.  
+    /// * Ensures the caller (`signer`) is `protocol.owner`, or approves for
+    ///   it when the owner is a multisig. Only the upgrade authority can
+    ///   become the owner through `InitProtocol`.  
     /// 
     /// * Derives PDA `find_controller_global_config_address()` and creates
     ///   an account of size `ControllerGlobalConfig::LEN`, funded to
@@ -108,7 +186,9 @@ pub enum ProtocolInstruction {
     /// 0. `[signer]`            signer                             – **must** be the protocol owner  
     /// 1. `[]`                  protocol_account                   – protocol PDA (already exists)  
     /// 2. `[writable]`          controller_global_config_account   – PDA derived from `b"controller_global_config"`  
-    /// 3. `[]`                  system_program_account             – `solana_program::system_program`
+    /// 3. `[]`                  system_program_account             – `solana_program::system_program`  
+    /// * `[]`                   multisig_account                   – optional, when the protocol owner is a multisig  
+    /// * `[signer]`             member[i]                          – optional, approving multisig members
     ///
     /// ### Instruction data
     /// * `max_index_components: u32`
@@ -117,6 +197,7 @@ pub enum ProtocolInstruction {
     /// * `InvalidMaxIndexComponents`        if the provided max is zero  
     /// * `ProtocolNotInitialized`           if protocol_account is still zeroed  
     /// * `OnlyProtocolOwner`                if signer ≠ protocol.owner  
+    /// * `MultisigThresholdNotMet`          if too few members signed  
     /// * `IncorrectControllerGlobalConfigAccount` if PDA derivation mismatches  
     /// * `AccountAlreadyInitialized`        if controller_global_config_account already has lamports  
     /// * `MissingRequiredSignature`         if signer did not sign
@@ -153,10 +234,11 @@ pub enum ProtocolInstruction {
     ///
     /// ### Accounts
     /// 0. `[signer]`            signer                       – **must** be the protocol owner  
-    /// 1. `[]`                  protocol_account             – protocol PDA  
-    /// 2. `[]`                  module_signer_account        – the external program’s signer / upgrade authority  
-    /// 3. `[writable]`          registered_module_account    – PDA derived from (`b"module"`, module_signer_account)  
-    /// 4. `[]`                  system_program               – `solana_program::system_program`
+    /// 1. `[signer, writable]`  payer                        – pays rent  
+    /// 2. `[]`                  protocol_account             – protocol PDA  
+    /// 3. `[]`                  module_signer_account        – the external program’s signer / upgrade authority  
+    /// 4. `[writable]`          registered_module_account    – PDA derived from (`b"module"`, module_signer_account)  
+    /// 5. `[]`                  system_program               – `solana_program::system_program`
     ///
     /// ### Instruction data
     /// * _none_
@@ -166,7 +248,7 @@ pub enum ProtocolInstruction {
     /// * `OnlyProtocolOwner`                if signer ≠ protocol.owner  
     /// * `IncorrectModuleAccount`           if PDA derivation mismatches provided account  
     /// * `AccountAlreadyInitialized`        if registered_module_account already has lamports  
-    /// * `MissingRequiredSignature`         if signer did not sign
+    /// * `MissingRequiredSignature`         if signer or payer did not sign
     InitModule,
     
     /// 4. **CreateIndex**
@@ -191,34 +273,62 @@ pub enum ProtocolInstruction {
     ///     * `mint_account`   (seed `b"index_mint"`)  
     /// 
     /// * Allocates both accounts rent-exempt and initialises the mint with
-    ///   `decimals = 9`.  
+    ///   `controller_config.default_decimals`, or `decimals = 9` when the
+    ///   controller has no config or leaves it unset.  
     /// 
-    /// * Serialises an `Index { id, owner = signer, manager, bump }`.  
+    /// * Serialises an `Index { id, owner = controller.owner, manager, bump }` together
+    ///   with the mint, mint-authority and index-mints PDA bumps, so later
+    ///   instructions never need `find_program_address`.  
     /// * Serialises the updated `Controller`, so the next call gets a fresh
     ///   `index_id`.
+    /// * Transfers `controller_global_config.index_creation_fee` lamports, if
+    ///   any, from the payer to the treasury.
+    /// * Makes the mint-authority PDA the freeze authority of the mint, used
+    ///   by `FreezeHolder` and `ThawHolder`, unless `drop_freeze_authority`
+    ///   creates a permissionless index whose holders can never be frozen.
+    /// * With `transfer_hook_program`, creates a Token-2022 mint whose
+    ///   transfers are checked by that program, and registers the transfer
+    ///   allowlist entries of both token account owners as its extra
+    ///   accounts. Holders are then managed through `SetTransferAllowlist`.
     ///
     /// ### Accounts
-    /// 0. `[signer]`            signer                             – **must** be `controller.owner` and pays rent  
-    /// 1. `[]`                  manager                            – delegate that will manage the index  
-    /// 2. `[writable]`          index_account                      – PDA (`b"index"`, controller_account, index_id)  
-    /// 3. `[writable]`          mint_account                       – PDA (`b"index_mint"`, controller_account, index_id)  
-    /// 4. `[writable]`          controller_account                 – controller PDA (mutated to bump next_index_id)  
-    /// 5. `[]`                  controller_global_config_account   – global config PDA (read-only)  
-    /// 6. `[]`                  system_program_account             – `solana_program::system_program`  
-    /// 7. `[]`                  token_program_account              – `spl_token::id()`
+    /// 0. `[signer]`            signer                             – `controller.owner` or an `IndexCreator`  
+    /// 1. `[signer, writable]`  payer                              – pays rent and the index creation fee  
+    /// 2. `[]`                  manager                            – delegate that will manage the index  
+    /// 3. `[writable]`          index_account                      – PDA (`b"index"`, controller_account, index_id)  
+    /// 4. `[writable]`          mint_account                       – PDA (`b"index_mint"`, controller_account, index_id)  
+    /// 5. `[writable]`          controller_account                 – controller PDA (mutated to bump next_index_id)  
+    /// 6. `[]`                  controller_global_config_account   – global config PDA (read-only)  
+    /// 7. `[]`                  controller_config_account          – PDA (`b"controller_config"`, controller_account); may be uncreated  
+    /// 8. `[]`                  system_program_account             – `solana_program::system_program`  
+    /// 9. `[]`                  token_program_account              – `spl_token::id()`, or `spl_token_2022::id()` with `transfer_hook_program`  
+    /// 10. `[writable]`         treasury_account                   – PDA (`b"treasury"`) receiving the index creation fee  
+    /// 11. `[]`                 controller_roles_account           – PDA (`b"controller_roles"`, controller_account); may be uncreated  
+    /// 12. `[]`                 transfer_hook_program_account      – only with `transfer_hook_program`  
+    /// 13. `[writable]`         extra_account_metas_account        – only with `transfer_hook_program`; PDA of the hook for the mint  
+    /// 14. `[]`                 mint_authority_account             – only with `transfer_hook_program`; PDA (`b"index_mint_authority"`, controller_account, index_id)
     ///
     /// ### Instruction data
-    /// * _none_ (all data is implicit)
+    /// * `drop_freeze_authority: bool`            – create the mint without a freeze authority  
+    /// * `transfer_hook_program: Option<Pubkey>`  – restrict transfers to the index allowlist; must be `TRANSFER_HOOK_PROGRAM_ID` and requires `spl_token_2022::id()` as token program
     ///
     /// ### Fails with
-    /// * `OnlyControllerOwner`                 if signer ≠ controller.owner  
+    /// * `MissingControllerRole`               if signer is neither controller.owner nor an `IndexCreator`  
+    /// * `IncorrectControllerRolesAccount`     if the controller roles PDA mismatches  
     /// * `ControllerGlobalConfigNotInitialized` if global config is zeroed  
+    /// * `IncorrectControllerConfigAccount`    if the controller config PDA mismatches  
+    /// * `IncorrectTreasuryAccount`            if the treasury PDA mismatches  
     /// * `IncorrectIndexAccount` / `IncorrectMintAccount`
     ///   if PDA derivations don’t match supplied accounts  
     /// * `AccountAlreadyInitialized`
     ///   if `index_account` or `mint_account` already carry lamports  
-    /// * `MissingRequiredSignature`            if signer did not sign
-    CreateIndex,
+    /// * `UnsupportedTransferHookProgram`      if `transfer_hook_program` is not `TRANSFER_HOOK_PROGRAM_ID`  
+    /// * `IncorrectExtraAccountMetasAccount`   if the extra account metas PDA of the hook mismatches  
+    /// * `MissingRequiredSignature`            if signer or payer did not sign
+    CreateIndex {
+        drop_freeze_authority: bool,
+        transfer_hook_program: Option<Pubkey>,
+    },
 
     /// 5. **AddIndexComponents**
     ///
@@ -243,8 +353,22 @@ pub enum ProtocolInstruction {
     /// * Checks `signer == controller.owner`; only controller owner may add
     ///   components.  
     /// * Enforces `mints.len() > 0` and that it does not exceed
+    ///   `controller_config.max_index_components`, falling back to
     ///   `controller_global_config.max_index_components`.  
-    /// * Requires `mints.len() == amounts.len()`.  
+    /// * Rejects mints missing from a non-empty
+    ///   `controller_config.allowed_mints`.  
+    /// * While `controller_global_config.asset_registry_required` is set and
+    ///   the controller config does not set `override_asset_checks`, rejects
+    ///   mints without an `AssetRegistryEntry` and mints whose freeze
+    ///   authority or Token-2022 extensions are not in the entry's
+    ///   `accepted_risks`.  
+    /// * Requires `mints.len() == amounts.len()`, non-zero amounts and
+    ///   distinct mints other than the index mint, checked before any
+    ///   account is created.  
+    /// * Raises `controller_global_config.largest_index_components` when this
+    ///   index is the largest one so far.  
+    /// * Increments `index.composition_version`, which `Mint` and `Redeem`
+    ///   callers can pin with `expected_version`.  
     /// * Derives `index_mints_account` PDA, allocates it rent-exempt with
     ///   size `IndexMints::calc_len(mints.len())`, and serialises
     ///   `IndexMints { mints, bump }`.  
@@ -254,17 +378,23 @@ pub enum ProtocolInstruction {
     ///     * `vault_pda` & `vault_ata`       (`b"component_vault"`, …)  
     ///   Allocates the component account and creates the vault ATA via
     ///   `spl_associated_token_account::create`.  
-    ///   Serialises `Component { units = amount, mint, bumps }`.
+    ///   Serialises `Component { units = amount, mint, bumps }`.  
+    /// * For components flagged in `nested_components`, checks that the mint
+    ///   is the index mint of the given underlying index, which must not be
+    ///   this index, and records it as `component.underlying_index`.
     ///
     /// ### Accounts
-    /// 0. `[signer]`            signer                             – **must** be `controller.owner`  
-    /// 1. `[]`                  index_account                      – index PDA  
-    /// 2. `[writable]`          index_mints_account                – PDA (`b"index_mints"`, controller_account, index_id) (created)  
-    /// 3. `[]`                  controller_account                 – controller PDA  
-    /// 4. `[]`                  controller_global_config_account   – global config PDA (read-only)  
-    /// 5. `[]`                  system_program_account             – `solana_program::system_program`  
-    /// 6. `[]`                  associated_token_program_account   – `spl_associated_token_account::id()`  
-    /// 7. `[]`                  token_program_account              – `spl_token::id()`
+    /// 0. `[signer]`            signer                             – `controller.owner` or a `ComponentManager`  
+    /// 1. `[signer, writable]`  payer                              – pays rent  
+    /// 2. `[writable]`          index_account                      – index PDA (bumps `composition_version`)  
+    /// 3. `[writable]`          index_mints_account                – PDA (`b"index_mints"`, controller_account, index_id) (created)  
+    /// 4. `[]`                  controller_account                 – controller PDA  
+    /// 5. `[writable]`          controller_global_config_account   – global config PDA (tracks the largest index)  
+    /// 6. `[]`                  controller_config_account          – PDA (`b"controller_config"`, controller_account); may be uncreated  
+    /// 7. `[]`                  controller_roles_account           – PDA (`b"controller_roles"`, controller_account); may be uncreated  
+    /// 8. `[]`                  system_program_account             – `solana_program::system_program`  
+    /// 9. `[]`                  associated_token_program_account   – `spl_associated_token_account::id()`  
+    /// 10. `[]`                 token_program_account              – `spl_token::id()`
     ///
     /// ### Per-component bundle (repeated *N* = `mints.len()` times)
     /// * `[writable]` `mint_account[i]`            – the SPL mint in `mints[i]`  
@@ -272,33 +402,53 @@ pub enum ProtocolInstruction {
     /// * `[]`         `vault_pda[i]`               – PDA (`b"component_vault"`, …)  
     /// * `[writable]` `vault_ata[i]`               – ATA owned by `vault_pda[i]`
     ///
-    /// Total accounts = 8 + *N* × 4
+    /// ### Asset registry accounts (only while registry checks apply, in `mints` order)
+    /// * `[]` `asset_registry_account[i]` – PDA (`b"asset_registry"`, mint); may be uncreated
+    ///
+    /// ### Underlying index pair (repeated for every nested component, in `mints` order)
+    /// * `[]` `underlying_controller_account` – controller PDA of the underlying index  
+    /// * `[]` `underlying_index_account`      – index PDA whose mint is the component
+    ///
+    /// Total accounts = 11 + *N* × 4 + (*N* while registry checks apply) + *nested* × 2
     ///
     /// ### Instruction data
-    /// * `mints:   Vec<Pubkey>` – ordered list of component mints  
-    /// * `amounts: Vec<u64>`    – component units per **one** index token
+    /// * `mints:             Vec<Pubkey>` – ordered list of component mints  
+    /// * `amounts:           Vec<u64>`    – component units per **one** index token  
+    /// * `nested_components: Vec<bool>`   – which mints are index mints; empty when none are
     ///
     /// ### Fails with
-    /// * `OnlyControllerOwner`                 if signer ≠ controller.owner  
+    /// * `MissingControllerRole`               if signer is neither controller.owner nor a `ComponentManager`  
+    /// * `IncorrectControllerRolesAccount`     if the controller roles PDA mismatches  
     /// * `NoMintsProvided`                     if `mints` is empty  
-    /// * `MaxIndexComponentsExceeded`          if `mints.len()` exceeds global cap  
+    /// * `MaxIndexComponentsExceeded`          if `mints.len()` exceeds the controller or global cap  
+    /// * `ComponentMintNotAllowed`             if a mint is not in the controller's allow-list  
     /// * `MintsAmountsLenMismatch`             if lengths differ  
+    /// * `ZeroComponentUnits`                  if an amount is zero  
+    /// * `DuplicateComponentMint`              if a mint is listed twice  
+    /// * `IndexMintAsComponent`                if a mint is this index's own mint  
     /// * `InvalidMintAccount`                  if a supplied mint_account ≠ `mints[i]`  
+    /// * `InvalidUnderlyingIndex`              if a nested mint is not the underlying index mint, or is this index's own  
+    /// * `IncorrectAssetRegistryAccount`       if an asset registry PDA mismatches  
+    /// * `AssetNotRegistered`                  if registry checks apply and a mint has no entry  
+    /// * `AssetRiskNotAccepted`                if registry checks apply and a mint carries an unaccepted risk  
+    /// * `NotEnoughAccountKeys`                if registry checks apply and the asset registry accounts are missing  
     /// * `IncorrectComponentAccount`, `IncorrectVaultAccount`,  
     ///   `IncorrectVaultATA`                   if PDA derivations mismatch  
     /// * `AccountAlreadyInitialized`           if any PDA already holds lamports  
-    /// * `MissingRequiredSignature`            if signer did not sign
+    /// * `MissingRequiredSignature`            if signer or payer did not sign
     AddIndexComponents {
         amounts: Vec<u64>,
         mints: Vec<Pubkey>,
+        nested_components: Vec<bool>,
     },
 
  
     /// 6. **Mint**
     ///
-    /// Mints `amount` of **index tokens** to the signer and simultaneously
+    /// Mints `amount` of **index tokens** to the recipient and simultaneously
     /// transfers the proportional quantity of each component token from the
-    /// signer into the protocol vaults.
+    /// signer into the protocol vaults. The recipient defaults to the signer,
+    /// so a custodian can deposit while a client receives the index tokens.
     ///
     /// For every component `i`:
     ///
@@ -316,12 +466,19 @@ pub enum ProtocolInstruction {
     ///     * Checks PDA correctness for `component_account`, `vault_pda`,
     ///       `vault_ata`.  
     ///     * Calculates `component_amount = amount × component.units`.  
-    ///     * Executes `spl_token::transfer` from the signer’s
-    ///       `component_token_account` to the vault’s ATA.  
-    /// * Verifies `token_account.mint == mint_account`.  
-    /// * Executes `spl_token::mint_to` (CPI, signed by
-    ///   `mint_authority_pda`) to credit `amount` index tokens to
-    ///   `token_account`.
+    ///     * Requires `component_token_account` to hold the component mint
+    ///       and to be owned by the signer, or to have the signer as its SPL
+    ///       delegate with at least `component_amount` approved.  
+    ///     * Executes `spl_token::transfer` from `component_token_account`
+    ///       to the vault’s ATA, signed by the signer as owner or delegate.  
+    ///     * For the wrapped SOL mint, `component_token_account` may instead
+    ///       be the signer’s wallet: `component_amount` lamports are then
+    ///       transferred to the vault’s ATA followed by `sync_native`.  
+    /// * Verifies `token_account.mint == mint_account` and that
+    ///   `token_account.owner` is `recipient`, or the signer when unset.  
+    /// * Executes `mint_to` (CPI, signed by `mint_authority_pda`) to credit
+    ///   `amount` index tokens to `token_account`, through the Token-2022
+    ///   program for a Token-2022 index mint.
     ///
     /// ### Static accounts (first 8)
     /// 0. `[signer]`            signer                             – caller providing components  
@@ -330,50 +487,72 @@ pub enum ProtocolInstruction {
     /// 3. `[]`                  mint_authority_account             – PDA that signs `mint_to`  
     /// 4. `[]`                  index_account                      – index PDA  
     /// 5. `[]`                  index_mints_account                – PDA holding ordered component mints  
-    /// 6. `[writable]`          token_account                      – signer’s ATA for the **index mint**  
-    /// 7. `[]`                  token_program_account              – `spl_token::id()`
+    /// 6. `[writable]`          token_account                      – recipient’s token account for the **index mint**  
+    /// 7. `[]`                  token_program_account              – `spl_token::id()`, also used for the components
     ///
     /// ### Per-component bundle (repeated *N* = `mints.len()` times)
     /// * `[]` `component_mint_account[i]`      – SPL mint of component *i*  
     /// * `[]`         `component_account[i]`           – component metadata PDA  
     /// * `[]` `vault_pda[i]`                   – PDA owning the vault ATA  
     /// * `[writable]` `vault_ata[i]`                   – ATA holding component *i* inside vault  
-    /// * `[writable]` `component_token_account[i]`     – signer’s ATA for component *i*
+    /// * `[writable]` `component_token_account[i]`     – token account for component *i* owned by or delegated to the signer,
+    ///   or the signer itself to pay wrapped SOL in lamports
+    ///
+    /// ### Trailing account (only when paying wrapped SOL in lamports)
+    /// * `[]` `system_program` – `solana_program::system_program`; the signer must then be writable
     ///
-    /// Total accounts = 8 + *N* × 5
+    /// ### Trailing account (only for a Token-2022 index mint)
+    /// * `[]` `index_token_program_account` – `spl_token_2022::id()`, see `with_token_2022`
+    ///
+    /// Total accounts = 8 + *N* × 5, plus 1 when paying lamports and 1 for a Token-2022 index mint
     ///
     /// ### Instruction data
-    /// * `index_id: u64` – index identifier inside controller  
-    /// * `amount:   u64` – number of index tokens to mint
+    /// * `index_id:         u64`              – index identifier inside controller  
+    /// * `amount:           u64`              – number of index tokens to mint  
+    /// * `expected_version: Option<u64>`      – `index.composition_version` the accounts were built for  
+    /// * `max_amounts_in:   Option<Vec<u64>>` – upper bound on each component deposit, in `mints` order  
+    /// * `recipient:        Option<Pubkey>`   – owner of `token_account`; defaults to the signer
     ///
     /// ### Fails with
     /// * `AmountMustBeGreaterThanZero`          if `amount == 0`  
+    /// * `CompositionVersionMismatch`           if the index composition changed since `expected_version`  
+    /// * `MaxAmountInExceeded`                  if a deposit is above its `max_amounts_in` entry  
+    /// * `MintsAmountsLenMismatch`              if `max_amounts_in` does not match the component count  
     /// * `UnknownControllerAccount`             if controller_account.owner ≠ program_id  
     /// * `UnknownIndexAccount`                  if index_account.owner ≠ program_id  
+    /// * `IncorrectIndexAccount` / `IncorrectMintAccount`
+    ///   if index_account or mint_account mismatch the stored-bump PDAs  
     /// * `IncorrectIndexMintsAccount`           if supplied PDA mismatches derivation  
     /// * `IncorrectMintAuthority`               if mint_authority_account ≠ derived PDA  
     /// * `IncorrectComponentAccount` / `IncorrectVaultAccount` /
     ///   `IncorrectVaultATA`                    if any PDA derivation mismatches  
     /// * `ComponentNotInitialized`              if a component_account is zeroed  
     /// * `InvalidMintAccount`                   if `token_account.mint` ≠ mint_account  
+    /// * `IncorrectRecipient`                   if `token_account` is not owned by the recipient  
+    /// * `InvalidTokenMint`                     if a component token account holds another mint  
+    /// * `InvalidTokenAccountAuthority`         if the signer neither owns nor is delegate of a component token account  
+    /// * `InsufficientDelegatedAmount`          if a delegated allowance is below the component amount  
     /// * `ArithmeticOverflow`                   on `amount × component.units`  
     /// * `MissingRequiredSignature`             if signer did not sign
     Mint {
         index_id: u64,
         amount: u64,
+        expected_version: Option<u64>,
+        max_amounts_in: Option<Vec<u64>>,
+        recipient: Option<Pubkey>,
     },
 
     /// 7. **Redeem**
     ///
-    /// Burns `amount` of index tokens from the signer and returns the
+    /// Burns `amount` of index tokens from `token_account` and returns the
     /// corresponding quantities of each underlying component token from the
-    /// protocol vaults back to the signer.
+    /// protocol vaults to the recipient, which defaults to the signer.
     ///
     /// For every component `i`:
     ///
     /// ```text
-    /// vault_ata[i]  ──►  signer ATA (component)
-    /// signer ATA (index)  ──►  burned
+    /// vault_ata[i]  ──►  recipient ATA (component)
+    /// token_account (index)  ──►  burned
     /// ```
     ///
     /// ### Behaviour
@@ -385,237 +564,2621 @@ pub enum ProtocolInstruction {
     ///     * Checks PDA correctness for `component_account[i]`, `vault_pda[i]`,
     ///       `vault_ata[i]`.  
     ///     * Asserts `component_mint_account[i] == mints[i]`.  
+    ///     * Requires `component_token_account[i]` to be the recipient’s
+    ///       associated token account for `mints[i]`, creating it with the
+    ///       signer as payer when `create_destination_accounts` is set.  
     ///     * Calculates `component_amount = amount × component.units`.  
-    ///     * Executes `spl_token::transfer` from the vault’s ATA to the
-    ///       signer’s `component_token_account[i]` (CPI, signed by
-    ///       `vault_pda[i]`).  
+    ///     * Executes `spl_token::transfer` from the vault’s ATA to
+    ///       `component_token_account[i]` (CPI, signed by `vault_pda[i]`).  
+    ///     * With `unwrap_native`, closes the wrapped SOL destination into
+    ///       the signer’s wallet, so it receives lamports. The recipient must
+    ///       then be the signer, which must be writable.  
     /// 
-    /// * Executes `spl_token::burn` to destroy `amount` index tokens from the
-    ///   signer’s `token_account`.
+    /// * Requires `token_account` to hold the index mint and to be owned by
+    ///   the signer, or to have the signer as SPL delegate for at least
+    ///   `amount`.  
+    /// * Executes `burn` to destroy `amount` index tokens from
+    ///   `token_account`, signed by the signer as owner or delegate, through
+    ///   the Token-2022 program for a Token-2022 index mint.
     ///
     /// ### Static accounts (first 8)
-    /// 0. `[signer]`            signer                             – caller receiving components  
+    /// 0. `[signer]`            signer                             – index token owner or delegate; writable when it pays for destinations  
     /// 1. `[]`                  controller_account                 – controller PDA  
     /// 2. `[writable]`          mint_account                       – index SPL mint (read-only)  
     /// 3. `[]`                  mint_authority_account             – PDA used only for PDA check  
     /// 4. `[]`                  index_account                      – index PDA  
     /// 5. `[]`                  index_mints_account                – PDA holding ordered component mints  
-    /// 6. `[writable]`          token_account                      – signer’s ATA for the **index mint** (debited & burned)  
-    /// 7. `[]`                  token_program_account              – `spl_token::id()`
+    /// 6. `[writable]`          token_account                      – token account for the **index mint** (debited & burned)  
+    /// 7. `[]`                  token_program_account              – `spl_token::id()`, also used for the components
     ///
     /// ### Per-component bundle (repeated *N* = `mints.len()` times)
     /// * `[]` `component_mint_account[i]`      – SPL mint of component *i*  
     /// * `[]`         `component_account[i]`           – component metadata PDA  
     /// * `[]` `vault_pda[i]`                   – PDA owning the vault ATA (signs transfer)  
     /// * `[writable]` `vault_ata[i]`                   – ATA holding component *i* inside vault (debited)  
-    /// * `[writable]` `component_token_account[i]`     – signer’s ATA for component *i* (credited)
+    /// * `[writable]` `component_token_account[i]`     – recipient’s ATA for component *i* (credited)
+    ///
+    /// ### Destination creation accounts (only with `create_destination_accounts`)
+    /// * `[]` `recipient_account`                – the recipient wallet  
+    /// * `[]` `system_program`                   – `solana_program::system_program`  
+    /// * `[]` `associated_token_program_account` – `spl_associated_token_account::id()`
+    ///
+    /// ### Trailing account (only for a Token-2022 index mint)
+    /// * `[]` `index_token_program_account` – `spl_token_2022::id()`, see `with_token_2022`
     ///
-    /// Total accounts = 8 + *N* × 5
+    /// Total accounts = 8 + *N* × 5, plus 3 when creating destinations and 1 for a Token-2022 index mint
     ///
     /// ### Instruction data
-    /// * `index_id: u64` – index identifier inside controller  
-    /// * `amount:   u64` – number of index tokens to redeem (burn)
+    /// * `index_id:                    u64`              – index identifier inside controller  
+    /// * `amount:                      u64`              – number of index tokens to redeem (burn)  
+    /// * `expected_version:            Option<u64>`      – `index.composition_version` the accounts were built for  
+    /// * `min_amounts_out:             Option<Vec<u64>>` – lower bound on each component returned, in `mints` order  
+    /// * `recipient:                   Option<Pubkey>`   – wallet whose ATAs receive the components; defaults to the signer  
+    /// * `create_destination_accounts: bool`             – create missing recipient ATAs idempotently  
+    /// * `unwrap_native:               bool`             – close the signer’s wrapped SOL ATA into lamports
     ///
     /// ### Fails with
     /// * `AmountMustBeGreaterThanZero`          if `amount == 0`  
+    /// * `CompositionVersionMismatch`           if the index composition changed since `expected_version`  
+    /// * `MinAmountOutNotMet`                   if a withdrawal is below its `min_amounts_out` entry  
+    /// * `MintsAmountsLenMismatch`              if `min_amounts_out` does not match the component count  
     /// * `UnknownControllerAccount`             if controller_account.owner ≠ program_id  
     /// * `UnknownIndexAccount`                  if index_account.owner ≠ program_id  
+    /// * `IncorrectIndexAccount` / `IncorrectMintAccount`
+    ///   if index_account or mint_account mismatch the stored-bump PDAs  
     /// * `IncorrectIndexMintsAccount`           if supplied PDA mismatches derivation  
     /// * `IncorrectMintAuthority`               if mint_authority_account ≠ derived PDA  
-    /// * `InvalidMintAccount`                   if a component mint mismatch occurs  
+    /// * `InvalidMintAccount`                   if a component mint or the `token_account` mint mismatches  
     /// * `IncorrectComponentAccount` / `IncorrectVaultAccount` /
     ///   `IncorrectVaultATA`                    if any PDA derivation mismatches  
+    /// * `IncorrectDestinationAccount`          if a component token account is not the recipient’s ATA  
+    /// * `InvalidTokenMint`                     if a component token account holds another mint  
+    /// * `IncorrectRecipient`                   if `recipient_account` is not the recipient  
+    /// * `InvalidUnwrapRecipient`               if `unwrap_native` is set for a recipient other than the signer  
+    /// * `InvalidTokenAccountAuthority`         if the signer neither owns nor is delegate of `token_account`  
+    /// * `InsufficientDelegatedAmount`          if the delegated allowance is below `amount`  
     /// * `ArithmeticOverflow`                   on `amount × component.units`  
     /// * `MissingRequiredSignature`             if signer did not sign
     
     Redeem {
         index_id: u64,
         amount: u64,
+        expected_version: Option<u64>,
+        min_amounts_out: Option<Vec<u64>>,
+        recipient: Option<Pubkey>,
+        create_destination_accounts: bool,
+        unwrap_native: bool,
     },
-}
 
-pub fn init_protocol_instruction(
-    program_id: Pubkey,
-    caller: Pubkey,
-    protocol_account: Pubkey,
-) -> Instruction {
-    let accounts = vec![
-        AccountMeta::new_readonly(caller, true),
-        AccountMeta::new(protocol_account, false),
-        AccountMeta::new_readonly(system_program::ID, false),
-    ];
-    let instruction = ProtocolInstruction::InitProtocol;
-    let data = borsh::to_vec(&instruction).unwrap();
-    Instruction {
-        program_id,
-        accounts,
-        data,
-    }
-}
+    /// 8. **BeginMint**
+    ///
+    /// Opens a **staged mint** for indexes whose component list does not fit
+    /// into a single `Mint` transaction.  
+    /// Creates a `mint_request_account` PDA recording the target `amount`,
+    /// `index.composition_version` and a snapshot of the component mints
+    /// (in `IndexMints` order) with the amount deposited for each.
+    ///
+    /// ### Accounts
+    /// 0. `[signer]`            signer                   – request owner, pays rent  
+    /// 1. `[]`                  controller_account       – controller PDA  
+    /// 2. `[]`                  index_account            – index PDA  
+    /// 3. `[]`                  index_mints_account      – PDA holding ordered component mints  
+    /// 4. `[writable]`          mint_request_account     – PDA (`b"mint_request"`, index_account, signer) (created)  
+    /// 5. `[]`                  system_program_account   – `solana_program::system_program`
+    ///
+    /// ### Instruction data
+    /// * `index_id: u64` – index identifier inside controller  
+    /// * `amount:   u64` – number of index tokens to mint once complete
+    ///
+    /// ### Fails with
+    /// * `AmountMustBeGreaterThanZero`          if `amount == 0`  
+    /// * `IncorrectIndexAccount` / `IncorrectIndexMintsAccount` /
+    ///   `IncorrectMintRequestAccount`          if any PDA derivation mismatches  
+    /// * `AccountAlreadyInitialized`            if the signer already has an open request  
+    /// * `MissingRequiredSignature`             if signer did not sign
+    BeginMint {
+        index_id: u64,
+        amount: u64,
+    },
 
-pub fn init_controller_instruction(
-    program_id: Pubkey,
-    caller: Pubkey,
-    protocol_account: Pubkey,
-    controller_account: Pubkey,
-) -> Instruction {
-    let accounts = vec![
-        AccountMeta::new_readonly(caller, true),
-        AccountMeta::new(protocol_account, false),
-        AccountMeta::new(controller_account, false),
-        AccountMeta::new_readonly(system_program::ID, false),
-    ];
-    let instruction = ProtocolInstruction::InitController;
-    let data = borsh::to_vec(&instruction).unwrap();
-    Instruction {
-        program_id,
-        accounts,
-        data,
-    }
-}
+    /// 9. **DepositComponents**
+    ///
+    /// Transfers `mint_request.amount × component.units` of each supplied
+    /// component from the signer into its vault and records the deposited
+    /// amount. Any subset of the not-yet-deposited components can be sent
+    /// per call.
+    ///
+    /// ### Static accounts (first 5)
+    /// 0. `[signer]`            signer                   – request owner providing components  
+    /// 1. `[]`                  controller_account       – controller PDA  
+    /// 2. `[]`                  index_account            – index PDA  
+    /// 3. `[writable]`          mint_request_account     – the signer's mint request  
+    /// 4. `[]`                  token_program_account    – `spl_token::id()`
+    ///
+    /// ### Per-component bundle (repeated for each deposited component)
+    /// * `[]`         `component_mint_account[i]`
+    /// * `[]`         `component_account[i]`
+    /// * `[]`         `vault_pda[i]`
+    /// * `[writable]` `vault_ata[i]`
+    /// * `[writable]` `component_token_account[i]` – signer's token account for component *i*
+    ///
+    /// ### Instruction data
+    /// * `index_id: u64` – index identifier inside controller
+    ///
+    /// ### Fails with
+    /// * `NoMintsProvided`                      if no bundle is supplied  
+    /// * `InvalidMintAccount`                   if a mint is not part of the index  
+    /// * `ComponentAlreadyDeposited`            if a component was already deposited  
+    /// * `OnlyMintRequestOwner`                 if signer ≠ mint_request.owner  
+    /// * `CompositionVersionMismatch`           if the index composition changed since `BeginMint`
+    DepositComponents {
+        index_id: u64,
+    },
 
-pub fn init_controller_global_config_instruction(
-    program_id: Pubkey,
-    caller: Pubkey,
-    protocol_account: Pubkey,
-    controller_global_config_account: Pubkey,
-    max_index_components: u32,
-) -> Instruction {
-    let accounts = vec![
-        AccountMeta::new_readonly(caller, true),
-        AccountMeta::new_readonly(protocol_account, false),
-        AccountMeta::new(controller_global_config_account, false),
-        AccountMeta::new_readonly(system_program::ID, false),
-    ];
-    let instruction = ProtocolInstruction::InitControllerGlobalConfig {
-        max_index_components,
-    };
-    let data = borsh::to_vec(&instruction).unwrap();
-    Instruction {
-        program_id,
-        accounts,
-        data,
-    }
-}
+    /// 10. **FinalizeMint**
+    ///
+    /// Mints `mint_request.amount` index tokens to `token_account` once every
+    /// component has been deposited, then closes the mint request and
+    /// returns its rent to the signer.
+    ///
+    /// ### Accounts
+    /// 0. `[signer, writable]`  signer                   – request owner (receives rent)  
+    /// 1. `[]`                  controller_account       – controller PDA  
+    /// 2. `[writable]`          mint_account             – index SPL mint  
+    /// 3. `[]`                  mint_authority_account   – PDA that signs `mint_to`  
+    /// 4. `[]`                  index_account            – index PDA  
+    /// 5. `[writable]`          mint_request_account     – the signer's mint request (closed)  
+    /// 6. `[writable]`          token_account            – token account for the **index mint**  
+    /// 7. `[]`                  token_program_account    – `spl_token::id()`, or `spl_token_2022::id()` for Token-2022 index mints
+    ///
+    /// ### Instruction data
+    /// * `index_id: u64` – index identifier inside controller
+    ///
+    /// ### Fails with
+    /// * `MintRequestIncomplete`                if a component is still missing  
+    /// * `OnlyMintRequestOwner`                 if signer ≠ mint_request.owner  
+    /// * `CompositionVersionMismatch`           if the index composition changed since `BeginMint`  
+    /// * `InvalidMintAccount`                   if `token_account.mint` ≠ mint_account
+    FinalizeMint {
+        index_id: u64,
+    },
 
-pub fn init_module_instruction(
-    program_id: Pubkey,
-    caller: Pubkey,
-    protocol_account: Pubkey,
-    module_signer_account: Pubkey,
-    registered_module_account: Pubkey,
-) -> Instruction {
-    let accounts = vec![
-        AccountMeta::new_readonly(caller, true),
-        AccountMeta::new_readonly(protocol_account, false),
-        AccountMeta::new_readonly(module_signer_account, false),
-        AccountMeta::new(registered_module_account, false),
-        AccountMeta::new_readonly(system_program::ID, false),
-    ];
-    let instruction = ProtocolInstruction::InitModule;
-    let data = borsh::to_vec(&instruction).unwrap();
-    Instruction {
-        program_id,
-        accounts,
-        data,
-    }
-}
+    /// 11. **CancelMint**
+    ///
+    /// Refunds deposited components of a mint request back to the signer.
+    /// The recorded deposit amounts are refunded, so a request can still be
+    /// cancelled after the index composition changed.
+    /// Like `DepositComponents`, any subset can be refunded per call; the
+    /// mint request is closed once nothing remains deposited.
+    ///
+    /// ### Static accounts (first 5)
+    /// 0. `[signer, writable]`  signer                   – request owner (receives rent)  
+    /// 1. `[]`                  controller_account       – controller PDA  
+    /// 2. `[]`                  index_account            – index PDA  
+    /// 3. `[writable]`          mint_request_account     – the signer's mint request  
+    /// 4. `[]`                  token_program_account    – `spl_token::id()`
+    ///
+    /// ### Per-component bundle (repeated for each refunded component)
+    /// * same layout as `DepositComponents`
+    ///
+    /// ### Instruction data
+    /// * `index_id: u64` – index identifier inside controller
+    ///
+    /// ### Fails with
+    /// * `ComponentNotDeposited`                if a component has nothing to refund  
+    /// * `OnlyMintRequestOwner`                 if signer ≠ mint_request.owner
+    CancelMint {
+        index_id: u64,
+    },
 
-#[allow(clippy::too_many_arguments)]
-pub fn create_index_instruction(
-    program_id: Pubkey,
-    caller: Pubkey,
-    manager: Pubkey,
-    index_account: Pubkey,
-    mint_account: Pubkey,
-    controller_account: Pubkey,
-    controller_global_config_account: Pubkey,
-) -> Instruction {
-    let accounts = vec![
-        AccountMeta::new_readonly(caller, true),
-        AccountMeta::new_readonly(manager, false),
-        AccountMeta::new(index_account, false),
-        AccountMeta::new(mint_account, false),
-        AccountMeta::new(controller_account, false),
-        AccountMeta::new_readonly(controller_global_config_account, false),
-        AccountMeta::new_readonly(system_program::ID, false),
-        AccountMeta::new_readonly(spl_token::ID, false),
-    ];
-    let instruction = ProtocolInstruction::CreateIndex;
-    let data = borsh::to_vec(&instruction).unwrap();
-    Instruction {
-        program_id,
-        accounts,
-        data,
-    }
-}
+    /// 12. **BeginRedeem**
+    ///
+    /// Opens a **staged redemption** for indexes whose component list does not
+    /// fit into a single `Redeem` transaction.  
+    /// Burns `amount` index tokens from `token_account` up front and creates a
+    /// `redeem_request_account` PDA recording a snapshot of the component
+    /// mints (in `IndexMints` order) with `amount × component.units` owed for
+    /// each, so later composition changes do not alter the payout.
+    ///
+    /// ### Static accounts (first 9)
+    /// 0. `[signer, writable]`  signer                   – token holder, pays rent  
+    /// 1. `[]`                  controller_account       – controller PDA  
+    /// 2. `[writable]`          mint_account             – index SPL mint  
+    /// 3. `[]`                  index_account            – index PDA  
+    /// 4. `[]`                  index_mints_account      – PDA holding ordered component mints  
+    /// 5. `[writable]`          redeem_request_account   – PDA (`b"redeem_request"`, index_account, signer) (created)  
+    /// 6. `[writable]`          token_account            – signer's token account for the **index mint**  
+    /// 7. `[]`                  token_program_account    – `spl_token::id()`, or `spl_token_2022::id()` for Token-2022 index mints  
+    /// 8. `[]`                  system_program_account   – `solana_program::system_program`
+    ///
+    /// ### Per-component accounts (one for each mint, in `IndexMints` order)
+    /// * `[]`         `component_account[i]`
+    ///
+    /// ### Instruction data
+    /// * `index_id: u64` – index identifier inside controller  
+    /// * `amount:   u64` – number of index tokens to burn
+    ///
+    /// ### Fails with
+    /// * `AmountMustBeGreaterThanZero`          if `amount == 0`  
+    /// * `IncorrectIndexAccount` / `IncorrectMintAccount` /
+    ///   `IncorrectRedeemRequestAccount`        if any PDA derivation mismatches  
+    /// * `AccountAlreadyInitialized`            if the signer already has an open request  
+    /// * `IncorrectComponentAccount`            if a component account is missing or out of order  
+    /// * SPL-Token `InsufficientFunds`          if `amount` exceeds the holder's balance
+    BeginRedeem {
+        index_id: u64,
+        amount: u64,
+    },
 
-#[allow(clippy::too_many_arguments)]
-pub fn add_index_components_instruction(
-    program_id: Pubkey,
-    caller: Pubkey,
-    index_account: Pubkey,
-    index_mints_data_account: Pubkey,
-    controller_account: Pubkey,
-    controller_global_config_account: Pubkey,
-    mints: Vec<Pubkey>,
-    amounts: Vec<u64>,
-) -> Instruction {
-    let accounts = vec![
-        AccountMeta::new_readonly(caller, true),
-        AccountMeta::new_readonly(index_account, false),
-        AccountMeta::new(index_mints_data_account, false),
-        AccountMeta::new_readonly(controller_account, false),
-        AccountMeta::new_readonly(controller_global_config_account, false),
-        AccountMeta::new_readonly(system_program::ID, false),
-        AccountMeta::new_readonly(spl_associated_token_account::ID, false),
-        AccountMeta::new_readonly(spl_token::ID, false),
-    ];
+    /// 13. **WithdrawComponents**
+    ///
+    /// Transfers the amount `BeginRedeem` recorded as owed for each supplied
+    /// component from its vault to the signer and marks it as withdrawn.
+    /// Any subset of the not-yet-withdrawn components can be sent per call;
+    /// the redeem request is closed once every component has been released.
+    ///
+    /// ### Static accounts (first 5)
+    /// 0. `[signer, writable]`  signer                   – request owner (receives rent)  
+    /// 1. `[]`                  controller_account       – controller PDA  
+    /// 2. `[]`                  index_account            – index PDA  
+    /// 3. `[writable]`          redeem_request_account   – the signer's redeem request  
+    /// 4. `[]`                  token_program_account    – `spl_token::id()`
+    ///
+    /// ### Per-component bundle (repeated for each withdrawn component)
+    /// * same layout as `DepositComponents`
+    ///
+    /// ### Instruction data
+    /// * `index_id: u64` – index identifier inside controller
+    ///
+    /// ### Fails with
+    /// * `NoMintsProvided`                      if no bundle is supplied  
+    /// * `InvalidMintAccount`                   if a mint is not part of the index  
+    /// * `ComponentAlreadyWithdrawn`            if a component was already released  
+    /// * `OnlyRedeemRequestOwner`               if signer ≠ redeem_request.owner
+    WithdrawComponents {
+        index_id: u64,
+    },
 
-    let instruction = ProtocolInstruction::AddIndexComponents { amounts, mints };
-    let data = borsh::to_vec(&instruction).unwrap();
-    Instruction {
-        program_id,
-        accounts,
-        data,
-    }
-}
+    /// 14. **UpdateControllerGlobalConfig**
+    ///
+    /// Changes the protocol-wide limits stored in
+    /// `controller_global_config_account` after `InitControllerGlobalConfig`.
+    /// Every parameter is optional; `None` keeps the current value.
+    ///
+    /// ### Behaviour
+    /// * Ensures the caller (`signer`) is exactly `protocol.owner`.  
+    /// * If the account was created with an older, shorter layout it is
+    ///   reallocated to `ControllerGlobalConfig::LEN` and topped up to
+    ///   rent-exemption by the signer; new fields start at zero.  
+    /// * With `check_existing_indexes`, refuses a `max_index_components`
+    ///   below `largest_index_components`. This is a best-effort guard: the
+    ///   value is the largest component count `AddIndexComponents` and
+    ///   `ExecuteCompositionChange` have written since the field was
+    ///   introduced. It misses older indexes and changes made while the
+    ///   account was on the legacy layout, and it never decreases when an
+    ///   index shrinks.
+    /// * Creates the program-owned `treasury_account` that collects creation
+    ///   fees if it does not exist yet.
+    ///
+    /// ### Accounts
+    /// 0. `[signer, writable]`  signer                             – **must** be the protocol owner, pays for reallocation  
+    /// 1. `[]`                  protocol_account                   – protocol PDA  
+    /// 2. `[writable]`          controller_global_config_account   – global config PDA  
+    /// 3. `[]`                  system_program_account             – `solana_program::system_program`  
+    /// 4. `[writable]`          treasury_account                   – PDA (`b"treasury"`)
+    ///
+    /// ### Instruction data
+    /// * `max_index_components:       Option<u32>`                      – new hard cap  
+    /// * `check_existing_indexes:     bool`                             – safety flag described above  
+    /// * `controller_creation_policy: Option<ControllerCreationPolicy>` – who may call `InitController`  
+    /// * `controller_creation_fee:    Option<u64>`                      – lamports charged by the `Fee` policy  
+    /// * `index_creation_fee:         Option<u64>`                      – lamports charged by `CreateIndex`, 0 disables it  
+    /// * `flash_mint_fee_bps:         Option<u16>`                      – component fee charged by `SettleFlashMint`  
+    /// * `asset_registry_required:    Option<bool>`                     – whether `AddIndexComponents` checks the asset registry
+    ///
+    /// ### Fails with
+    /// * `OnlyProtocolOwner`                    if signer ≠ protocol.owner  
+    /// * `ControllerGlobalConfigNotInitialized` if the global config is zeroed  
+    /// * `IncorrectControllerGlobalConfigAccount` if PDA derivation mismatches  
+    /// * `InvalidMaxIndexComponents`            if the provided max is zero  
+    /// * `MaxIndexComponentsBelowLargestIndex`  if the safety flag is set and the new max is too low  
+    /// * `InvalidFeeBps`                        if `flash_mint_fee_bps` exceeds 10 000  
+    /// * `IncorrectTreasuryAccount`             if the treasury PDA mismatches  
+    /// * `MissingRequiredSignature`             if signer did not sign
+    UpdateControllerGlobalConfig {
+        max_index_components: Option<u32>,
+        check_existing_indexes: bool,
+        controller_creation_policy: Option<ControllerCreationPolicy>,
+        controller_creation_fee: Option<u64>,
+        index_creation_fee: Option<u64>,
+        flash_mint_fee_bps: Option<u16>,
+        asset_registry_required: Option<bool>,
+    },
 
-#[allow(clippy::too_many_arguments)]
-pub fn add_index_components_instruction_with_dynamic_accounts(
-    program_id: Pubkey,
-    caller: Pubkey,
-    index_account: Pubkey,
-    index_mints_data_account: Pubkey,
-    controller_account: Pubkey,
-    controller_global_config_account: Pubkey,
-    mints: Vec<Pubkey>,
-    amounts: Vec<u64>,
-) -> Instruction {
-    let mut accounts = vec![
-        AccountMeta::new_readonly(caller, true),
-        AccountMeta::new_readonly(index_account, false),
-        AccountMeta::new(index_mints_data_account, false),
-        AccountMeta::new_readonly(controller_account, false),
-        AccountMeta::new_readonly(controller_global_config_account, false),
-        AccountMeta::new_readonly(system_program::ID, false),
-        AccountMeta::new_readonly(spl_associated_token_account::ID, false),
-        AccountMeta::new_readonly(spl_token::ID, false),
+    /// 15. **SetControllerConfig**
+    ///
+    /// Creates or replaces the per-controller `controller_config_account`
+    /// whose values take precedence over `ControllerGlobalConfig` for every
+    /// index under the controller. Unset values (`None` / empty list) fall
+    /// back to the global config and protocol defaults.
+    ///
+    /// ### Behaviour
+    /// * Checks `signer == controller.owner`.  
+    /// * Creates the PDA on first use; later calls resize it to the new
+    ///   `allowed_mints` length, topping up rent from the signer.  
+    /// * The component cap can only tighten
+    ///   `ControllerGlobalConfig::max_index_components`; larger values are
+    ///   clamped to the global cap when they are read.  
+    /// * `FlashMint` charges the lower of `max_mint_fee_bps` and the global
+    ///   `flash_mint_fee_bps`. `SetFlashLoanConfig` refuses a flash loan fee
+    ///   above `max_redeem_fee_bps`, since borrowers take components out of
+    ///   the vaults as a redeem does.  
+    /// * Existing indexes are not re-validated; the config applies to
+    ///   subsequent `CreateIndex` / `AddIndexComponents` /
+    ///   `SetFlashLoanConfig` calls and to every later `FlashMint`.
+    ///
+    /// ### Accounts
+    /// 0. `[signer, writable]`  signer                      – **must** be `controller.owner`, pays rent  
+    /// 1. `[]`                  controller_account          – controller PDA  
+    /// 2. `[writable]`          controller_config_account   – PDA (`b"controller_config"`, controller_account)  
+    /// 3. `[]`                  system_program_account      – `solana_program::system_program`
+    ///
+    /// ### Instruction data
+    /// * `max_index_components: Option<u32>` – per-controller component cap, clamped to the global cap  
+    /// * `max_mint_fee_bps:     u16`         – flash mint fee cap in basis points  
+    /// * `max_redeem_fee_bps:   u16`         – flash loan fee cap in basis points  
+    /// * `default_decimals:     Option<u8>`  – decimals of new index mints  
+    /// * `allowed_mints:        Vec<Pubkey>` – component allow-list; empty allows any mint  
+    /// * `override_asset_checks: bool`       – skip the asset registry checks of `AddIndexComponents`
+    ///
+    /// ### Fails with
+    /// * `OnlyControllerOwner`                  if signer ≠ controller.owner  
+    /// * `InvalidMaxIndexComponents`            if the cap is `Some(0)`  
+    /// * `InvalidFeeBps`                        if a fee cap exceeds 10 000  
+    /// * `IncorrectControllerConfigAccount`     if PDA derivation mismatches  
+    /// * `MissingRequiredSignature`             if signer did not sign
+    SetControllerConfig {
+        max_index_components: Option<u32>,
+        max_mint_fee_bps: u16,
+        max_redeem_fee_bps: u16,
+        default_decimals: Option<u8>,
+        allowed_mints: Vec<Pubkey>,
+        override_asset_checks: bool,
+    },
+
+    /// 16. **SetControllerCreator**
+    ///
+    /// Adds `creator` to, or removes it from, the allowlist consulted by
+    /// `InitController` under the `Allowlist` creation policy.  
+    /// Approval creates the `controller_creator_account` PDA; revocation
+    /// closes it and returns the rent to the signer.
+    ///
+    /// ### Accounts
+    /// 0. `[signer, writable]`  signer                       – **must** be the protocol owner, pays rent  
+    /// 1. `[]`                  protocol_account             – protocol PDA  
+    /// 2. `[]`                  creator                      – wallet being approved or revoked  
+    /// 3. `[writable]`          controller_creator_account   – PDA (`b"controller_creator"`, creator)  
+    /// 4. `[]`                  system_program_account       – `solana_program::system_program`
+    ///
+    /// ### Instruction data
+    /// * `allowed: bool` – `true` approves, `false` revokes
+    ///
+    /// ### Fails with
+    /// * `OnlyProtocolOwner`                    if signer ≠ protocol.owner  
+    /// * `IncorrectControllerCreatorAccount`    if PDA derivation mismatches  
+    /// * `AccountAlreadyInitialized`            if the creator is already approved  
+    /// * `UninitializedAccount`                 if revoking a creator that is not approved
+    SetControllerCreator {
+        allowed: bool,
+    },
+
+    /// 17. **WithdrawTreasury**
+    ///
+    /// Moves `amount` lamports of collected creation fees from the treasury
+    /// to `destination`. The treasury always keeps its rent-exempt minimum.
+    ///
+    /// ### Accounts
+    /// 0. `[signer]`            signer             – **must** be the protocol owner  
+    /// 1. `[]`                  protocol_account   – protocol PDA  
+    /// 2. `[writable]`          treasury_account   – PDA (`b"treasury"`)  
+    /// 3. `[writable]`          destination        – any account receiving the lamports
+    ///
+    /// ### Instruction data
+    /// * `amount: u64` – lamports to withdraw
+    ///
+    /// ### Fails with
+    /// * `OnlyProtocolOwner`                    if signer ≠ protocol.owner  
+    /// * `IncorrectTreasuryAccount`             if the treasury PDA mismatches or was never created  
+    /// * `InsufficientTreasuryBalance`          if `amount` would dip below the rent-exempt minimum
+    WithdrawTreasury {
+        amount: u64,
+    },
+
+    /// 18. **InitControllerWithNonce**
+    ///
+    /// Creates a controller at a PDA derived from the signer and a
+    /// signer-chosen `nonce` instead of `protocol.next_controller_id`.  
+    /// `protocol_account` is only read, so controllers created this way do not
+    /// contend for a write lock on the protocol and can be created in parallel.
+    ///
+    /// ### Behaviour
+    /// * Enforces the controller creation policy exactly like `InitController`.  
+    /// * Writes a `Controller` with `id = 0`; id-based controllers start at 1,
+    ///   so a zero id marks a nonce-derived controller.  
+    /// * Increments the `controller_counter_account` shard picked by
+    ///   `controller_counter_shard(signer)`, creating it on first use. Summing
+    ///   all `CONTROLLER_COUNTER_SHARDS` shards gives the number of
+    ///   nonce-derived controllers.
+    ///
+    /// ### Accounts
+    /// 0. `[signer]`            signer                             – wallet that becomes controller authority  
+    /// 1. `[signer, writable]`  payer                              – pays rent and the creation fee  
+    /// 2. `[]`                  protocol_account                   – protocol PDA (read-only)  
+    /// 3. `[writable]`          controller_account                 – PDA (`b"creator_controller"`, signer, nonce)  
+    /// 4. `[]`                  system_program                     – `solana_program::system_program`  
+    /// 5. `[]`                  controller_global_config_account   – global config PDA; may be uncreated  
+    /// 6. `[]`                  controller_creator_account         – PDA (`b"controller_creator"`, signer); may be uncreated  
+    /// 7. `[writable]`          treasury_account                   – PDA (`b"treasury"`) receiving the creation fee  
+    /// 8. `[writable]`          controller_counter_account         – PDA (`b"controller_counter"`, shard)
+    ///
+    /// ### Instruction data
+    /// * `nonce: u64` – any value not yet used by the signer
+    ///
+    /// ### Fails with
+    /// * `ProtocolNotInitialized`               if protocol_account is still zeroed  
+    /// * `IncorrectControllerAccount`           if PDA derivation mismatches  
+    /// * `IncorrectControllerCounterAccount`    if the counter shard mismatches  
+    /// * `AccountAlreadyInitialized`            if the nonce was already used  
+    /// * `ControllerCreatorNotAllowed`          if the allowlist policy is active and signer is not approved  
+    /// * `MissingRequiredSignature`             if signer or payer did not sign
+    InitControllerWithNonce {
+        nonce: u64,
+    },
+
+    /// 19. **CreateIndexWithSeed**
+    ///
+    /// Same as `CreateIndex`, but every index PDA is derived from a
+    /// creator-chosen 32-byte `seed` instead of `controller.next_index_id`.
+    /// Concurrent creations under one controller therefore no longer race for
+    /// the same addresses; each still receives the next sequential `id`, which
+    /// is kept for display and for the `index_id` argument of later
+    /// instructions.
+    ///
+    /// ### Behaviour
+    /// * Identical to `CreateIndex`, with `seed` in place of `index_id` in the
+    ///   index, mint, mint-authority and index-mints PDAs.  
+    /// * Stores `seed` in the `Index`, so later instructions derive the same
+    ///   PDAs from the account alone.  
+    /// * `IndexSeed::from_symbol` turns a ticker symbol into a seed.
+    ///
+    /// ### Accounts
+    /// 0. `[signer]`            signer                             – `controller.owner` or an `IndexCreator`  
+    /// 1. `[signer, writable]`  payer                              – pays rent and the index creation fee  
+    /// 2. `[]`                  manager                            – delegate that will manage the index  
+    /// 3. `[writable]`          index_account                      – PDA (`b"index"`, controller_account, seed)  
+    /// 4. `[writable]`          mint_account                       – PDA (`b"index_mint"`, controller_account, seed)  
+    /// 5. `[writable]`          controller_account                 – controller PDA (mutated to bump next_index_id)  
+    /// 6. `[]`                  controller_global_config_account   – global config PDA (read-only)  
+    /// 7. `[]`                  controller_config_account          – PDA (`b"controller_config"`, controller_account); may be uncreated  
+    /// 8. `[]`                  system_program_account             – `solana_program::system_program`  
+    /// 9. `[]`                  token_program_account              – `spl_token::id()`, or `spl_token_2022::id()` with `transfer_hook_program`  
+    /// 10. `[writable]`         treasury_account                   – PDA (`b"treasury"`) receiving the index creation fee  
+    /// 11. `[]`                 controller_roles_account           – PDA (`b"controller_roles"`, controller_account); may be uncreated  
+    /// 12. `[]`                 transfer_hook_program_account      – only with `transfer_hook_program`  
+    /// 13. `[writable]`         extra_account_metas_account        – only with `transfer_hook_program`; PDA of the hook for the mint  
+    /// 14. `[]`                 mint_authority_account             – only with `transfer_hook_program`; PDA (`b"index_mint_authority"`, controller_account, index_id)
+    ///
+    /// ### Instruction data
+    /// * `seed: [u8; 32]`                         – unique per controller; must not be all zeroes  
+    /// * `drop_freeze_authority: bool`            – create the mint without a freeze authority  
+    /// * `transfer_hook_program: Option<Pubkey>`  – restrict transfers to the index allowlist
+    ///
+    /// ### Fails with
+    /// * `InvalidIndexSeed`                     if `seed` is all zeroes  
+    /// * `AccountAlreadyInitialized`            if the seed was already used  
+    /// * every error of `CreateIndex`
+    CreateIndexWithSeed {
+        seed: [u8; 32],
+        drop_freeze_authority: bool,
+        transfer_hook_program: Option<Pubkey>,
+    },
+
+    /// 20. **GrantControllerRole**
+    ///
+    /// Grants `role` on a controller to `holder`, letting it perform the
+    /// matching owner-only actions without holding the controller itself.
+    ///
+    /// ### Behaviour
+    /// * Creates the controller's role registry on first use and grows it by
+    ///   one entry per grant.
+    ///
+    /// ### Accounts
+    /// 0. `[signer, writable]`  signer                     – **must** be `controller.owner`, pays rent  
+    /// 1. `[]`                  controller_account         – controller PDA  
+    /// 2. `[]`                  holder                     – wallet receiving the role  
+    /// 3. `[writable]`          controller_roles_account   – PDA (`b"controller_roles"`, controller_account)  
+    /// 4. `[]`                  system_program_account     – `solana_program::system_program`
+    ///
+    /// ### Instruction data
+    /// * `role: ControllerRole` – role to grant
+    ///
+    /// ### Fails with
+    /// * `OnlyControllerOwner`                  if signer ≠ controller.owner  
+    /// * `IncorrectControllerRolesAccount`      if PDA derivation mismatches  
+    /// * `ControllerRoleAlreadyGranted`         if `holder` already has `role`  
+    /// * `MissingRequiredSignature`             if signer did not sign
+    GrantControllerRole {
+        role: ControllerRole,
+    },
+
+    /// 21. **RevokeControllerRole**
+    ///
+    /// Removes `role` from `holder` and shrinks the role registry, returning
+    /// the freed rent to the signer.
+    ///
+    /// ### Accounts
+    /// 0. `[signer, writable]`  signer                     – **must** be `controller.owner`, receives freed rent  
+    /// 1. `[]`                  controller_account         – controller PDA  
+    /// 2. `[]`                  holder                     – wallet losing the role  
+    /// 3. `[writable]`          controller_roles_account   – PDA (`b"controller_roles"`, controller_account)
+    ///
+    /// ### Instruction data
+    /// * `role: ControllerRole` – role to revoke
+    ///
+    /// ### Fails with
+    /// * `OnlyControllerOwner`                  if signer ≠ controller.owner  
+    /// * `IncorrectControllerRolesAccount`      if PDA derivation mismatches  
+    /// * `ControllerRoleNotGranted`             if `holder` does not have `role`  
+    /// * `MissingRequiredSignature`             if signer did not sign
+    RevokeControllerRole {
+        role: ControllerRole,
+    },
+
+    /// 22. **CreateMultisig**
+    ///
+    /// Creates an m-of-n `Multisig` account that can be made the owner of
+    /// the protocol, a controller or an index with `TransferOwnership`.
+    ///
+    /// Owner checks then accept the multisig in place of a single key: the
+    /// instruction appends the multisig account followed by the approving
+    /// members as signers after its regular accounts (see
+    /// `with_multisig_signers`). The instruction's `signer` counts as an
+    /// approval when it is a member. This applies to `InitModule`,
+    /// `InitControllerGlobalConfig`, `UpdateControllerGlobalConfig`,
+    /// `SetControllerCreator`, `WithdrawTreasury`, `SetControllerConfig`,
+    /// `GrantControllerRole`, `RevokeControllerRole`, `CreateIndex`,
+    /// `CreateIndexWithSeed`, `AddIndexComponents`, `TransferOwnership`,
+    /// `SetCompositionDelay`, `SetFlashLoanConfig`, `FreezeHolder` and
+    /// `ThawHolder`. `QueueCompositionChange` and `CancelCompositionChange`
+    /// accept a multisig `index.manager` the same way.
+    ///
+    /// ### Accounts
+    /// 0. `[signer, writable]`  signer               – pays rent  
+    /// 1. `[signer]`            create_key           – any key; makes the address unique  
+    /// 2. `[writable]`          multisig_account     – PDA (`b"multisig"`, create_key)  
+    /// 3. `[]`                  system_program       – `solana_program::system_program`
+    ///
+    /// ### Instruction data
+    /// * `members:   Vec<Pubkey>` – distinct member keys, at most 11  
+    /// * `threshold: u8`          – member signatures required
+    ///
+    /// ### Fails with
+    /// * `InvalidMultisigMembers`               if `members` is empty, too long or has duplicates  
+    /// * `InvalidMultisigThreshold`             if `threshold` is 0 or above `members.len()`  
+    /// * `IncorrectMultisigAccount`             if PDA derivation mismatches  
+    /// * `AccountAlreadyInitialized`            if the create key was already used  
+    /// * `MissingRequiredSignature`             if signer or create_key did not sign
+    CreateMultisig {
+        members: Vec<Pubkey>,
+        threshold: u8,
+    },
+
+    /// 23. **TransferOwnership**
+    ///
+    /// Sets the `owner` of a `Protocol`, `Controller` or `Index` account to
+    /// `new_owner`, which may be a wallet or a `Multisig` account.
+    ///
+    /// ### Accounts
+    /// 0. `[signer]`            signer               – current owner, or a multisig member  
+    /// 1. `[writable]`          target_account       – protocol, controller or index account  
+    /// * `[]`                   multisig_account     – optional, when the current owner is a multisig  
+    /// * `[signer]`             member[i]            – optional, approving multisig members
+    ///
+    /// ### Instruction data
+    /// * `new_owner: Pubkey` – new owner of `target_account`
+    ///
+    /// ### Fails with
+    /// * `OnlyProtocolOwner` / `OnlyControllerOwner` / `OnlyIndexOwner`
+    ///   if signer is not the current owner  
+    /// * `MultisigThresholdNotMet`              if too few members signed  
+    /// * `UnsupportedOwnershipTransfer`         if `target_account` is another account type  
+    /// * `MissingRequiredSignature`             if signer did not sign
+    TransferOwnership {
+        new_owner: Pubkey,
+    },
+
+    /// 24. **SetCompositionDelay**
+    ///
+    /// Sets the number of seconds a queued composition change must wait
+    /// before `ExecuteCompositionChange` can apply it. The delay can only
+    /// grow, so holders can rely on the notice period they bought into.
+    /// Index accounts on an older layout are reallocated first.
+    ///
+    /// ### Accounts
+    /// 0. `[signer, writable]`  signer               – **must** be `index.owner`; pays for reallocation  
+    /// 1. `[writable]`          index_account        – index PDA  
+    /// 2. `[]`                  controller_account   – controller PDA of the index  
+    /// 3. `[]`                  system_program       – `solana_program::system_program`
+    ///
+    /// ### Instruction data
+    /// * `delay: i64` – notice period in seconds
+    ///
+    /// ### Fails with
+    /// * `OnlyIndexOwner`                       if signer is not `index.owner`  
+    /// * `InvalidCompositionDelay`              if `delay` is negative  
+    /// * `CompositionDelayDecreased`            if `delay` is below the current delay  
+    /// * `IncorrectIndexAccount`                if PDA derivation mismatches
+    SetCompositionDelay {
+        delay: i64,
+    },
+
+    /// 25. **QueueCompositionChange**
+    ///
+    /// Publishes a new recipe for an index in a `PendingCompositionChange`
+    /// PDA. It becomes executable `index.composition_delay` seconds later,
+    /// which gives holders time to redeem under the current recipe.
+    ///
+    /// ### Behaviour
+    /// * Only one change can be queued per index; cancel it to queue another.  
+    /// * Applies the recipe checks of `AddIndexComponents`: non-empty `mints`,
+    ///   matching `amounts`, the component cap, non-zero units, distinct mints
+    ///   other than the index mint and the controller allow-list. The asset
+    ///   registry and the underlying indexes of `nested_components` are
+    ///   checked by `ExecuteCompositionChange`.
+    ///
+    /// ### Accounts
+    /// 0. `[signer, writable]`  signer                             – **must** be `index.manager`; pays rent  
+    /// 1. `[]`                  index_account                      – index PDA  
+    /// 2. `[]`                  controller_account                 – controller PDA of the index  
+    /// 3. `[]`                  controller_global_config_account   – global config PDA  
+    /// 4. `[]`                  controller_config_account          – PDA (`b"controller_config"`, controller_account); may be uncreated  
+    /// 5. `[writable]`          pending_change_account             – PDA (`b"composition_change"`, index_account)  
+    /// 6. `[]`                  system_program                     – `solana_program::system_program`  
+    /// * `[]`                   multisig_account                   – optional, when the manager is a multisig  
+    /// * `[signer]`             member[i]                          – optional, approving multisig members
+    ///
+    /// ### Instruction data
+    /// * `mints:             Vec<Pubkey>` – new ordered list of component mints  
+    /// * `amounts:           Vec<u64>`    – new component units per **one** index token  
+    /// * `nested_components: Vec<bool>`   – which mints are index mints; empty when none are
+    ///
+    /// ### Fails with
+    /// * `OnlyIndexManager`                     if signer is not `index.manager`  
+    /// * `MultisigThresholdNotMet`              if too few members signed  
+    /// * `CompositionChangeAlreadyQueued`       if a change is already pending  
+    /// * `NoMintsProvided` / `MintsAmountsLenMismatch` / `MaxIndexComponentsExceeded` /
+    ///   `ZeroComponentUnits` / `DuplicateComponentMint` / `IndexMintAsComponent` /
+    ///   `ComponentMintNotAllowed`              as in `AddIndexComponents`
+    QueueCompositionChange {
+        mints: Vec<Pubkey>,
+        amounts: Vec<u64>,
+        nested_components: Vec<bool>,
+    },
+
+    /// 26. **ExecuteCompositionChange**
+    ///
+    /// Applies a queued composition change once its delay has elapsed.
+    /// Anyone may call it.
+    ///
+    /// ### Behaviour
+    /// * Requires `Clock::unix_timestamp >= pending_change.executable_at`.  
+    /// * While index tokens are outstanding, every vault of a new component,
+    ///   or of a component whose units go up, must already hold
+    ///   `units * supply`; the manager funds it before execution. Open staged
+    ///   mint and redeem requests keep the amounts they recorded:
+    ///   `FinalizeMint` then fails on the new `composition_version` and
+    ///   `CancelMint` refunds the deposits.  
+    /// * Checks the queued recipe again as in `AddIndexComponents`, including
+    ///   the asset registry and the underlying index of every nested
+    ///   component, which is recorded as `component.underlying_index`.  
+    /// * Updates the units of components that stay in the index and creates
+    ///   the component account and vault of each new mint, unless the vault
+    ///   already exists. Components that are dropped keep their accounts but
+    ///   no longer take part in `Mint` / `Redeem`.  
+    /// * While index tokens are outstanding, a component keeps the larger of
+    ///   its current and queued units, and dropped components stay at the
+    ///   end of the recipe in their current order, so every outstanding
+    ///   token remains redeemable.  
+    /// * Rewrites `index_mints_account` with the new mint order, resizing it,
+    ///   and increments `index.composition_version`.  
+    /// * Closes `pending_change_account` and refunds its rent to the manager
+    ///   that queued it.
+    ///
+    /// ### Accounts
+    /// 0. `[signer, writable]`  signer                             – anyone; pays for new accounts  
+    /// 1. `[writable]`          index_account                      – index PDA (bumps `composition_version`)  
+    /// 2. `[writable]`          index_mints_account                – PDA (`b"index_mints"`, controller_account, index_id)  
+    /// 3. `[]`                  mint_account                       – index mint PDA  
+    /// 4. `[]`                  controller_account                 – controller PDA of the index  
+    /// 5. `[writable]`          controller_global_config_account   – global config PDA (tracks the largest index)  
+    /// 6. `[]`                  controller_config_account          – PDA (`b"controller_config"`, controller_account); may be uncreated  
+    /// 7. `[writable]`          pending_change_account             – PDA (`b"composition_change"`, index_account)  
+    /// 8. `[writable]`          rent_receiver                      – `pending_change.manager`  
+    /// 9. `[]`                  system_program_account             – `solana_program::system_program`  
+    /// 10. `[]`                 associated_token_program_account   – `spl_associated_token_account::id()`  
+    /// 11. `[]`                 token_program_account              – `spl_token::id()`
+    ///
+    /// ### Per-component bundle (repeated for each mint of the new recipe)
+    /// * `[writable]` `mint_account[i]`            – the SPL mint in `pending_change.mints[i]`  
+    /// * `[writable]` `component_account[i]`       – PDA (`b"component"`, index_account, mint)  
+    /// * `[]`         `vault_pda[i]`               – PDA (`b"component_vault"`, …)  
+    /// * `[writable]` `vault_ata[i]`               – ATA owned by `vault_pda[i]`
+    ///
+    /// Followed by the asset registry accounts and underlying index pairs, as
+    /// in `AddIndexComponents`.
+    ///
+    /// ### Fails with
+    /// * `CompositionChangeTimelocked`          if the delay has not elapsed  
+    /// * `InvalidPendingCompositionChangeAccountData` if nothing is queued  
+    /// * `IncorrectMintAccount`                 if mint_account is not the index mint  
+    /// * `ComponentVaultUnderfunded`            if a vault does not back the outstanding supply  
+    /// * `MaxIndexComponentsExceeded`           if kept dropped components exceed the cap  
+    /// * `InvalidMintAccount`                   if a bundle does not match the queued mint  
+    /// * recipe, asset registry and underlying index errors as in `AddIndexComponents`
+    ExecuteCompositionChange,
+
+    /// 27. **CancelCompositionChange**
+    ///
+    /// Drops a queued composition change and refunds its rent to the
+    /// manager that queued it. Either the index manager or the index owner
+    /// may cancel.
+    ///
+    /// ### Accounts
+    /// 0. `[signer]`            signer                   – `index.manager` or `index.owner`  
+    /// 1. `[]`                  index_account            – index PDA  
+    /// 2. `[]`                  controller_account       – controller PDA of the index  
+    /// 3. `[writable]`          pending_change_account   – PDA (`b"composition_change"`, index_account)  
+    /// 4. `[writable]`          rent_receiver            – `pending_change.manager`  
+    /// * `[]`                   multisig_account         – optional, when the manager or owner is a multisig  
+    /// * `[signer]`             member[i]                – optional, approving multisig members
+    ///
+    /// ### Fails with
+    /// * `OnlyIndexManager`                     if signer is neither manager nor owner  
+    /// * `MultisigThresholdNotMet`              if too few members signed  
+    /// * `InvalidPendingCompositionChangeAccountData` if nothing is queued
+    CancelCompositionChange,
+
+    /// 28. **RedeemUnderlying**
+    ///
+    /// Redeems an index of indexes straight to its base assets. Runs
+    /// `Redeem` for the signer, then redeems every nested component it paid
+    /// out the same way, down to `MAX_NESTING_DEPTH` levels.
+    ///
+    /// ### Behaviour
+    /// * Performs `Redeem` with the accounts below, so the signer receives
+    ///   every component into its own associated token accounts.  
+    /// * For each nested component, in `mints` order, redeems
+    ///   `amount × component.units` underlying index tokens from the
+    ///   signer's token account for the underlying index mint, which is the
+    ///   component token account of the outer level.  
+    /// * Applies this recursively to components of the underlying index.
+    ///
+    /// ### Accounts
+    /// The 8 + *N* × 5 accounts of `Redeem`, followed for every nested
+    /// component by its underlying index:
+    /// * `[]`         `underlying_controller_account`     – controller PDA of the underlying index  
+    /// * `[writable]` `underlying_mint_account`           – underlying index SPL mint  
+    /// * `[]`         `underlying_mint_authority_account` – its mint authority PDA  
+    /// * `[]`         `underlying_index_account`          – `component.underlying_index`  
+    /// * `[]`         `underlying_index_mints_account`    – its index mints PDA  
+    /// * *M* × 5 per-component bundles of the underlying index, as in `Redeem`  
+    /// * the underlying indexes of its own nested components, depth first
+    ///
+    /// ### Instruction data
+    /// * `index_id: u64` – index identifier inside controller  
+    /// * `amount:   u64` – number of index tokens to redeem (burn)
+    ///
+    /// ### Fails with
+    /// * everything `Redeem` fails with, at any level  
+    /// * `IncorrectUnderlyingIndexAccount`      if an underlying index account ≠ `component.underlying_index`  
+    /// * `MaxNestingDepthExceeded`              if indexes nest deeper than `MAX_NESTING_DEPTH`  
+    /// * `NotEnoughAccountKeys`                 if an underlying index is missing
+    RedeemUnderlying { index_id: u64, amount: u64 },
+
+    /// 29. **FlashMint**
+    ///
+    /// Mints index tokens before their components are deposited and
+    /// records the obligation in a transient PDA. The transaction must
+    /// settle it with a later `SettleFlashMint` for the same flash mint
+    /// account, which is checked through the instructions sysvar.
+    ///
+    /// ### Behaviour
+    /// * Creates the flash mint PDA holding `amount`, the current  
+    ///   `flash_mint_fee_bps`, lowered to the controller's
+    ///   `max_mint_fee_bps` when it has a config, and the index composition
+    ///   version.  
+    /// * Mints `amount` index tokens to `token_account`.
+    ///
+    /// ### Accounts
+    /// 0. `[signer, writable]`  signer                             – flash minter, pays the PDA rent  
+    /// 1. `[]`                  controller_account                 – controller PDA of the index  
+    /// 2. `[writable]`          mint_account                       – index SPL mint  
+    /// 3. `[]`                  mint_authority_account             – PDA (`b"index_mint_authority"`, controller_account, index_id)  
+    /// 4. `[]`                  index_account                      – index PDA  
+    /// 5. `[]`                  controller_global_config_account   – global config PDA (`flash_mint_fee_bps`)  
+    /// 6. `[]`                  controller_config_account          – PDA (`b"controller_config"`, controller_account); may be uncreated  
+    /// 7. `[writable]`          flash_mint_account                 – PDA (`b"open_index_flash_mint"`, index_account, signer)  
+    /// 8. `[writable]`          token_account                      – receives the index tokens  
+    /// 9. `[]`                  system_program_account             – `solana_program::system_program`  
+    /// 10. `[]`                 token_program_account              – `spl_token::id()`, or `spl_token_2022::id()` for a Token-2022 index mint  
+    /// 11. `[]`                 instructions_sysvar_account        – `solana_program::sysvar::instructions`
+    ///
+    /// ### Instruction data
+    /// * `index_id: u64` – index identifier inside controller  
+    /// * `amount:   u64` – number of index tokens to mint
+    ///
+    /// ### Fails with
+    /// * `MissingFlashMintSettlement`           if no later `SettleFlashMint` closes the flash mint account  
+    /// * `IncorrectFlashMintAccount`            if the flash mint PDA does not match  
+    /// * `AccountAlreadyInitialized`            if the signer already has an open flash mint on the index
+    FlashMint { index_id: u64, amount: u64 },
+
+    /// 30. **SettleFlashMint**
+    ///
+    /// Deposits the components owed for a `FlashMint` and closes its PDA.
+    /// For every component the signer pays
+    /// `amount × units + ⌈amount × units × fee_bps / 10 000⌉`; the fee
+    /// stays in the vaults and so accrues to index holders.
+    ///
+    /// ### Accounts
+    /// 0. `[signer, writable]`  signer                   – flash minter, receives the PDA rent  
+    /// 1. `[]`                  controller_account       – controller PDA of the index  
+    /// 2. `[]`                  index_account            – index PDA  
+    /// 3. `[]`                  index_mints_account      – PDA (`b"index_mints"`, controller_account, index_id)  
+    /// 4. `[writable]`          flash_mint_account       – PDA (`b"open_index_flash_mint"`, index_account, signer)  
+    /// 5. `[]`                  token_program_account    – `spl_token::id()`
+    ///
+    /// ### Per-component bundle (repeated *N* times, in `IndexMints` order)
+    /// * `[]`         `mint_account[i]`            – component SPL mint  
+    /// * `[]`         `component_account[i]`       – PDA (`b"component"`, index_account, mint)  
+    /// * `[]`         `vault_pda[i]`               – PDA (`b"component_vault"`, …)  
+    /// * `[writable]` `vault_ata[i]`               – ATA owned by `vault_pda[i]`  
+    /// * `[writable]` `token_account[i]`           – signer's source token account
+    ///
+    /// ### Instruction data
+    /// * `index_id: u64` – index identifier inside controller
+    ///
+    /// ### Fails with
+    /// * `InvalidFlashMintAccountData`          if no flash mint is open on the index  
+    /// * `IncorrectFlashMintAccount`            if the flash mint PDA does not belong to the signer  
+    /// * `CompositionVersionMismatch`           if the composition changed since the flash mint
+    SettleFlashMint { index_id: u64 },
+
+    /// 31. **SetFlashLoanConfig**
+    ///
+    /// Opts an index in or out of lending its vault balances through
+    /// `FlashBorrow`, and sets the fee borrowers pay back to the vaults.
+    /// Index accounts on an older layout are reallocated first.
+    ///
+    /// ### Accounts
+    /// 0. `[signer, writable]`  signer                      – **must** be `index.owner`; pays for reallocation  
+    /// 1. `[writable]`          index_account               – index PDA  
+    /// 2. `[]`                  controller_account          – controller PDA of the index  
+    /// 3. `[]`                  controller_config_account   – PDA (`b"controller_config"`, controller_account); may be uncreated  
+    /// 4. `[]`                  system_program              – `solana_program::system_program`
+    ///
+    /// ### Instruction data
+    /// * `enabled: bool` – whether the vaults of the index can be borrowed  
+    /// * `fee_bps: u16`  – fee in basis points of the borrowed amount
+    ///
+    /// ### Fails with
+    /// * `OnlyIndexOwner`                       if signer is not `index.owner`  
+    /// * `InvalidFeeBps`                        if `fee_bps` exceeds 10 000  
+    /// * `FeeAboveControllerCap`                if `fee_bps` exceeds `controller_config.max_redeem_fee_bps`  
+    /// * `IncorrectIndexAccount`                if PDA derivation mismatches
+    SetFlashLoanConfig { enabled: bool, fee_bps: u16 },
+
+    /// 32. **FlashBorrow**
+    ///
+    /// Lends `amount` of a component vault balance to the signer and records
+    /// the loan in a transient PDA. The transaction must repay it with a
+    /// later `FlashRepay` for the same flash loan account, which is checked
+    /// through the instructions sysvar.
+    ///
+    /// ### Behaviour
+    /// * Requires `index.flash_loans_enabled`.  
+    /// * Creates the flash loan PDA holding `amount` and the fee
+    ///   `⌈amount × index.flash_loan_fee_bps / 10 000⌉`.  
+    /// * Transfers `amount` from the vault to `token_account`.
+    ///
+    /// ### Accounts
+    /// 0. `[signer, writable]`  signer                   – borrower, pays the PDA rent  
+    /// 1. `[]`                  controller_account       – controller PDA of the index  
+    /// 2. `[]`                  index_account            – index PDA  
+    /// 3. `[]`                  mint_account             – component SPL mint  
+    /// 4. `[]`                  component_account        – PDA (`b"component"`, index_account, mint)  
+    /// 5. `[]`                  vault_pda                – PDA (`b"component_vault"`, …)  
+    /// 6. `[writable]`          vault_ata                – ATA owned by `vault_pda`  
+    /// 7. `[writable]`          flash_loan_account       – PDA (`b"open_index_flash_loan"`, component_account, signer)  
+    /// 8. `[writable]`          token_account            – receives the borrowed tokens  
+    /// 9. `[]`                  system_program_account   – `solana_program::system_program`  
+    /// 10. `[]`                 token_program_account    – `spl_token::id()`  
+    /// 11. `[]`                 instructions_sysvar      – `solana_program::sysvar::instructions`
+    ///
+    /// ### Instruction data
+    /// * `index_id: u64` – index identifier inside controller  
+    /// * `amount:   u64` – component amount to borrow
+    ///
+    /// ### Fails with
+    /// * `FlashLoansDisabled`                   if the index has not opted in  
+    /// * `MissingFlashRepay`                    if no later `FlashRepay` closes the flash loan account  
+    /// * `IncorrectFlashLoanAccount`            if the flash loan PDA does not match  
+    /// * `AccountAlreadyInitialized`            if the signer already borrows from this vault
+    FlashBorrow { index_id: u64, amount: u64 },
+
+    /// 33. **FlashRepay**
+    ///
+    /// Returns a `FlashBorrow` loan plus its fee to the vault and closes the
+    /// flash loan PDA. The fee stays in the vault and so accrues to index
+    /// holders.
+    ///
+    /// ### Accounts
+    /// 0. `[signer, writable]`  signer                   – borrower, receives the PDA rent  
+    /// 1. `[]`                  component_account        – component the loan was taken from  
+    /// 2. `[writable]`          vault_ata                – vault token account of the component  
+    /// 3. `[writable]`          flash_loan_account       – PDA (`b"open_index_flash_loan"`, component_account, signer)  
+    /// 4. `[writable]`          token_account            – signer's source token account  
+    /// 5. `[]`                  token_program_account    – `spl_token::id()`
+    ///
+    /// ### Fails with
+    /// * `InvalidFlashLoanAccountData`          if no loan is open on the component  
+    /// * `IncorrectFlashLoanAccount`            if the flash loan PDA does not belong to the signer  
+    /// * `IncorrectVaultATA`                    if `vault_ata` is not the vault the loan came from
+    FlashRepay,
+
+    /// 34. **FreezeHolder**
+    ///
+    /// Freezes a holder's index token account through the freeze authority
+    /// `CreateIndex` gives the mint-authority PDA, for compliance holds.
+    ///
+    /// ### Accounts
+    /// 0. `[signer]`            signer                     – `index.owner`, or `controller.owner` / a `ComplianceOfficer`  
+    /// 1. `[]`                  controller_account         – controller PDA of the index  
+    /// 2. `[]`                  index_account              – index PDA  
+    /// 3. `[]`                  mint_account               – index SPL mint  
+    /// 4. `[]`                  mint_authority_account     – PDA (`b"index_mint_authority"`, controller_account, index_id)  
+    /// 5. `[writable]`          token_account              – holder's index token account  
+    /// 6. `[]`                  token_program_account      – `spl_token::id()`, or `spl_token_2022::id()` for a Token-2022 index mint  
+    /// 7. `[]`                  controller_roles_account   – PDA (`b"controller_roles"`, controller_account); may be uncreated
+    ///
+    /// ### Instruction data
+    /// * `index_id: u64` – index identifier inside controller
+    ///
+    /// ### Fails with
+    /// * `MissingControllerRole`                if signer is neither owner nor a `ComplianceOfficer`  
+    /// * `IndexNotFreezable`                    if the index was created with `drop_freeze_authority`  
+    /// * `InvalidMintAccount`                   if `token_account` does not hold the index mint
+    FreezeHolder { index_id: u64 },
+
+    /// 35. **ThawHolder**
+    ///
+    /// Thaws a holder's index token account frozen by `FreezeHolder`.
+    ///
+    /// ### Accounts
+    /// Same as `FreezeHolder`.
+    ///
+    /// ### Instruction data
+    /// * `index_id: u64` – index identifier inside controller
+    ///
+    /// ### Fails with
+    /// * every error of `FreezeHolder`
+    ThawHolder { index_id: u64 },
+
+    /// 36. **SetTransferAllowlist**
+    ///
+    /// Adds `wallet` to, or removes it from, the transfer allowlist of an
+    /// index created with a transfer hook. Token accounts of the index mint
+    /// only transfer between owners on the allowlist.
+    ///
+    /// ### Behaviour
+    /// * `allowed` creates the entry PDA, paid by the signer.  
+    /// * `!allowed` closes it and returns the rent to the signer.  
+    /// * Setting the current state again is a no-op.
+    ///
+    /// ### Accounts
+    /// 0. `[signer, writable]`  signer                     – `index.owner`, or `controller.owner` / a `ComplianceOfficer`  
+    /// 1. `[]`                  controller_account         – controller PDA of the index  
+    /// 2. `[]`                  index_account              – index PDA  
+    /// 3. `[]`                  mint_account               – index Token-2022 mint  
+    /// 4. `[writable]`          transfer_allowlist_account – PDA (`b"open_index_allowlist"`, mint_account, wallet)  
+    /// 5. `[]`                  system_program_account     – `solana_program::system_program`  
+    /// 6. `[]`                  controller_roles_account   – PDA (`b"controller_roles"`, controller_account); may be uncreated
+    ///
+    /// ### Instruction data
+    /// * `index_id: u64`  – index identifier inside controller  
+    /// * `wallet: Pubkey` – owner of the token accounts to allow or disallow  
+    /// * `allowed: bool`  – add or remove the wallet
+    ///
+    /// ### Fails with
+    /// * `MissingControllerRole`                if signer is neither owner nor a `ComplianceOfficer`  
+    /// * `TransferHookNotEnabled`               if the index was created without a transfer hook  
+    /// * `IncorrectTransferAllowlistAccount`    if the allowlist PDA mismatches
+    SetTransferAllowlist {
+        index_id: u64,
+        wallet: Pubkey,
+        allowed: bool,
+    },
+
+    /// 37. **SetAssetRegistryEntry**
+    ///
+    /// Approves `mint_account` as an index component in the protocol asset
+    /// registry, or removes it. `AddIndexComponents` consults the registry
+    /// while `ControllerGlobalConfig::asset_registry_required` is set.
+    ///
+    /// ### Behaviour
+    /// * `approved` creates the entry PDA, paid by the signer, or overwrites
+    ///   the risk metadata of an existing one.  
+    /// * `!approved` closes the entry and returns the rent to the signer.  
+    /// * Components already added are not re-validated.
+    ///
+    /// ### Accounts
+    /// 0. `[signer, writable]`  signer                   – **must** be the protocol owner, pays rent  
+    /// 1. `[]`                  protocol_account         – protocol PDA  
+    /// 2. `[]`                  mint_account             – component mint being approved or removed  
+    /// 3. `[writable]`          asset_registry_account   – PDA (`b"asset_registry"`, mint_account)  
+    /// 4. `[]`                  system_program_account   – `solana_program::system_program`
+    ///
+    /// ### Instruction data
+    /// * `approved:       bool` – `true` approves or updates, `false` removes  
+    /// * `risk_tier:      u8`   – governance rating of the asset, informational  
+    /// * `accepted_risks: u8`   – `ASSET_RISK_*` flags the mint may carry
+    ///
+    /// ### Fails with
+    /// * `OnlyProtocolOwner`                    if signer ≠ protocol.owner  
+    /// * `IncorrectAssetRegistryAccount`        if PDA derivation mismatches  
+    /// * `InvalidAssetRisks`                    if `accepted_risks` has unknown flags  
+    /// * `UninitializedAccount`                 if removing a mint that is not registered
+    SetAssetRegistryEntry {
+        approved: bool,
+        risk_tier: u8,
+        accepted_risks: u8,
+    },
+}
+
+/// Accounts of an index nested in another one, for
+/// `redeem_underlying_instruction`.
+pub struct UnderlyingIndex {
+    pub controller_account: Pubkey,
+    pub index_id: u64,
+    pub mints: Vec<Pubkey>,
+    /// Indexes nested in this one, in `mints` order.
+    pub underlying: Vec<UnderlyingIndex>,
+}
+
+pub fn init_protocol_instruction(
+    program_id: Pubkey,
+    caller: Pubkey,
+    protocol_account: Pubkey,
+) -> Instruction {
+    let accounts = vec![
+        AccountMeta::new_readonly(caller, true),
+        AccountMeta::new(protocol_account, false),
+        AccountMeta::new_readonly(system_program::ID, false),
+        AccountMeta::new_readonly(get_program_data_address(&program_id), false),
+    ];
+    let instruction = ProtocolInstruction::InitProtocol;
+    let data = borsh::to_vec(&instruction).unwrap();
+    Instruction {
+        program_id,
+        accounts,
+        data,
+    }
+}
+
+pub fn init_controller_instruction(
+    program_id: Pubkey,
+    caller: Pubkey,
+    payer: Pubkey,
+    protocol_account: Pubkey,
+    controller_account: Pubkey,
+) -> Instruction {
+    let accounts = vec![
+        AccountMeta::new_readonly(caller, true),
+        AccountMeta::new(payer, true),
+        AccountMeta::new(protocol_account, false),
+        AccountMeta::new(controller_account, false),
+        AccountMeta::new_readonly(system_program::ID, false),
+        AccountMeta::new_readonly(find_controller_global_config_address(&program_id).0, false),
+        AccountMeta::new_readonly(find_controller_creator_address(&program_id, &caller).0, false),
+        AccountMeta::new(find_treasury_address(&program_id).0, false),
+    ];
+    let instruction = ProtocolInstruction::InitController;
+    let data = borsh::to_vec(&instruction).unwrap();
+    Instruction {
+        program_id,
+        accounts,
+        data,
+    }
+}
+
+pub fn init_controller_global_config_instruction(
+    program_id: Pubkey,
+    caller: Pubkey,
+    protocol_account: Pubkey,
+    controller_global_config_account: Pubkey,
+    max_index_components: u32,
+) -> Instruction {
+    let accounts = vec![
+        AccountMeta::new_readonly(caller, true),
+        AccountMeta::new_readonly(protocol_account, false),
+        AccountMeta::new(controller_global_config_account, false),
+        AccountMeta::new_readonly(system_program::ID, false),
+    ];
+    let instruction = ProtocolInstruction::InitControllerGlobalConfig {
+        max_index_components,
+    };
+    let data = borsh::to_vec(&instruction).unwrap();
+    Instruction {
+        program_id,
+        accounts,
+        data,
+    }
+}
+
+pub fn init_module_instruction(
+    program_id: Pubkey,
+    caller: Pubkey,
+    payer: Pubkey,
+    protocol_account: Pubkey,
+    module_signer_account: Pubkey,
+    registered_module_account: Pubkey,
+) -> Instruction {
+    let accounts = vec![
+        AccountMeta::new_readonly(caller, true),
+        AccountMeta::new(payer, true),
+        AccountMeta::new_readonly(protocol_account, false),
+        AccountMeta::new_readonly(module_signer_account, false),
+        AccountMeta::new(registered_module_account, false),
+        AccountMeta::new_readonly(system_program::ID, false),
+    ];
+    let instruction = ProtocolInstruction::InitModule;
+    let data = borsh::to_vec(&instruction).unwrap();
+    Instruction {
+        program_id,
+        accounts,
+        data,
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn create_index_instruction(
+    program_id: Pubkey,
+    caller: Pubkey,
+    payer: Pubkey,
+    manager: Pubkey,
+    index_account: Pubkey,
+    mint_account: Pubkey,
+    controller_account: Pubkey,
+    controller_global_config_account: Pubkey,
+    controller_config_account: Pubkey,
+) -> Instruction {
+    let accounts = vec![
+        AccountMeta::new_readonly(caller, true),
+        AccountMeta::new(payer, true),
+        AccountMeta::new_readonly(manager, false),
+        AccountMeta::new(index_account, false),
+        AccountMeta::new(mint_account, false),
+        AccountMeta::new(controller_account, false),
+        AccountMeta::new_readonly(controller_global_config_account, false),
+        AccountMeta::new_readonly(controller_config_account, false),
+        AccountMeta::new_readonly(system_program::ID, false),
+        AccountMeta::new_readonly(spl_token::ID, false),
+        AccountMeta::new(find_treasury_address(&program_id).0, false),
+        AccountMeta::new_readonly(
+            find_controller_roles_address(&program_id, &controller_account).0,
+            false,
+        ),
+    ];
+    let instruction = ProtocolInstruction::CreateIndex {
+        drop_freeze_authority: false,
+        transfer_hook_program: None,
+    };
+    let data = borsh::to_vec(&instruction).unwrap();
+    Instruction {
+        program_id,
+        accounts,
+        data,
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn create_index_with_seed_instruction(
+    program_id: Pubkey,
+    caller: Pubkey,
+    payer: Pubkey,
+    manager: Pubkey,
+    index_account: Pubkey,
+    mint_account: Pubkey,
+    controller_account: Pubkey,
+    controller_global_config_account: Pubkey,
+    controller_config_account: Pubkey,
+    seed: [u8; 32],
+) -> Instruction {
+    let accounts = vec![
+        AccountMeta::new_readonly(caller, true),
+        AccountMeta::new(payer, true),
+        AccountMeta::new_readonly(manager, false),
+        AccountMeta::new(index_account, false),
+        AccountMeta::new(mint_account, false),
+        AccountMeta::new(controller_account, false),
+        AccountMeta::new_readonly(controller_global_config_account, false),
+        AccountMeta::new_readonly(controller_config_account, false),
+        AccountMeta::new_readonly(system_program::ID, false),
+        AccountMeta::new_readonly(spl_token::ID, false),
+        AccountMeta::new(find_treasury_address(&program_id).0, false),
+        AccountMeta::new_readonly(
+            find_controller_roles_address(&program_id, &controller_account).0,
+            false,
+        ),
+    ];
+    let instruction = ProtocolInstruction::CreateIndexWithSeed {
+        seed,
+        drop_freeze_authority: false,
+        transfer_hook_program: None,
+    };
+    let data = borsh::to_vec(&instruction).unwrap();
+    Instruction {
+        program_id,
+        accounts,
+        data,
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn add_index_components_instruction(
+    program_id: Pubkey,
+    caller: Pubkey,
+    payer: Pubkey,
+    index_account: Pubkey,
+    index_mints_data_account: Pubkey,
+    controller_account: Pubkey,
+    controller_global_config_account: Pubkey,
+    controller_config_account: Pubkey,
+    mints: Vec<Pubkey>,
+    amounts: Vec<u64>,
+) -> Instruction {
+    let accounts = vec![
+        AccountMeta::new_readonly(caller, true),
+        AccountMeta::new(payer, true),
+        AccountMeta::new(index_account, false),
+        AccountMeta::new(index_mints_data_account, false),
+        AccountMeta::new_readonly(controller_account, false),
+        AccountMeta::new(controller_global_config_account, false),
+        AccountMeta::new_readonly(controller_config_account, false),
+        AccountMeta::new_readonly(
+            find_controller_roles_address(&program_id, &controller_account).0,
+            false,
+        ),
+        AccountMeta::new_readonly(system_program::ID, false),
+        AccountMeta::new_readonly(spl_associated_token_account::ID, false),
+        AccountMeta::new_readonly(spl_token::ID, false),
+    ];
+
+    let instruction = ProtocolInstruction::AddIndexComponents {
+        amounts,
+        mints,
+        nested_components: vec![],
+    };
+    let data = borsh::to_vec(&instruction).unwrap();
+    Instruction {
+        program_id,
+        accounts,
+        data,
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn add_index_components_instruction_with_dynamic_accounts(
+    program_id: Pubkey,
+    caller: Pubkey,
+    payer: Pubkey,
+    index_account: Pubkey,
+    index_mints_data_account: Pubkey,
+    controller_account: Pubkey,
+    controller_global_config_account: Pubkey,
+    controller_config_account: Pubkey,
+    mints: Vec<Pubkey>,
+    amounts: Vec<u64>,
+) -> Instruction {
+    let mut accounts = vec![
+        AccountMeta::new_readonly(caller, true),
+        AccountMeta::new(payer, true),
+        AccountMeta::new(index_account, false),
+        AccountMeta::new(index_mints_data_account, false),
+        AccountMeta::new_readonly(controller_account, false),
+        AccountMeta::new(controller_global_config_account, false),
+        AccountMeta::new_readonly(controller_config_account, false),
+        AccountMeta::new_readonly(
+            find_controller_roles_address(&program_id, &controller_account).0,
+            false,
+        ),
+        AccountMeta::new_readonly(system_program::ID, false),
+        AccountMeta::new_readonly(spl_associated_token_account::ID, false),
+        AccountMeta::new_readonly(spl_token::ID, false),
+    ];
+
+    for mint in mints.iter() {
+        let (component_pda, _) = find_component_address(&program_id, &index_account, mint);
+        let (vault_pda, _) = find_component_vault_address(&program_id, &index_account, mint);
+        let vault_ata =
+            get_associated_token_address_with_program_id(&vault_pda, mint, &spl_token::ID);
+
+        accounts.push(AccountMeta::new(mint.clone(), false));
+        accounts.push(AccountMeta::new(component_pda, false));
+        accounts.push(AccountMeta::new_readonly(vault_pda, false));
+        accounts.push(AccountMeta::new(vault_ata, false));
+    }
+
+    let instruction = ProtocolInstruction::AddIndexComponents {
+        amounts,
+        mints,
+        nested_components: vec![],
+    };
+    let data = borsh::to_vec(&instruction).unwrap();
+    Instruction {
+        program_id,
+        accounts,
+        data,
+    }
+}
+
+/// redeem
+pub fn mint_instruction(
+    caller: Pubkey,
+    program_id: Pubkey,
+    controller_account: Pubkey,
+    mint_account: Pubkey,
+    mint_authority_account: Pubkey,
+    index_account: Pubkey,
+    index_mints_data_account: Pubkey,
+    token_account: Pubkey,
+    token_program_account: Pubkey,
+    index_id: u64,
+    amount: u64,
+) -> Instruction {
+    let accounts = vec![
+        AccountMeta::new_readonly(caller, true),
+        AccountMeta::new_readonly(controller_account, false),
+        AccountMeta::new(mint_account, false),
+        AccountMeta::new_readonly(mint_authority_account, false),
+        AccountMeta::new_readonly(index_account, false),
+        AccountMeta::new_readonly(index_mints_data_account, false),
+        AccountMeta::new_readonly(program_id, false),
+        AccountMeta::new(token_account, false),
+        AccountMeta::new_readonly(token_program_account, false),
+    ];
+    let instruction = ProtocolInstruction::Mint {
+        index_id,
+        amount,
+        expected_version: None,
+        max_amounts_in: None,
+        recipient: None,
+    };
+    let data = borsh::to_vec(&instruction).unwrap();
+    Instruction {
+        program_id,
+        accounts,
+        data,
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn mint_instruction_with_dynamic_accounts(
+    caller: Pubkey,
+    program_id: Pubkey,
+    controller_account: Pubkey,
+    mint_account: Pubkey,
+    mint_authority_account: Pubkey,
+    index_account: Pubkey,
+    index_mints_data_account: Pubkey,
+    token_account: Pubkey,
+    token_program_account: Pubkey,
+    mints: Vec<Pubkey>,
+    token_accounts: Vec<Pubkey>,
+    index_id: u64,
+    amount: u64,
+) -> Instruction {
+    let mut accounts = vec![
+        AccountMeta::new_readonly(caller, true),
+        AccountMeta::new_readonly(controller_account, false),
+        AccountMeta::new(mint_account, false),
+        AccountMeta::new_readonly(mint_authority_account, false),
+        AccountMeta::new_readonly(index_account, false),
+        AccountMeta::new_readonly(index_mints_data_account, false),
+        AccountMeta::new(token_account, false),
+        AccountMeta::new_readonly(token_program_account, false),
+    ];
+    let instruction = ProtocolInstruction::Mint {
+        index_id,
+        amount,
+        expected_version: None,
+        max_amounts_in: None,
+        recipient: None,
+    };
+    let data = borsh::to_vec(&instruction).unwrap();
+
+    for (index, _mint) in mints.iter().enumerate() {
+        let (component_pda, _) = find_component_address(&program_id, &index_account, _mint);
+        let (vault_pda, _) = find_component_vault_address(&program_id, &index_account, _mint);
+        let vault_ata =
+            get_associated_token_address_with_program_id(&vault_pda, _mint, &spl_token::ID);
+
+        accounts.push(AccountMeta::new_readonly(_mint.clone(), false));
+        accounts.push(AccountMeta::new_readonly(component_pda, false));
+        accounts.push(AccountMeta::new_readonly(vault_pda, false));
+        accounts.push(AccountMeta::new(vault_ata, false));
+        let _token_account = token_accounts.get(index).unwrap();
+        accounts.push(AccountMeta::new(*_token_account, false));
+    }
+
+    Instruction {
+        program_id,
+        accounts,
+        data,
+    }
+}
+
+/// redeem
+pub fn redeem_instruction(
+    caller: Pubkey,
+    program_id: Pubkey,
+    controller_account: Pubkey,
+    mint_account: Pubkey,
+    mint_authority_account: Pubkey,
+    index_account: Pubkey,
+    index_mints_data_account: Pubkey,
+    token_account: Pubkey,
+    token_program_account: Pubkey,
+    index_id: u64,
+    amount: u64,
+) -> Instruction {
+    let accounts = vec![
+        AccountMeta::new_readonly(caller, true),
+        AccountMeta::new_readonly(controller_account, false),
+        AccountMeta::new(mint_account, false),
+        AccountMeta::new_readonly(mint_authority_account, false),
+        AccountMeta::new_readonly(index_account, false),
+        AccountMeta::new_readonly(index_mints_data_account, false),
+        AccountMeta::new_readonly(program_id, false),
+        AccountMeta::new(token_account, false),
+        AccountMeta::new_readonly(token_program_account, false),
+    ];
+    let instruction = ProtocolInstruction::Redeem {
+        index_id,
+        amount,
+        expected_version: None,
+        min_amounts_out: None,
+        recipient: None,
+        create_destination_accounts: false,
+        unwrap_native: false,
+    };
+    let data = borsh::to_vec(&instruction).unwrap();
+    Instruction {
+        program_id,
+        accounts,
+        data,
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn redeem_instruction_with_dynamic_accounts(
+    caller: Pubkey,
+    program_id: Pubkey,
+    controller_account: Pubkey,
+    mint_account: Pubkey,
+    mint_authority_account: Pubkey,
+    index_account: Pubkey,
+    index_mints_data_account: Pubkey,
+    token_account: Pubkey,
+    token_program_account: Pubkey,
+    mints: Vec<Pubkey>,
+    token_accounts: Vec<Pubkey>,
+    index_id: u64,
+    amount: u64,
+) -> Instruction {
+    let mut accounts = vec![
+        AccountMeta::new_readonly(caller, true),
+        AccountMeta::new_readonly(controller_account, false),
+        AccountMeta::new(mint_account, false),
+        AccountMeta::new_readonly(mint_authority_account, false),
+        AccountMeta::new_readonly(index_account, false),
+        AccountMeta::new_readonly(index_mints_data_account, false),
+        AccountMeta::new(token_account, false),
+        AccountMeta::new_readonly(token_program_account, false),
+    ];
+    let instruction = ProtocolInstruction::Redeem {
+        index_id,
+        amount,
+        expected_version: None,
+        min_amounts_out: None,
+        recipient: None,
+        create_destination_accounts: false,
+        unwrap_native: false,
+    };
+    let data = borsh::to_vec(&instruction).unwrap();
+
+    for (index, _mint) in mints.iter().enumerate() {
+        let (component_pda, _) = find_component_address(&program_id, &index_account, _mint);
+        let (vault_pda, _) = find_component_vault_address(&program_id, &index_account, _mint);
+        let vault_ata =
+            get_associated_token_address_with_program_id(&vault_pda, _mint, &spl_token::ID);
+
+        accounts.push(AccountMeta::new_readonly(_mint.clone(), false));
+        accounts.push(AccountMeta::new_readonly(component_pda, false));
+        accounts.push(AccountMeta::new_readonly(vault_pda, false));
+        accounts.push(AccountMeta::new(vault_ata, false));
+        let _token_account = token_accounts.get(index).unwrap();
+        accounts.push(AccountMeta::new(*_token_account, false));
+    }
+
+    Instruction {
+        program_id,
+        accounts,
+        data,
+    }
+}
+
+/// Same as `mint_instruction_with_dynamic_accounts`, but reverts unless the
+/// index is still at `expected_version` and no deposit exceeds `max_amounts_in`.
+#[allow(clippy::too_many_arguments)]
+pub fn mint_instruction_with_guard(
+    caller: Pubkey,
+    program_id: Pubkey,
+    controller_account: Pubkey,
+    mint_account: Pubkey,
+    mint_authority_account: Pubkey,
+    index_account: Pubkey,
+    index_mints_data_account: Pubkey,
+    token_account: Pubkey,
+    token_program_account: Pubkey,
+    mints: Vec<Pubkey>,
+    token_accounts: Vec<Pubkey>,
+    index_id: u64,
+    amount: u64,
+    expected_version: Option<u64>,
+    max_amounts_in: Option<Vec<u64>>,
+) -> Instruction {
+    let mut instruction = mint_instruction_with_dynamic_accounts(
+        caller,
+        program_id,
+        controller_account,
+        mint_account,
+        mint_authority_account,
+        index_account,
+        index_mints_data_account,
+        token_account,
+        token_program_account,
+        mints,
+        token_accounts,
+        index_id,
+        amount,
+    );
+    instruction.data = borsh::to_vec(&ProtocolInstruction::Mint {
+        index_id,
+        amount,
+        expected_version,
+        max_amounts_in,
+        recipient: None,
+    })
+    .unwrap();
+    instruction
+}
+
+/// Same as `redeem_instruction_with_dynamic_accounts`, but reverts unless the
+/// index is still at `expected_version` and every withdrawal reaches
+/// `min_amounts_out`.
+#[allow(clippy::too_many_arguments)]
+pub fn redeem_instruction_with_guard(
+    caller: Pubkey,
+    program_id: Pubkey,
+    controller_account: Pubkey,
+    mint_account: Pubkey,
+    mint_authority_account: Pubkey,
+    index_account: Pubkey,
+    index_mints_data_account: Pubkey,
+    token_account: Pubkey,
+    token_program_account: Pubkey,
+    mints: Vec<Pubkey>,
+    token_accounts: Vec<Pubkey>,
+    index_id: u64,
+    amount: u64,
+    expected_version: Option<u64>,
+    min_amounts_out: Option<Vec<u64>>,
+) -> Instruction {
+    let mut instruction = redeem_instruction_with_dynamic_accounts(
+        caller,
+        program_id,
+        controller_account,
+        mint_account,
+        mint_authority_account,
+        index_account,
+        index_mints_data_account,
+        token_account,
+        token_program_account,
+        mints,
+        token_accounts,
+        index_id,
+        amount,
+    );
+    instruction.data = borsh::to_vec(&ProtocolInstruction::Redeem {
+        index_id,
+        amount,
+        expected_version,
+        min_amounts_out,
+        recipient: None,
+        create_destination_accounts: false,
+        unwrap_native: false,
+    })
+    .unwrap();
+    instruction
+}
+
+/// Same as `mint_instruction_with_dynamic_accounts`, but mints into
+/// `token_account` owned by `recipient` instead of the caller. Entries of
+/// `token_accounts` may be owned by anyone who approved the caller as SPL
+/// delegate for the component amount.
+#[allow(clippy::too_many_arguments)]
+pub fn mint_instruction_with_recipient(
+    caller: Pubkey,
+    program_id: Pubkey,
+    controller_account: Pubkey,
+    mint_account: Pubkey,
+    mint_authority_account: Pubkey,
+    index_account: Pubkey,
+    index_mints_data_account: Pubkey,
+    token_account: Pubkey,
+    token_program_account: Pubkey,
+    mints: Vec<Pubkey>,
+    token_accounts: Vec<Pubkey>,
+    index_id: u64,
+    amount: u64,
+    recipient: Pubkey,
+) -> Instruction {
+    let mut instruction = mint_instruction_with_dynamic_accounts(
+        caller,
+        program_id,
+        controller_account,
+        mint_account,
+        mint_authority_account,
+        index_account,
+        index_mints_data_account,
+        token_account,
+        token_program_account,
+        mints,
+        token_accounts,
+        index_id,
+        amount,
+    );
+    instruction.data = borsh::to_vec(&ProtocolInstruction::Mint {
+        index_id,
+        amount,
+        expected_version: None,
+        max_amounts_in: None,
+        recipient: Some(recipient),
+    })
+    .unwrap();
+    instruction
+}
+
+/// Same as `redeem_instruction_with_dynamic_accounts`, but sends every
+/// component to the associated token account of `recipient`. With
+/// `create_destination_accounts` the caller pays for any missing one.
+#[allow(clippy::too_many_arguments)]
+pub fn redeem_instruction_with_recipient(
+    caller: Pubkey,
+    program_id: Pubkey,
+    controller_account: Pubkey,
+    mint_account: Pubkey,
+    mint_authority_account: Pubkey,
+    index_account: Pubkey,
+    index_mints_data_account: Pubkey,
+    token_account: Pubkey,
+    token_program_account: Pubkey,
+    mints: Vec<Pubkey>,
+    index_id: u64,
+    amount: u64,
+    recipient: Pubkey,
+    create_destination_accounts: bool,
+) -> Instruction {
+    let token_accounts = mints
+        .iter()
+        .map(|mint| {
+            get_associated_token_address_with_program_id(&recipient, mint, &token_program_account)
+        })
+        .collect();
+    let mut instruction = redeem_instruction_with_dynamic_accounts(
+        caller,
+        program_id,
+        controller_account,
+        mint_account,
+        mint_authority_account,
+        index_account,
+        index_mints_data_account,
+        token_account,
+        token_program_account,
+        mints,
+        token_accounts,
+        index_id,
+        amount,
+    );
+    if create_destination_accounts {
+        instruction.accounts[0].is_writable = true;
+        instruction.accounts.extend([
+            AccountMeta::new_readonly(recipient, false),
+            AccountMeta::new_readonly(system_program::ID, false),
+            AccountMeta::new_readonly(spl_associated_token_account::ID, false),
+        ]);
+    }
+    instruction.data = borsh::to_vec(&ProtocolInstruction::Redeem {
+        index_id,
+        amount,
+        expected_version: None,
+        min_amounts_out: None,
+        recipient: Some(recipient),
+        create_destination_accounts,
+        unwrap_native: false,
+    })
+    .unwrap();
+    instruction
+}
+
+/// Adds components like `add_index_components_instruction_with_dynamic_accounts`,
+/// where `underlying_indexes[i]` names the `(controller, index)` accounts of
+/// `mints[i]` when that mint is itself an index mint.
+#[allow(clippy::too_many_arguments)]
+pub fn add_index_components_instruction_with_underlying_indexes(
+    program_id: Pubkey,
+    caller: Pubkey,
+    payer: Pubkey,
+    index_account: Pubkey,
+    index_mints_data_account: Pubkey,
+    controller_account: Pubkey,
+    controller_global_config_account: Pubkey,
+    controller_config_account: Pubkey,
+    mints: Vec<Pubkey>,
+    amounts: Vec<u64>,
+    underlying_indexes: Vec<Option<(Pubkey, Pubkey)>>,
+) -> Instruction {
+    let mut instruction = add_index_components_instruction_with_dynamic_accounts(
+        program_id,
+        caller,
+        payer,
+        index_account,
+        index_mints_data_account,
+        controller_account,
+        controller_global_config_account,
+        controller_config_account,
+        mints.clone(),
+        amounts.clone(),
+    );
+    for (underlying_controller, underlying_index) in underlying_indexes.iter().flatten() {
+        instruction.accounts.extend([
+            AccountMeta::new_readonly(*underlying_controller, false),
+            AccountMeta::new_readonly(*underlying_index, false),
+        ]);
+    }
+    instruction.data = borsh::to_vec(&ProtocolInstruction::AddIndexComponents {
+        amounts,
+        mints,
+        nested_components: underlying_indexes
+            .iter()
+            .map(|underlying_index| underlying_index.is_some())
+            .collect(),
+    })
+    .unwrap();
+    instruction
+}
+
+/// Redeems `amount` tokens of an index down to its base assets. Every
+/// component is paid to the caller's associated token account, so those
+/// accounts must exist for the index and for all `underlying` indexes.
+#[allow(clippy::too_many_arguments)]
+pub fn redeem_underlying_instruction(
+    caller: Pubkey,
+    program_id: Pubkey,
+    controller_account: Pubkey,
+    token_program_account: Pubkey,
+    mints: Vec<Pubkey>,
+    underlying: Vec<UnderlyingIndex>,
+    index_id: u64,
+    amount: u64,
+) -> Instruction {
+    let index_account = find_index_address(&program_id, &controller_account, index_id).0;
+    let mint_account = find_index_mint_address(&program_id, &controller_account, index_id).0;
+    let token_accounts: Vec<Pubkey> = mints
+        .iter()
+        .map(|mint| {
+            get_associated_token_address_with_program_id(&caller, mint, &token_program_account)
+        })
+        .collect();
+
+    let mut accounts = vec![
+        AccountMeta::new_readonly(caller, true),
+        AccountMeta::new_readonly(controller_account, false),
+        AccountMeta::new(mint_account, false),
+        AccountMeta::new_readonly(
+            find_index_mint_authority_address(&program_id, &controller_account, index_id).0,
+            false,
+        ),
+        AccountMeta::new_readonly(index_account, false),
+        AccountMeta::new_readonly(
+            find_index_mints_data_address(&program_id, &controller_account, index_id).0,
+            false,
+        ),
+        AccountMeta::new(
+            get_associated_token_address_with_program_id(
+                &caller,
+                &mint_account,
+                &token_program_account,
+            ),
+            false,
+        ),
+        AccountMeta::new_readonly(token_program_account, false),
+    ];
+    push_component_bundles(
+        &mut accounts,
+        &program_id,
+        &index_account,
+        &mints,
+        &token_accounts,
+    );
+    push_underlying_index_accounts(
+        &mut accounts,
+        &program_id,
+        &caller,
+        &token_program_account,
+        &underlying,
+    );
+
+    let instruction = ProtocolInstruction::RedeemUnderlying { index_id, amount };
+    let data = borsh::to_vec(&instruction).unwrap();
+    Instruction {
+        program_id,
+        accounts,
+        data,
+    }
+}
+
+pub fn flash_mint_instruction(
+    caller: Pubkey,
+    program_id: Pubkey,
+    controller_account: Pubkey,
+    token_account: Pubkey,
+    index_id: u64,
+    amount: u64,
+) -> Instruction {
+    let index_account = find_index_address(&program_id, &controller_account, index_id).0;
+    let accounts = vec![
+        AccountMeta::new(caller, true),
+        AccountMeta::new_readonly(controller_account, false),
+        AccountMeta::new(
+            find_index_mint_address(&program_id, &controller_account, index_id).0,
+            false,
+        ),
+        AccountMeta::new_readonly(
+            find_index_mint_authority_address(&program_id, &controller_account, index_id).0,
+            false,
+        ),
+        AccountMeta::new_readonly(index_account, false),
+        AccountMeta::new_readonly(find_controller_global_config_address(&program_id).0, false),
+        AccountMeta::new_readonly(
+            find_controller_config_address(&program_id, &controller_account).0,
+            false,
+        ),
+        AccountMeta::new(
+            find_flash_mint_address(&program_id, &index_account, &caller).0,
+            false,
+        ),
+        AccountMeta::new(token_account, false),
+        AccountMeta::new_readonly(system_program::ID, false),
+        AccountMeta::new_readonly(spl_token::ID, false),
+        AccountMeta::new_readonly(solana_program::sysvar::instructions::ID, false),
+    ];
+
+    let instruction = ProtocolInstruction::FlashMint { index_id, amount };
+    let data = borsh::to_vec(&instruction).unwrap();
+    Instruction {
+        program_id,
+        accounts,
+        data,
+    }
+}
+
+pub fn settle_flash_mint_instruction(
+    caller: Pubkey,
+    program_id: Pubkey,
+    controller_account: Pubkey,
+    mints: Vec<Pubkey>,
+    token_accounts: Vec<Pubkey>,
+    index_id: u64,
+) -> Instruction {
+    let index_account = find_index_address(&program_id, &controller_account, index_id).0;
+    let mut accounts = vec![
+        AccountMeta::new(caller, true),
+        AccountMeta::new_readonly(controller_account, false),
+        AccountMeta::new_readonly(index_account, false),
+        AccountMeta::new_readonly(
+            find_index_mints_data_address(&program_id, &controller_account, index_id).0,
+            false,
+        ),
+        AccountMeta::new(
+            find_flash_mint_address(&program_id, &index_account, &caller).0,
+            false,
+        ),
+        AccountMeta::new_readonly(spl_token::ID, false),
+    ];
+    push_component_bundles(
+        &mut accounts,
+        &program_id,
+        &index_account,
+        &mints,
+        &token_accounts,
+    );
+
+    let instruction = ProtocolInstruction::SettleFlashMint { index_id };
+    let data = borsh::to_vec(&instruction).unwrap();
+    Instruction {
+        program_id,
+        accounts,
+        data,
+    }
+}
+
+pub fn set_flash_loan_config_instruction(
+    program_id: Pubkey,
+    caller: Pubkey,
+    index_account: Pubkey,
+    controller_account: Pubkey,
+    enabled: bool,
+    fee_bps: u16,
+) -> Instruction {
+    let accounts = vec![
+        AccountMeta::new(caller, true),
+        AccountMeta::new(index_account, false),
+        AccountMeta::new_readonly(controller_account, false),
+        AccountMeta::new_readonly(
+            find_controller_config_address(&program_id, &controller_account).0,
+            false,
+        ),
+        AccountMeta::new_readonly(system_program::ID, false),
+    ];
+    let instruction = ProtocolInstruction::SetFlashLoanConfig { enabled, fee_bps };
+    let data = borsh::to_vec(&instruction).unwrap();
+    Instruction {
+        program_id,
+        accounts,
+        data,
+    }
+}
+
+pub fn flash_borrow_instruction(
+    caller: Pubkey,
+    program_id: Pubkey,
+    controller_account: Pubkey,
+    mint: Pubkey,
+    token_account: Pubkey,
+    index_id: u64,
+    amount: u64,
+) -> Instruction {
+    let index_account = find_index_address(&program_id, &controller_account, index_id).0;
+    let component_account = find_component_address(&program_id, &index_account, &mint).0;
+    let vault_pda = find_component_vault_address(&program_id, &index_account, &mint).0;
+    let accounts = vec![
+        AccountMeta::new(caller, true),
+        AccountMeta::new_readonly(controller_account, false),
+        AccountMeta::new_readonly(index_account, false),
+        AccountMeta::new_readonly(mint, false),
+        AccountMeta::new_readonly(component_account, false),
+        AccountMeta::new_readonly(vault_pda, false),
+        AccountMeta::new(
+            get_associated_token_address_with_program_id(&vault_pda, &mint, &spl_token::ID),
+            false,
+        ),
+        AccountMeta::new(
+            find_flash_loan_address(&program_id, &component_account, &caller).0,
+            false,
+        ),
+        AccountMeta::new(token_account, false),
+        AccountMeta::new_readonly(system_program::ID, false),
+        AccountMeta::new_readonly(spl_token::ID, false),
+        AccountMeta::new_readonly(solana_program::sysvar::instructions::ID, false),
+    ];
+
+    let instruction = ProtocolInstruction::FlashBorrow { index_id, amount };
+    let data = borsh::to_vec(&instruction).unwrap();
+    Instruction {
+        program_id,
+        accounts,
+        data,
+    }
+}
+
+pub fn flash_repay_instruction(
+    caller: Pubkey,
+    program_id: Pubkey,
+    index_account: Pubkey,
+    mint: Pubkey,
+    token_account: Pubkey,
+) -> Instruction {
+    let component_account = find_component_address(&program_id, &index_account, &mint).0;
+    let vault_pda = find_component_vault_address(&program_id, &index_account, &mint).0;
+    let accounts = vec![
+        AccountMeta::new(caller, true),
+        AccountMeta::new_readonly(component_account, false),
+        AccountMeta::new(
+            get_associated_token_address_with_program_id(&vault_pda, &mint, &spl_token::ID),
+            false,
+        ),
+        AccountMeta::new(
+            find_flash_loan_address(&program_id, &component_account, &caller).0,
+            false,
+        ),
+        AccountMeta::new(token_account, false),
+        AccountMeta::new_readonly(spl_token::ID, false),
+    ];
+
+    let instruction = ProtocolInstruction::FlashRepay;
+    let data = borsh::to_vec(&instruction).unwrap();
+    Instruction {
+        program_id,
+        accounts,
+        data,
+    }
+}
+
+/// Pushes the accounts `RedeemUnderlying` expects for `underlying` indexes,
+/// depth first.
+fn push_underlying_index_accounts(
+    accounts: &mut Vec<AccountMeta>,
+    program_id: &Pubkey,
+    caller: &Pubkey,
+    token_program_account: &Pubkey,
+    underlying: &[UnderlyingIndex],
+) {
+    for underlying_index in underlying {
+        let controller_account = underlying_index.controller_account;
+        let index_id = underlying_index.index_id;
+        let index_account = find_index_address(program_id, &controller_account, index_id).0;
+        let token_accounts: Vec<Pubkey> = underlying_index
+            .mints
+            .iter()
+            .map(|mint| {
+                get_associated_token_address_with_program_id(caller, mint, token_program_account)
+            })
+            .collect();
+
+        accounts.extend([
+            AccountMeta::new_readonly(controller_account, false),
+            AccountMeta::new(
+                find_index_mint_address(program_id, &controller_account, index_id).0,
+                false,
+            ),
+            AccountMeta::new_readonly(
+                find_index_mint_authority_address(program_id, &controller_account, index_id).0,
+                false,
+            ),
+            AccountMeta::new_readonly(index_account, false),
+            AccountMeta::new_readonly(
+                find_index_mints_data_address(program_id, &controller_account, index_id).0,
+                false,
+            ),
+        ]);
+        push_component_bundles(
+            accounts,
+            program_id,
+            &index_account,
+            &underlying_index.mints,
+            &token_accounts,
+        );
+        push_underlying_index_accounts(
+            accounts,
+            program_id,
+            caller,
+            token_program_account,
+            &underlying_index.underlying,
+        );
+    }
+}
+
+/// Pushes the per-component bundle used by the staged mint and redeem instructions.
+fn push_component_bundles(
+    accounts: &mut Vec<AccountMeta>,
+    program_id: &Pubkey,
+    index_account: &Pubkey,
+    mints: &[Pubkey],
+    token_accounts: &[Pubkey],
+) {
+    for (index, mint) in mints.iter().enumerate() {
+        let (component_pda, _) = find_component_address(program_id, index_account, mint);
+        let (vault_pda, _) = find_component_vault_address(program_id, index_account, mint);
+        let vault_ata =
+            get_associated_token_address_with_program_id(&vault_pda, mint, &spl_token::ID);
+
+        accounts.push(AccountMeta::new_readonly(*mint, false));
+        accounts.push(AccountMeta::new_readonly(component_pda, false));
+        accounts.push(AccountMeta::new_readonly(vault_pda, false));
+        accounts.push(AccountMeta::new(vault_ata, false));
+        accounts.push(AccountMeta::new(token_accounts[index], false));
+    }
+}
+
+pub fn begin_mint_instruction(
+    caller: Pubkey,
+    program_id: Pubkey,
+    controller_account: Pubkey,
+    index_account: Pubkey,
+    index_mints_data_account: Pubkey,
+    index_id: u64,
+    amount: u64,
+) -> Instruction {
+    let (mint_request_account, _) =
+        find_mint_request_address(&program_id, &index_account, &caller);
+    let accounts = vec![
+        AccountMeta::new(caller, true),
+        AccountMeta::new_readonly(controller_account, false),
+        AccountMeta::new_readonly(index_account, false),
+        AccountMeta::new_readonly(index_mints_data_account, false),
+        AccountMeta::new(mint_request_account, false),
+        AccountMeta::new_readonly(system_program::ID, false),
+    ];
+    let instruction = ProtocolInstruction::BeginMint { index_id, amount };
+    let data = borsh::to_vec(&instruction).unwrap();
+    Instruction {
+        program_id,
+        accounts,
+        data,
+    }
+}
+
+pub fn deposit_components_instruction(
+    caller: Pubkey,
+    program_id: Pubkey,
+    controller_account: Pubkey,
+    index_account: Pubkey,
+    mints: Vec<Pubkey>,
+    token_accounts: Vec<Pubkey>,
+    index_id: u64,
+) -> Instruction {
+    let (mint_request_account, _) =
+        find_mint_request_address(&program_id, &index_account, &caller);
+    let mut accounts = vec![
+        AccountMeta::new_readonly(caller, true),
+        AccountMeta::new_readonly(controller_account, false),
+        AccountMeta::new_readonly(index_account, false),
+        AccountMeta::new(mint_request_account, false),
+        AccountMeta::new_readonly(spl_token::ID, false),
+    ];
+    push_component_bundles(
+        &mut accounts,
+        &program_id,
+        &index_account,
+        &mints,
+        &token_accounts,
+    );
+
+    let instruction = ProtocolInstruction::DepositComponents { index_id };
+    let data = borsh::to_vec(&instruction).unwrap();
+    Instruction {
+        program_id,
+        accounts,
+        data,
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn finalize_mint_instruction(
+    caller: Pubkey,
+    program_id: Pubkey,
+    controller_account: Pubkey,
+    mint_account: Pubkey,
+    mint_authority_account: Pubkey,
+    index_account: Pubkey,
+    token_account: Pubkey,
+    index_id: u64,
+) -> Instruction {
+    let (mint_request_account, _) =
+        find_mint_request_address(&program_id, &index_account, &caller);
+    let accounts = vec![
+        AccountMeta::new(caller, true),
+        AccountMeta::new_readonly(controller_account, false),
+        AccountMeta::new(mint_account, false),
+        AccountMeta::new_readonly(mint_authority_account, false),
+        AccountMeta::new_readonly(index_account, false),
+        AccountMeta::new(mint_request_account, false),
+        AccountMeta::new(token_account, false),
+        AccountMeta::new_readonly(spl_token::ID, false),
+    ];
+    let instruction = ProtocolInstruction::FinalizeMint { index_id };
+    let data = borsh::to_vec(&instruction).unwrap();
+    Instruction {
+        program_id,
+        accounts,
+        data,
+    }
+}
+
+pub fn cancel_mint_instruction(
+    caller: Pubkey,
+    program_id: Pubkey,
+    controller_account: Pubkey,
+    index_account: Pubkey,
+    mints: Vec<Pubkey>,
+    token_accounts: Vec<Pubkey>,
+    index_id: u64,
+) -> Instruction {
+    let (mint_request_account, _) =
+        find_mint_request_address(&program_id, &index_account, &caller);
+    let mut accounts = vec![
+        AccountMeta::new(caller, true),
+        AccountMeta::new_readonly(controller_account, false),
+        AccountMeta::new_readonly(index_account, false),
+        AccountMeta::new(mint_request_account, false),
+        AccountMeta::new_readonly(spl_token::ID, false),
     ];
+    push_component_bundles(
+        &mut accounts,
+        &program_id,
+        &index_account,
+        &mints,
+        &token_accounts,
+    );
+
+    let instruction = ProtocolInstruction::CancelMint { index_id };
+    let data = borsh::to_vec(&instruction).unwrap();
+    Instruction {
+        program_id,
+        accounts,
+        data,
+    }
+}
 
+#[allow(clippy::too_many_arguments)]
+pub fn begin_redeem_instruction(
+    caller: Pubkey,
+    program_id: Pubkey,
+    controller_account: Pubkey,
+    mint_account: Pubkey,
+    index_account: Pubkey,
+    index_mints_data_account: Pubkey,
+    token_account: Pubkey,
+    mints: Vec<Pubkey>,
+    index_id: u64,
+    amount: u64,
+) -> Instruction {
+    let (redeem_request_account, _) =
+        find_redeem_request_address(&program_id, &index_account, &caller);
+    let mut accounts = vec![
+        AccountMeta::new(caller, true),
+        AccountMeta::new_readonly(controller_account, false),
+        AccountMeta::new(mint_account, false),
+        AccountMeta::new_readonly(index_account, false),
+        AccountMeta::new_readonly(index_mints_data_account, false),
+        AccountMeta::new(redeem_request_account, false),
+        AccountMeta::new(token_account, false),
+        AccountMeta::new_readonly(spl_token::ID, false),
+        AccountMeta::new_readonly(system_program::ID, false),
+    ];
     for mint in mints.iter() {
         let (component_pda, _) = find_component_address(&program_id, &index_account, mint);
-        let (vault_pda, _) = find_component_vault_address(&program_id, &index_account, mint);
-        let vault_ata =
-            get_associated_token_address_with_program_id(&vault_pda, mint, &spl_token::ID);
+        accounts.push(AccountMeta::new_readonly(component_pda, false));
+    }
+    let instruction = ProtocolInstruction::BeginRedeem { index_id, amount };
+    let data = borsh::to_vec(&instruction).unwrap();
+    Instruction {
+        program_id,
+        accounts,
+        data,
+    }
+}
+
+pub fn withdraw_components_instruction(
+    caller: Pubkey,
+    program_id: Pubkey,
+    controller_account: Pubkey,
+    index_account: Pubkey,
+    mints: Vec<Pubkey>,
+    token_accounts: Vec<Pubkey>,
+    index_id: u64,
+) -> Instruction {
+    let (redeem_request_account, _) =
+        find_redeem_request_address(&program_id, &index_account, &caller);
+    let mut accounts = vec![
+        AccountMeta::new(caller, true),
+        AccountMeta::new_readonly(controller_account, false),
+        AccountMeta::new_readonly(index_account, false),
+        AccountMeta::new(redeem_request_account, false),
+        AccountMeta::new_readonly(spl_token::ID, false),
+    ];
+    push_component_bundles(
+        &mut accounts,
+        &program_id,
+        &index_account,
+        &mints,
+        &token_accounts,
+    );
+
+    let instruction = ProtocolInstruction::WithdrawComponents { index_id };
+    let data = borsh::to_vec(&instruction).unwrap();
+    Instruction {
+        program_id,
+        accounts,
+        data,
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn update_controller_global_config_instruction(
+    program_id: Pubkey,
+    caller: Pubkey,
+    protocol_account: Pubkey,
+    controller_global_config_account: Pubkey,
+    max_index_components: Option<u32>,
+    check_existing_indexes: bool,
+    controller_creation_policy: Option<ControllerCreationPolicy>,
+    controller_creation_fee: Option<u64>,
+    index_creation_fee: Option<u64>,
+    flash_mint_fee_bps: Option<u16>,
+    asset_registry_required: Option<bool>,
+) -> Instruction {
+    let accounts = vec![
+        AccountMeta::new(caller, true),
+        AccountMeta::new_readonly(protocol_account, false),
+        AccountMeta::new(controller_global_config_account, false),
+        AccountMeta::new_readonly(system_program::ID, false),
+        AccountMeta::new(find_treasury_address(&program_id).0, false),
+    ];
+    let instruction = ProtocolInstruction::UpdateControllerGlobalConfig {
+        max_index_components,
+        check_existing_indexes,
+        controller_creation_policy,
+        controller_creation_fee,
+        index_creation_fee,
+        flash_mint_fee_bps,
+        asset_registry_required,
+    };
+    let data = borsh::to_vec(&instruction).unwrap();
+    Instruction {
+        program_id,
+        accounts,
+        data,
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn set_controller_config_instruction(
+    program_id: Pubkey,
+    caller: Pubkey,
+    controller_account: Pubkey,
+    controller_config_account: Pubkey,
+    max_index_components: Option<u32>,
+    max_mint_fee_bps: u16,
+    max_redeem_fee_bps: u16,
+    default_decimals: Option<u8>,
+    allowed_mints: Vec<Pubkey>,
+    override_asset_checks: bool,
+) -> Instruction {
+    let accounts = vec![
+        AccountMeta::new(caller, true),
+        AccountMeta::new_readonly(controller_account, false),
+        AccountMeta::new(controller_config_account, false),
+        AccountMeta::new_readonly(system_program::ID, false),
+    ];
+    let instruction = ProtocolInstruction::SetControllerConfig {
+        max_index_components,
+        max_mint_fee_bps,
+        max_redeem_fee_bps,
+        default_decimals,
+        allowed_mints,
+        override_asset_checks,
+    };
+    let data = borsh::to_vec(&instruction).unwrap();
+    Instruction {
+        program_id,
+        accounts,
+        data,
+    }
+}
+
+pub fn set_controller_creator_instruction(
+    program_id: Pubkey,
+    caller: Pubkey,
+    protocol_account: Pubkey,
+    creator: Pubkey,
+    allowed: bool,
+) -> Instruction {
+    let accounts = vec![
+        AccountMeta::new(caller, true),
+        AccountMeta::new_readonly(protocol_account, false),
+        AccountMeta::new_readonly(creator, false),
+        AccountMeta::new(find_controller_creator_address(&program_id, &creator).0, false),
+        AccountMeta::new_readonly(system_program::ID, false),
+    ];
+    let instruction = ProtocolInstruction::SetControllerCreator { allowed };
+    let data = borsh::to_vec(&instruction).unwrap();
+    Instruction {
+        program_id,
+        accounts,
+        data,
+    }
+}
+
+pub fn set_asset_registry_entry_instruction(
+    program_id: Pubkey,
+    caller: Pubkey,
+    protocol_account: Pubkey,
+    mint: Pubkey,
+    approved: bool,
+    risk_tier: u8,
+    accepted_risks: u8,
+) -> Instruction {
+    let accounts = vec![
+        AccountMeta::new(caller, true),
+        AccountMeta::new_readonly(protocol_account, false),
+        AccountMeta::new_readonly(mint, false),
+        AccountMeta::new(find_asset_registry_address(&program_id, &mint).0, false),
+        AccountMeta::new_readonly(system_program::ID, false),
+    ];
+    let instruction = ProtocolInstruction::SetAssetRegistryEntry {
+        approved,
+        risk_tier,
+        accepted_risks,
+    };
+    let data = borsh::to_vec(&instruction).unwrap();
+    Instruction {
+        program_id,
+        accounts,
+        data,
+    }
+}
+
+pub fn withdraw_treasury_instruction(
+    program_id: Pubkey,
+    caller: Pubkey,
+    protocol_account: Pubkey,
+    destination: Pubkey,
+    amount: u64,
+) -> Instruction {
+    let accounts = vec![
+        AccountMeta::new_readonly(caller, true),
+        AccountMeta::new_readonly(protocol_account, false),
+        AccountMeta::new(find_treasury_address(&program_id).0, false),
+        AccountMeta::new(destination, false),
+    ];
+    let instruction = ProtocolInstruction::WithdrawTreasury { amount };
+    let data = borsh::to_vec(&instruction).unwrap();
+    Instruction {
+        program_id,
+        accounts,
+        data,
+    }
+}
+
+pub fn init_controller_with_nonce_instruction(
+    program_id: Pubkey,
+    caller: Pubkey,
+    payer: Pubkey,
+    protocol_account: Pubkey,
+    controller_account: Pubkey,
+    nonce: u64,
+) -> Instruction {
+    let shard = controller_counter_shard(&caller);
+    let accounts = vec![
+        AccountMeta::new_readonly(caller, true),
+        AccountMeta::new(payer, true),
+        AccountMeta::new_readonly(protocol_account, false),
+        AccountMeta::new(controller_account, false),
+        AccountMeta::new_readonly(system_program::ID, false),
+        AccountMeta::new_readonly(find_controller_global_config_address(&program_id).0, false),
+        AccountMeta::new_readonly(find_controller_creator_address(&program_id, &caller).0, false),
+        AccountMeta::new(find_treasury_address(&program_id).0, false),
+        AccountMeta::new(find_controller_counter_address(&program_id, shard).0, false),
+    ];
+    let instruction = ProtocolInstruction::InitControllerWithNonce { nonce };
+    let data = borsh::to_vec(&instruction).unwrap();
+    Instruction {
+        program_id,
+        accounts,
+        data,
+    }
+}
+
+pub fn grant_controller_role_instruction(
+    program_id: Pubkey,
+    caller: Pubkey,
+    controller_account: Pubkey,
+    holder: Pubkey,
+    role: ControllerRole,
+) -> Instruction {
+    let accounts = controller_role_accounts(program_id, caller, controller_account, holder);
+    let instruction = ProtocolInstruction::GrantControllerRole { role };
+    let data = borsh::to_vec(&instruction).unwrap();
+    Instruction {
+        program_id,
+        accounts,
+        data,
+    }
+}
+
+pub fn revoke_controller_role_instruction(
+    program_id: Pubkey,
+    caller: Pubkey,
+    controller_account: Pubkey,
+    holder: Pubkey,
+    role: ControllerRole,
+) -> Instruction {
+    let mut accounts = controller_role_accounts(program_id, caller, controller_account, holder);
+    // revoking never allocates, so the system program is not needed
+    accounts.pop();
+    let instruction = ProtocolInstruction::RevokeControllerRole { role };
+    let data = borsh::to_vec(&instruction).unwrap();
+    Instruction {
+        program_id,
+        accounts,
+        data,
+    }
+}
 
-        accounts.push(AccountMeta::new(mint.clone(), false));
-        accounts.push(AccountMeta::new(component_pda, false));
-        accounts.push(AccountMeta::new_readonly(vault_pda, false));
-        accounts.push(AccountMeta::new(vault_ata, false));
+fn controller_role_accounts(
+    program_id: Pubkey,
+    caller: Pubkey,
+    controller_account: Pubkey,
+    holder: Pubkey,
+) -> Vec<AccountMeta> {
+    vec![
+        AccountMeta::new(caller, true),
+        AccountMeta::new_readonly(controller_account, false),
+        AccountMeta::new_readonly(holder, false),
+        AccountMeta::new(
+            find_controller_roles_address(&program_id, &controller_account).0,
+            false,
+        ),
+        AccountMeta::new_readonly(system_program::ID, false),
+    ]
+}
+
+pub fn create_multisig_instruction(
+    program_id: Pubkey,
+    caller: Pubkey,
+    create_key: Pubkey,
+    members: Vec<Pubkey>,
+    threshold: u8,
+) -> Instruction {
+    let accounts = vec![
+        AccountMeta::new(caller, true),
+        AccountMeta::new_readonly(create_key, true),
+        AccountMeta::new(find_multisig_address(&program_id, &create_key).0, false),
+        AccountMeta::new_readonly(system_program::ID, false),
+    ];
+    let instruction = ProtocolInstruction::CreateMultisig { members, threshold };
+    let data = borsh::to_vec(&instruction).unwrap();
+    Instruction {
+        program_id,
+        accounts,
+        data,
     }
+}
 
-    let instruction = ProtocolInstruction::AddIndexComponents { amounts, mints };
+pub fn transfer_ownership_instruction(
+    program_id: Pubkey,
+    caller: Pubkey,
+    target_account: Pubkey,
+    new_owner: Pubkey,
+) -> Instruction {
+    let accounts = vec![
+        AccountMeta::new_readonly(caller, true),
+        AccountMeta::new(target_account, false),
+    ];
+    let instruction = ProtocolInstruction::TransferOwnership { new_owner };
     let data = borsh::to_vec(&instruction).unwrap();
     Instruction {
         program_id,
@@ -624,32 +3187,126 @@ pub fn add_index_components_instruction_with_dynamic_accounts(
     }
 }
 
-/// redeem
-pub fn mint_instruction(
+/// Makes a `CreateIndex` or `CreateIndexWithSeed` instruction create a
+/// permissionless index, whose mint has no freeze authority.
+pub fn without_freeze_authority(mut instruction: Instruction) -> Instruction {
+    let mut data = ProtocolInstruction::try_from_slice(&instruction.data).unwrap();
+    if let ProtocolInstruction::CreateIndex {
+        drop_freeze_authority,
+        ..
+    }
+    | ProtocolInstruction::CreateIndexWithSeed {
+        drop_freeze_authority,
+        ..
+    } = &mut data
+    {
+        *drop_freeze_authority = true;
+    }
+    instruction.data = borsh::to_vec(&data).unwrap();
+    instruction
+}
+
+/// Makes a `CreateIndex` or `CreateIndexWithSeed` instruction create a
+/// Token-2022 index mint whose transfers are restricted by
+/// `transfer_hook_program` to the wallets on the index transfer allowlist.
+/// `mint_authority_account` signs the registration of the hook's extra
+/// accounts. Instructions moving the index tokens then need
+/// `with_token_2022`.
+pub fn with_transfer_hook(
+    mut instruction: Instruction,
+    transfer_hook_program: Pubkey,
+    mint_authority_account: Pubkey,
+) -> Instruction {
+    let mut data = ProtocolInstruction::try_from_slice(&instruction.data).unwrap();
+    if let ProtocolInstruction::CreateIndex {
+        transfer_hook_program: hook,
+        ..
+    }
+    | ProtocolInstruction::CreateIndexWithSeed {
+        transfer_hook_program: hook,
+        ..
+    } = &mut data
+    {
+        *hook = Some(transfer_hook_program);
+    }
+    instruction.data = borsh::to_vec(&data).unwrap();
+
+    let mint_account = instruction.accounts[4].pubkey;
+    instruction.accounts[9] = AccountMeta::new_readonly(spl_token_2022::ID, false);
+    instruction.accounts.splice(
+        12..12,
+        [
+            AccountMeta::new_readonly(transfer_hook_program, false),
+            AccountMeta::new(
+                get_extra_account_metas_address(&mint_account, &transfer_hook_program),
+                false,
+            ),
+            AccountMeta::new_readonly(mint_authority_account, false),
+        ],
+    );
+    instruction
+}
+
+/// Adds the asset registry account of every component mint to an
+/// `AddIndexComponents` or `ExecuteCompositionChange` instruction, which need
+/// them while `ControllerGlobalConfig::asset_registry_required` is set and the
+/// controller config does not set `override_asset_checks`. `mints` is the
+/// recipe the instruction adds or executes.
+pub fn with_asset_registry_accounts(mut instruction: Instruction, mints: &[Pubkey]) -> Instruction {
+    let static_accounts = match ProtocolInstruction::try_from_slice(&instruction.data).unwrap() {
+        ProtocolInstruction::AddIndexComponents { .. } => 11,
+        ProtocolInstruction::ExecuteCompositionChange => 12,
+        _ => return instruction,
+    };
+    let program_id = instruction.program_id;
+    let position = static_accounts + mints.len() * 4;
+    instruction.accounts.splice(
+        position..position,
+        mints.iter().map(|mint| {
+            AccountMeta::new_readonly(find_asset_registry_address(&program_id, mint).0, false)
+        }),
+    );
+    instruction
+}
+
+/// Makes an instruction moving index tokens use the Token-2022 program, for
+/// index mints created `with_transfer_hook`. `Mint` and `Redeem` keep SPL
+/// Token for their components and get the Token-2022 program appended;
+/// `FinalizeMint`, `BeginRedeem`, `FlashMint`, `FreezeHolder` and
+/// `ThawHolder` have their token program replaced.
+pub fn with_token_2022(mut instruction: Instruction) -> Instruction {
+    if matches!(
+        ProtocolInstruction::try_from_slice(&instruction.data),
+        Ok(ProtocolInstruction::Mint { .. } | ProtocolInstruction::Redeem { .. })
+    ) {
+        instruction
+            .accounts
+            .push(AccountMeta::new_readonly(spl_token_2022::ID, false));
+        return instruction;
+    }
+    for account in instruction.accounts.iter_mut() {
+        if account.pubkey == spl_token::ID {
+            account.pubkey = spl_token_2022::ID;
+        }
+    }
+    instruction
+}
+
+pub fn freeze_holder_instruction(
     caller: Pubkey,
     program_id: Pubkey,
     controller_account: Pubkey,
-    mint_account: Pubkey,
-    mint_authority_account: Pubkey,
-    index_account: Pubkey,
-    index_mints_data_account: Pubkey,
     token_account: Pubkey,
-    token_program_account: Pubkey,
     index_id: u64,
-    amount: u64,
 ) -> Instruction {
-    let accounts = vec![
-        AccountMeta::new_readonly(caller, true),
-        AccountMeta::new_readonly(controller_account, false),
-        AccountMeta::new(mint_account, false),
-        AccountMeta::new_readonly(mint_authority_account, false),
-        AccountMeta::new_readonly(index_account, false),
-        AccountMeta::new_readonly(index_mints_data_account, false),
-        AccountMeta::new_readonly(program_id, false),
-        AccountMeta::new(token_account, false),
-        AccountMeta::new_readonly(token_program_account, false),
-    ];
-    let instruction = ProtocolInstruction::Mint { index_id, amount };
+    let accounts = holder_freeze_accounts(
+        caller,
+        &program_id,
+        controller_account,
+        token_account,
+        index_id,
+    );
+    let instruction = ProtocolInstruction::FreezeHolder { index_id };
     let data = borsh::to_vec(&instruction).unwrap();
     Instruction {
         program_id,
@@ -658,49 +3315,134 @@ pub fn mint_instruction(
     }
 }
 
-#[allow(clippy::too_many_arguments)]
-pub fn mint_instruction_with_dynamic_accounts(
+pub fn thaw_holder_instruction(
     caller: Pubkey,
     program_id: Pubkey,
     controller_account: Pubkey,
-    mint_account: Pubkey,
-    mint_authority_account: Pubkey,
-    index_account: Pubkey,
-    index_mints_data_account: Pubkey,
     token_account: Pubkey,
-    token_program_account: Pubkey,
-    mints: Vec<Pubkey>,
-    token_accounts: Vec<Pubkey>,
     index_id: u64,
-    amount: u64,
 ) -> Instruction {
-    let mut accounts = vec![
+    let accounts = holder_freeze_accounts(
+        caller,
+        &program_id,
+        controller_account,
+        token_account,
+        index_id,
+    );
+    let instruction = ProtocolInstruction::ThawHolder { index_id };
+    let data = borsh::to_vec(&instruction).unwrap();
+    Instruction {
+        program_id,
+        accounts,
+        data,
+    }
+}
+
+/// Accounts shared by `FreezeHolder` and `ThawHolder`.
+fn holder_freeze_accounts(
+    caller: Pubkey,
+    program_id: &Pubkey,
+    controller_account: Pubkey,
+    token_account: Pubkey,
+    index_id: u64,
+) -> Vec<AccountMeta> {
+    vec![
         AccountMeta::new_readonly(caller, true),
         AccountMeta::new_readonly(controller_account, false),
-        AccountMeta::new(mint_account, false),
-        AccountMeta::new_readonly(mint_authority_account, false),
-        AccountMeta::new_readonly(index_account, false),
-        AccountMeta::new_readonly(index_mints_data_account, false),
+        AccountMeta::new_readonly(
+            find_index_address(program_id, &controller_account, index_id).0,
+            false,
+        ),
+        AccountMeta::new_readonly(
+            find_index_mint_address(program_id, &controller_account, index_id).0,
+            false,
+        ),
+        AccountMeta::new_readonly(
+            find_index_mint_authority_address(program_id, &controller_account, index_id).0,
+            false,
+        ),
         AccountMeta::new(token_account, false),
-        AccountMeta::new_readonly(token_program_account, false),
+        AccountMeta::new_readonly(spl_token::ID, false),
+        AccountMeta::new_readonly(
+            find_controller_roles_address(program_id, &controller_account).0,
+            false,
+        ),
+    ]
+}
+
+pub fn set_transfer_allowlist_instruction(
+    caller: Pubkey,
+    program_id: Pubkey,
+    controller_account: Pubkey,
+    index_id: u64,
+    wallet: Pubkey,
+    allowed: bool,
+) -> Instruction {
+    let mint_account = find_index_mint_address(&program_id, &controller_account, index_id).0;
+    let accounts = vec![
+        AccountMeta::new(caller, true),
+        AccountMeta::new_readonly(controller_account, false),
+        AccountMeta::new_readonly(
+            find_index_address(&program_id, &controller_account, index_id).0,
+            false,
+        ),
+        AccountMeta::new_readonly(mint_account, false),
+        AccountMeta::new(
+            find_transfer_allowlist_address(&program_id, &mint_account, &wallet).0,
+            false,
+        ),
+        AccountMeta::new_readonly(system_program::ID, false),
+        AccountMeta::new_readonly(
+            find_controller_roles_address(&program_id, &controller_account).0,
+            false,
+        ),
     ];
-    let instruction = ProtocolInstruction::Mint { index_id, amount };
+    let instruction = ProtocolInstruction::SetTransferAllowlist {
+        index_id,
+        wallet,
+        allowed,
+    };
     let data = borsh::to_vec(&instruction).unwrap();
-
-    for (index, _mint) in mints.iter().enumerate() {
-        let (component_pda, _) = find_component_address(&program_id, &index_account, _mint);
-        let (vault_pda, _) = find_component_vault_address(&program_id, &index_account, _mint);
-        let vault_ata =
-            get_associated_token_address_with_program_id(&vault_pda, _mint, &spl_token::ID);
-
-        accounts.push(AccountMeta::new_readonly(_mint.clone(), false));
-        accounts.push(AccountMeta::new_readonly(component_pda, false));
-        accounts.push(AccountMeta::new_readonly(vault_pda, false));
-        accounts.push(AccountMeta::new(vault_ata, false));
-        let _token_account = token_accounts.get(index).unwrap();
-        accounts.push(AccountMeta::new(*_token_account, false));
+    Instruction {
+        program_id,
+        accounts,
+        data,
     }
+}
+
+/// Appends `multisig` and its approving `members` to an owner-gated
+/// instruction, so a multisig owner's threshold can be checked.
+pub fn with_multisig_signers(
+    mut instruction: Instruction,
+    multisig: Pubkey,
+    members: &[Pubkey],
+) -> Instruction {
+    instruction
+        .accounts
+        .push(AccountMeta::new_readonly(multisig, false));
+    instruction.accounts.extend(
+        members
+            .iter()
+            .map(|member| AccountMeta::new_readonly(*member, true)),
+    );
+    instruction
+}
 
+pub fn set_composition_delay_instruction(
+    program_id: Pubkey,
+    caller: Pubkey,
+    index_account: Pubkey,
+    controller_account: Pubkey,
+    delay: i64,
+) -> Instruction {
+    let accounts = vec![
+        AccountMeta::new(caller, true),
+        AccountMeta::new(index_account, false),
+        AccountMeta::new_readonly(controller_account, false),
+        AccountMeta::new_readonly(system_program::ID, false),
+    ];
+    let instruction = ProtocolInstruction::SetCompositionDelay { delay };
+    let data = borsh::to_vec(&instruction).unwrap();
     Instruction {
         program_id,
         accounts,
@@ -708,32 +3450,34 @@ pub fn mint_instruction_with_dynamic_accounts(
     }
 }
 
-/// redeem
-pub fn redeem_instruction(
-    caller: Pubkey,
+#[allow(clippy::too_many_arguments)]
+pub fn queue_composition_change_instruction(
     program_id: Pubkey,
-    controller_account: Pubkey,
-    mint_account: Pubkey,
-    mint_authority_account: Pubkey,
+    caller: Pubkey,
     index_account: Pubkey,
-    index_mints_data_account: Pubkey,
-    token_account: Pubkey,
-    token_program_account: Pubkey,
-    index_id: u64,
-    amount: u64,
+    controller_account: Pubkey,
+    controller_global_config_account: Pubkey,
+    controller_config_account: Pubkey,
+    mints: Vec<Pubkey>,
+    amounts: Vec<u64>,
 ) -> Instruction {
     let accounts = vec![
-        AccountMeta::new_readonly(caller, true),
-        AccountMeta::new_readonly(controller_account, false),
-        AccountMeta::new(mint_account, false),
-        AccountMeta::new_readonly(mint_authority_account, false),
+        AccountMeta::new(caller, true),
         AccountMeta::new_readonly(index_account, false),
-        AccountMeta::new_readonly(index_mints_data_account, false),
-        AccountMeta::new_readonly(program_id, false),
-        AccountMeta::new(token_account, false),
-        AccountMeta::new_readonly(token_program_account, false),
+        AccountMeta::new_readonly(controller_account, false),
+        AccountMeta::new_readonly(controller_global_config_account, false),
+        AccountMeta::new_readonly(controller_config_account, false),
+        AccountMeta::new(
+            find_pending_composition_change_address(&program_id, &index_account).0,
+            false,
+        ),
+        AccountMeta::new_readonly(system_program::ID, false),
     ];
-    let instruction = ProtocolInstruction::Redeem { index_id, amount };
+    let instruction = ProtocolInstruction::QueueCompositionChange {
+        mints,
+        amounts,
+        nested_components: vec![],
+    };
     let data = borsh::to_vec(&instruction).unwrap();
     Instruction {
         program_id,
@@ -742,49 +3486,150 @@ pub fn redeem_instruction(
     }
 }
 
+/// `mints` must be the queued recipe, in order, so that the component
+/// bundles can be appended.
 #[allow(clippy::too_many_arguments)]
-pub fn redeem_instruction_with_dynamic_accounts(
-    caller: Pubkey,
+pub fn execute_composition_change_instruction(
     program_id: Pubkey,
-    controller_account: Pubkey,
-    mint_account: Pubkey,
-    mint_authority_account: Pubkey,
+    caller: Pubkey,
     index_account: Pubkey,
     index_mints_data_account: Pubkey,
-    token_account: Pubkey,
-    token_program_account: Pubkey,
-    mints: Vec<Pubkey>,
-    token_accounts: Vec<Pubkey>,
-    index_id: u64,
-    amount: u64,
+    mint_account: Pubkey,
+    controller_account: Pubkey,
+    controller_global_config_account: Pubkey,
+    controller_config_account: Pubkey,
+    rent_receiver: Pubkey,
+    mints: &[Pubkey],
 ) -> Instruction {
     let mut accounts = vec![
-        AccountMeta::new_readonly(caller, true),
+        AccountMeta::new(caller, true),
+        AccountMeta::new(index_account, false),
+        AccountMeta::new(index_mints_data_account, false),
+        AccountMeta::new_readonly(mint_account, false),
         AccountMeta::new_readonly(controller_account, false),
-        AccountMeta::new(mint_account, false),
-        AccountMeta::new_readonly(mint_authority_account, false),
-        AccountMeta::new_readonly(index_account, false),
-        AccountMeta::new_readonly(index_mints_data_account, false),
-        AccountMeta::new(token_account, false),
-        AccountMeta::new_readonly(token_program_account, false),
+        AccountMeta::new(controller_global_config_account, false),
+        AccountMeta::new_readonly(controller_config_account, false),
+        AccountMeta::new(
+            find_pending_composition_change_address(&program_id, &index_account).0,
+            false,
+        ),
+        AccountMeta::new(rent_receiver, false),
+        AccountMeta::new_readonly(system_program::ID, false),
+        AccountMeta::new_readonly(spl_associated_token_account::ID, false),
+        AccountMeta::new_readonly(spl_token::ID, false),
     ];
-    let instruction = ProtocolInstruction::Redeem { index_id, amount };
-    let data = borsh::to_vec(&instruction).unwrap();
 
-    for (index, _mint) in mints.iter().enumerate() {
-        let (component_pda, _) = find_component_address(&program_id, &index_account, _mint);
-        let (vault_pda, _) = find_component_vault_address(&program_id, &index_account, _mint);
+    for mint in mints.iter() {
+        let (component_pda, _) = find_component_address(&program_id, &index_account, mint);
+        let (vault_pda, _) = find_component_vault_address(&program_id, &index_account, mint);
         let vault_ata =
-            get_associated_token_address_with_program_id(&vault_pda, _mint, &spl_token::ID);
+            get_associated_token_address_with_program_id(&vault_pda, mint, &spl_token::ID);
 
-        accounts.push(AccountMeta::new_readonly(_mint.clone(), false));
-        accounts.push(AccountMeta::new_readonly(component_pda, false));
+        accounts.push(AccountMeta::new(*mint, false));
+        accounts.push(AccountMeta::new(component_pda, false));
         accounts.push(AccountMeta::new_readonly(vault_pda, false));
         accounts.push(AccountMeta::new(vault_ata, false));
-        let _token_account = token_accounts.get(index).unwrap();
-        accounts.push(AccountMeta::new(*_token_account, false));
     }
 
+    let instruction = ProtocolInstruction::ExecuteCompositionChange;
+    let data = borsh::to_vec(&instruction).unwrap();
+    Instruction {
+        program_id,
+        accounts,
+        data,
+    }
+}
+
+/// Queues a recipe like `queue_composition_change_instruction`, where
+/// `nested_components[i]` is set when `mints[i]` is itself an index mint.
+#[allow(clippy::too_many_arguments)]
+pub fn queue_nested_composition_change_instruction(
+    program_id: Pubkey,
+    caller: Pubkey,
+    index_account: Pubkey,
+    controller_account: Pubkey,
+    controller_global_config_account: Pubkey,
+    controller_config_account: Pubkey,
+    mints: Vec<Pubkey>,
+    amounts: Vec<u64>,
+    nested_components: Vec<bool>,
+) -> Instruction {
+    let mut instruction = queue_composition_change_instruction(
+        program_id,
+        caller,
+        index_account,
+        controller_account,
+        controller_global_config_account,
+        controller_config_account,
+        mints.clone(),
+        amounts.clone(),
+    );
+    instruction.data = borsh::to_vec(&ProtocolInstruction::QueueCompositionChange {
+        mints,
+        amounts,
+        nested_components,
+    })
+    .unwrap();
+    instruction
+}
+
+/// Executes a recipe like `execute_composition_change_instruction`, where
+/// `underlying_indexes[i]` names the `(controller, index)` accounts of
+/// `mints[i]` when that mint is itself an index mint.
+#[allow(clippy::too_many_arguments)]
+pub fn execute_composition_change_instruction_with_underlying_indexes(
+    program_id: Pubkey,
+    caller: Pubkey,
+    index_account: Pubkey,
+    index_mints_data_account: Pubkey,
+    mint_account: Pubkey,
+    controller_account: Pubkey,
+    controller_global_config_account: Pubkey,
+    controller_config_account: Pubkey,
+    rent_receiver: Pubkey,
+    mints: &[Pubkey],
+    underlying_indexes: Vec<Option<(Pubkey, Pubkey)>>,
+) -> Instruction {
+    let mut instruction = execute_composition_change_instruction(
+        program_id,
+        caller,
+        index_account,
+        index_mints_data_account,
+        mint_account,
+        controller_account,
+        controller_global_config_account,
+        controller_config_account,
+        rent_receiver,
+        mints,
+    );
+    for (underlying_controller, underlying_index) in underlying_indexes.iter().flatten() {
+        instruction.accounts.extend([
+            AccountMeta::new_readonly(*underlying_controller, false),
+            AccountMeta::new_readonly(*underlying_index, false),
+        ]);
+    }
+    instruction
+}
+
+pub fn cancel_composition_change_instruction(
+    program_id: Pubkey,
+    caller: Pubkey,
+    index_account: Pubkey,
+    controller_account: Pubkey,
+    rent_receiver: Pubkey,
+) -> Instruction {
+    let accounts = vec![
+        AccountMeta::new_readonly(caller, true),
+        AccountMeta::new_readonly(index_account, false),
+        AccountMeta::new_readonly(controller_account, false),
+        AccountMeta::new(
+            find_pending_composition_change_address(&program_id, &index_account).0,
+            false,
+        ),
+        AccountMeta::new(rent_receiver, false),
+    ];
+    let instruction = ProtocolInstruction::CancelCompositionChange;
+    let data = borsh::to_vec(&instruction).unwrap();
     Instruction {
         program_id,
         accounts,
@@ -4,3 +4,4 @@ pub mod pda;
 pub mod seeds;
 #[cfg(feature = "transaction")]
 pub mod transaction;
+pub mod transfer_hook;
@@ -1,9 +1,18 @@
 use crate::openindex::seeds::{
-    COMPONENT_SEED, COMPONENT_VAULT_SEED, CONTROLLER_GLOBAL_CONFIG_SEED, CONTROLLER_SEED,
-    INDEX_MINTS_DATA_SEED, INDEX_MINT_AUTHORITY_SEED, INDEX_MINT_SEED, INDEX_SEED, MODULE_SEED,
-    PROTOCOL_SEED,
+    ASSET_REGISTRY_SEED, COMPONENT_SEED, COMPONENT_VAULT_SEED, CONTROLLER_CONFIG_SEED,
+    CONTROLLER_COUNTER_SEED, CONTROLLER_CREATOR_SEED, CONTROLLER_GLOBAL_CONFIG_SEED,
+    CONTROLLER_ROLES_SEED, CONTROLLER_SEED, CREATOR_CONTROLLER_SEED, FLASH_LOAN_SEED,
+    FLASH_MINT_SEED, INDEX_MINTS_DATA_SEED, INDEX_MINT_AUTHORITY_SEED, INDEX_MINT_SEED,
+    INDEX_SEED, MINT_REQUEST_SEED, MODULE_SEED, MULTISIG_SEED, PENDING_COMPOSITION_CHANGE_SEED,
+    PROTOCOL_SEED, REDEEM_REQUEST_SEED, TRANSFER_ALLOWLIST_SEED, TREASURY_SEED,
 };
-use solana_program::pubkey::{Pubkey, PubkeyError};
+use solana_program::{
+    hash::hash,
+    pubkey::{Pubkey, PubkeyError},
+};
+
+/// Number of shards the controller statistics counter is split into.
+pub const CONTROLLER_COUNTER_SHARDS: u8 = 16;
 
 pub fn find_protocol_address(program_id: &Pubkey) -> (Pubkey, u8) {
     let (pda, bump) = Pubkey::find_program_address(&[PROTOCOL_SEED], program_id);
@@ -27,27 +36,44 @@ pub fn find_index_address(
     program_id: &Pubkey,
     controller_key: &Pubkey,
     index_id: u64,
+) -> (Pubkey, u8) {
+    find_index_address_from_seed(program_id, controller_key, &index_id.to_le_bytes())
+}
+
+pub fn create_index_address(
+    program_id: &Pubkey,
+    controller_account: &Pubkey,
+    index_id: u64,
+    bump: u8,
+) -> Result<Pubkey, PubkeyError> {
+    create_index_address_from_seed(
+        program_id,
+        controller_account,
+        &index_id.to_le_bytes(),
+        bump,
+    )
+}
+
+pub fn find_index_address_from_seed(
+    program_id: &Pubkey,
+    controller_key: &Pubkey,
+    index_seed: &[u8],
 ) -> (Pubkey, u8) {
     let (pda, bump) = Pubkey::find_program_address(
-        &[INDEX_SEED, controller_key.as_ref(), &index_id.to_le_bytes()],
+        &[INDEX_SEED, controller_key.as_ref(), index_seed],
         program_id,
     );
     (pda, bump)
 }
 
-pub fn create_index_address(
+pub fn create_index_address_from_seed(
     program_id: &Pubkey,
     controller_account: &Pubkey,
-    index_id: u64,
+    index_seed: &[u8],
     bump: u8,
 ) -> Result<Pubkey, PubkeyError> {
     let index_pda = Pubkey::create_program_address(
-        &[
-            INDEX_SEED,
-            controller_account.as_ref(),
-            &index_id.to_le_bytes(),
-            &[bump],
-        ],
+        &[INDEX_SEED, controller_account.as_ref(), index_seed, &[bump]],
         program_id,
     )?;
     Ok(index_pda)
@@ -58,17 +84,53 @@ pub fn find_controller_global_config_address(program_id: &Pubkey) -> (Pubkey, u8
     (pda, bump)
 }
 
+pub fn create_controller_global_config_address(
+    program_id: &Pubkey,
+    bump: u8,
+) -> Result<Pubkey, PubkeyError> {
+    let controller_global_config_pda =
+        Pubkey::create_program_address(&[CONTROLLER_GLOBAL_CONFIG_SEED, &[bump]], program_id)?;
+    Ok(controller_global_config_pda)
+}
+
+pub fn find_controller_config_address(
+    program_id: &Pubkey,
+    controller_key: &Pubkey,
+) -> (Pubkey, u8) {
+    let (pda, bump) = Pubkey::find_program_address(
+        &[CONTROLLER_CONFIG_SEED, controller_key.as_ref()],
+        program_id,
+    );
+    (pda, bump)
+}
+
+pub fn create_controller_config_address(
+    program_id: &Pubkey,
+    controller_key: &Pubkey,
+    bump: u8,
+) -> Result<Pubkey, PubkeyError> {
+    let controller_config_pda = Pubkey::create_program_address(
+        &[CONTROLLER_CONFIG_SEED, controller_key.as_ref(), &[bump]],
+        program_id,
+    )?;
+    Ok(controller_config_pda)
+}
+
 pub fn find_index_mint_address(
     program_id: &Pubkey,
     controller_account: &Pubkey,
     index_id: u64,
+) -> (Pubkey, u8) {
+    find_index_mint_address_from_seed(program_id, controller_account, &index_id.to_le_bytes())
+}
+
+pub fn find_index_mint_address_from_seed(
+    program_id: &Pubkey,
+    controller_account: &Pubkey,
+    index_seed: &[u8],
 ) -> (Pubkey, u8) {
     let (pda, bump) = Pubkey::find_program_address(
-        &[
-            INDEX_MINT_SEED,
-            controller_account.as_ref(),
-            &index_id.to_le_bytes(),
-        ],
+        &[INDEX_MINT_SEED, controller_account.as_ref(), index_seed],
         program_id,
     );
     (pda, bump)
@@ -78,12 +140,20 @@ pub fn find_index_mints_data_address(
     program_id: &Pubkey,
     controller_account: &Pubkey,
     index_id: u64,
+) -> (Pubkey, u8) {
+    find_index_mints_data_address_from_seed(program_id, controller_account, &index_id.to_le_bytes())
+}
+
+pub fn find_index_mints_data_address_from_seed(
+    program_id: &Pubkey,
+    controller_account: &Pubkey,
+    index_seed: &[u8],
 ) -> (Pubkey, u8) {
     let (pda, bump) = Pubkey::find_program_address(
         &[
             INDEX_MINTS_DATA_SEED,
             controller_account.as_ref(),
-            &index_id.to_le_bytes(),
+            index_seed,
         ],
         program_id,
     );
@@ -95,12 +165,26 @@ pub fn create_index_mints_data_address(
     controller_account: &Pubkey,
     index_id: u64,
     bump: u8,
+) -> Result<Pubkey, PubkeyError> {
+    create_index_mints_data_address_from_seed(
+        program_id,
+        controller_account,
+        &index_id.to_le_bytes(),
+        bump,
+    )
+}
+
+pub fn create_index_mints_data_address_from_seed(
+    program_id: &Pubkey,
+    controller_account: &Pubkey,
+    index_seed: &[u8],
+    bump: u8,
 ) -> Result<Pubkey, PubkeyError> {
     let index_mints_pda = Pubkey::create_program_address(
         &[
             INDEX_MINTS_DATA_SEED,
             controller_account.as_ref(),
-            &index_id.to_le_bytes(),
+            index_seed,
             &[bump],
         ],
         program_id,
@@ -185,14 +269,368 @@ pub fn find_index_mint_authority_address(
     program_id: &Pubkey,
     controller_account: &Pubkey,
     index_id: u64,
+) -> (Pubkey, u8) {
+    find_index_mint_authority_address_from_seed(
+        program_id,
+        controller_account,
+        &index_id.to_le_bytes(),
+    )
+}
+
+pub fn find_index_mint_authority_address_from_seed(
+    program_id: &Pubkey,
+    controller_account: &Pubkey,
+    index_seed: &[u8],
 ) -> (Pubkey, u8) {
     let (pda, nump) = Pubkey::find_program_address(
         &[
             INDEX_MINT_AUTHORITY_SEED,
             controller_account.as_ref(),
-            &index_id.to_le_bytes(),
+            index_seed,
         ],
         program_id,
     );
     (pda, nump)
 }
+
+pub fn create_index_mint_authority_address(
+    program_id: &Pubkey,
+    controller_account: &Pubkey,
+    index_id: u64,
+    bump: u8,
+) -> Result<Pubkey, PubkeyError> {
+    create_index_mint_authority_address_from_seed(
+        program_id,
+        controller_account,
+        &index_id.to_le_bytes(),
+        bump,
+    )
+}
+
+pub fn create_index_mint_authority_address_from_seed(
+    program_id: &Pubkey,
+    controller_account: &Pubkey,
+    index_seed: &[u8],
+    bump: u8,
+) -> Result<Pubkey, PubkeyError> {
+    let mint_authority_pda = Pubkey::create_program_address(
+        &[
+            INDEX_MINT_AUTHORITY_SEED,
+            controller_account.as_ref(),
+            index_seed,
+            &[bump],
+        ],
+        program_id,
+    )?;
+    Ok(mint_authority_pda)
+}
+
+pub fn create_index_mint_address(
+    program_id: &Pubkey,
+    controller_account: &Pubkey,
+    index_id: u64,
+    bump: u8,
+) -> Result<Pubkey, PubkeyError> {
+    create_index_mint_address_from_seed(
+        program_id,
+        controller_account,
+        &index_id.to_le_bytes(),
+        bump,
+    )
+}
+
+pub fn create_index_mint_address_from_seed(
+    program_id: &Pubkey,
+    controller_account: &Pubkey,
+    index_seed: &[u8],
+    bump: u8,
+) -> Result<Pubkey, PubkeyError> {
+    let mint_pda = Pubkey::create_program_address(
+        &[
+            INDEX_MINT_SEED,
+            controller_account.as_ref(),
+            index_seed,
+            &[bump],
+        ],
+        program_id,
+    )?;
+    Ok(mint_pda)
+}
+
+pub fn find_mint_request_address(
+    program_id: &Pubkey,
+    index_key: &Pubkey,
+    owner: &Pubkey,
+) -> (Pubkey, u8) {
+    let (pda, bump) = Pubkey::find_program_address(
+        &[MINT_REQUEST_SEED, index_key.as_ref(), owner.as_ref()],
+        program_id,
+    );
+    (pda, bump)
+}
+
+pub fn create_mint_request_address(
+    program_id: &Pubkey,
+    index_key: &Pubkey,
+    owner: &Pubkey,
+    bump: u8,
+) -> Result<Pubkey, PubkeyError> {
+    let mint_request_pda = Pubkey::create_program_address(
+        &[
+            MINT_REQUEST_SEED,
+            index_key.as_ref(),
+            owner.as_ref(),
+            &[bump],
+        ],
+        program_id,
+    )?;
+    Ok(mint_request_pda)
+}
+
+pub fn find_redeem_request_address(
+    program_id: &Pubkey,
+    index_key: &Pubkey,
+    owner: &Pubkey,
+) -> (Pubkey, u8) {
+    let (pda, bump) = Pubkey::find_program_address(
+        &[REDEEM_REQUEST_SEED, index_key.as_ref(), owner.as_ref()],
+        program_id,
+    );
+    (pda, bump)
+}
+
+pub fn create_redeem_request_address(
+    program_id: &Pubkey,
+    index_key: &Pubkey,
+    owner: &Pubkey,
+    bump: u8,
+) -> Result<Pubkey, PubkeyError> {
+    let redeem_request_pda = Pubkey::create_program_address(
+        &[
+            REDEEM_REQUEST_SEED,
+            index_key.as_ref(),
+            owner.as_ref(),
+            &[bump],
+        ],
+        program_id,
+    )?;
+    Ok(redeem_request_pda)
+}
+
+pub fn find_controller_creator_address(program_id: &Pubkey, creator: &Pubkey) -> (Pubkey, u8) {
+    let (pda, bump) =
+        Pubkey::find_program_address(&[CONTROLLER_CREATOR_SEED, creator.as_ref()], program_id);
+    (pda, bump)
+}
+
+pub fn create_controller_creator_address(
+    program_id: &Pubkey,
+    creator: &Pubkey,
+    bump: u8,
+) -> Result<Pubkey, PubkeyError> {
+    let controller_creator_pda = Pubkey::create_program_address(
+        &[CONTROLLER_CREATOR_SEED, creator.as_ref(), &[bump]],
+        program_id,
+    )?;
+    Ok(controller_creator_pda)
+}
+
+pub fn find_treasury_address(program_id: &Pubkey) -> (Pubkey, u8) {
+    let (pda, bump) = Pubkey::find_program_address(&[TREASURY_SEED], program_id);
+    (pda, bump)
+}
+
+/// Controller derived from its creator and a creator-chosen nonce, so that
+/// creating it does not need to write `Protocol.next_controller_id`.
+pub fn find_creator_controller_address(
+    program_id: &Pubkey,
+    creator: &Pubkey,
+    nonce: u64,
+) -> (Pubkey, u8) {
+    let (pda, bump) = Pubkey::find_program_address(
+        &[
+            CREATOR_CONTROLLER_SEED,
+            creator.as_ref(),
+            &nonce.to_le_bytes(),
+        ],
+        program_id,
+    );
+    (pda, bump)
+}
+
+/// Counter shard updated when `creator` creates a nonce-based controller.
+pub fn controller_counter_shard(creator: &Pubkey) -> u8 {
+    creator.as_ref()[0] % CONTROLLER_COUNTER_SHARDS
+}
+
+pub fn find_controller_counter_address(program_id: &Pubkey, shard: u8) -> (Pubkey, u8) {
+    let (pda, bump) =
+        Pubkey::find_program_address(&[CONTROLLER_COUNTER_SEED, &[shard]], program_id);
+    (pda, bump)
+}
+
+pub fn find_controller_roles_address(program_id: &Pubkey, controller_key: &Pubkey) -> (Pubkey, u8) {
+    let (pda, bump) = Pubkey::find_program_address(
+        &[CONTROLLER_ROLES_SEED, controller_key.as_ref()],
+        program_id,
+    );
+    (pda, bump)
+}
+
+pub fn create_controller_roles_address(
+    program_id: &Pubkey,
+    controller_key: &Pubkey,
+    bump: u8,
+) -> Result<Pubkey, PubkeyError> {
+    let controller_roles_pda = Pubkey::create_program_address(
+        &[CONTROLLER_ROLES_SEED, controller_key.as_ref(), &[bump]],
+        program_id,
+    )?;
+    Ok(controller_roles_pda)
+}
+
+pub fn find_multisig_address(program_id: &Pubkey, create_key: &Pubkey) -> (Pubkey, u8) {
+    let (pda, bump) =
+        Pubkey::find_program_address(&[MULTISIG_SEED, create_key.as_ref()], program_id);
+    (pda, bump)
+}
+
+/// How an index is addressed: by the sequential id assigned by `CreateIndex`,
+/// or by the creator-chosen seed passed to `CreateIndexWithSeed`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum IndexSeed {
+    Id(u64),
+    Seed([u8; 32]),
+}
+
+impl IndexSeed {
+    /// Seed derived from a ticker symbol, so the index address can be found
+    /// from the symbol alone.
+    pub fn from_symbol(symbol: &str) -> Self {
+        IndexSeed::Seed(hash(symbol.as_bytes()).to_bytes())
+    }
+
+    /// Bytes used in place of the index id in every index PDA.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        match self {
+            IndexSeed::Id(index_id) => index_id.to_le_bytes().to_vec(),
+            IndexSeed::Seed(seed) => seed.to_vec(),
+        }
+    }
+}
+
+/// Every PDA that belongs to a single index.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct IndexAddresses {
+    pub index: Pubkey,
+    pub mint: Pubkey,
+    pub mint_authority: Pubkey,
+    pub index_mints_data: Pubkey,
+}
+
+/// Derives all index PDAs from either addressing form.
+pub fn find_index_addresses(
+    program_id: &Pubkey,
+    controller_key: &Pubkey,
+    index_seed: &IndexSeed,
+) -> IndexAddresses {
+    let index_seed = index_seed.to_bytes();
+    IndexAddresses {
+        index: find_index_address_from_seed(program_id, controller_key, &index_seed).0,
+        mint: find_index_mint_address_from_seed(program_id, controller_key, &index_seed).0,
+        mint_authority: find_index_mint_authority_address_from_seed(
+            program_id,
+            controller_key,
+            &index_seed,
+        )
+        .0,
+        index_mints_data: find_index_mints_data_address_from_seed(
+            program_id,
+            controller_key,
+            &index_seed,
+        )
+        .0,
+    }
+}
+
+pub fn find_pending_composition_change_address(
+    program_id: &Pubkey,
+    index_key: &Pubkey,
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[PENDING_COMPOSITION_CHANGE_SEED, index_key.as_ref()],
+        program_id,
+    )
+}
+
+pub fn create_pending_composition_change_address(
+    program_id: &Pubkey,
+    index_key: &Pubkey,
+    bump: u8,
+) -> Result<Pubkey, PubkeyError> {
+    Pubkey::create_program_address(
+        &[PENDING_COMPOSITION_CHANGE_SEED, index_key.as_ref(), &[bump]],
+        program_id,
+    )
+}
+
+pub fn find_flash_mint_address(
+    program_id: &Pubkey,
+    index_key: &Pubkey,
+    owner: &Pubkey,
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[FLASH_MINT_SEED, index_key.as_ref(), owner.as_ref()],
+        program_id,
+    )
+}
+
+pub fn create_flash_mint_address(
+    program_id: &Pubkey,
+    index_key: &Pubkey,
+    owner: &Pubkey,
+    bump: u8,
+) -> Result<Pubkey, PubkeyError> {
+    Pubkey::create_program_address(
+        &[FLASH_MINT_SEED, index_key.as_ref(), owner.as_ref(), &[bump]],
+        program_id,
+    )
+}
+
+pub fn find_flash_loan_address(
+    program_id: &Pubkey,
+    component_key: &Pubkey,
+    borrower: &Pubkey,
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[FLASH_LOAN_SEED, component_key.as_ref(), borrower.as_ref()],
+        program_id,
+    )
+}
+
+pub fn create_flash_loan_address(
+    program_id: &Pubkey,
+    component_key: &Pubkey,
+    borrower: &Pubkey,
+    bump: u8,
+) -> Result<Pubkey, PubkeyError> {
+    Pubkey::create_program_address(
+        &[FLASH_LOAN_SEED, component_key.as_ref(), borrower.as_ref(), &[bump]],
+        program_id,
+    )
+}
+
+pub fn find_transfer_allowlist_address(
+    program_id: &Pubkey,
+    mint_key: &Pubkey,
+    wallet: &Pubkey,
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[TRANSFER_ALLOWLIST_SEED, mint_key.as_ref(), wallet.as_ref()],
+        program_id,
+    )
+}
+
+pub fn find_asset_registry_address(program_id: &Pubkey, mint_key: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[ASSET_REGISTRY_SEED, mint_key.as_ref()], program_id)
+}
@@ -8,3 +8,17 @@ pub const INDEX_MINT_AUTHORITY_SEED: &[u8] = b"open_index_mint_authority";
 pub const COMPONENT_SEED: &[u8] = b"open_index_component";
 pub const COMPONENT_VAULT_SEED: &[u8] = b"open_index_component_vault";
 pub const MODULE_SEED: &[u8] = b"open_index_module";
+pub const MINT_REQUEST_SEED: &[u8] = b"open_index_mint_request";
+pub const REDEEM_REQUEST_SEED: &[u8] = b"open_index_redeem_request";
+pub const CONTROLLER_CONFIG_SEED: &[u8] = b"open_index_controller_config";
+pub const CONTROLLER_CREATOR_SEED: &[u8] = b"open_index_controller_creator";
+pub const TREASURY_SEED: &[u8] = b"open_index_treasury";
+pub const CREATOR_CONTROLLER_SEED: &[u8] = b"open_index_creator_controller";
+pub const CONTROLLER_COUNTER_SEED: &[u8] = b"open_index_controller_counter";
+pub const CONTROLLER_ROLES_SEED: &[u8] = b"open_index_controller_roles";
+pub const MULTISIG_SEED: &[u8] = b"open_index_multisig";
+pub const PENDING_COMPOSITION_CHANGE_SEED: &[u8] = b"open_index_composition_change";
+pub const FLASH_MINT_SEED: &[u8] = b"open_index_flash_mint";
+pub const FLASH_LOAN_SEED: &[u8] = b"open_index_flash_loan";
+pub const TRANSFER_ALLOWLIST_SEED: &[u8] = b"open_index_allowlist";
+pub const ASSET_REGISTRY_SEED: &[u8] = b"open_index_asset_registry";
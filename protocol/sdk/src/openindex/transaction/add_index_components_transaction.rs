@@ -1,7 +1,8 @@
 use crate::openindex::{
     instruction::add_index_components_instruction_with_dynamic_accounts,
     pda::{
-        find_controller_address, find_controller_global_config_address, find_index_address,
+        find_controller_address, find_controller_config_address,
+        find_controller_global_config_address, find_index_address,
         find_index_mints_data_address,
     },
 };
@@ -13,8 +14,10 @@ use {
 };
 
 /// Creates a transaction to add index components
+#[allow(clippy::too_many_arguments)]
 pub fn add_index_components_transaction(
     payer: &Keypair,
+    authority: &Keypair,
     program_id: Pubkey,
     index_id: u64,
     controller_id: u64,
@@ -25,16 +28,19 @@ pub fn add_index_components_transaction(
     let controller_pda = find_controller_address(&program_id, controller_id).0;
     let (index_pda, _) = find_index_address(&program_id, &controller_pda, index_id);
     let (controller_global, _) = find_controller_global_config_address(&program_id);
+    let (controller_config, _) = find_controller_config_address(&program_id, &controller_pda);
     let (index_mints_data_pda, _) =
         find_index_mints_data_address(&program_id, &controller_pda, index_id);
 
     let instruction = add_index_components_instruction_with_dynamic_accounts(
         program_id.clone(),
-        payer.pubkey().clone(),
+        authority.pubkey(),
+        payer.pubkey(),
         index_pda,
         index_mints_data_pda,
         controller_pda,
         controller_global,
+        controller_config,
         mints,
         amounts,
     );
@@ -42,7 +48,7 @@ pub fn add_index_components_transaction(
     Transaction::new_signed_with_payer(
         &[instruction],
         Some(&payer.pubkey()),
-        &[&payer],
+        &[payer, authority],
         recent_blockhashes,
     )
 }
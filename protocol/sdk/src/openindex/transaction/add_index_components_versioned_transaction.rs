@@ -1,7 +1,8 @@
 use crate::openindex::{
     instruction::add_index_components_instruction,
     pda::{
-        find_controller_address, find_controller_global_config_address, find_index_address,
+        find_controller_address, find_controller_config_address,
+        find_controller_global_config_address, find_index_address,
         find_index_mints_data_address,
     },
 };
@@ -17,8 +18,10 @@ use {
 };
 
 /// Creates a transaction to add index components
+#[allow(clippy::too_many_arguments)]
 pub fn add_index_components_versioned_transaction(
     payer: &Keypair,
+    authority: &Keypair,
     program_id: Pubkey,
     index_id: u64,
     controller_id: u64,
@@ -30,16 +33,19 @@ pub fn add_index_components_versioned_transaction(
     let controller_pda = find_controller_address(&program_id, controller_id).0;
     let (index_pda, _) = find_index_address(&program_id, &controller_pda, index_id);
     let (controller_global, _) = find_controller_global_config_address(&program_id);
+    let (controller_config, _) = find_controller_config_address(&program_id, &controller_pda);
     let (index_mints_data_pda, _) =
         find_index_mints_data_address(&program_id, &controller_pda, index_id);
 
     let instruction = add_index_components_instruction(
         program_id.clone(),
-        payer.pubkey().clone(),
+        authority.pubkey(),
+        payer.pubkey(),
         index_pda,
         index_mints_data_pda,
         controller_pda,
         controller_global,
+        controller_config,
         mints,
         amounts,
     );
@@ -54,10 +60,12 @@ pub fn add_index_components_versioned_transaction(
 
     let versioned = VersionedMessage::V0(v0_msg);
 
-    let versioned_tx = VersionedTransaction::try_new(
-        versioned,
-        &[payer], // only real signers (PDAs never sign)
-    )
+    // only real signers (PDAs never sign), each listed once
+    let versioned_tx = if authority.pubkey() == payer.pubkey() {
+        VersionedTransaction::try_new(versioned, &[payer])
+    } else {
+        VersionedTransaction::try_new(versioned, &[payer, authority])
+    }
     .unwrap();
 
     versioned_tx
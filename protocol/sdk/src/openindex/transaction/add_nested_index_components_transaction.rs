@@ -0,0 +1,73 @@
+use crate::openindex::{
+    instruction::add_index_components_instruction_with_underlying_indexes,
+    pda::{
+        find_controller_address, find_controller_config_address,
+        find_controller_global_config_address, find_index_address, find_index_mints_data_address,
+    },
+};
+use solana_sdk::{hash::Hash, transaction::Transaction};
+
+use {
+    solana_program::pubkey::Pubkey,
+    solana_sdk::signature::{Keypair, Signer},
+};
+
+/// Creates a transaction to add index components, where
+/// `underlying_indexes[i]` is the `(controller_id, index_id)` of `mints[i]`
+/// when that mint is itself an index mint.
+#[allow(clippy::too_many_arguments)]
+pub fn add_nested_index_components_transaction(
+    payer: &Keypair,
+    authority: &Keypair,
+    program_id: Pubkey,
+    index_id: u64,
+    controller_id: u64,
+    recent_blockhashes: Hash,
+    mints: Vec<Pubkey>,
+    amounts: Vec<u64>,
+    underlying_indexes: Vec<Option<(u64, u64)>>,
+) -> Transaction {
+    let controller_pda = find_controller_address(&program_id, controller_id).0;
+    let (index_pda, _) = find_index_address(&program_id, &controller_pda, index_id);
+    let (controller_global, _) = find_controller_global_config_address(&program_id);
+    let (controller_config, _) = find_controller_config_address(&program_id, &controller_pda);
+    let (index_mints_data_pda, _) =
+        find_index_mints_data_address(&program_id, &controller_pda, index_id);
+    let underlying_indexes = underlying_indexes
+        .into_iter()
+        .map(|underlying_index| {
+            underlying_index.map(|(underlying_controller_id, underlying_index_id)| {
+                let underlying_controller_pda =
+                    find_controller_address(&program_id, underlying_controller_id).0;
+                let underlying_index_pda = find_index_address(
+                    &program_id,
+                    &underlying_controller_pda,
+                    underlying_index_id,
+                )
+                .0;
+                (underlying_controller_pda, underlying_index_pda)
+            })
+        })
+        .collect();
+
+    let instruction = add_index_components_instruction_with_underlying_indexes(
+        program_id,
+        authority.pubkey(),
+        payer.pubkey(),
+        index_pda,
+        index_mints_data_pda,
+        controller_pda,
+        controller_global,
+        controller_config,
+        mints,
+        amounts,
+        underlying_indexes,
+    );
+
+    Transaction::new_signed_with_payer(
+        &[instruction],
+        Some(&payer.pubkey()),
+        &[payer, authority],
+        recent_blockhashes,
+    )
+}
@@ -0,0 +1,41 @@
+use crate::openindex::{
+    instruction::begin_mint_instruction,
+    pda::{find_controller_address, find_index_address, find_index_mints_data_address},
+};
+use solana_sdk::{hash::Hash, transaction::Transaction};
+
+use {
+    solana_program::pubkey::Pubkey,
+    solana_sdk::signature::{Keypair, Signer},
+};
+
+pub fn begin_mint_transaction(
+    amount: u64,
+    payer: &Keypair,
+    program_id: Pubkey,
+    index_id: u64,
+    controller_id: u64,
+    recent_blockhashes: Hash,
+) -> Transaction {
+    let controller_account = find_controller_address(&program_id, controller_id).0;
+    let index_account = find_index_address(&program_id, &controller_account, index_id).0;
+    let index_mints_data_account =
+        find_index_mints_data_address(&program_id, &controller_account, index_id).0;
+
+    let instruction = begin_mint_instruction(
+        payer.pubkey(),
+        program_id,
+        controller_account,
+        index_account,
+        index_mints_data_account,
+        index_id,
+        amount,
+    );
+
+    Transaction::new_signed_with_payer(
+        &[instruction],
+        Some(&payer.pubkey()),
+        &[&payer],
+        recent_blockhashes,
+    )
+}
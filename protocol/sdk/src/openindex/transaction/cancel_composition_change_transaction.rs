@@ -0,0 +1,37 @@
+use crate::openindex::{
+    instruction::cancel_composition_change_instruction,
+    pda::{find_controller_address, find_index_address},
+};
+use solana_sdk::{hash::Hash, transaction::Transaction};
+use {
+    solana_program::pubkey::Pubkey,
+    solana_sdk::signature::{Keypair, Signer},
+};
+
+/// Creates a transaction to cancel the queued composition change of an index,
+/// refunding the queue rent to `manager`
+pub fn cancel_composition_change_transaction(
+    payer: &Keypair,
+    program_id: Pubkey,
+    index_id: u64,
+    controller_id: u64,
+    manager: Pubkey,
+    recent_blockhashes: Hash,
+) -> Transaction {
+    let controller_pda = find_controller_address(&program_id, controller_id).0;
+    let index_pda = find_index_address(&program_id, &controller_pda, index_id).0;
+    let instruction = cancel_composition_change_instruction(
+        program_id,
+        payer.pubkey(),
+        index_pda,
+        controller_pda,
+        manager,
+    );
+
+    Transaction::new_signed_with_payer(
+        &[instruction],
+        Some(&payer.pubkey()),
+        &[payer],
+        recent_blockhashes,
+    )
+}
@@ -0,0 +1,40 @@
+use crate::openindex::{
+    instruction::cancel_mint_instruction,
+    pda::{find_controller_address, find_index_address},
+};
+use solana_sdk::{hash::Hash, transaction::Transaction};
+
+use {
+    solana_program::pubkey::Pubkey,
+    solana_sdk::signature::{Keypair, Signer},
+};
+
+pub fn cancel_mint_transaction(
+    payer: &Keypair,
+    program_id: Pubkey,
+    index_id: u64,
+    controller_id: u64,
+    recent_blockhashes: Hash,
+    mints: Vec<Pubkey>,
+    token_accounts: Vec<Pubkey>,
+) -> Transaction {
+    let controller_account = find_controller_address(&program_id, controller_id).0;
+    let index_account = find_index_address(&program_id, &controller_account, index_id).0;
+
+    let instruction = cancel_mint_instruction(
+        payer.pubkey(),
+        program_id,
+        controller_account,
+        index_account,
+        mints,
+        token_accounts,
+        index_id,
+    );
+
+    Transaction::new_signed_with_payer(
+        &[instruction],
+        Some(&payer.pubkey()),
+        &[&payer],
+        recent_blockhashes,
+    )
+}
@@ -1,7 +1,8 @@
 use crate::openindex::{
     instruction::create_index_instruction,
     pda::{
-        find_controller_address, find_controller_global_config_address, find_index_address,
+        find_controller_address, find_controller_config_address,
+        find_controller_global_config_address, find_index_address,
         find_index_mint_address,
     },
 };
@@ -11,6 +12,7 @@ use {solana_program::pubkey::Pubkey, solana_sdk::signature::Signer};
 /// Creates a transaction to create an index
 pub fn create_index_transaction(
     payer: &Keypair,
+    authority: &Keypair,
     program_id: Pubkey,
     index_id: u64,
     controller_id: u64,
@@ -20,21 +22,24 @@ pub fn create_index_transaction(
     let controller_pda = find_controller_address(&program_id, controller_id).0;
     let index_pda = find_index_address(&program_id, &controller_pda, index_id).0;
     let (controller_global, _) = find_controller_global_config_address(&program_id);
+    let (controller_config, _) = find_controller_config_address(&program_id, &controller_pda);
     let mint = find_index_mint_address(&program_id, &controller_pda, index_id).0;
     let instruction = create_index_instruction(
         program_id.clone(),
-        payer.pubkey().clone(),
+        authority.pubkey(),
+        payer.pubkey(),
         manager,
         index_pda,
         mint,
         controller_pda,
         controller_global,
+        controller_config,
     );
 
     Transaction::new_signed_with_payer(
         &[instruction],
         Some(&payer.pubkey()),
-        &[payer],
+        &[payer, authority],
         recent_blockhashes,
     )
 }
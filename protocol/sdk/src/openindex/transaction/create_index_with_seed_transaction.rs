@@ -0,0 +1,45 @@
+use crate::openindex::{
+    instruction::create_index_with_seed_instruction,
+    pda::{
+        find_controller_address, find_controller_config_address,
+        find_controller_global_config_address, find_index_addresses, IndexSeed,
+    },
+};
+use solana_sdk::{hash::Hash, signature::Keypair, transaction::Transaction};
+use {solana_program::pubkey::Pubkey, solana_sdk::signature::Signer};
+
+/// Creates a transaction to create an index addressed by `seed`
+pub fn create_index_with_seed_transaction(
+    payer: &Keypair,
+    authority: &Keypair,
+    program_id: Pubkey,
+    controller_id: u64,
+    manager: Pubkey,
+    seed: [u8; 32],
+    recent_blockhashes: Hash,
+) -> Transaction {
+    let controller_pda = find_controller_address(&program_id, controller_id).0;
+    let index_addresses =
+        find_index_addresses(&program_id, &controller_pda, &IndexSeed::Seed(seed));
+    let (controller_global, _) = find_controller_global_config_address(&program_id);
+    let (controller_config, _) = find_controller_config_address(&program_id, &controller_pda);
+    let instruction = create_index_with_seed_instruction(
+        program_id,
+        authority.pubkey(),
+        payer.pubkey(),
+        manager,
+        index_addresses.index,
+        index_addresses.mint,
+        controller_pda,
+        controller_global,
+        controller_config,
+        seed,
+    );
+
+    Transaction::new_signed_with_payer(
+        &[instruction],
+        Some(&payer.pubkey()),
+        &[payer, authority],
+        recent_blockhashes,
+    )
+}
@@ -0,0 +1,31 @@
+use crate::openindex::instruction::create_multisig_instruction;
+use solana_sdk::{hash::Hash, transaction::Transaction};
+use {
+    solana_program::pubkey::Pubkey,
+    solana_sdk::signature::{Keypair, Signer},
+};
+
+/// Creates a transaction to create an m-of-n multisig keyed by `create_key`
+pub fn create_multisig_transaction(
+    payer: &Keypair,
+    program_id: Pubkey,
+    create_key: &Keypair,
+    members: Vec<Pubkey>,
+    threshold: u8,
+    recent_blockhashes: Hash,
+) -> Transaction {
+    let instruction = create_multisig_instruction(
+        program_id,
+        payer.pubkey(),
+        create_key.pubkey(),
+        members,
+        threshold,
+    );
+
+    Transaction::new_signed_with_payer(
+        &[instruction],
+        Some(&payer.pubkey()),
+        &[payer, create_key],
+        recent_blockhashes,
+    )
+}
@@ -0,0 +1,52 @@
+use crate::openindex::{
+    instruction::execute_composition_change_instruction,
+    pda::{
+        find_controller_address, find_controller_config_address,
+        find_controller_global_config_address, find_index_address, find_index_mint_address,
+        find_index_mints_data_address,
+    },
+};
+use solana_sdk::{hash::Hash, transaction::Transaction};
+use {
+    solana_program::pubkey::Pubkey,
+    solana_sdk::signature::{Keypair, Signer},
+};
+
+/// Creates a transaction to apply the queued recipe `mints` of an index,
+/// refunding the queue rent to `manager`
+pub fn execute_composition_change_transaction(
+    payer: &Keypair,
+    program_id: Pubkey,
+    index_id: u64,
+    controller_id: u64,
+    manager: Pubkey,
+    mints: &[Pubkey],
+    recent_blockhashes: Hash,
+) -> Transaction {
+    let controller_pda = find_controller_address(&program_id, controller_id).0;
+    let index_pda = find_index_address(&program_id, &controller_pda, index_id).0;
+    let (controller_global, _) = find_controller_global_config_address(&program_id);
+    let (controller_config, _) = find_controller_config_address(&program_id, &controller_pda);
+    let (mint_pda, _) = find_index_mint_address(&program_id, &controller_pda, index_id);
+    let (index_mints_data_pda, _) =
+        find_index_mints_data_address(&program_id, &controller_pda, index_id);
+    let instruction = execute_composition_change_instruction(
+        program_id,
+        payer.pubkey(),
+        index_pda,
+        index_mints_data_pda,
+        mint_pda,
+        controller_pda,
+        controller_global,
+        controller_config,
+        manager,
+        mints,
+    );
+
+    Transaction::new_signed_with_payer(
+        &[instruction],
+        Some(&payer.pubkey()),
+        &[payer],
+        recent_blockhashes,
+    )
+}
@@ -0,0 +1,46 @@
+use crate::openindex::{
+    instruction::finalize_mint_instruction,
+    pda::{
+        find_controller_address, find_index_address, find_index_mint_address,
+        find_index_mint_authority_address,
+    },
+};
+use solana_sdk::{hash::Hash, transaction::Transaction};
+
+use {
+    solana_program::pubkey::Pubkey,
+    solana_sdk::signature::{Keypair, Signer},
+};
+
+pub fn finalize_mint_transaction(
+    payer: &Keypair,
+    program_id: Pubkey,
+    index_id: u64,
+    controller_id: u64,
+    token_account: Pubkey,
+    recent_blockhashes: Hash,
+) -> Transaction {
+    let controller_account = find_controller_address(&program_id, controller_id).0;
+    let index_account = find_index_address(&program_id, &controller_account, index_id).0;
+    let mint_account = find_index_mint_address(&program_id, &controller_account, index_id).0;
+    let mint_authority_account =
+        find_index_mint_authority_address(&program_id, &controller_account, index_id).0;
+
+    let instruction = finalize_mint_instruction(
+        payer.pubkey(),
+        program_id,
+        controller_account,
+        mint_account,
+        mint_authority_account,
+        index_account,
+        token_account,
+        index_id,
+    );
+
+    Transaction::new_signed_with_payer(
+        &[instruction],
+        Some(&payer.pubkey()),
+        &[&payer],
+        recent_blockhashes,
+    )
+}
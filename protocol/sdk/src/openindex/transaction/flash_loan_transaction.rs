@@ -0,0 +1,53 @@
+use crate::openindex::{
+    instruction::{flash_borrow_instruction, flash_repay_instruction},
+    pda::{find_controller_address, find_index_address},
+};
+use solana_sdk::{hash::Hash, instruction::Instruction, transaction::Transaction};
+
+use {
+    solana_program::pubkey::Pubkey,
+    solana_sdk::signature::{Keypair, Signer},
+};
+
+/// Creates a transaction that borrows `amount` of the `mint` vault into
+/// `token_account`, runs `instructions` and then repays the loan with its
+/// fee from the same token account.
+#[allow(clippy::too_many_arguments)]
+pub fn flash_loan_transaction(
+    amount: u64,
+    payer: &Keypair,
+    program_id: Pubkey,
+    index_id: u64,
+    controller_id: u64,
+    mint: Pubkey,
+    token_account: Pubkey,
+    recent_blockhashes: Hash,
+    instructions: Vec<Instruction>,
+) -> Transaction {
+    let controller_account = find_controller_address(&program_id, controller_id).0;
+    let index_account = find_index_address(&program_id, &controller_account, index_id).0;
+    let mut all_instructions = vec![flash_borrow_instruction(
+        payer.pubkey(),
+        program_id,
+        controller_account,
+        mint,
+        token_account,
+        index_id,
+        amount,
+    )];
+    all_instructions.extend(instructions);
+    all_instructions.push(flash_repay_instruction(
+        payer.pubkey(),
+        program_id,
+        index_account,
+        mint,
+        token_account,
+    ));
+
+    Transaction::new_signed_with_payer(
+        &all_instructions,
+        Some(&payer.pubkey()),
+        &[&payer],
+        recent_blockhashes,
+    )
+}
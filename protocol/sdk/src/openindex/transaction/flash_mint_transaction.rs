@@ -0,0 +1,53 @@
+use crate::openindex::{
+    instruction::{flash_mint_instruction, settle_flash_mint_instruction},
+    pda::find_controller_address,
+};
+use solana_sdk::{hash::Hash, instruction::Instruction, transaction::Transaction};
+
+use {
+    solana_program::pubkey::Pubkey,
+    solana_sdk::signature::{Keypair, Signer},
+};
+
+/// Creates a transaction that flash mints index tokens to `token_account`,
+/// runs `instructions` and then settles the flash mint from the payer's
+/// component `token_accounts`.
+#[allow(clippy::too_many_arguments)]
+pub fn flash_mint_transaction(
+    amount: u64,
+    payer: &Keypair,
+    program_id: Pubkey,
+    index_id: u64,
+    controller_id: u64,
+    token_account: Pubkey,
+    recent_blockhashes: Hash,
+    mints: Vec<Pubkey>,
+    token_accounts: Vec<Pubkey>,
+    instructions: Vec<Instruction>,
+) -> Transaction {
+    let controller_account = find_controller_address(&program_id, controller_id).0;
+    let mut all_instructions = vec![flash_mint_instruction(
+        payer.pubkey(),
+        program_id,
+        controller_account,
+        token_account,
+        index_id,
+        amount,
+    )];
+    all_instructions.extend(instructions);
+    all_instructions.push(settle_flash_mint_instruction(
+        payer.pubkey(),
+        program_id,
+        controller_account,
+        mints,
+        token_accounts,
+        index_id,
+    ));
+
+    Transaction::new_signed_with_payer(
+        &all_instructions,
+        Some(&payer.pubkey()),
+        &[&payer],
+        recent_blockhashes,
+    )
+}
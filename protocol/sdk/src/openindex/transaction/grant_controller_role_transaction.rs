@@ -0,0 +1,30 @@
+use crate::openindex::{
+    instruction::{grant_controller_role_instruction, ControllerRole},
+    pda::find_controller_address,
+};
+use solana_sdk::{hash::Hash, transaction::Transaction};
+use {
+    solana_program::pubkey::Pubkey,
+    solana_sdk::signature::{Keypair, Signer},
+};
+
+/// Creates a transaction to grant a controller role to `holder`
+pub fn grant_controller_role_transaction(
+    payer: &Keypair,
+    program_id: Pubkey,
+    controller_id: u64,
+    holder: Pubkey,
+    role: ControllerRole,
+    recent_blockhashes: Hash,
+) -> Transaction {
+    let controller_pda = find_controller_address(&program_id, controller_id).0;
+    let instruction =
+        grant_controller_role_instruction(program_id, payer.pubkey(), controller_pda, holder, role);
+
+    Transaction::new_signed_with_payer(
+        &[instruction],
+        Some(&payer.pubkey()),
+        &[payer],
+        recent_blockhashes,
+    )
+}
@@ -10,6 +10,7 @@ use {
 
 pub fn init_controller_transaction(
     payer: &Keypair,
+    authority: &Keypair,
     program_id: Pubkey,
     controller_id: u64,
     recent_blockhashes: Hash,
@@ -19,7 +20,8 @@ pub fn init_controller_transaction(
 
     let instruction = init_controller_instruction(
         program_id.clone(),
-        payer.pubkey().clone(),
+        authority.pubkey(),
+        payer.pubkey(),
         protocol_pda.clone(),
         controller_pda.clone(),
     );
@@ -27,7 +29,7 @@ pub fn init_controller_transaction(
     Transaction::new_signed_with_payer(
         &[instruction],
         Some(&payer.pubkey()),
-        &[payer],
+        &[payer, authority],
         recent_blockhashes,
     )
 }
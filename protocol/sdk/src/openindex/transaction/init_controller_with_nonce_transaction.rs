@@ -0,0 +1,38 @@
+use crate::openindex::{
+    instruction::init_controller_with_nonce_instruction,
+    pda::{find_creator_controller_address, find_protocol_address},
+};
+use solana_sdk::{hash::Hash, transaction::Transaction};
+use {
+    solana_program::pubkey::Pubkey,
+    solana_sdk::signature::{Keypair, Signer},
+};
+
+/// Creates a transaction to create a controller derived from the authority and `nonce`
+pub fn init_controller_with_nonce_transaction(
+    payer: &Keypair,
+    authority: &Keypair,
+    program_id: Pubkey,
+    nonce: u64,
+    recent_blockhashes: Hash,
+) -> Transaction {
+    let protocol_pda = find_protocol_address(&program_id).0;
+    let controller_pda =
+        find_creator_controller_address(&program_id, &authority.pubkey(), nonce).0;
+
+    let instruction = init_controller_with_nonce_instruction(
+        program_id,
+        authority.pubkey(),
+        payer.pubkey(),
+        protocol_pda,
+        controller_pda,
+        nonce,
+    );
+
+    Transaction::new_signed_with_payer(
+        &[instruction],
+        Some(&payer.pubkey()),
+        &[payer, authority],
+        recent_blockhashes,
+    )
+}
@@ -11,6 +11,7 @@ use {
 
 pub fn init_module_transaction(
     payer: &Keypair,
+    authority: &Keypair,
     program_id: Pubkey,
     module_program_id: Pubkey,
     recent_blockhashes: Hash,
@@ -21,7 +22,8 @@ pub fn init_module_transaction(
 
     let instruction = init_module_instruction(
         program_id.clone(),
-        payer.pubkey().clone(),
+        authority.pubkey(),
+        payer.pubkey(),
         protocol_pda,
         module_signer_pda,
         registered_module_pda,
@@ -30,7 +32,7 @@ pub fn init_module_transaction(
     Transaction::new_signed_with_payer(
         &[instruction],
         Some(&payer.pubkey()),
-        &[payer],
+        &[payer, authority],
         recent_blockhashes,
     )
 }
@@ -5,13 +5,16 @@ use crate::openindex::{
         find_index_mint_authority_address, find_index_mints_data_address,
     },
 };
-use solana_sdk::{hash::Hash, transaction::Transaction};
+use solana_sdk::{hash::Hash, instruction::AccountMeta, transaction::Transaction};
 
 use {
-    solana_program::pubkey::Pubkey,
+    solana_program::{pubkey::Pubkey, system_program},
     solana_sdk::signature::{Keypair, Signer},
 };
 
+/// Creates a transaction to mint an index. A wrapped SOL component is paid
+/// in lamports from `payer`, so its entry in `token_accounts` is ignored.
+#[allow(clippy::too_many_arguments)]
 pub fn mint_transaction(
     amount: u64,
     payer: &Keypair,
@@ -33,7 +36,14 @@ pub fn mint_transaction(
     let index_mints_data_account =
         find_index_mints_data_address(&program_id, &controller_account, index_id).0;
     let token_program_account = spl_token::ID;
-    let instruction = mint_instruction_with_dynamic_accounts(
+    let native_position = mints
+        .iter()
+        .position(|mint| *mint == spl_token::native_mint::ID);
+    let mut token_accounts = token_accounts;
+    if let Some(position) = native_position {
+        token_accounts[position] = payer.pubkey();
+    }
+    let mut instruction = mint_instruction_with_dynamic_accounts(
         payer.pubkey(),
         program_id,
         controller_account,
@@ -48,6 +58,12 @@ pub fn mint_transaction(
         index_id,
         amount,
     );
+    if native_position.is_some() {
+        instruction.accounts[0].is_writable = true;
+        instruction
+            .accounts
+            .push(AccountMeta::new_readonly(system_program::ID, false));
+    }
 
     Transaction::new_signed_with_payer(
         &[instruction],
@@ -1,28 +1,94 @@
 mod add_index_components_transaction;
 mod add_index_components_versioned_transaction;
+mod add_nested_index_components_transaction;
+mod begin_mint_transaction;
+mod begin_redeem_transaction;
+mod cancel_composition_change_transaction;
+mod cancel_mint_transaction;
 mod create_acccount_transaction;
 mod create_index_transaction;
+mod create_index_with_seed_transaction;
+mod create_multisig_transaction;
 
 mod create_mint_acccount_transaction;
 mod create_token_account_transaction;
+mod deposit_components_transaction;
+mod execute_composition_change_transaction;
+mod execute_nested_composition_change_transaction;
+mod finalize_mint_transaction;
+mod flash_loan_transaction;
+mod flash_mint_transaction;
+mod freeze_holder_transaction;
+mod grant_controller_role_transaction;
 mod init_controller_global_config_transaction;
 mod init_controller_transaction;
+mod init_controller_with_nonce_transaction;
 mod init_module_transaction;
 mod init_protocol_transaction;
 mod mint_to_transaction;
 mod mint_transaction;
+mod mint_with_recipient_transaction;
+mod queue_composition_change_transaction;
+mod queue_nested_composition_change_transaction;
 mod redeem_transaction;
+mod redeem_underlying_transaction;
+mod redeem_with_recipient_transaction;
+mod revoke_controller_role_transaction;
+mod set_asset_registry_entry_transaction;
+mod set_composition_delay_transaction;
+mod set_controller_config_transaction;
+mod set_controller_creator_transaction;
+mod set_flash_loan_config_transaction;
+mod set_transfer_allowlist_transaction;
+mod thaw_holder_transaction;
+mod transfer_ownership_transaction;
+mod update_controller_global_config_transaction;
+mod withdraw_components_transaction;
+mod withdraw_treasury_transaction;
 
 pub use add_index_components_transaction::*;
 pub use add_index_components_versioned_transaction::*;
+pub use add_nested_index_components_transaction::*;
+pub use begin_mint_transaction::*;
+pub use begin_redeem_transaction::*;
+pub use cancel_composition_change_transaction::*;
+pub use cancel_mint_transaction::*;
 pub use create_acccount_transaction::*;
 pub use create_index_transaction::*;
+pub use create_index_with_seed_transaction::*;
+pub use create_multisig_transaction::*;
 pub use create_mint_acccount_transaction::*;
 pub use create_token_account_transaction::*;
+pub use deposit_components_transaction::*;
+pub use execute_composition_change_transaction::*;
+pub use execute_nested_composition_change_transaction::*;
+pub use finalize_mint_transaction::*;
+pub use flash_loan_transaction::*;
+pub use flash_mint_transaction::*;
+pub use freeze_holder_transaction::*;
+pub use grant_controller_role_transaction::*;
 pub use init_controller_global_config_transaction::*;
 pub use init_controller_transaction::*;
+pub use init_controller_with_nonce_transaction::*;
 pub use init_module_transaction::*;
 pub use init_protocol_transaction::*;
 pub use mint_to_transaction::*;
 pub use mint_transaction::*;
+pub use mint_with_recipient_transaction::*;
+pub use queue_composition_change_transaction::*;
+pub use queue_nested_composition_change_transaction::*;
 pub use redeem_transaction::*;
+pub use redeem_underlying_transaction::*;
+pub use redeem_with_recipient_transaction::*;
+pub use revoke_controller_role_transaction::*;
+pub use set_asset_registry_entry_transaction::*;
+pub use set_composition_delay_transaction::*;
+pub use set_controller_config_transaction::*;
+pub use set_controller_creator_transaction::*;
+pub use set_flash_loan_config_transaction::*;
+pub use set_transfer_allowlist_transaction::*;
+pub use thaw_holder_transaction::*;
+pub use transfer_ownership_transaction::*;
+pub use update_controller_global_config_transaction::*;
+pub use withdraw_components_transaction::*;
+pub use withdraw_treasury_transaction::*;
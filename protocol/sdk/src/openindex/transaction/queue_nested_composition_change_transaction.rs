@@ -0,0 +1,49 @@
+use crate::openindex::{
+    instruction::queue_nested_composition_change_instruction,
+    pda::{
+        find_controller_address, find_controller_config_address,
+        find_controller_global_config_address, find_index_address,
+    },
+};
+use solana_sdk::{hash::Hash, transaction::Transaction};
+use {
+    solana_program::pubkey::Pubkey,
+    solana_sdk::signature::{Keypair, Signer},
+};
+
+/// Creates a transaction to queue a new recipe for an index, where
+/// `nested_components[i]` is set when `mints[i]` is itself an index mint
+#[allow(clippy::too_many_arguments)]
+pub fn queue_nested_composition_change_transaction(
+    payer: &Keypair,
+    program_id: Pubkey,
+    index_id: u64,
+    controller_id: u64,
+    recent_blockhashes: Hash,
+    mints: Vec<Pubkey>,
+    amounts: Vec<u64>,
+    nested_components: Vec<bool>,
+) -> Transaction {
+    let controller_pda = find_controller_address(&program_id, controller_id).0;
+    let index_pda = find_index_address(&program_id, &controller_pda, index_id).0;
+    let (controller_global, _) = find_controller_global_config_address(&program_id);
+    let (controller_config, _) = find_controller_config_address(&program_id, &controller_pda);
+    let instruction = queue_nested_composition_change_instruction(
+        program_id,
+        payer.pubkey(),
+        index_pda,
+        controller_pda,
+        controller_global,
+        controller_config,
+        mints,
+        amounts,
+        nested_components,
+    );
+
+    Transaction::new_signed_with_payer(
+        &[instruction],
+        Some(&payer.pubkey()),
+        &[payer],
+        recent_blockhashes,
+    )
+}
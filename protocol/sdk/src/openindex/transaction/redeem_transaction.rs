@@ -1,17 +1,23 @@
 use crate::openindex::{
-    instruction::redeem_instruction_with_dynamic_accounts,
+    instruction::{redeem_instruction_with_dynamic_accounts, ProtocolInstruction},
     pda::{
         find_controller_address, find_index_address, find_index_mint_address,
         find_index_mint_authority_address, find_index_mints_data_address,
     },
 };
-use solana_sdk::{hash::Hash, transaction::Transaction};
+use solana_sdk::{hash::Hash, instruction::AccountMeta, transaction::Transaction};
+use spl_associated_token_account::get_associated_token_address;
 
 use {
-    solana_program::pubkey::Pubkey,
+    solana_program::{pubkey::Pubkey, system_program},
     solana_sdk::signature::{Keypair, Signer},
 };
 
+/// Creates a transaction to redeem an index. A wrapped SOL component is
+/// unwrapped into lamports of `payer`, so its entry in `token_accounts` is
+/// replaced by the payer's wrapped SOL ATA, which the instruction creates
+/// and closes again.
+#[allow(clippy::too_many_arguments)]
 pub fn redeem_transaction(
     amount: u64,
     payer: &Keypair,
@@ -25,7 +31,7 @@ pub fn redeem_transaction(
 ) -> Transaction {
     let controller_account = find_controller_address(&program_id, controller_id).0;
     let index_account = find_index_address(&program_id, &controller_account, index_id).0;
-    let mint_account = find_index_mint_address(&program_id, &controller_account, controller_id).0;
+    let mint_account = find_index_mint_address(&program_id, &controller_account, index_id).0;
 
     let mint_authority_account =
         find_index_mint_authority_address(&program_id, &controller_account, index_id).0;
@@ -33,7 +39,15 @@ pub fn redeem_transaction(
     let index_mints_data_account =
         find_index_mints_data_address(&program_id, &controller_account, index_id).0;
     let token_program_account = spl_token::ID;
-    let instruction = redeem_instruction_with_dynamic_accounts(
+    let native_position = mints
+        .iter()
+        .position(|mint| *mint == spl_token::native_mint::ID);
+    let mut token_accounts = token_accounts;
+    if let Some(position) = native_position {
+        token_accounts[position] =
+            get_associated_token_address(&payer.pubkey(), &spl_token::native_mint::ID);
+    }
+    let mut instruction = redeem_instruction_with_dynamic_accounts(
         payer.pubkey(),
         program_id,
         controller_account,
@@ -48,6 +62,24 @@ pub fn redeem_transaction(
         index_id,
         amount,
     );
+    if native_position.is_some() {
+        instruction.accounts[0].is_writable = true;
+        instruction.accounts.extend([
+            AccountMeta::new_readonly(payer.pubkey(), false),
+            AccountMeta::new_readonly(system_program::ID, false),
+            AccountMeta::new_readonly(spl_associated_token_account::ID, false),
+        ]);
+        instruction.data = borsh::to_vec(&ProtocolInstruction::Redeem {
+            index_id,
+            amount,
+            expected_version: None,
+            min_amounts_out: None,
+            recipient: None,
+            create_destination_accounts: true,
+            unwrap_native: true,
+        })
+        .unwrap();
+    }
 
     Transaction::new_signed_with_payer(
         &[instruction],
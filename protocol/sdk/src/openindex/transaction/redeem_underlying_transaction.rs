@@ -0,0 +1,43 @@
+use crate::openindex::{
+    instruction::{redeem_underlying_instruction, UnderlyingIndex},
+    pda::find_controller_address,
+};
+use solana_sdk::{hash::Hash, transaction::Transaction};
+
+use {
+    solana_program::pubkey::Pubkey,
+    solana_sdk::signature::{Keypair, Signer},
+};
+
+/// Creates a transaction to redeem an index down to its base assets,
+/// paid to the payer's associated token accounts.
+#[allow(clippy::too_many_arguments)]
+pub fn redeem_underlying_transaction(
+    amount: u64,
+    payer: &Keypair,
+    program_id: Pubkey,
+    index_id: u64,
+    controller_id: u64,
+    recent_blockhashes: Hash,
+    mints: Vec<Pubkey>,
+    underlying: Vec<UnderlyingIndex>,
+) -> Transaction {
+    let controller_account = find_controller_address(&program_id, controller_id).0;
+    let instruction = redeem_underlying_instruction(
+        payer.pubkey(),
+        program_id,
+        controller_account,
+        spl_token::ID,
+        mints,
+        underlying,
+        index_id,
+        amount,
+    );
+
+    Transaction::new_signed_with_payer(
+        &[instruction],
+        Some(&payer.pubkey()),
+        &[&payer],
+        recent_blockhashes,
+    )
+}
@@ -0,0 +1,36 @@
+use crate::openindex::{
+    instruction::set_asset_registry_entry_instruction, pda::find_protocol_address,
+};
+use solana_sdk::{hash::Hash, signature::Keypair, transaction::Transaction};
+
+use {solana_program::pubkey::Pubkey, solana_sdk::signature::Signer};
+
+/// Creates a transaction to approve, update or remove an asset registry entry
+pub fn set_asset_registry_entry_transaction(
+    payer: &Keypair,
+    program_id: Pubkey,
+    mint: Pubkey,
+    approved: bool,
+    risk_tier: u8,
+    accepted_risks: u8,
+    recent_blockhashes: Hash,
+) -> Transaction {
+    let protocol_pda = find_protocol_address(&program_id).0;
+
+    let instruction = set_asset_registry_entry_instruction(
+        program_id,
+        payer.pubkey(),
+        protocol_pda,
+        mint,
+        approved,
+        risk_tier,
+        accepted_risks,
+    );
+
+    Transaction::new_signed_with_payer(
+        &[instruction],
+        Some(&payer.pubkey()),
+        &[&payer],
+        recent_blockhashes,
+    )
+}
@@ -0,0 +1,45 @@
+use crate::openindex::{
+    instruction::set_controller_config_instruction,
+    pda::{find_controller_address, find_controller_config_address},
+};
+use solana_sdk::{hash::Hash, signature::Keypair, transaction::Transaction};
+
+use {solana_program::pubkey::Pubkey, solana_sdk::signature::Signer};
+
+/// Creates a transaction to create or update a controller config
+#[allow(clippy::too_many_arguments)]
+pub fn set_controller_config_transaction(
+    payer: &Keypair,
+    program_id: Pubkey,
+    controller_id: u64,
+    max_index_components: Option<u32>,
+    max_mint_fee_bps: u16,
+    max_redeem_fee_bps: u16,
+    default_decimals: Option<u8>,
+    allowed_mints: Vec<Pubkey>,
+    override_asset_checks: bool,
+    recent_blockhashes: Hash,
+) -> Transaction {
+    let controller_pda = find_controller_address(&program_id, controller_id).0;
+    let controller_config_pda = find_controller_config_address(&program_id, &controller_pda).0;
+
+    let instruction = set_controller_config_instruction(
+        program_id,
+        payer.pubkey(),
+        controller_pda,
+        controller_config_pda,
+        max_index_components,
+        max_mint_fee_bps,
+        max_redeem_fee_bps,
+        default_decimals,
+        allowed_mints,
+        override_asset_checks,
+    );
+
+    Transaction::new_signed_with_payer(
+        &[instruction],
+        Some(&payer.pubkey()),
+        &[&payer],
+        recent_blockhashes,
+    )
+}
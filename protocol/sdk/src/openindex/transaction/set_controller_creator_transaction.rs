@@ -0,0 +1,32 @@
+use crate::openindex::{
+    instruction::set_controller_creator_instruction, pda::find_protocol_address,
+};
+use solana_sdk::{hash::Hash, signature::Keypair, transaction::Transaction};
+
+use {solana_program::pubkey::Pubkey, solana_sdk::signature::Signer};
+
+/// Creates a transaction to approve or revoke a controller creator
+pub fn set_controller_creator_transaction(
+    payer: &Keypair,
+    program_id: Pubkey,
+    creator: Pubkey,
+    allowed: bool,
+    recent_blockhashes: Hash,
+) -> Transaction {
+    let protocol_pda = find_protocol_address(&program_id).0;
+
+    let instruction = set_controller_creator_instruction(
+        program_id,
+        payer.pubkey(),
+        protocol_pda,
+        creator,
+        allowed,
+    );
+
+    Transaction::new_signed_with_payer(
+        &[instruction],
+        Some(&payer.pubkey()),
+        &[&payer],
+        recent_blockhashes,
+    )
+}
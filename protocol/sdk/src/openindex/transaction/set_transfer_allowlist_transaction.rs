@@ -0,0 +1,37 @@
+use crate::openindex::{
+    instruction::set_transfer_allowlist_instruction, pda::find_controller_address,
+};
+use solana_sdk::{hash::Hash, transaction::Transaction};
+use {
+    solana_program::pubkey::Pubkey,
+    solana_sdk::signature::{Keypair, Signer},
+};
+
+/// Creates a transaction to add a wallet to, or remove it from, the transfer
+/// allowlist of an index
+pub fn set_transfer_allowlist_transaction(
+    payer: &Keypair,
+    program_id: Pubkey,
+    index_id: u64,
+    controller_id: u64,
+    wallet: Pubkey,
+    allowed: bool,
+    recent_blockhashes: Hash,
+) -> Transaction {
+    let controller_pda = find_controller_address(&program_id, controller_id).0;
+    let instruction = set_transfer_allowlist_instruction(
+        payer.pubkey(),
+        program_id,
+        controller_pda,
+        index_id,
+        wallet,
+        allowed,
+    );
+
+    Transaction::new_signed_with_payer(
+        &[instruction],
+        Some(&payer.pubkey()),
+        &[payer],
+        recent_blockhashes,
+    )
+}
@@ -0,0 +1,32 @@
+use crate::openindex::{instruction::thaw_holder_instruction, pda::find_controller_address};
+use solana_sdk::{hash::Hash, transaction::Transaction};
+use {
+    solana_program::pubkey::Pubkey,
+    solana_sdk::signature::{Keypair, Signer},
+};
+
+/// Creates a transaction to thaw a holder's index token account
+pub fn thaw_holder_transaction(
+    payer: &Keypair,
+    program_id: Pubkey,
+    index_id: u64,
+    controller_id: u64,
+    token_account: Pubkey,
+    recent_blockhashes: Hash,
+) -> Transaction {
+    let controller_pda = find_controller_address(&program_id, controller_id).0;
+    let instruction = thaw_holder_instruction(
+        payer.pubkey(),
+        program_id,
+        controller_pda,
+        token_account,
+        index_id,
+    );
+
+    Transaction::new_signed_with_payer(
+        &[instruction],
+        Some(&payer.pubkey()),
+        &[payer],
+        recent_blockhashes,
+    )
+}
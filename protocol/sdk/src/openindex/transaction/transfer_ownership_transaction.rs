@@ -0,0 +1,25 @@
+use crate::openindex::instruction::transfer_ownership_instruction;
+use solana_sdk::{hash::Hash, transaction::Transaction};
+use {
+    solana_program::pubkey::Pubkey,
+    solana_sdk::signature::{Keypair, Signer},
+};
+
+/// Creates a transaction to hand a protocol, controller or index account to `new_owner`
+pub fn transfer_ownership_transaction(
+    payer: &Keypair,
+    program_id: Pubkey,
+    target_account: Pubkey,
+    new_owner: Pubkey,
+    recent_blockhashes: Hash,
+) -> Transaction {
+    let instruction =
+        transfer_ownership_instruction(program_id, payer.pubkey(), target_account, new_owner);
+
+    Transaction::new_signed_with_payer(
+        &[instruction],
+        Some(&payer.pubkey()),
+        &[payer],
+        recent_blockhashes,
+    )
+}
@@ -0,0 +1,45 @@
+use crate::openindex::{
+    instruction::{update_controller_global_config_instruction, ControllerCreationPolicy},
+    pda::{find_controller_global_config_address, find_protocol_address},
+};
+use solana_sdk::{hash::Hash, signature::Keypair, transaction::Transaction};
+
+use {solana_program::pubkey::Pubkey, solana_sdk::signature::Signer};
+
+#[allow(clippy::too_many_arguments)]
+pub fn update_controller_global_config_transaction(
+    payer: &Keypair,
+    program_id: Pubkey,
+    max_index_components: Option<u32>,
+    check_existing_indexes: bool,
+    controller_creation_policy: Option<ControllerCreationPolicy>,
+    controller_creation_fee: Option<u64>,
+    index_creation_fee: Option<u64>,
+    flash_mint_fee_bps: Option<u16>,
+    asset_registry_required: Option<bool>,
+    recent_blockhashes: Hash,
+) -> Transaction {
+    let protocol_pda = find_protocol_address(&program_id).0;
+    let controller_global_pda = find_controller_global_config_address(&program_id).0;
+
+    let instruction = update_controller_global_config_instruction(
+        program_id,
+        payer.pubkey(),
+        protocol_pda,
+        controller_global_pda,
+        max_index_components,
+        check_existing_indexes,
+        controller_creation_policy,
+        controller_creation_fee,
+        index_creation_fee,
+        flash_mint_fee_bps,
+        asset_registry_required,
+    );
+
+    Transaction::new_signed_with_payer(
+        &[instruction],
+        Some(&payer.pubkey()),
+        &[&payer],
+        recent_blockhashes,
+    )
+}
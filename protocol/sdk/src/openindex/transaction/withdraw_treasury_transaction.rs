@@ -0,0 +1,30 @@
+use crate::openindex::{instruction::withdraw_treasury_instruction, pda::find_protocol_address};
+use solana_sdk::{hash::Hash, signature::Keypair, transaction::Transaction};
+
+use {solana_program::pubkey::Pubkey, solana_sdk::signature::Signer};
+
+/// Creates a transaction to withdraw collected fees from the treasury
+pub fn withdraw_treasury_transaction(
+    payer: &Keypair,
+    program_id: Pubkey,
+    destination: Pubkey,
+    amount: u64,
+    recent_blockhashes: Hash,
+) -> Transaction {
+    let protocol_pda = find_protocol_address(&program_id).0;
+
+    let instruction = withdraw_treasury_instruction(
+        program_id,
+        payer.pubkey(),
+        protocol_pda,
+        destination,
+        amount,
+    );
+
+    Transaction::new_signed_with_payer(
+        &[instruction],
+        Some(&payer.pubkey()),
+        &[&payer],
+        recent_blockhashes,
+    )
+}
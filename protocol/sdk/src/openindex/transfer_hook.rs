@@ -0,0 +1,80 @@
+use solana_program::instruction::AccountMeta;
+use solana_program::program_error::ProgramError;
+use solana_program::pubkey;
+use solana_program::pubkey::Pubkey;
+use spl_tlv_account_resolution::account::ExtraAccountMeta;
+use spl_tlv_account_resolution::seeds::Seed;
+use spl_transfer_hook_interface::get_extra_account_metas_address;
+
+use super::pda::find_transfer_allowlist_address;
+use super::seeds::TRANSFER_ALLOWLIST_SEED;
+
+/// Program id of the OpenIndex transfer hook, the only program `CreateIndex`
+/// registers as the transfer hook of an index mint.
+pub const TRANSFER_HOOK_PROGRAM_ID: Pubkey = pubkey!("B8hS8xwbnBp33NWXBbTFvdoaoZD2Gz3gJsdv5tpGg7sx");
+
+/// Position of the OpenIndex program in the accounts of the transfer hook
+/// `Execute`, right after source, mint, destination, authority and the
+/// extra account metas account.
+const OPENINDEX_PROGRAM_INDEX: u8 = 5;
+
+/// Extra accounts `CreateIndex` registers with the transfer hook of an
+/// index mint: the OpenIndex program, then the transfer allowlist entries of
+/// the source and destination token account owners.
+pub fn transfer_allowlist_extra_account_metas(
+    program_id: &Pubkey,
+) -> Result<Vec<ExtraAccountMeta>, ProgramError> {
+    // the owner of a token account is stored at bytes 32..64; packed seed
+    // configs are limited to 32 bytes, which bounds the literal seed length
+    let allowlist_entry = |token_account_index: u8| {
+        ExtraAccountMeta::new_external_pda_with_seeds(
+            OPENINDEX_PROGRAM_INDEX,
+            &[
+                Seed::Literal {
+                    bytes: TRANSFER_ALLOWLIST_SEED.to_vec(),
+                },
+                Seed::AccountKey { index: 1 },
+                Seed::AccountData {
+                    account_index: token_account_index,
+                    data_index: 32,
+                    length: 32,
+                },
+            ],
+            false,
+            false,
+        )
+    };
+
+    Ok(vec![
+        ExtraAccountMeta::new_with_pubkey(program_id, false, false)?,
+        allowlist_entry(0)?,
+        allowlist_entry(2)?,
+    ])
+}
+
+/// Accounts to append to a Token-2022 `transfer_checked` of an index mint
+/// whose transfers go through `transfer_hook_program`.
+pub fn transfer_hook_extra_accounts(
+    program_id: &Pubkey,
+    transfer_hook_program: &Pubkey,
+    mint: &Pubkey,
+    source_owner: &Pubkey,
+    destination_owner: &Pubkey,
+) -> Vec<AccountMeta> {
+    vec![
+        AccountMeta::new_readonly(*program_id, false),
+        AccountMeta::new_readonly(
+            find_transfer_allowlist_address(program_id, mint, source_owner).0,
+            false,
+        ),
+        AccountMeta::new_readonly(
+            find_transfer_allowlist_address(program_id, mint, destination_owner).0,
+            false,
+        ),
+        AccountMeta::new_readonly(*transfer_hook_program, false),
+        AccountMeta::new_readonly(
+            get_extra_account_metas_address(mint, transfer_hook_program),
+            false,
+        ),
+    ]
+}
@@ -1,19 +1,65 @@
 #[cfg(test)]
 mod test_add_index_components;
 #[cfg(test)]
+mod test_asset_registry;
+#[cfg(test)]
+mod test_composition_change;
+#[cfg(all(test, feature = "test-sbf"))]
+mod test_compute_units;
+#[cfg(test)]
+mod test_controller_config;
+#[cfg(test)]
+mod test_controller_creation_policy;
+#[cfg(test)]
 mod test_controller_global_config;
 #[cfg(test)]
+mod test_controller_roles;
+#[cfg(test)]
 mod test_create_index;
 #[cfg(test)]
+mod test_create_index_with_seed;
+#[cfg(test)]
+mod test_flash_loan;
+#[cfg(test)]
+mod test_flash_mint;
+#[cfg(test)]
+mod test_freeze_holder;
+#[cfg(test)]
 mod test_init_controller;
 #[cfg(test)]
+mod test_init_controller_with_nonce;
+#[cfg(test)]
 mod test_init_module;
 #[cfg(test)]
 mod test_init_protocol;
 #[cfg(test)]
 mod test_mint;
 #[cfg(test)]
+mod test_mint_guard;
+#[cfg(test)]
+mod test_mint_recipient;
+#[cfg(test)]
+mod test_multisig;
+#[cfg(test)]
+mod test_native_sol;
+#[cfg(test)]
+mod test_nested_index;
+#[cfg(test)]
+mod test_prefunded_pda;
+#[cfg(test)]
 mod test_redeem;
+#[cfg(test)]
+mod test_redeem_recipient;
+#[cfg(test)]
+mod test_separate_payer;
+#[cfg(test)]
+mod test_staged_mint;
+#[cfg(test)]
+mod test_staged_redeem;
+#[cfg(test)]
+mod test_transfer_hook;
+#[cfg(test)]
+mod test_update_controller_global_config;
 
 mod process_add_index_components;
 mod process_controller_global_config;
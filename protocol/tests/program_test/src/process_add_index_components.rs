@@ -23,7 +23,7 @@ pub async fn process_add_index_components(
         let create_mint_tx = create_mint_acccount_transaction(
             &_setup.payer,
             &mint,
-            _setup.recent_blockhashes,
+            _setup.recent_blockhashes().await,
             &_setup.rent,
         );
         let _ = _setup
@@ -34,11 +34,12 @@ pub async fn process_add_index_components(
     }
 
     let transaction = add_index_components_transaction(
+        &_setup.payer,
         &_setup.payer,
         _setup.program_id,
         index_id,
         controller_id,
-        _setup.recent_blockhashes.clone(),
+        _setup.recent_blockhashes().await,
         mints.clone(),
         units.clone(),
     );
@@ -1,5 +1,6 @@
-use openindex_sdk::openindex::transaction::{
-    init_controller_global_config_transaction, init_protocol_transaction,
+use openindex_sdk::openindex::{
+    pda::find_protocol_address,
+    transaction::{init_controller_global_config_transaction, init_protocol_transaction},
 };
 use crate::{ProcessControllerGlobalConfigResult, Setup};
 
@@ -11,16 +12,33 @@ pub async fn process_controller_global_config(
         &_setup.payer,
         _setup.program_id,
         max_index_components,
-        _setup.recent_blockhashes,
+        _setup.recent_blockhashes().await,
     );
 
-    let init_protocol_instruction =
-        init_protocol_transaction(&_setup.payer, _setup.program_id, _setup.recent_blockhashes);
-
-    let _ = _setup
+    // re-sending an already processed transaction stalls the banks client
+    let protocol_pda = find_protocol_address(&_setup.program_id).0;
+    let protocol_account = _setup
         .banks_client
-        .process_transaction(init_protocol_instruction.clone())
-        .await;
+        .get_account(protocol_pda)
+        .await
+        .unwrap();
+
+    // the PDA may hold lamports without having been created by the program yet
+    let protocol_created =
+        protocol_account.is_some_and(|account| account.owner == _setup.program_id);
+
+    if !protocol_created {
+        let init_protocol_instruction = init_protocol_transaction(
+            &_setup.payer,
+            _setup.program_id,
+            _setup.recent_blockhashes().await,
+        );
+
+        let _ = _setup
+            .banks_client
+            .process_transaction(init_protocol_instruction.clone())
+            .await;
+    }
 
     let result = _setup
         .banks_client
@@ -25,12 +25,13 @@ pub async fn process_create_index(
     let controller: Controller = Controller::try_from_slice(&controller_account.data).unwrap();
 
     let create_index_tx = create_index_transaction(
+        &_setup.payer,
         &_setup.payer,
         program_id,
         controller.next_index_id,
         controller_id,
         manager,
-        _setup.recent_blockhashes,
+        _setup.recent_blockhashes().await,
     );
 
     let result = _setup
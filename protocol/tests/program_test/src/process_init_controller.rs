@@ -21,10 +21,11 @@ pub async fn process_init_controller(_setup: &Setup) -> ProcessInitControllerRes
     let controller_id = protocol.get_next_controller_id();
     let controller_pda = find_controller_address(&program_id, controller_id).0;
     let int_controller_tx = init_controller_transaction(
+        &_setup.payer,
         &_setup.payer,
         program_id,
         controller_id,
-        _setup.recent_blockhashes,
+        _setup.recent_blockhashes().await,
     );
     let result = _setup
         .banks_client
@@ -12,10 +12,11 @@ pub async fn process_init_module(
     _setup: &Setup,
 ) -> ProcessInitModuleResult {
     let transaction = init_module_transaction(
+        &_setup.payer,
         &_setup.payer,
         _setup.program_id,
         module_program_id,
-        _setup.recent_blockhashes,
+        _setup.recent_blockhashes().await,
     );
 
     let result = _setup
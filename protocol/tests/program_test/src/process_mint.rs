@@ -28,7 +28,7 @@ pub async fn process_mint(
             _setup.payer.pubkey(),
             _setup.payer.pubkey(),
             mint.clone(),
-            _setup.recent_blockhashes,
+            _setup.recent_blockhashes().await,
         );
         // execute create token account transaction
         let result = _setup
@@ -46,7 +46,7 @@ pub async fn process_mint(
             tokens_mint_amount,
             mint.clone(),
             token_account.clone(),
-            _setup.recent_blockhashes,
+            _setup.recent_blockhashes().await,
         )
         .unwrap();
 
@@ -73,7 +73,7 @@ pub async fn process_mint(
         _setup.payer.pubkey(),
         _setup.payer.pubkey(),
         mint,
-        _setup.recent_blockhashes,
+        _setup.recent_blockhashes().await,
     );
 
     let result = _setup
@@ -94,7 +94,7 @@ pub async fn process_mint(
         index_id,
         controller_id,
         token_account,
-        _setup.recent_blockhashes,
+        _setup.recent_blockhashes().await,
         mints.clone(),
         token_accounts.clone(),
     );
@@ -14,7 +14,7 @@ pub async fn process_redeem(
 ) -> ProcessRedeemResult {
     let payer = &_setup.payer;
     let program_id = _setup.program_id;
-    let recent_blockhashes = _setup.recent_blockhashes;
+    let recent_blockhashes = _setup.recent_blockhashes().await;
 
     let redeem_tx = redeem_transaction(
         amount,
@@ -1,36 +1,108 @@
 use std::ops::{Add, Mul};
+use std::time::Duration;
 
+use crate::{
+    process_add_index_components, process_controller_global_config, process_create_index,
+    process_init_controller, process_mint, ProcessAddIndexComponentsResult,
+    ProcessControllerGlobalConfigResult, ProcessCreateIndexResult, ProcessInitControllerResult,
+    ProcessMintResult,
+};
 use {
+    openindex_sdk::openindex::{
+        error::ProtocolError, transaction::create_mint_acccount_transaction,
+    },
     solana_address_lookup_table_program::processor::Entrypoint,
-    solana_program_test::{processor, BanksClient, ProgramTest},
+    solana_loader_v3_interface::{get_program_data_address, state::UpgradeableLoaderState},
+    solana_program_test::{processor, tokio, BanksClient, ProgramTest, ProgramTestContext},
     solana_sdk::{
+        account::Account,
+        bpf_loader_upgradeable,
+        clock::Clock,
         hash::Hash,
+        instruction::InstructionError,
         pubkey::Pubkey,
         rent::Rent,
-        signature::Keypair,
+        signature::{Keypair, Signer},
         sysvar::{Sysvar, SysvarId},
+        transaction::{Transaction, TransactionError},
     },
+    spl_token_2022::extension::StateWithExtensions,
 };
 
 pub struct Setup {
     pub banks_client: BanksClient,
     pub payer: Keypair,
-    pub recent_blockhashes: Hash,
     pub program_id: Pubkey,
     pub issuance_program_id: Pubkey,
+    pub transfer_hook_program_id: Pubkey,
     pub rent: Rent,
+    /// keeps the simulated PoH running for the lifetime of the test
+    context: ProgramTestContext,
+}
+
+impl Setup {
+    /// Returns the latest blockhash. The simulated PoH registers a new
+    /// blockhash every few milliseconds, so the genesis blockhash expires
+    /// while longer tests are still sending transactions.
+    pub async fn recent_blockhashes(&self) -> Hash {
+        self.banks_client
+            .clone()
+            .get_latest_blockhash()
+            .await
+            .expect("RPC error")
+    }
+
+    /// Overwrites the account at `address`, e.g. to install state written by
+    /// an older version of the program.
+    pub fn set_account(&mut self, address: &Pubkey, account: Account) {
+        self.context.set_account(address, &account.into());
+    }
+
+    /// Moves the `Clock` sysvar `seconds` into the future, e.g. to get past
+    /// a timelock.
+    pub async fn advance_clock(&mut self, seconds: i64) {
+        let mut clock: Clock = self.banks_client.get_sysvar().await.unwrap();
+        clock.unix_timestamp += seconds;
+        self.context.set_sysvar(&clock);
+    }
 }
 
 pub async fn setup() -> Setup {
     let program_id = Pubkey::new_unique();
     let issuance_program_id = Pubkey::new_unique();
+    let transfer_hook_program_id = openindex_transfer_hook::ID;
     let mut program_test = ProgramTest::new(
         "openindex",
         program_id,
         processor!(openindex::entrypoint::process_instruction),
     );
+    program_test.add_program(
+        "openindex_transfer_hook",
+        transfer_hook_program_id,
+        processor!(openindex_transfer_hook::entrypoint::process_instruction),
+    );
+
+    let mut context = program_test.start_with_context().await;
 
-    let (mut banks_client, payer, recent_blockhashes) = program_test.start().await;
+    // native programs are not deployed through the upgradeable loader, so
+    // the program data account naming the payer as upgrade authority is
+    // installed by hand
+    let program_data_state = UpgradeableLoaderState::ProgramData {
+        slot: 0,
+        upgrade_authority_address: Some(context.payer.pubkey()),
+    };
+    let program_data = Account {
+        lamports: 1_000_000_000,
+        data: bincode::serde::encode_to_vec(program_data_state, bincode::config::legacy())
+            .unwrap(),
+        owner: bpf_loader_upgradeable::ID,
+        executable: false,
+        rent_epoch: 0,
+    };
+    context.set_account(&get_program_data_address(&program_id), &program_data.into());
+
+    let mut banks_client = context.banks_client.clone();
+    let payer = context.payer.insecure_clone();
     // get rent
     let rent_account = banks_client
         .get_account(Rent::id())
@@ -42,10 +114,125 @@ pub async fn setup() -> Setup {
 
     Setup {
         banks_client,
-        recent_blockhashes,
         payer,
         program_id,
         issuance_program_id,
+        transfer_hook_program_id,
         rent,
+        context,
+    }
+}
+
+/// The error of a transaction whose first instruction failed with `error`.
+pub fn custom_error(error: ProtocolError) -> TransactionError {
+    TransactionError::InstructionError(0, InstructionError::Custom(error as u32))
+}
+
+pub async fn latest_blockhash(setup: &Setup) -> Hash {
+    setup.banks_client.get_latest_blockhash().await.unwrap()
+}
+
+/// Waits for a blockhash other than `previous`, so that resending an
+/// identical instruction is not rejected as an already processed transaction.
+pub async fn new_blockhash(setup: &Setup, previous: &Hash) -> Hash {
+    loop {
+        let blockhash = latest_blockhash(setup).await;
+        if blockhash != *previous {
+            return blockhash;
+        }
+        tokio::time::sleep(Duration::from_millis(50)).await;
     }
 }
+
+pub async fn process(transaction: Transaction, setup: &Setup) -> Result<(), TransactionError> {
+    setup
+        .banks_client
+        .process_transaction(transaction)
+        .await
+        .map_err(|error| error.unwrap())
+}
+
+/// Returns the balance of an SPL Token or Token-2022 account.
+pub async fn token_balance(token_account: Pubkey, setup: &Setup) -> u64 {
+    let account = setup
+        .banks_client
+        .get_account(token_account)
+        .await
+        .unwrap()
+        .unwrap();
+    StateWithExtensions::<spl_token_2022::state::Account>::unpack(&account.data)
+        .unwrap()
+        .base
+        .amount
+}
+
+/// Creates an SPL Token mint whose authority is the setup payer.
+pub async fn create_mint(setup: &Setup) -> Pubkey {
+    let mint = Keypair::new();
+    let transaction = create_mint_acccount_transaction(
+        &setup.payer,
+        &mint,
+        latest_blockhash(setup).await,
+        &setup.rent,
+    );
+    assert!(process(transaction, setup).await.is_ok());
+    mint.pubkey()
+}
+
+/// Creates a controller and an index without components, managed by a
+/// random key. Returns `(controller_id, index_id)`.
+pub async fn setup_index(setup: &Setup) -> (u64, u64) {
+    let _ = process_controller_global_config(10, setup).await;
+
+    let ProcessInitControllerResult {
+        controller_id,
+        controller_pda: _,
+        result,
+    } = process_init_controller(setup).await;
+    assert!(result.is_ok());
+
+    let ProcessCreateIndexResult {
+        index_id,
+        controller_pda: _,
+        result,
+    } = process_create_index(controller_id, Keypair::new().pubkey(), setup).await;
+    assert!(result.is_ok());
+
+    (controller_id, index_id)
+}
+
+/// Creates an index managed by the setup payer with units `[1, 2]` and
+/// mints `index_mint_amount` index tokens to the payer, out of 100 tokens
+/// of each component.
+pub async fn setup_minted_index(
+    index_mint_amount: u64,
+    setup: &Setup,
+) -> (ProcessMintResult, Vec<Pubkey>) {
+    let ProcessControllerGlobalConfigResult { result } =
+        process_controller_global_config(10, setup).await;
+    assert!(result.is_ok());
+
+    let ProcessInitControllerResult { controller_id, .. } = process_init_controller(setup).await;
+
+    let ProcessCreateIndexResult {
+        index_id, result, ..
+    } = process_create_index(controller_id, setup.payer.pubkey(), setup).await;
+    assert!(result.is_ok());
+
+    let ProcessAddIndexComponentsResult { mints, result, .. } = process_add_index_components(
+        index_id,
+        controller_id,
+        setup.payer.pubkey(),
+        2,
+        vec![1, 2],
+        setup,
+    )
+    .await;
+    assert!(result.is_ok());
+
+    let minted =
+        process_mint(index_mint_amount, 100, controller_id, index_id, mints.clone(), setup).await;
+    assert!(minted.result.is_ok());
+
+    (minted, mints)
+}
@@ -1,14 +1,19 @@
 use crate::{
-    process_add_index_components, process_controller_global_config, process_create_index,
-    process_init_controller, process_init_protocol, setup, BanksClientResult,
-    ProcessAddIndexComponentsResult, ProcessCreateIndexResult, ProcessInitControllerResult, Setup,
+    custom_error, process_add_index_components, process_controller_global_config,
+    process_create_index, process_init_controller, process_init_protocol, setup, setup_index,
+    BanksClientResult, ProcessAddIndexComponentsResult, ProcessCreateIndexResult,
+    ProcessInitControllerResult, Setup,
 };
 
 use borsh::BorshDeserialize;
 use openindex::state::{Component, Controller, Index, IndexMints, Protocol};
-use openindex_sdk::openindex::pda::{
-    find_component_address, find_controller_address, find_index_address,
-    find_index_mints_data_address,
+use openindex_sdk::openindex::{
+    error::ProtocolError,
+    pda::{
+        find_component_address, find_controller_address, find_index_address,
+        find_index_mint_address, find_index_mints_data_address,
+    },
+    transaction::{add_index_components_transaction, create_mint_acccount_transaction},
 };
 use serde::Deserialize;
 use solana_program_test::BanksClientError;
@@ -124,3 +129,92 @@ async fn test_add_index_components() {
     let mint_2_amount = units.get(1).unwrap().clone();
     assert_eq!(component_2_data.uints, mint_2_amount);
 }
+
+async fn add_components(
+    controller_id: u64,
+    index_id: u64,
+    mints: Vec<Pubkey>,
+    units: Vec<u64>,
+    _setup: &Setup,
+) -> Result<(), TransactionError> {
+    let transaction = add_index_components_transaction(
+        &_setup.payer,
+        &_setup.payer,
+        _setup.program_id,
+        index_id,
+        controller_id,
+        _setup.recent_blockhashes().await,
+        mints,
+        units,
+    );
+    _setup
+        .banks_client
+        .process_transaction(transaction)
+        .await
+        .map_err(|err| err.unwrap())
+}
+
+#[tokio::test]
+async fn test_add_index_components_zero_units() {
+    let _setup = setup().await;
+    let (controller_id, index_id) = setup_index(&_setup).await;
+
+    let ProcessAddIndexComponentsResult { result, .. } = process_add_index_components(
+        index_id,
+        controller_id,
+        _setup.payer.pubkey(),
+        2,
+        vec![1, 0],
+        &_setup,
+    )
+    .await;
+    assert_eq!(
+        result.unwrap_err().unwrap(),
+        custom_error(ProtocolError::ZeroComponentUnits)
+    );
+}
+
+#[tokio::test]
+async fn test_add_index_components_duplicate_mint() {
+    let _setup = setup().await;
+    let (controller_id, index_id) = setup_index(&_setup).await;
+
+    let mint = Keypair::new();
+    let create_mint_tx = create_mint_acccount_transaction(
+        &_setup.payer,
+        &mint,
+        _setup.recent_blockhashes().await,
+        &_setup.rent,
+    );
+    assert!(_setup
+        .banks_client
+        .process_transaction(create_mint_tx)
+        .await
+        .is_ok());
+
+    assert_eq!(
+        add_components(
+            controller_id,
+            index_id,
+            vec![mint.pubkey(), mint.pubkey()],
+            vec![1, 2],
+            &_setup,
+        )
+        .await,
+        Err(custom_error(ProtocolError::DuplicateComponentMint))
+    );
+}
+
+#[tokio::test]
+async fn test_add_index_components_own_index_mint() {
+    let _setup = setup().await;
+    let (controller_id, index_id) = setup_index(&_setup).await;
+
+    let controller_pda = find_controller_address(&_setup.program_id, controller_id).0;
+    let index_mint = find_index_mint_address(&_setup.program_id, &controller_pda, index_id).0;
+
+    assert_eq!(
+        add_components(controller_id, index_id, vec![index_mint], vec![1], &_setup).await,
+        Err(custom_error(ProtocolError::IndexMintAsComponent))
+    );
+}
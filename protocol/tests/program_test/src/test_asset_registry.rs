@@ -0,0 +1,274 @@
+use borsh::BorshDeserialize;
+use openindex::state::AssetRegistryEntry;
+use openindex_sdk::openindex::{
+    error::ProtocolError,
+    instruction::{
+        add_index_components_instruction_with_dynamic_accounts,
+        set_asset_registry_entry_instruction, with_asset_registry_accounts, ASSET_RISK_ALL,
+        ASSET_RISK_FREEZE_AUTHORITY, ASSET_RISK_TRANSFER_HOOK,
+    },
+    pda::{
+        find_asset_registry_address, find_controller_address, find_controller_config_address,
+        find_controller_global_config_address, find_index_address, find_index_mints_data_address,
+        find_protocol_address,
+    },
+    transaction::{
+        add_index_components_transaction, set_asset_registry_entry_transaction,
+        set_controller_config_transaction, update_controller_global_config_transaction,
+    },
+};
+use solana_program_test::tokio;
+use solana_sdk::{
+    instruction::InstructionError,
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+    transaction::{Transaction, TransactionError},
+};
+
+use crate::{
+    create_mint, custom_error, process, process_controller_global_config, setup, setup_index, Setup,
+};
+
+async fn require_asset_registry(_setup: &Setup) {
+    let transaction = update_controller_global_config_transaction(
+        &_setup.payer,
+        _setup.program_id,
+        None,
+        false,
+        None,
+        None,
+        None,
+        None,
+        Some(true),
+        _setup.recent_blockhashes().await,
+    );
+    let result = _setup.banks_client.process_transaction(transaction).await;
+    assert!(result.is_ok());
+}
+
+async fn set_asset_registry_entry(
+    mint: Pubkey,
+    approved: bool,
+    risk_tier: u8,
+    accepted_risks: u8,
+    _setup: &Setup,
+) -> Result<(), TransactionError> {
+    let transaction = set_asset_registry_entry_transaction(
+        &_setup.payer,
+        _setup.program_id,
+        mint,
+        approved,
+        risk_tier,
+        accepted_risks,
+        _setup.recent_blockhashes().await,
+    );
+    _setup
+        .banks_client
+        .process_transaction(transaction)
+        .await
+        .map_err(|err| err.unwrap())
+}
+
+async fn add_component(
+    controller_id: u64,
+    index_id: u64,
+    mint: Pubkey,
+    units: u64,
+    _setup: &Setup,
+) -> Result<(), TransactionError> {
+    let transaction = add_index_components_transaction(
+        &_setup.payer,
+        &_setup.payer,
+        _setup.program_id,
+        index_id,
+        controller_id,
+        _setup.recent_blockhashes().await,
+        vec![mint],
+        vec![units],
+    );
+    process(transaction, _setup).await
+}
+
+/// Adds a component with the asset registry accounts registry checks need.
+async fn add_registered_component(
+    controller_id: u64,
+    index_id: u64,
+    mint: Pubkey,
+    units: u64,
+    _setup: &Setup,
+) -> Result<(), TransactionError> {
+    let controller_pda = find_controller_address(&_setup.program_id, controller_id).0;
+    let index_pda = find_index_address(&_setup.program_id, &controller_pda, index_id).0;
+    let instruction = with_asset_registry_accounts(
+        add_index_components_instruction_with_dynamic_accounts(
+            _setup.program_id,
+            _setup.payer.pubkey(),
+            _setup.payer.pubkey(),
+            index_pda,
+            find_index_mints_data_address(&_setup.program_id, &controller_pda, index_id).0,
+            controller_pda,
+            find_controller_global_config_address(&_setup.program_id).0,
+            find_controller_config_address(&_setup.program_id, &controller_pda).0,
+            vec![mint],
+            vec![units],
+        ),
+        &[mint],
+    );
+    let transaction = Transaction::new_signed_with_payer(
+        &[instruction],
+        Some(&_setup.payer.pubkey()),
+        &[&_setup.payer],
+        _setup.recent_blockhashes().await,
+    );
+    process(transaction, _setup).await
+}
+
+#[tokio::test]
+async fn test_asset_registry_required() {
+    let _setup: Setup = setup().await;
+    let (controller_id, index_id) = setup_index(&_setup).await;
+    let mint = create_mint(&_setup).await;
+
+    require_asset_registry(&_setup).await;
+
+    // registry checks need the asset registry accounts
+    assert_eq!(
+        add_component(controller_id, index_id, mint, 1, &_setup).await,
+        Err(TransactionError::InstructionError(
+            0,
+            InstructionError::NotEnoughAccountKeys
+        ))
+    );
+
+    assert_eq!(
+        add_registered_component(controller_id, index_id, mint, 1, &_setup).await,
+        Err(custom_error(ProtocolError::AssetNotRegistered))
+    );
+
+    // the test mints keep a freeze authority
+    assert!(set_asset_registry_entry(mint, true, 2, 0, &_setup)
+        .await
+        .is_ok());
+    assert_eq!(
+        add_registered_component(controller_id, index_id, mint, 2, &_setup).await,
+        Err(custom_error(ProtocolError::AssetRiskNotAccepted))
+    );
+
+    assert!(set_asset_registry_entry(
+        mint,
+        true,
+        3,
+        ASSET_RISK_FREEZE_AUTHORITY | ASSET_RISK_TRANSFER_HOOK,
+        &_setup
+    )
+    .await
+    .is_ok());
+
+    let asset_registry_pda = find_asset_registry_address(&_setup.program_id, &mint).0;
+    let account = _setup
+        .banks_client
+        .get_account(asset_registry_pda)
+        .await
+        .unwrap()
+        .unwrap();
+    let asset_registry_entry = AssetRegistryEntry::try_from_slice(&account.data).unwrap();
+    assert_eq!(asset_registry_entry.mint, mint);
+    assert_eq!(asset_registry_entry.risk_tier, 3);
+    assert_eq!(
+        asset_registry_entry.accepted_risks,
+        ASSET_RISK_FREEZE_AUTHORITY | ASSET_RISK_TRANSFER_HOOK
+    );
+
+    assert!(
+        add_registered_component(controller_id, index_id, mint, 3, &_setup)
+            .await
+            .is_ok()
+    );
+}
+
+#[tokio::test]
+async fn test_override_asset_checks() {
+    let _setup: Setup = setup().await;
+    let (controller_id, index_id) = setup_index(&_setup).await;
+    let mint = create_mint(&_setup).await;
+
+    require_asset_registry(&_setup).await;
+
+    let transaction = set_controller_config_transaction(
+        &_setup.payer,
+        _setup.program_id,
+        controller_id,
+        None,
+        0,
+        0,
+        None,
+        vec![],
+        true,
+        _setup.recent_blockhashes().await,
+    );
+    let result = _setup.banks_client.process_transaction(transaction).await;
+    assert!(result.is_ok());
+
+    assert!(add_component(controller_id, index_id, mint, 1, &_setup)
+        .await
+        .is_ok());
+}
+
+#[tokio::test]
+async fn test_set_asset_registry_entry() {
+    let _setup: Setup = setup().await;
+    let _ = process_controller_global_config(10, &_setup).await;
+    let mint = create_mint(&_setup).await;
+
+    // only the protocol owner manages the registry
+    let outsider = Keypair::new();
+    let instruction = set_asset_registry_entry_instruction(
+        _setup.program_id,
+        outsider.pubkey(),
+        find_protocol_address(&_setup.program_id).0,
+        mint,
+        true,
+        0,
+        0,
+    );
+    let transaction = Transaction::new_signed_with_payer(
+        &[instruction],
+        Some(&_setup.payer.pubkey()),
+        &[&_setup.payer, &outsider],
+        _setup.recent_blockhashes().await,
+    );
+    let result = _setup.banks_client.process_transaction(transaction).await;
+    assert_eq!(
+        result.unwrap_err().unwrap(),
+        custom_error(ProtocolError::OnlyProtocolOwner)
+    );
+
+    assert_eq!(
+        set_asset_registry_entry(mint, true, 0, ASSET_RISK_ALL + 1, &_setup).await,
+        Err(custom_error(ProtocolError::InvalidAssetRisks))
+    );
+
+    assert_eq!(
+        set_asset_registry_entry(mint, false, 0, 0, &_setup).await,
+        Err(TransactionError::InstructionError(
+            0,
+            InstructionError::UninitializedAccount
+        ))
+    );
+
+    assert!(set_asset_registry_entry(mint, true, 1, 0, &_setup)
+        .await
+        .is_ok());
+
+    // removal closes the entry
+    assert!(set_asset_registry_entry(mint, false, 1, 0, &_setup)
+        .await
+        .is_ok());
+    let asset_registry_pda = find_asset_registry_address(&_setup.program_id, &mint).0;
+    let account = _setup
+        .banks_client
+        .get_account(asset_registry_pda)
+        .await
+        .unwrap();
+    assert!(account.is_none());
+}
@@ -0,0 +1,581 @@
+use borsh::BorshDeserialize;
+use openindex::state::{Component, IndexMints, PendingCompositionChange};
+use openindex_sdk::openindex::{
+    error::ProtocolError,
+    instruction::{
+        cancel_composition_change_instruction, execute_composition_change_instruction,
+        with_asset_registry_accounts,
+    },
+    pda::{
+        find_component_address, find_component_vault_address, find_controller_address,
+        find_controller_config_address, find_controller_global_config_address, find_index_address,
+        find_index_mint_address, find_index_mints_data_address,
+        find_pending_composition_change_address,
+    },
+    transaction::{
+        cancel_composition_change_transaction, create_token_account_transaction,
+        execute_composition_change_transaction, execute_nested_composition_change_transaction,
+        mint_to_transaction, queue_composition_change_transaction,
+        queue_nested_composition_change_transaction, set_composition_delay_transaction,
+        update_controller_global_config_transaction,
+    },
+};
+use solana_program_test::tokio;
+use spl_associated_token_account::get_associated_token_address;
+
+use solana_sdk::{
+    instruction::InstructionError,
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+    transaction::{Transaction, TransactionError},
+};
+
+use crate::{
+    create_mint, custom_error, latest_blockhash, new_blockhash, process,
+    process_add_index_components, process_controller_global_config, process_create_index,
+    process_init_controller, process_mint, process_redeem, setup, token_balance,
+    ProcessAddIndexComponentsResult, ProcessControllerGlobalConfigResult, ProcessCreateIndexResult,
+    ProcessInitControllerResult, ProcessRedeemResult, Setup,
+};
+
+const DELAY: i64 = 3600;
+
+/// Creates an index with two components and a `DELAY` second timelock.
+async fn setup_timelocked_index(_setup: &Setup) -> (u64, u64, Vec<Pubkey>) {
+    let ProcessControllerGlobalConfigResult { result } =
+        process_controller_global_config(10, _setup).await;
+    assert!(result.is_ok());
+
+    let ProcessInitControllerResult {
+        controller_id,
+        result,
+        ..
+    } = process_init_controller(_setup).await;
+    assert!(result.is_ok());
+
+    let ProcessCreateIndexResult {
+        index_id, result, ..
+    } = process_create_index(controller_id, _setup.payer.pubkey(), _setup).await;
+    assert!(result.is_ok());
+
+    let ProcessAddIndexComponentsResult { mints, result, .. } = process_add_index_components(
+        index_id,
+        controller_id,
+        _setup.payer.pubkey(),
+        2,
+        vec![10, 20],
+        _setup,
+    )
+    .await;
+    assert!(result.is_ok());
+
+    let transaction = set_composition_delay_transaction(
+        &_setup.payer,
+        _setup.program_id,
+        index_id,
+        controller_id,
+        DELAY,
+        latest_blockhash(_setup).await,
+    );
+    assert!(process(transaction, _setup).await.is_ok());
+
+    (index_id, controller_id, mints)
+}
+
+#[tokio::test]
+async fn test_execute_composition_change() {
+    let mut _setup: Setup = setup().await;
+    let (index_id, controller_id, mints) = setup_timelocked_index(&_setup).await;
+
+    let transaction = set_composition_delay_transaction(
+        &_setup.payer,
+        _setup.program_id,
+        index_id,
+        controller_id,
+        DELAY - 1,
+        latest_blockhash(&_setup).await,
+    );
+    assert_eq!(
+        process(transaction, &_setup).await,
+        Err(custom_error(ProtocolError::CompositionDelayDecreased))
+    );
+
+    // keep the second component with new units, drop the first, add a new one
+    let new_mint = create_mint(&_setup).await;
+    let new_mints = vec![mints[1], new_mint];
+    let queue = |recent_blockhash| {
+        queue_composition_change_transaction(
+            &_setup.payer,
+            _setup.program_id,
+            index_id,
+            controller_id,
+            recent_blockhash,
+            new_mints.clone(),
+            vec![5, 7],
+        )
+    };
+    let queue_blockhash = latest_blockhash(&_setup).await;
+    assert!(process(queue(queue_blockhash), &_setup).await.is_ok());
+    assert_eq!(
+        process(
+            queue(new_blockhash(&_setup, &queue_blockhash).await),
+            &_setup
+        )
+        .await,
+        Err(custom_error(ProtocolError::CompositionChangeAlreadyQueued))
+    );
+
+    let controller_pda = find_controller_address(&_setup.program_id, controller_id).0;
+    let index_pda = find_index_address(&_setup.program_id, &controller_pda, index_id).0;
+    let pending_change_pda =
+        find_pending_composition_change_address(&_setup.program_id, &index_pda).0;
+    let account = _setup
+        .banks_client
+        .get_account(pending_change_pda)
+        .await
+        .unwrap()
+        .unwrap();
+    let pending_change = PendingCompositionChange::try_from_slice(&account.data).unwrap();
+    assert_eq!(pending_change.mints, new_mints);
+    assert_eq!(pending_change.amounts, vec![5, 7]);
+    assert_eq!(
+        pending_change.executable_at - pending_change.queued_at,
+        DELAY
+    );
+
+    let execute = |_setup: &Setup, recent_blockhash| {
+        execute_composition_change_transaction(
+            &_setup.payer,
+            _setup.program_id,
+            index_id,
+            controller_id,
+            _setup.payer.pubkey(),
+            &new_mints,
+            recent_blockhash,
+        )
+    };
+    let execute_blockhash = latest_blockhash(&_setup).await;
+    assert_eq!(
+        process(execute(&_setup, execute_blockhash), &_setup).await,
+        Err(custom_error(ProtocolError::CompositionChangeTimelocked))
+    );
+
+    _setup.advance_clock(DELAY).await;
+    let execute_blockhash = new_blockhash(&_setup, &execute_blockhash).await;
+    assert!(process(execute(&_setup, execute_blockhash), &_setup)
+        .await
+        .is_ok());
+
+    let index_mints_pda =
+        find_index_mints_data_address(&_setup.program_id, &controller_pda, index_id).0;
+    let account = _setup
+        .banks_client
+        .get_account(index_mints_pda)
+        .await
+        .unwrap()
+        .unwrap();
+    let index_mints = IndexMints::try_from_slice(&account.data).unwrap();
+    assert_eq!(index_mints.mints, new_mints);
+
+    for (mint, units) in new_mints.iter().zip([5, 7]) {
+        let component_pda = find_component_address(&_setup.program_id, &index_pda, mint).0;
+        let account = _setup
+            .banks_client
+            .get_account(component_pda)
+            .await
+            .unwrap()
+            .unwrap();
+        let component = Component::try_from_slice(&account.data).unwrap();
+        assert_eq!(component.uints, units);
+    }
+
+    let account = _setup
+        .banks_client
+        .get_account(pending_change_pda)
+        .await
+        .unwrap();
+    assert!(account.is_none());
+}
+
+#[tokio::test]
+async fn test_cancel_composition_change() {
+    let _setup: Setup = setup().await;
+    let (index_id, controller_id, mints) = setup_timelocked_index(&_setup).await;
+
+    let queue = |recent_blockhash| {
+        queue_composition_change_transaction(
+            &_setup.payer,
+            _setup.program_id,
+            index_id,
+            controller_id,
+            recent_blockhash,
+            vec![mints[0]],
+            vec![1],
+        )
+    };
+    let queue_blockhash = latest_blockhash(&_setup).await;
+    assert!(process(queue(queue_blockhash), &_setup).await.is_ok());
+
+    let controller_pda = find_controller_address(&_setup.program_id, controller_id).0;
+    let index_pda = find_index_address(&_setup.program_id, &controller_pda, index_id).0;
+    let stranger = Keypair::new();
+    let transaction = Transaction::new_signed_with_payer(
+        &[cancel_composition_change_instruction(
+            _setup.program_id,
+            stranger.pubkey(),
+            index_pda,
+            controller_pda,
+            _setup.payer.pubkey(),
+        )],
+        Some(&_setup.payer.pubkey()),
+        &[&_setup.payer, &stranger],
+        latest_blockhash(&_setup).await,
+    );
+    assert_eq!(
+        process(transaction, &_setup).await,
+        Err(custom_error(ProtocolError::OnlyIndexManager))
+    );
+
+    let transaction = cancel_composition_change_transaction(
+        &_setup.payer,
+        _setup.program_id,
+        index_id,
+        controller_id,
+        _setup.payer.pubkey(),
+        latest_blockhash(&_setup).await,
+    );
+    assert!(process(transaction, &_setup).await.is_ok());
+
+    let pending_change_pda =
+        find_pending_composition_change_address(&_setup.program_id, &index_pda).0;
+    let account = _setup
+        .banks_client
+        .get_account(pending_change_pda)
+        .await
+        .unwrap();
+    assert!(account.is_none());
+
+    // a new change can be queued once the previous one is gone
+    assert!(process(
+        queue(new_blockhash(&_setup, &queue_blockhash).await),
+        &_setup
+    )
+    .await
+    .is_ok());
+}
+
+#[tokio::test]
+async fn test_execute_composition_change_with_supply_outstanding() {
+    let mut _setup: Setup = setup().await;
+    let (index_id, controller_id, mints) = setup_timelocked_index(&_setup).await;
+
+    let minted = process_mint(3, 100, controller_id, index_id, mints.clone(), &_setup).await;
+    assert!(minted.result.is_ok());
+
+    // lower the first component, drop the second and add a new one
+    let new_mint = create_mint(&_setup).await;
+    let new_mints = vec![mints[0], new_mint];
+    let transaction = queue_composition_change_transaction(
+        &_setup.payer,
+        _setup.program_id,
+        index_id,
+        controller_id,
+        latest_blockhash(&_setup).await,
+        new_mints.clone(),
+        vec![5, 7],
+    );
+    assert!(process(transaction, &_setup).await.is_ok());
+
+    // the three outstanding tokens need 21 new component tokens behind them
+    _setup.advance_clock(DELAY).await;
+    let execute = |recent_blockhash| {
+        execute_composition_change_transaction(
+            &_setup.payer,
+            _setup.program_id,
+            index_id,
+            controller_id,
+            _setup.payer.pubkey(),
+            &new_mints,
+            recent_blockhash,
+        )
+    };
+    let execute_blockhash = latest_blockhash(&_setup).await;
+    assert_eq!(
+        process(execute(execute_blockhash), &_setup).await,
+        Err(custom_error(ProtocolError::ComponentVaultUnderfunded))
+    );
+
+    let controller_pda = find_controller_address(&_setup.program_id, controller_id).0;
+    let index_pda = find_index_address(&_setup.program_id, &controller_pda, index_id).0;
+    let vault_pda = find_component_vault_address(&_setup.program_id, &index_pda, &new_mint).0;
+    let vault_ata = get_associated_token_address(&vault_pda, &new_mint);
+    let transaction = create_token_account_transaction(
+        &_setup.payer,
+        _setup.payer.pubkey(),
+        vault_pda,
+        new_mint,
+        latest_blockhash(&_setup).await,
+    );
+    assert!(process(transaction, &_setup).await.is_ok());
+    let transaction = mint_to_transaction(
+        &_setup.payer,
+        21,
+        new_mint,
+        vault_ata,
+        latest_blockhash(&_setup).await,
+    )
+    .unwrap();
+    assert!(process(transaction, &_setup).await.is_ok());
+
+    let execute_blockhash = new_blockhash(&_setup, &execute_blockhash).await;
+    assert!(process(execute(execute_blockhash), &_setup).await.is_ok());
+
+    // the lowered component keeps its units and the dropped one stays last
+    // until the outstanding tokens are redeemed
+    let index_mints_pda =
+        find_index_mints_data_address(&_setup.program_id, &controller_pda, index_id).0;
+    let account = _setup
+        .banks_client
+        .get_account(index_mints_pda)
+        .await
+        .unwrap()
+        .unwrap();
+    let index_mints = IndexMints::try_from_slice(&account.data).unwrap();
+    assert_eq!(index_mints.mints, vec![mints[0], new_mint, mints[1]]);
+
+    for (mint, units) in index_mints.mints.iter().zip([10, 7, 20]) {
+        let component_pda = find_component_address(&_setup.program_id, &index_pda, mint).0;
+        let account = _setup
+            .banks_client
+            .get_account(component_pda)
+            .await
+            .unwrap()
+            .unwrap();
+        let component = Component::try_from_slice(&account.data).unwrap();
+        assert_eq!(component.uints, units);
+    }
+
+    let transaction = create_token_account_transaction(
+        &_setup.payer,
+        _setup.payer.pubkey(),
+        _setup.payer.pubkey(),
+        new_mint,
+        latest_blockhash(&_setup).await,
+    );
+    assert!(process(transaction, &_setup).await.is_ok());
+    let token_accounts = vec![
+        minted.token_accounts[0],
+        get_associated_token_address(&_setup.payer.pubkey(), &new_mint),
+        minted.token_accounts[1],
+    ];
+
+    let ProcessRedeemResult { result } = process_redeem(
+        3,
+        index_id,
+        controller_id,
+        minted.token_account,
+        index_mints.mints.clone(),
+        token_accounts.clone(),
+        &_setup,
+    )
+    .await;
+    assert!(result.is_ok());
+
+    for (token_account, balance) in token_accounts.iter().zip([100, 21, 100]) {
+        assert_eq!(token_balance(*token_account, &_setup).await, balance);
+    }
+}
+
+#[tokio::test]
+async fn test_queue_composition_change_checks_recipe() {
+    let _setup: Setup = setup().await;
+    let (index_id, controller_id, mints) = setup_timelocked_index(&_setup).await;
+
+    let queue = |mints: Vec<Pubkey>, amounts: Vec<u64>, recent_blockhash| {
+        queue_composition_change_transaction(
+            &_setup.payer,
+            _setup.program_id,
+            index_id,
+            controller_id,
+            recent_blockhash,
+            mints,
+            amounts,
+        )
+    };
+
+    assert_eq!(
+        process(
+            queue(
+                vec![mints[0], mints[0]],
+                vec![1, 2],
+                latest_blockhash(&_setup).await
+            ),
+            &_setup
+        )
+        .await,
+        Err(custom_error(ProtocolError::DuplicateComponentMint))
+    );
+
+    assert_eq!(
+        process(
+            queue(mints.clone(), vec![1, 0], latest_blockhash(&_setup).await),
+            &_setup
+        )
+        .await,
+        Err(custom_error(ProtocolError::ZeroComponentUnits))
+    );
+
+    let controller_pda = find_controller_address(&_setup.program_id, controller_id).0;
+    let index_mint = find_index_mint_address(&_setup.program_id, &controller_pda, index_id).0;
+    assert_eq!(
+        process(
+            queue(
+                vec![mints[0], index_mint],
+                vec![1, 2],
+                latest_blockhash(&_setup).await
+            ),
+            &_setup
+        )
+        .await,
+        Err(custom_error(ProtocolError::IndexMintAsComponent))
+    );
+}
+
+#[tokio::test]
+async fn test_execute_composition_change_checks_asset_registry() {
+    let mut _setup: Setup = setup().await;
+    let (index_id, controller_id, _) = setup_timelocked_index(&_setup).await;
+
+    let new_mint = create_mint(&_setup).await;
+    let transaction = queue_composition_change_transaction(
+        &_setup.payer,
+        _setup.program_id,
+        index_id,
+        controller_id,
+        latest_blockhash(&_setup).await,
+        vec![new_mint],
+        vec![1],
+    );
+    assert!(process(transaction, &_setup).await.is_ok());
+
+    // the registry becomes mandatory while the change is queued
+    let transaction = update_controller_global_config_transaction(
+        &_setup.payer,
+        _setup.program_id,
+        None,
+        false,
+        None,
+        None,
+        None,
+        None,
+        Some(true),
+        latest_blockhash(&_setup).await,
+    );
+    assert!(process(transaction, &_setup).await.is_ok());
+
+    _setup.advance_clock(DELAY).await;
+    let controller_pda = find_controller_address(&_setup.program_id, controller_id).0;
+    let index_pda = find_index_address(&_setup.program_id, &controller_pda, index_id).0;
+    let instruction = with_asset_registry_accounts(
+        execute_composition_change_instruction(
+            _setup.program_id,
+            _setup.payer.pubkey(),
+            index_pda,
+            find_index_mints_data_address(&_setup.program_id, &controller_pda, index_id).0,
+            find_index_mint_address(&_setup.program_id, &controller_pda, index_id).0,
+            controller_pda,
+            find_controller_global_config_address(&_setup.program_id).0,
+            find_controller_config_address(&_setup.program_id, &controller_pda).0,
+            _setup.payer.pubkey(),
+            &[new_mint],
+        ),
+        &[new_mint],
+    );
+    let transaction = Transaction::new_signed_with_payer(
+        &[instruction],
+        Some(&_setup.payer.pubkey()),
+        &[&_setup.payer],
+        latest_blockhash(&_setup).await,
+    );
+    assert_eq!(
+        process(transaction, &_setup).await,
+        Err(custom_error(ProtocolError::AssetNotRegistered))
+    );
+}
+
+#[tokio::test]
+async fn test_execute_nested_composition_change() {
+    let mut _setup: Setup = setup().await;
+    let (index_id, controller_id, mints) = setup_timelocked_index(&_setup).await;
+
+    let ProcessCreateIndexResult {
+        index_id: underlying_index_id,
+        result,
+        ..
+    } = process_create_index(controller_id, _setup.payer.pubkey(), &_setup).await;
+    assert!(result.is_ok());
+
+    let controller_pda = find_controller_address(&_setup.program_id, controller_id).0;
+    let underlying_mint =
+        find_index_mint_address(&_setup.program_id, &controller_pda, underlying_index_id).0;
+    let new_mints = vec![mints[0], underlying_mint];
+
+    let transaction = queue_nested_composition_change_transaction(
+        &_setup.payer,
+        _setup.program_id,
+        index_id,
+        controller_id,
+        latest_blockhash(&_setup).await,
+        new_mints.clone(),
+        vec![1, 3],
+        vec![false, true],
+    );
+    assert!(process(transaction, &_setup).await.is_ok());
+
+    _setup.advance_clock(DELAY).await;
+
+    // the underlying index of the nested component is required
+    let execute_blockhash = latest_blockhash(&_setup).await;
+    let transaction = execute_composition_change_transaction(
+        &_setup.payer,
+        _setup.program_id,
+        index_id,
+        controller_id,
+        _setup.payer.pubkey(),
+        &new_mints,
+        execute_blockhash,
+    );
+    assert_eq!(
+        process(transaction, &_setup).await,
+        Err(TransactionError::InstructionError(
+            0,
+            InstructionError::NotEnoughAccountKeys
+        ))
+    );
+
+    let transaction = execute_nested_composition_change_transaction(
+        &_setup.payer,
+        _setup.program_id,
+        index_id,
+        controller_id,
+        _setup.payer.pubkey(),
+        &new_mints,
+        vec![None, Some((controller_id, underlying_index_id))],
+        new_blockhash(&_setup, &execute_blockhash).await,
+    );
+    assert!(process(transaction, &_setup).await.is_ok());
+
+    let index_pda = find_index_address(&_setup.program_id, &controller_pda, index_id).0;
+    let underlying_index_pda =
+        find_index_address(&_setup.program_id, &controller_pda, underlying_index_id).0;
+    let component_pda = find_component_address(&_setup.program_id, &index_pda, &underlying_mint).0;
+    let account = _setup
+        .banks_client
+        .get_account(component_pda)
+        .await
+        .unwrap()
+        .unwrap();
+    let component = Component::try_from_slice(&account.data).unwrap();
+    assert_eq!(component.uints, 3);
+    assert_eq!(component.underlying_index, underlying_index_pda);
+}
@@ -0,0 +1,139 @@
+use openindex_sdk::openindex::transaction::{mint_transaction, redeem_transaction};
+use solana_program_test::tokio;
+use solana_sdk::signature::Keypair;
+use solana_sdk::signer::Signer;
+
+use crate::{
+    process_add_index_components, process_controller_global_config, process_create_index,
+    process_init_controller, process_init_protocol, process_mint, setup,
+    ProcessAddIndexComponentsResult, ProcessCreateIndexResult, ProcessInitControllerResult,
+    ProcessMintResult, Setup,
+};
+
+/// Upper bound on compute units consumed by `Mint` for each component.
+///
+/// Natively only the token program CPIs are metered, about 5_600 CU per
+/// component, so this module is only built by `make test_sbf`, which loads
+/// the program itself as SBF.
+const MINT_MAX_CU_PER_COMPONENT: u64 = 15_000;
+
+/// Upper bound on compute units consumed by `Redeem` for each component.
+const REDEEM_MAX_CU_PER_COMPONENT: u64 = 15_000;
+
+#[tokio::test]
+async fn test_mint_redeem_compute_units() {
+    let _setup: Setup = setup().await;
+    let manager = Keypair::new();
+
+    let _ = process_init_protocol(&_setup).await;
+
+    let _ = process_controller_global_config(10, &_setup).await;
+
+    let ProcessInitControllerResult {
+        controller_id,
+        controller_pda: _,
+        result: _,
+    } = process_init_controller(&_setup).await;
+
+    let ProcessCreateIndexResult {
+        index_id,
+        controller_pda: _,
+        result: _,
+    } = process_create_index(controller_id, manager.pubkey(), &_setup).await;
+
+    let components_count = 5;
+    let units: Vec<_> = (0..components_count).map(|i| i + 1).collect();
+
+    let ProcessAddIndexComponentsResult {
+        index_id,
+        controller_id,
+        mints,
+        units: _,
+        result,
+    } = process_add_index_components(
+        index_id,
+        controller_id,
+        manager.pubkey(),
+        components_count,
+        units,
+        &_setup,
+    )
+    .await;
+    assert!(result.is_ok());
+
+    // first mint creates and funds the user's token accounts
+    let ProcessMintResult {
+        index_id,
+        controller_id,
+        token_account,
+        token_accounts,
+        result,
+    } = process_mint(
+        10,
+        100_000_000,
+        controller_id,
+        index_id,
+        mints.clone(),
+        &_setup,
+    )
+    .await;
+    assert!(result.is_ok());
+
+    // simulated and metadata transactions are checked against the working
+    // bank, whose blockhash queue may have moved past the genesis blockhash
+    let latest_blockhash = _setup.banks_client.get_latest_blockhash().await.unwrap();
+    let mint_tx = mint_transaction(
+        20,
+        &_setup.payer,
+        _setup.program_id,
+        index_id,
+        controller_id,
+        token_account,
+        latest_blockhash,
+        mints.clone(),
+        token_accounts.clone(),
+    );
+
+    let mint_result = _setup
+        .banks_client
+        .process_transaction_with_metadata(mint_tx)
+        .await
+        .unwrap();
+    assert!(mint_result.result.is_ok(), "{:?}", mint_result.result);
+
+    let mint_units = mint_result.metadata.unwrap().compute_units_consumed;
+    let mint_units_per_component = mint_units / components_count;
+    assert!(
+        mint_units_per_component <= MINT_MAX_CU_PER_COMPONENT,
+        "mint consumed {} CU per component",
+        mint_units_per_component
+    );
+
+    let latest_blockhash = _setup.banks_client.get_latest_blockhash().await.unwrap();
+    let redeem_tx = redeem_transaction(
+        30,
+        &_setup.payer,
+        _setup.program_id,
+        index_id,
+        controller_id,
+        token_account,
+        latest_blockhash,
+        mints.clone(),
+        token_accounts.clone(),
+    );
+
+    let redeem_result = _setup
+        .banks_client
+        .process_transaction_with_metadata(redeem_tx)
+        .await
+        .unwrap();
+    assert!(redeem_result.result.is_ok());
+
+    let redeem_units = redeem_result.metadata.unwrap().compute_units_consumed;
+    let redeem_units_per_component = redeem_units / components_count;
+    assert!(
+        redeem_units_per_component <= REDEEM_MAX_CU_PER_COMPONENT,
+        "redeem consumed {} CU per component",
+        redeem_units_per_component
+    );
+}
@@ -0,0 +1,358 @@
+use borsh::BorshDeserialize;
+use openindex::state::ControllerConfig;
+use openindex_sdk::openindex::{
+    error::ProtocolError,
+    instruction::set_controller_config_instruction,
+    pda::{find_controller_config_address, find_index_mint_address},
+    transaction::{
+        add_index_components_transaction, create_mint_acccount_transaction,
+        set_controller_config_transaction,
+    },
+};
+use solana_program_test::tokio;
+use solana_sdk::{
+    program_pack::Pack,
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+    transaction::Transaction,
+};
+use spl_token::state::Mint;
+
+use crate::{
+    custom_error, process_controller_global_config, process_create_index, process_init_controller,
+    setup, ProcessCreateIndexResult, ProcessInitControllerResult, Setup,
+};
+
+async fn create_mints(count: usize, _setup: &Setup) -> Vec<Pubkey> {
+    let mut mints = vec![];
+    for _ in 0..count {
+        let mint = Keypair::new();
+        let create_mint_tx = create_mint_acccount_transaction(
+            &_setup.payer,
+            &mint,
+            _setup.recent_blockhashes().await,
+            &_setup.rent,
+        );
+        let result = _setup
+            .banks_client
+            .process_transaction(create_mint_tx)
+            .await;
+        assert!(result.is_ok());
+        mints.push(mint.pubkey());
+    }
+    mints
+}
+
+async fn index_mint_decimals(controller_pda: Pubkey, index_id: u64, _setup: &Setup) -> u8 {
+    let index_mint = find_index_mint_address(&_setup.program_id, &controller_pda, index_id).0;
+    let account = _setup
+        .banks_client
+        .get_account(index_mint)
+        .await
+        .unwrap()
+        .unwrap();
+    Mint::unpack(&account.data).unwrap().decimals
+}
+
+async fn get_controller_config(
+    controller_pda: Pubkey,
+    _setup: &Setup,
+) -> (usize, ControllerConfig) {
+    let controller_config_pda =
+        find_controller_config_address(&_setup.program_id, &controller_pda).0;
+    let account = _setup
+        .banks_client
+        .get_account(controller_config_pda)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(account.owner, _setup.program_id);
+    (
+        account.data.len(),
+        ControllerConfig::try_from_slice(&account.data).unwrap(),
+    )
+}
+
+#[tokio::test]
+async fn test_controller_config() {
+    let _setup: Setup = setup().await;
+    let manager = Keypair::new();
+
+    let _ = process_controller_global_config(10, &_setup).await;
+
+    let ProcessInitControllerResult {
+        controller_id,
+        controller_pda,
+        result,
+    } = process_init_controller(&_setup).await;
+    assert!(result.is_ok());
+
+    // only the controller owner can configure it
+    let outsider = Keypair::new();
+    let instruction = set_controller_config_instruction(
+        _setup.program_id,
+        outsider.pubkey(),
+        controller_pda,
+        find_controller_config_address(&_setup.program_id, &controller_pda).0,
+        Some(2),
+        0,
+        0,
+        None,
+        vec![],
+        false,
+    );
+    let transaction = Transaction::new_signed_with_payer(
+        &[instruction],
+        Some(&_setup.payer.pubkey()),
+        &[&_setup.payer, &outsider],
+        _setup.recent_blockhashes().await,
+    );
+    let result = _setup.banks_client.process_transaction(transaction).await;
+    assert_eq!(
+        result.unwrap_err().unwrap(),
+        custom_error(ProtocolError::OnlyControllerOwner)
+    );
+
+    let mints = create_mints(4, &_setup).await;
+
+    let transaction = set_controller_config_transaction(
+        &_setup.payer,
+        _setup.program_id,
+        controller_id,
+        Some(2),
+        100,
+        50,
+        Some(6),
+        mints[..3].to_vec(),
+        false,
+        _setup.recent_blockhashes().await,
+    );
+    let result = _setup.banks_client.process_transaction(transaction).await;
+    assert!(result.is_ok());
+
+    let (_, controller_config) = get_controller_config(controller_pda, &_setup).await;
+    assert_eq!(controller_config.max_index_components, Some(2));
+    assert_eq!(controller_config.max_mint_fee_bps, 100);
+    assert_eq!(controller_config.max_redeem_fee_bps, 50);
+    assert_eq!(controller_config.default_decimals, Some(6));
+    assert_eq!(controller_config.allowed_mints, mints[..3].to_vec());
+
+    let ProcessCreateIndexResult {
+        index_id,
+        controller_pda: _,
+        result,
+    } = process_create_index(controller_id, manager.pubkey(), &_setup).await;
+    assert!(result.is_ok());
+    assert_eq!(
+        index_mint_decimals(controller_pda, index_id, &_setup).await,
+        6
+    );
+
+    // the controller cap wins over the global cap of 10
+    let transaction = add_index_components_transaction(
+        &_setup.payer,
+        &_setup.payer,
+        _setup.program_id,
+        index_id,
+        controller_id,
+        _setup.recent_blockhashes().await,
+        mints[..3].to_vec(),
+        vec![1, 2, 3],
+    );
+    let result = _setup.banks_client.process_transaction(transaction).await;
+    assert_eq!(
+        result.unwrap_err().unwrap(),
+        custom_error(ProtocolError::MaxIndexComponentsExceeded)
+    );
+
+    let transaction = add_index_components_transaction(
+        &_setup.payer,
+        &_setup.payer,
+        _setup.program_id,
+        index_id,
+        controller_id,
+        _setup.recent_blockhashes().await,
+        vec![mints[0], mints[3]],
+        vec![1, 2],
+    );
+    let result = _setup.banks_client.process_transaction(transaction).await;
+    assert_eq!(
+        result.unwrap_err().unwrap(),
+        custom_error(ProtocolError::ComponentMintNotAllowed)
+    );
+
+    let transaction = add_index_components_transaction(
+        &_setup.payer,
+        &_setup.payer,
+        _setup.program_id,
+        index_id,
+        controller_id,
+        _setup.recent_blockhashes().await,
+        mints[..2].to_vec(),
+        vec![1, 2],
+    );
+    let result = _setup.banks_client.process_transaction(transaction).await;
+    assert!(result.is_ok());
+}
+
+#[tokio::test]
+async fn test_controller_config_update() {
+    let _setup: Setup = setup().await;
+    let manager = Keypair::new();
+
+    let _ = process_controller_global_config(10, &_setup).await;
+
+    let ProcessInitControllerResult {
+        controller_id,
+        controller_pda,
+        result,
+    } = process_init_controller(&_setup).await;
+    assert!(result.is_ok());
+
+    let transaction = set_controller_config_transaction(
+        &_setup.payer,
+        _setup.program_id,
+        controller_id,
+        None,
+        10_001,
+        0,
+        None,
+        vec![],
+        false,
+        _setup.recent_blockhashes().await,
+    );
+    let result = _setup.banks_client.process_transaction(transaction).await;
+    assert_eq!(
+        result.unwrap_err().unwrap(),
+        custom_error(ProtocolError::InvalidFeeBps)
+    );
+
+    let allowed_mints = create_mints(3, &_setup).await;
+    let transaction = set_controller_config_transaction(
+        &_setup.payer,
+        _setup.program_id,
+        controller_id,
+        Some(2),
+        0,
+        0,
+        Some(6),
+        allowed_mints,
+        false,
+        _setup.recent_blockhashes().await,
+    );
+    let result = _setup.banks_client.process_transaction(transaction).await;
+    assert!(result.is_ok());
+
+    // clearing the overrides shrinks the account and restores the fallbacks
+    let transaction = set_controller_config_transaction(
+        &_setup.payer,
+        _setup.program_id,
+        controller_id,
+        None,
+        0,
+        0,
+        None,
+        vec![],
+        false,
+        _setup.recent_blockhashes().await,
+    );
+    let result = _setup.banks_client.process_transaction(transaction).await;
+    assert!(result.is_ok());
+
+    let (data_len, controller_config) = get_controller_config(controller_pda, &_setup).await;
+    assert_eq!(data_len, controller_config.packed_len());
+    assert!(controller_config.allowed_mints.is_empty());
+
+    let ProcessCreateIndexResult {
+        index_id,
+        controller_pda: _,
+        result,
+    } = process_create_index(controller_id, manager.pubkey(), &_setup).await;
+    assert!(result.is_ok());
+    assert_eq!(
+        index_mint_decimals(controller_pda, index_id, &_setup).await,
+        9
+    );
+
+    let mints = create_mints(3, &_setup).await;
+    let transaction = add_index_components_transaction(
+        &_setup.payer,
+        &_setup.payer,
+        _setup.program_id,
+        index_id,
+        controller_id,
+        _setup.recent_blockhashes().await,
+        mints,
+        vec![1, 2, 3],
+    );
+    let result = _setup.banks_client.process_transaction(transaction).await;
+    assert!(result.is_ok());
+}
+
+#[tokio::test]
+async fn test_controller_config_cap_clamped_to_global() {
+    let _setup: Setup = setup().await;
+    let manager = Keypair::new();
+
+    let _ = process_controller_global_config(2, &_setup).await;
+
+    let ProcessInitControllerResult {
+        controller_id,
+        controller_pda: _,
+        result,
+    } = process_init_controller(&_setup).await;
+    assert!(result.is_ok());
+
+    // a controller owner cannot raise the cap above the global one
+    let transaction = set_controller_config_transaction(
+        &_setup.payer,
+        _setup.program_id,
+        controller_id,
+        Some(10),
+        0,
+        0,
+        None,
+        vec![],
+        false,
+        _setup.recent_blockhashes().await,
+    );
+    let result = _setup.banks_client.process_transaction(transaction).await;
+    assert!(result.is_ok());
+
+    let ProcessCreateIndexResult {
+        index_id,
+        controller_pda: _,
+        result,
+    } = process_create_index(controller_id, manager.pubkey(), &_setup).await;
+    assert!(result.is_ok());
+
+    let mints = create_mints(3, &_setup).await;
+    let transaction = add_index_components_transaction(
+        &_setup.payer,
+        &_setup.payer,
+        _setup.program_id,
+        index_id,
+        controller_id,
+        _setup.recent_blockhashes().await,
+        mints.clone(),
+        vec![1, 2, 3],
+    );
+    let result = _setup.banks_client.process_transaction(transaction).await;
+    assert_eq!(
+        result.unwrap_err().unwrap(),
+        custom_error(ProtocolError::MaxIndexComponentsExceeded)
+    );
+
+    let transaction = add_index_components_transaction(
+        &_setup.payer,
+        &_setup.payer,
+        _setup.program_id,
+        index_id,
+        controller_id,
+        _setup.recent_blockhashes().await,
+        mints[..2].to_vec(),
+        vec![1, 2],
+    );
+    let result = _setup.banks_client.process_transaction(transaction).await;
+    assert!(result.is_ok());
+}
@@ -0,0 +1,245 @@
+use borsh::BorshDeserialize;
+use openindex::state::{Controller, Protocol};
+use openindex_sdk::openindex::{
+    error::ProtocolError,
+    instruction::ControllerCreationPolicy,
+    pda::{
+        find_controller_address, find_controller_creator_address, find_protocol_address,
+        find_treasury_address,
+    },
+    transaction::{
+        init_controller_transaction, set_controller_creator_transaction,
+        update_controller_global_config_transaction, withdraw_treasury_transaction,
+    },
+};
+use solana_program_test::tokio;
+use solana_sdk::{
+    native_token::LAMPORTS_PER_SOL,
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+    system_instruction,
+    transaction::Transaction,
+};
+
+use crate::{
+    custom_error, process_controller_global_config, process_create_index, process_init_controller,
+    setup, ProcessControllerGlobalConfigResult, ProcessCreateIndexResult,
+    ProcessInitControllerResult, Setup,
+};
+
+async fn next_controller_id(_setup: &Setup) -> u64 {
+    let protocol_pda = find_protocol_address(&_setup.program_id).0;
+    let account = _setup
+        .banks_client
+        .get_account(protocol_pda)
+        .await
+        .unwrap()
+        .unwrap();
+    Protocol::try_from_slice(&account.data)
+        .unwrap()
+        .get_next_controller_id()
+}
+
+async fn lamports(_setup: &Setup, address: Pubkey) -> u64 {
+    _setup.banks_client.get_balance(address).await.unwrap()
+}
+
+async fn update_policy(
+    policy: ControllerCreationPolicy,
+    controller_creation_fee: u64,
+    index_creation_fee: u64,
+    _setup: &Setup,
+) {
+    let transaction = update_controller_global_config_transaction(
+        &_setup.payer,
+        _setup.program_id,
+        None,
+        false,
+        Some(policy),
+        Some(controller_creation_fee),
+        Some(index_creation_fee),
+        None,
+        None,
+        _setup.recent_blockhashes().await,
+    );
+    let result = _setup.banks_client.process_transaction(transaction).await;
+    assert!(result.is_ok());
+}
+
+#[tokio::test]
+async fn test_controller_creation_allowlist() {
+    let _setup: Setup = setup().await;
+
+    let ProcessControllerGlobalConfigResult { result } =
+        process_controller_global_config(10, &_setup).await;
+    assert!(result.is_ok());
+
+    update_policy(ControllerCreationPolicy::Allowlist, 0, 0, &_setup).await;
+
+    let creator = Keypair::new();
+    let fund_tx = Transaction::new_signed_with_payer(
+        &[system_instruction::transfer(
+            &_setup.payer.pubkey(),
+            &creator.pubkey(),
+            LAMPORTS_PER_SOL,
+        )],
+        Some(&_setup.payer.pubkey()),
+        &[&_setup.payer],
+        _setup.recent_blockhashes().await,
+    );
+    let result = _setup.banks_client.process_transaction(fund_tx).await;
+    assert!(result.is_ok());
+
+    let controller_id = next_controller_id(&_setup).await;
+    let transaction = init_controller_transaction(
+        &creator,
+        &creator,
+        _setup.program_id,
+        controller_id,
+        _setup.recent_blockhashes().await,
+    );
+    let result = _setup.banks_client.process_transaction(transaction).await;
+    assert_eq!(
+        result.unwrap_err().unwrap(),
+        custom_error(ProtocolError::ControllerCreatorNotAllowed)
+    );
+
+    let transaction = set_controller_creator_transaction(
+        &_setup.payer,
+        _setup.program_id,
+        creator.pubkey(),
+        true,
+        _setup.recent_blockhashes().await,
+    );
+    let result = _setup.banks_client.process_transaction(transaction).await;
+    assert!(result.is_ok());
+
+    // a fresh blockhash keeps the retry from being rejected as a duplicate
+    let latest_blockhash = _setup.banks_client.get_latest_blockhash().await.unwrap();
+    let transaction = init_controller_transaction(
+        &creator,
+        &creator,
+        _setup.program_id,
+        controller_id,
+        latest_blockhash,
+    );
+    let result = _setup.banks_client.process_transaction(transaction).await;
+    assert!(result.is_ok());
+
+    let controller_pda = find_controller_address(&_setup.program_id, controller_id).0;
+    let account = _setup
+        .banks_client
+        .get_account(controller_pda)
+        .await
+        .unwrap()
+        .unwrap();
+    let controller = Controller::try_from_slice(&account.data).unwrap();
+    assert_eq!(controller.owner, creator.pubkey());
+
+    // revocation closes the allowlist entry
+    let transaction = set_controller_creator_transaction(
+        &_setup.payer,
+        _setup.program_id,
+        creator.pubkey(),
+        false,
+        _setup.recent_blockhashes().await,
+    );
+    let result = _setup.banks_client.process_transaction(transaction).await;
+    assert!(result.is_ok());
+
+    let controller_creator_pda =
+        find_controller_creator_address(&_setup.program_id, &creator.pubkey()).0;
+    let account = _setup
+        .banks_client
+        .get_account(controller_creator_pda)
+        .await
+        .unwrap();
+    assert!(account.is_none());
+
+    let transaction = init_controller_transaction(
+        &creator,
+        &creator,
+        _setup.program_id,
+        controller_id + 1,
+        _setup.recent_blockhashes().await,
+    );
+    let result = _setup.banks_client.process_transaction(transaction).await;
+    assert_eq!(
+        result.unwrap_err().unwrap(),
+        custom_error(ProtocolError::ControllerCreatorNotAllowed)
+    );
+}
+
+#[tokio::test]
+async fn test_creation_fees() {
+    let _setup: Setup = setup().await;
+    let manager = Keypair::new();
+    let controller_creation_fee = 1_000_000;
+    let index_creation_fee = 500_000;
+
+    let ProcessControllerGlobalConfigResult { result } =
+        process_controller_global_config(10, &_setup).await;
+    assert!(result.is_ok());
+
+    update_policy(
+        ControllerCreationPolicy::Fee,
+        controller_creation_fee,
+        index_creation_fee,
+        &_setup,
+    )
+    .await;
+
+    let treasury_pda = find_treasury_address(&_setup.program_id).0;
+    let treasury_rent = _setup.rent.minimum_balance(0);
+    assert_eq!(lamports(&_setup, treasury_pda).await, treasury_rent);
+
+    let ProcessInitControllerResult {
+        controller_id,
+        controller_pda: _,
+        result,
+    } = process_init_controller(&_setup).await;
+    assert!(result.is_ok());
+    assert_eq!(
+        lamports(&_setup, treasury_pda).await,
+        treasury_rent + controller_creation_fee
+    );
+
+    let ProcessCreateIndexResult {
+        index_id: _,
+        controller_pda: _,
+        result,
+    } = process_create_index(controller_id, manager.pubkey(), &_setup).await;
+    assert!(result.is_ok());
+    let collected = controller_creation_fee + index_creation_fee;
+    assert_eq!(
+        lamports(&_setup, treasury_pda).await,
+        treasury_rent + collected
+    );
+
+    // the rent-exempt minimum cannot be withdrawn
+    let destination = Pubkey::new_unique();
+    let transaction = withdraw_treasury_transaction(
+        &_setup.payer,
+        _setup.program_id,
+        destination,
+        collected + 1,
+        _setup.recent_blockhashes().await,
+    );
+    let result = _setup.banks_client.process_transaction(transaction).await;
+    assert_eq!(
+        result.unwrap_err().unwrap(),
+        custom_error(ProtocolError::InsufficientTreasuryBalance)
+    );
+
+    let transaction = withdraw_treasury_transaction(
+        &_setup.payer,
+        _setup.program_id,
+        destination,
+        collected,
+        _setup.recent_blockhashes().await,
+    );
+    let result = _setup.banks_client.process_transaction(transaction).await;
+    assert!(result.is_ok());
+    assert_eq!(lamports(&_setup, treasury_pda).await, treasury_rent);
+    assert_eq!(lamports(&_setup, destination).await, collected);
+}
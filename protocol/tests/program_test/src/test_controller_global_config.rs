@@ -1,12 +1,19 @@
 use crate::{
-    process_controller_global_config, setup, BanksClientResult,
-    ProcessControllerGlobalConfigResult, Setup,
+    process_controller_global_config, process_init_protocol, setup, BanksClientResult,
+    ProcessControllerGlobalConfigResult, ProcessInitProtocolResult, Setup,
 };
 use borsh::BorshDeserialize;
 use openindex::state::ControllerGlobalConfig;
 use openindex_sdk::openindex::{
-    pda::find_controller_global_config_address,
-    transaction::{init_controller_global_config_transaction, init_protocol_transaction},
+    error::ProtocolError,
+    instruction::init_controller_global_config_instruction,
+    pda::{find_controller_global_config_address, find_protocol_address},
+    transaction::init_controller_global_config_transaction,
+};
+use solana_sdk::{
+    instruction::InstructionError,
+    signature::Keypair,
+    transaction::{Transaction, TransactionError},
 };
 use {solana_program_test::tokio, solana_sdk::signature::Signer};
 
@@ -31,3 +38,43 @@ async fn test_controller_global_config() {
     assert_eq!(cg.initialized, true);
     assert_eq!(cg.max_index_components, max_index_components);
 }
+
+#[tokio::test]
+async fn test_controller_global_config_protocol_owner() {
+    let _setup: Setup = setup().await;
+    let ProcessInitProtocolResult { result } = process_init_protocol(&_setup).await;
+    assert!(result.is_ok());
+
+    let outsider = Keypair::new();
+    let protocol_pda = find_protocol_address(&_setup.program_id).0;
+    let instruction = init_controller_global_config_instruction(
+        _setup.program_id,
+        outsider.pubkey(),
+        protocol_pda,
+        find_controller_global_config_address(&_setup.program_id).0,
+        10,
+    );
+    let transaction = Transaction::new_signed_with_payer(
+        &[instruction],
+        Some(&_setup.payer.pubkey()),
+        &[&_setup.payer, &outsider],
+        _setup.recent_blockhashes().await,
+    );
+    let result = _setup.banks_client.process_transaction(transaction).await;
+    assert_eq!(
+        result.unwrap_err().unwrap(),
+        TransactionError::InstructionError(
+            0,
+            InstructionError::Custom(ProtocolError::OnlyProtocolOwner as u32)
+        )
+    );
+
+    let transaction = init_controller_global_config_transaction(
+        &_setup.payer,
+        _setup.program_id,
+        10,
+        _setup.recent_blockhashes().await,
+    );
+    let result = _setup.banks_client.process_transaction(transaction).await;
+    assert!(result.is_ok());
+}
@@ -0,0 +1,280 @@
+use borsh::BorshDeserialize;
+use openindex::state::{ControllerRoles, Index};
+use openindex_sdk::openindex::{
+    error::ProtocolError,
+    instruction::ControllerRole,
+    pda::{find_controller_roles_address, find_index_address},
+    transaction::{
+        add_index_components_transaction, create_index_transaction,
+        create_mint_acccount_transaction, grant_controller_role_transaction,
+        revoke_controller_role_transaction,
+    },
+};
+use solana_program_test::tokio;
+
+use solana_sdk::{
+    hash::Hash,
+    native_token::LAMPORTS_PER_SOL,
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+    system_instruction,
+    transaction::{Transaction, TransactionError},
+};
+
+use crate::{
+    custom_error, latest_blockhash, new_blockhash, process_controller_global_config,
+    process_init_controller, setup, ProcessControllerGlobalConfigResult,
+    ProcessInitControllerResult, Setup,
+};
+
+async fn set_role(
+    grant: bool,
+    controller_id: u64,
+    holder: Pubkey,
+    role: ControllerRole,
+    recent_blockhash: Hash,
+    _setup: &Setup,
+) -> Result<(), TransactionError> {
+    let transaction = if grant {
+        grant_controller_role_transaction(
+            &_setup.payer,
+            _setup.program_id,
+            controller_id,
+            holder,
+            role,
+            recent_blockhash,
+        )
+    } else {
+        revoke_controller_role_transaction(
+            &_setup.payer,
+            _setup.program_id,
+            controller_id,
+            holder,
+            role,
+            recent_blockhash,
+        )
+    };
+    _setup
+        .banks_client
+        .process_transaction(transaction)
+        .await
+        .map_err(|error| error.unwrap())
+}
+
+async fn create_index_as(
+    signer: &Keypair,
+    index_id: u64,
+    controller_id: u64,
+    recent_blockhash: Hash,
+    _setup: &Setup,
+) -> Result<(), TransactionError> {
+    let transaction = create_index_transaction(
+        signer,
+        signer,
+        _setup.program_id,
+        index_id,
+        controller_id,
+        signer.pubkey(),
+        recent_blockhash,
+    );
+    _setup
+        .banks_client
+        .process_transaction(transaction)
+        .await
+        .map_err(|error| error.unwrap())
+}
+
+#[tokio::test]
+async fn test_controller_roles() {
+    let _setup: Setup = setup().await;
+
+    let ProcessControllerGlobalConfigResult { result } =
+        process_controller_global_config(10, &_setup).await;
+    assert!(result.is_ok());
+
+    let ProcessInitControllerResult {
+        controller_id,
+        controller_pda,
+        result,
+    } = process_init_controller(&_setup).await;
+    assert!(result.is_ok());
+
+    let delegate = Keypair::new();
+    let fund_tx = Transaction::new_signed_with_payer(
+        &[system_instruction::transfer(
+            &_setup.payer.pubkey(),
+            &delegate.pubkey(),
+            LAMPORTS_PER_SOL,
+        )],
+        Some(&_setup.payer.pubkey()),
+        &[&_setup.payer],
+        _setup.recent_blockhashes().await,
+    );
+    let result = _setup.banks_client.process_transaction(fund_tx).await;
+    assert!(result.is_ok());
+
+    let index_id = 1;
+    let denied_blockhash = latest_blockhash(&_setup).await;
+    assert_eq!(
+        create_index_as(
+            &delegate,
+            index_id,
+            controller_id,
+            denied_blockhash,
+            &_setup
+        )
+        .await,
+        Err(custom_error(ProtocolError::MissingControllerRole))
+    );
+
+    let grant_blockhash = latest_blockhash(&_setup).await;
+    let result = set_role(
+        true,
+        controller_id,
+        delegate.pubkey(),
+        ControllerRole::IndexCreator,
+        grant_blockhash,
+        &_setup,
+    )
+    .await;
+    assert!(result.is_ok());
+    assert_eq!(
+        set_role(
+            true,
+            controller_id,
+            delegate.pubkey(),
+            ControllerRole::IndexCreator,
+            new_blockhash(&_setup, &grant_blockhash).await,
+            &_setup
+        )
+        .await,
+        Err(custom_error(ProtocolError::ControllerRoleAlreadyGranted))
+    );
+
+    let allowed_blockhash = new_blockhash(&_setup, &denied_blockhash).await;
+    assert!(create_index_as(
+        &delegate,
+        index_id,
+        controller_id,
+        allowed_blockhash,
+        &_setup
+    )
+    .await
+    .is_ok());
+
+    // the index still belongs to the controller owner
+    let index_pda = find_index_address(&_setup.program_id, &controller_pda, index_id).0;
+    let account = _setup
+        .banks_client
+        .get_account(index_pda)
+        .await
+        .unwrap()
+        .unwrap();
+    let index = Index::try_from_slice(&account.data).unwrap();
+    assert_eq!(index.owner, _setup.payer.pubkey());
+    assert_eq!(index.manager, delegate.pubkey());
+
+    // index creation does not imply component management
+    let mint = Keypair::new();
+    let transaction = create_mint_acccount_transaction(
+        &_setup.payer,
+        &mint,
+        _setup.recent_blockhashes().await,
+        &_setup.rent,
+    );
+    let result = _setup.banks_client.process_transaction(transaction).await;
+    assert!(result.is_ok());
+
+    let add_components_as_delegate = |recent_blockhash| {
+        add_index_components_transaction(
+            &delegate,
+            &delegate,
+            _setup.program_id,
+            index_id,
+            controller_id,
+            recent_blockhash,
+            vec![mint.pubkey()],
+            vec![10],
+        )
+    };
+    let denied_blockhash = latest_blockhash(&_setup).await;
+    let transaction = add_components_as_delegate(denied_blockhash);
+    let result = _setup.banks_client.process_transaction(transaction).await;
+    assert_eq!(
+        result.unwrap_err().unwrap(),
+        custom_error(ProtocolError::MissingControllerRole)
+    );
+
+    let result = set_role(
+        true,
+        controller_id,
+        delegate.pubkey(),
+        ControllerRole::ComponentManager,
+        latest_blockhash(&_setup).await,
+        &_setup,
+    )
+    .await;
+    assert!(result.is_ok());
+
+    let transaction = add_components_as_delegate(new_blockhash(&_setup, &denied_blockhash).await);
+    let result = _setup.banks_client.process_transaction(transaction).await;
+    assert!(result.is_ok());
+
+    let controller_roles_pda = find_controller_roles_address(&_setup.program_id, &controller_pda).0;
+    let account = _setup
+        .banks_client
+        .get_account(controller_roles_pda)
+        .await
+        .unwrap()
+        .unwrap();
+    let controller_roles = ControllerRoles::try_from_slice(&account.data).unwrap();
+    assert_eq!(controller_roles.assignments.len(), 2);
+    let registry_len = account.data.len();
+
+    let revoke_blockhash = latest_blockhash(&_setup).await;
+    let result = set_role(
+        false,
+        controller_id,
+        delegate.pubkey(),
+        ControllerRole::IndexCreator,
+        revoke_blockhash,
+        &_setup,
+    )
+    .await;
+    assert!(result.is_ok());
+    assert_eq!(
+        set_role(
+            false,
+            controller_id,
+            delegate.pubkey(),
+            ControllerRole::IndexCreator,
+            new_blockhash(&_setup, &revoke_blockhash).await,
+            &_setup
+        )
+        .await,
+        Err(custom_error(ProtocolError::ControllerRoleNotGranted))
+    );
+
+    let account = _setup
+        .banks_client
+        .get_account(controller_roles_pda)
+        .await
+        .unwrap()
+        .unwrap();
+    let controller_roles = ControllerRoles::try_from_slice(&account.data).unwrap();
+    assert!(!controller_roles.has_role(&delegate.pubkey(), ControllerRole::IndexCreator));
+    assert!(controller_roles.has_role(&delegate.pubkey(), ControllerRole::ComponentManager));
+    assert_eq!(account.data.len(), registry_len - 33);
+
+    assert_eq!(
+        create_index_as(
+            &delegate,
+            index_id + 1,
+            controller_id,
+            latest_blockhash(&_setup).await,
+            &_setup
+        )
+        .await,
+        Err(custom_error(ProtocolError::MissingControllerRole))
+    );
+}
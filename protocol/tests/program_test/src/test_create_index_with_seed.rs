@@ -0,0 +1,291 @@
+use borsh::BorshDeserialize;
+use openindex::state::{Controller, Index};
+use openindex_sdk::openindex::{
+    error::ProtocolError,
+    instruction::{
+        add_index_components_instruction_with_dynamic_accounts,
+        mint_instruction_with_dynamic_accounts,
+    },
+    pda::{
+        find_controller_address, find_controller_config_address,
+        find_controller_global_config_address, find_index_addresses, IndexSeed,
+    },
+    transaction::{
+        create_index_with_seed_transaction, create_mint_acccount_transaction,
+        create_token_account_transaction, mint_to_transaction,
+    },
+};
+use solana_program_test::tokio;
+use solana_sdk::{
+    instruction::InstructionError,
+    program_pack::Pack,
+    signature::{Keypair, Signer},
+    transaction::{Transaction, TransactionError},
+};
+use spl_associated_token_account::get_associated_token_address_with_program_id;
+use spl_token::state::Account as TokenAccount;
+
+use crate::{
+    process_controller_global_config, process_init_controller, setup,
+    ProcessControllerGlobalConfigResult, ProcessInitControllerResult, Setup,
+};
+
+async fn get_index(_setup: &Setup, index_seed: &IndexSeed, controller_id: u64) -> Index {
+    let controller_pda = find_controller_address(&_setup.program_id, controller_id).0;
+    let index_pda = find_index_addresses(&_setup.program_id, &controller_pda, index_seed).index;
+    let account = _setup
+        .banks_client
+        .get_account(index_pda)
+        .await
+        .unwrap()
+        .unwrap();
+    Index::try_from_slice(&account.data).unwrap()
+}
+
+#[tokio::test]
+async fn test_create_index_with_seed() {
+    let _setup: Setup = setup().await;
+    let manager = Keypair::new();
+
+    let ProcessControllerGlobalConfigResult { result } =
+        process_controller_global_config(10, &_setup).await;
+    assert!(result.is_ok());
+
+    let ProcessInitControllerResult {
+        controller_id,
+        controller_pda,
+        result,
+    } = process_init_controller(&_setup).await;
+    assert!(result.is_ok());
+
+    // both transactions are built before either lands, as two concurrent
+    // creators would; the seeds keep them from deriving the same addresses
+    let seeds = [
+        IndexSeed::from_symbol("OIDX"),
+        IndexSeed::from_symbol("OIDX2"),
+    ];
+    let recent_blockhashes = _setup.recent_blockhashes().await;
+    let transactions: Vec<_> = seeds
+        .iter()
+        .map(|index_seed| {
+            let IndexSeed::Seed(seed) = index_seed else {
+                unreachable!()
+            };
+            create_index_with_seed_transaction(
+                &_setup.payer,
+                &_setup.payer,
+                _setup.program_id,
+                controller_id,
+                manager.pubkey(),
+                *seed,
+                recent_blockhashes,
+            )
+        })
+        .collect();
+    for transaction in transactions {
+        let result = _setup.banks_client.process_transaction(transaction).await;
+        assert!(result.is_ok());
+    }
+
+    // sequential ids are still assigned for display
+    for (expected_id, index_seed) in (1..).zip(seeds.iter()) {
+        let index = get_index(&_setup, index_seed, controller_id).await;
+        assert_eq!(index.id, expected_id);
+        assert!(index.is_seeded());
+        assert_eq!(index.address_seed(), index_seed.to_bytes());
+        assert_eq!(index.manager, manager.pubkey());
+    }
+
+    let account = _setup
+        .banks_client
+        .get_account(controller_pda)
+        .await
+        .unwrap()
+        .unwrap();
+    let controller = Controller::try_from_slice(&account.data).unwrap();
+    assert_eq!(controller.get_next_index_id(), 3);
+
+    // a seed can only be used once per controller
+    let latest_blockhash = _setup.banks_client.get_latest_blockhash().await.unwrap();
+    let IndexSeed::Seed(seed) = seeds[0] else {
+        unreachable!()
+    };
+    let transaction = create_index_with_seed_transaction(
+        &_setup.payer,
+        &_setup.payer,
+        _setup.program_id,
+        controller_id,
+        manager.pubkey(),
+        seed,
+        latest_blockhash,
+    );
+    let result = _setup.banks_client.process_transaction(transaction).await;
+    assert_eq!(
+        result.unwrap_err().unwrap(),
+        TransactionError::InstructionError(0, InstructionError::AccountAlreadyInitialized)
+    );
+
+    let transaction = create_index_with_seed_transaction(
+        &_setup.payer,
+        &_setup.payer,
+        _setup.program_id,
+        controller_id,
+        manager.pubkey(),
+        [0; 32],
+        latest_blockhash,
+    );
+    let result = _setup.banks_client.process_transaction(transaction).await;
+    assert_eq!(
+        result.unwrap_err().unwrap(),
+        TransactionError::InstructionError(
+            0,
+            InstructionError::Custom(ProtocolError::InvalidIndexSeed as u32)
+        )
+    );
+}
+
+#[tokio::test]
+async fn test_mint_seeded_index() {
+    let _setup: Setup = setup().await;
+    let payer = &_setup.payer;
+    let program_id = _setup.program_id;
+
+    let ProcessControllerGlobalConfigResult { result } =
+        process_controller_global_config(10, &_setup).await;
+    assert!(result.is_ok());
+
+    let ProcessInitControllerResult {
+        controller_id,
+        controller_pda,
+        result,
+    } = process_init_controller(&_setup).await;
+    assert!(result.is_ok());
+
+    let index_seed = IndexSeed::from_symbol("OIDX");
+    let IndexSeed::Seed(seed) = index_seed else {
+        unreachable!()
+    };
+    let transaction = create_index_with_seed_transaction(
+        payer,
+        payer,
+        program_id,
+        controller_id,
+        payer.pubkey(),
+        seed,
+        _setup.recent_blockhashes().await,
+    );
+    let result = _setup.banks_client.process_transaction(transaction).await;
+    assert!(result.is_ok());
+
+    let index_addresses = find_index_addresses(&program_id, &controller_pda, &index_seed);
+    let index_id = get_index(&_setup, &index_seed, controller_id).await.id;
+
+    let component_mint = Keypair::new();
+    let transaction = create_mint_acccount_transaction(
+        payer,
+        &component_mint,
+        _setup.recent_blockhashes().await,
+        &_setup.rent,
+    );
+    let result = _setup.banks_client.process_transaction(transaction).await;
+    assert!(result.is_ok());
+
+    let units = 10;
+    let instruction = add_index_components_instruction_with_dynamic_accounts(
+        program_id,
+        payer.pubkey(),
+        payer.pubkey(),
+        index_addresses.index,
+        index_addresses.index_mints_data,
+        controller_pda,
+        find_controller_global_config_address(&program_id).0,
+        find_controller_config_address(&program_id, &controller_pda).0,
+        vec![component_mint.pubkey()],
+        vec![units],
+    );
+    let transaction = Transaction::new_signed_with_payer(
+        &[instruction],
+        Some(&payer.pubkey()),
+        &[payer],
+        _setup.recent_blockhashes().await,
+    );
+    let result = _setup.banks_client.process_transaction(transaction).await;
+    assert!(result.is_ok());
+
+    for mint in [component_mint.pubkey(), index_addresses.mint] {
+        let transaction = create_token_account_transaction(
+            payer,
+            payer.pubkey(),
+            payer.pubkey(),
+            mint,
+            _setup.recent_blockhashes().await,
+        );
+        let result = _setup.banks_client.process_transaction(transaction).await;
+        assert!(result.is_ok());
+    }
+
+    let component_token_account = get_associated_token_address_with_program_id(
+        &payer.pubkey(),
+        &component_mint.pubkey(),
+        &spl_token::ID,
+    );
+    let transaction = mint_to_transaction(
+        payer,
+        1_000,
+        component_mint.pubkey(),
+        component_token_account,
+        _setup.recent_blockhashes().await,
+    )
+    .unwrap();
+    let result = _setup.banks_client.process_transaction(transaction).await;
+    assert!(result.is_ok());
+
+    let index_token_account = get_associated_token_address_with_program_id(
+        &payer.pubkey(),
+        &index_addresses.mint,
+        &spl_token::ID,
+    );
+    let amount = 5;
+    let instruction = mint_instruction_with_dynamic_accounts(
+        payer.pubkey(),
+        program_id,
+        controller_pda,
+        index_addresses.mint,
+        index_addresses.mint_authority,
+        index_addresses.index,
+        index_addresses.index_mints_data,
+        index_token_account,
+        spl_token::ID,
+        vec![component_mint.pubkey()],
+        vec![component_token_account],
+        index_id,
+        amount,
+    );
+    let transaction = Transaction::new_signed_with_payer(
+        &[instruction],
+        Some(&payer.pubkey()),
+        &[payer],
+        _setup.recent_blockhashes().await,
+    );
+    let result = _setup.banks_client.process_transaction(transaction).await;
+    assert!(result.is_ok());
+
+    let account = _setup
+        .banks_client
+        .get_account(index_token_account)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(TokenAccount::unpack(&account.data).unwrap().amount, amount);
+
+    let account = _setup
+        .banks_client
+        .get_account(component_token_account)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(
+        TokenAccount::unpack(&account.data).unwrap().amount,
+        1_000 - amount * units
+    );
+}
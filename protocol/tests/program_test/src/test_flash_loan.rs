@@ -0,0 +1,246 @@
+use openindex::state::Index;
+use openindex_sdk::openindex::{
+    error::ProtocolError,
+    instruction::flash_borrow_instruction,
+    pda::{find_component_vault_address, find_controller_address, find_index_address},
+    transaction::{
+        create_token_account_transaction, flash_loan_transaction,
+        set_controller_config_transaction, set_flash_loan_config_transaction,
+    },
+};
+use solana_program_test::tokio;
+use solana_sdk::{
+    instruction::InstructionError,
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+    transaction::{Transaction, TransactionError},
+};
+use spl_associated_token_account::get_associated_token_address;
+use spl_token::{error::TokenError, instruction::transfer};
+
+use crate::{
+    custom_error, latest_blockhash, process, setup, setup_minted_index, token_balance,
+    ProcessMintResult, Setup,
+};
+
+fn vault_ata(program_id: &Pubkey, minted: &ProcessMintResult, mint: &Pubkey) -> Pubkey {
+    let controller_pda = find_controller_address(program_id, minted.controller_id).0;
+    let index_pda = find_index_address(program_id, &controller_pda, minted.index_id).0;
+    let vault_pda = find_component_vault_address(program_id, &index_pda, mint).0;
+    get_associated_token_address(&vault_pda, mint)
+}
+
+#[tokio::test]
+async fn test_flash_loan_requires_opt_in() {
+    let _setup: Setup = setup().await;
+    let payer = &_setup.payer;
+    let (minted, mints) = setup_minted_index(10, &_setup).await;
+
+    let transaction = flash_loan_transaction(
+        10,
+        payer,
+        _setup.program_id,
+        minted.index_id,
+        minted.controller_id,
+        mints[1],
+        minted.token_accounts[1],
+        latest_blockhash(&_setup).await,
+        vec![],
+    );
+    assert_eq!(
+        process(transaction, &_setup).await.unwrap_err(),
+        custom_error(ProtocolError::FlashLoansDisabled)
+    );
+}
+
+#[tokio::test]
+async fn test_flash_loan_repaid_with_fee() {
+    let _setup: Setup = setup().await;
+    let program_id = _setup.program_id;
+    let payer = &_setup.payer;
+    let (minted, mints) = setup_minted_index(10, &_setup).await;
+
+    // 1% flash loan fee
+    let transaction = set_flash_loan_config_transaction(
+        payer,
+        program_id,
+        minted.index_id,
+        minted.controller_id,
+        true,
+        100,
+        latest_blockhash(&_setup).await,
+    );
+    assert!(process(transaction, &_setup).await.is_ok());
+
+    let controller_pda = find_controller_address(&program_id, minted.controller_id).0;
+    let index_pda = find_index_address(&program_id, &controller_pda, minted.index_id).0;
+    let account = _setup
+        .banks_client
+        .get_account(index_pda)
+        .await
+        .unwrap()
+        .unwrap();
+    let index = Index::unpack(&account.data).unwrap();
+    assert!(index.flash_loans_enabled);
+    assert_eq!(index.flash_loan_fee_bps, 100);
+
+    let transaction = flash_loan_transaction(
+        20,
+        payer,
+        program_id,
+        minted.index_id,
+        minted.controller_id,
+        mints[1],
+        minted.token_accounts[1],
+        latest_blockhash(&_setup).await,
+        vec![],
+    );
+    assert!(process(transaction, &_setup).await.is_ok());
+
+    // the fee rounds up to one token and accrues to the vault
+    assert_eq!(
+        token_balance(vault_ata(&program_id, &minted, &mints[1]), &_setup).await,
+        21
+    );
+    assert_eq!(token_balance(minted.token_accounts[1], &_setup).await, 79);
+}
+
+#[tokio::test]
+async fn test_flash_loan_not_repaid() {
+    let _setup: Setup = setup().await;
+    let program_id = _setup.program_id;
+    let payer = &_setup.payer;
+    let (minted, mints) = setup_minted_index(10, &_setup).await;
+
+    let transaction = set_flash_loan_config_transaction(
+        payer,
+        program_id,
+        minted.index_id,
+        minted.controller_id,
+        true,
+        0,
+        latest_blockhash(&_setup).await,
+    );
+    assert!(process(transaction, &_setup).await.is_ok());
+
+    // a borrow without a later repay is refused up front
+    let controller_pda = find_controller_address(&program_id, minted.controller_id).0;
+    let instruction = flash_borrow_instruction(
+        payer.pubkey(),
+        program_id,
+        controller_pda,
+        mints[1],
+        minted.token_accounts[1],
+        minted.index_id,
+        20,
+    );
+    let transaction = Transaction::new_signed_with_payer(
+        &[instruction],
+        Some(&payer.pubkey()),
+        &[payer],
+        latest_blockhash(&_setup).await,
+    );
+    assert_eq!(
+        process(transaction, &_setup).await.unwrap_err(),
+        custom_error(ProtocolError::MissingFlashRepay)
+    );
+
+    // moving the borrowed tokens away makes the repay, and so the whole
+    // transaction, fail
+    let receiver = Keypair::new().pubkey();
+    let transaction = create_token_account_transaction(
+        payer,
+        payer.pubkey(),
+        receiver,
+        mints[1],
+        latest_blockhash(&_setup).await,
+    );
+    assert!(process(transaction, &_setup).await.is_ok());
+    let receiver_token_account = get_associated_token_address(&receiver, &mints[1]);
+
+    let transaction = flash_loan_transaction(
+        20,
+        payer,
+        program_id,
+        minted.index_id,
+        minted.controller_id,
+        mints[1],
+        minted.token_accounts[1],
+        latest_blockhash(&_setup).await,
+        vec![transfer(
+            &spl_token::ID,
+            &minted.token_accounts[1],
+            &receiver_token_account,
+            &payer.pubkey(),
+            &[],
+            100,
+        )
+        .unwrap()],
+    );
+    assert_eq!(
+        process(transaction, &_setup).await.unwrap_err(),
+        TransactionError::InstructionError(
+            2,
+            InstructionError::Custom(TokenError::InsufficientFunds as u32)
+        )
+    );
+    assert_eq!(
+        token_balance(vault_ata(&program_id, &minted, &mints[1]), &_setup).await,
+        20
+    );
+    assert_eq!(token_balance(minted.token_accounts[1], &_setup).await, 80);
+}
+
+#[tokio::test]
+async fn test_flash_loan_fee_capped_by_controller() {
+    let _setup: Setup = setup().await;
+    let program_id = _setup.program_id;
+    let payer = &_setup.payer;
+    let (minted, _) = setup_minted_index(10, &_setup).await;
+
+    let transaction = set_controller_config_transaction(
+        payer,
+        program_id,
+        minted.controller_id,
+        None,
+        0,
+        50,
+        None,
+        vec![],
+        false,
+        latest_blockhash(&_setup).await,
+    );
+    assert!(process(transaction, &_setup).await.is_ok());
+
+    let set_fee = |fee_bps, recent_blockhash| {
+        set_flash_loan_config_transaction(
+            payer,
+            program_id,
+            minted.index_id,
+            minted.controller_id,
+            true,
+            fee_bps,
+            recent_blockhash,
+        )
+    };
+    assert_eq!(
+        process(set_fee(100, latest_blockhash(&_setup).await), &_setup).await,
+        Err(custom_error(ProtocolError::FeeAboveControllerCap))
+    );
+    assert!(
+        process(set_fee(50, latest_blockhash(&_setup).await), &_setup)
+            .await
+            .is_ok()
+    );
+
+    let controller_pda = find_controller_address(&program_id, minted.controller_id).0;
+    let index_pda = find_index_address(&program_id, &controller_pda, minted.index_id).0;
+    let account = _setup
+        .banks_client
+        .get_account(index_pda)
+        .await
+        .unwrap()
+        .unwrap();
+    let index = Index::unpack(&account.data).unwrap();
+    assert_eq!(index.flash_loan_fee_bps, 50);
+}
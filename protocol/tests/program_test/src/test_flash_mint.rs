@@ -0,0 +1,172 @@
+use openindex::state::ControllerGlobalConfig;
+use openindex_sdk::openindex::{
+    error::ProtocolError,
+    instruction::flash_mint_instruction,
+    pda::{
+        find_component_vault_address, find_controller_address,
+        find_controller_global_config_address, find_flash_mint_address, find_index_address,
+    },
+    transaction::{
+        flash_mint_transaction, set_controller_config_transaction,
+        update_controller_global_config_transaction,
+    },
+};
+use solana_program_test::tokio;
+use solana_sdk::{signature::Signer, transaction::Transaction};
+use spl_associated_token_account::get_associated_token_address;
+
+use crate::{
+    custom_error, latest_blockhash, process, setup, setup_minted_index, token_balance, Setup,
+};
+
+#[tokio::test]
+async fn test_flash_mint_settled_with_fee() {
+    let _setup: Setup = setup().await;
+    let program_id = _setup.program_id;
+    let payer = &_setup.payer;
+    let (minted, mints) = setup_minted_index(10, &_setup).await;
+
+    // 1% flash fee
+    let transaction = update_controller_global_config_transaction(
+        payer,
+        program_id,
+        None,
+        false,
+        None,
+        None,
+        None,
+        Some(100),
+        None,
+        latest_blockhash(&_setup).await,
+    );
+    assert!(process(transaction, &_setup).await.is_ok());
+
+    let account = _setup
+        .banks_client
+        .get_account(find_controller_global_config_address(&program_id).0)
+        .await
+        .unwrap()
+        .unwrap();
+    let controller_global_config = ControllerGlobalConfig::unpack(&account.data).unwrap();
+    assert_eq!(controller_global_config.flash_mint_fee_bps, 100);
+
+    let transaction = flash_mint_transaction(
+        10,
+        payer,
+        program_id,
+        minted.index_id,
+        minted.controller_id,
+        minted.token_account,
+        latest_blockhash(&_setup).await,
+        mints.clone(),
+        minted.token_accounts.clone(),
+        vec![],
+    );
+    assert!(process(transaction, &_setup).await.is_ok());
+
+    // the fee rounds up to one token per component
+    assert_eq!(token_balance(minted.token_account, &_setup).await, 20);
+    assert_eq!(token_balance(minted.token_accounts[0], &_setup).await, 79);
+    assert_eq!(token_balance(minted.token_accounts[1], &_setup).await, 59);
+
+    let controller_pda = find_controller_address(&program_id, minted.controller_id).0;
+    let index_pda = find_index_address(&program_id, &controller_pda, minted.index_id).0;
+    for (mint, expected) in mints.iter().zip([21, 41]) {
+        let vault_pda = find_component_vault_address(&program_id, &index_pda, mint).0;
+        let vault_ata = get_associated_token_address(&vault_pda, mint);
+        assert_eq!(token_balance(vault_ata, &_setup).await, expected);
+    }
+
+    // the obligation is closed within the transaction
+    let flash_mint_pda = find_flash_mint_address(&program_id, &index_pda, &payer.pubkey()).0;
+    let account = _setup
+        .banks_client
+        .get_account(flash_mint_pda)
+        .await
+        .unwrap();
+    assert!(account.is_none());
+}
+
+#[tokio::test]
+async fn test_flash_mint_requires_settlement() {
+    let _setup: Setup = setup().await;
+    let program_id = _setup.program_id;
+    let payer = &_setup.payer;
+    let (minted, _) = setup_minted_index(10, &_setup).await;
+
+    let controller_pda = find_controller_address(&program_id, minted.controller_id).0;
+    let instruction = flash_mint_instruction(
+        payer.pubkey(),
+        program_id,
+        controller_pda,
+        minted.token_account,
+        minted.index_id,
+        10,
+    );
+    let transaction = Transaction::new_signed_with_payer(
+        &[instruction],
+        Some(&payer.pubkey()),
+        &[payer],
+        latest_blockhash(&_setup).await,
+    );
+    assert_eq!(
+        process(transaction, &_setup).await.unwrap_err(),
+        custom_error(ProtocolError::MissingFlashMintSettlement)
+    );
+    assert_eq!(token_balance(minted.token_account, &_setup).await, 10);
+}
+
+#[tokio::test]
+async fn test_flash_mint_fee_capped_by_controller() {
+    let _setup: Setup = setup().await;
+    let program_id = _setup.program_id;
+    let payer = &_setup.payer;
+    let (minted, mints) = setup_minted_index(10, &_setup).await;
+
+    let transaction = update_controller_global_config_transaction(
+        payer,
+        program_id,
+        None,
+        false,
+        None,
+        None,
+        None,
+        Some(100),
+        None,
+        latest_blockhash(&_setup).await,
+    );
+    assert!(process(transaction, &_setup).await.is_ok());
+
+    // the controller waives the flash mint fee for its indexes
+    let transaction = set_controller_config_transaction(
+        payer,
+        program_id,
+        minted.controller_id,
+        None,
+        0,
+        0,
+        None,
+        vec![],
+        false,
+        latest_blockhash(&_setup).await,
+    );
+    assert!(process(transaction, &_setup).await.is_ok());
+
+    let transaction = flash_mint_transaction(
+        10,
+        payer,
+        program_id,
+        minted.index_id,
+        minted.controller_id,
+        minted.token_account,
+        latest_blockhash(&_setup).await,
+        mints.clone(),
+        minted.token_accounts.clone(),
+        vec![],
+    );
+    assert!(process(transaction, &_setup).await.is_ok());
+
+    assert_eq!(token_balance(minted.token_account, &_setup).await, 20);
+    assert_eq!(token_balance(minted.token_accounts[0], &_setup).await, 80);
+    assert_eq!(token_balance(minted.token_accounts[1], &_setup).await, 60);
+}
@@ -0,0 +1,267 @@
+use openindex_sdk::openindex::{
+    error::ProtocolError,
+    instruction::{create_index_instruction, without_freeze_authority, ControllerRole},
+    pda::{
+        find_controller_address, find_controller_config_address,
+        find_controller_global_config_address, find_index_address, find_index_mint_address,
+    },
+    transaction::{
+        create_token_account_transaction, freeze_holder_transaction,
+        grant_controller_role_transaction, thaw_holder_transaction,
+    },
+};
+use solana_program_test::tokio;
+use solana_sdk::{
+    instruction::InstructionError,
+    native_token::LAMPORTS_PER_SOL,
+    program_pack::Pack,
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+    system_instruction,
+    transaction::{Transaction, TransactionError},
+};
+use spl_associated_token_account::get_associated_token_address;
+use spl_token::{
+    error::TokenError,
+    instruction::transfer,
+    state::{Account, AccountState, Mint},
+};
+
+use crate::{
+    custom_error, latest_blockhash, process, process_controller_global_config,
+    process_init_controller, setup, setup_minted_index, ProcessControllerGlobalConfigResult,
+    ProcessInitControllerResult, Setup,
+};
+
+async fn token_state(token_account: Pubkey, _setup: &Setup) -> AccountState {
+    let account = _setup
+        .banks_client
+        .get_account(token_account)
+        .await
+        .unwrap()
+        .unwrap();
+    Account::unpack(&account.data).unwrap().state
+}
+
+/// Creates and funds a wallet that is not the owner of anything.
+async fn funded_wallet(_setup: &Setup) -> Keypair {
+    let wallet = Keypair::new();
+    let transaction = Transaction::new_signed_with_payer(
+        &[system_instruction::transfer(
+            &_setup.payer.pubkey(),
+            &wallet.pubkey(),
+            LAMPORTS_PER_SOL,
+        )],
+        Some(&_setup.payer.pubkey()),
+        &[&_setup.payer],
+        latest_blockhash(_setup).await,
+    );
+    assert!(process(transaction, _setup).await.is_ok());
+    wallet
+}
+
+#[tokio::test]
+async fn test_owner_freezes_and_thaws_holder() {
+    let _setup: Setup = setup().await;
+    let program_id = _setup.program_id;
+    let payer = &_setup.payer;
+    let (minted, _) = setup_minted_index(10, &_setup).await;
+
+    let controller_pda = find_controller_address(&program_id, minted.controller_id).0;
+    let index_mint = find_index_mint_address(&program_id, &controller_pda, minted.index_id).0;
+    let receiver = Keypair::new().pubkey();
+    let transaction = create_token_account_transaction(
+        payer,
+        payer.pubkey(),
+        receiver,
+        index_mint,
+        latest_blockhash(&_setup).await,
+    );
+    assert!(process(transaction, &_setup).await.is_ok());
+    let receiver_token_account = get_associated_token_address(&receiver, &index_mint);
+
+    let transaction = freeze_holder_transaction(
+        payer,
+        program_id,
+        minted.index_id,
+        minted.controller_id,
+        minted.token_account,
+        latest_blockhash(&_setup).await,
+    );
+    assert!(process(transaction, &_setup).await.is_ok());
+    assert_eq!(
+        token_state(minted.token_account, &_setup).await,
+        AccountState::Frozen
+    );
+
+    let transfer_instruction = transfer(
+        &spl_token::ID,
+        &minted.token_account,
+        &receiver_token_account,
+        &payer.pubkey(),
+        &[],
+        1,
+    )
+    .unwrap();
+    let transaction = Transaction::new_signed_with_payer(
+        std::slice::from_ref(&transfer_instruction),
+        Some(&payer.pubkey()),
+        &[payer],
+        latest_blockhash(&_setup).await,
+    );
+    assert_eq!(
+        process(transaction, &_setup).await.unwrap_err(),
+        TransactionError::InstructionError(
+            0,
+            InstructionError::Custom(TokenError::AccountFrozen as u32)
+        )
+    );
+
+    let transaction = thaw_holder_transaction(
+        payer,
+        program_id,
+        minted.index_id,
+        minted.controller_id,
+        minted.token_account,
+        latest_blockhash(&_setup).await,
+    );
+    assert!(process(transaction, &_setup).await.is_ok());
+    assert_eq!(
+        token_state(minted.token_account, &_setup).await,
+        AccountState::Initialized
+    );
+
+    let transaction = Transaction::new_signed_with_payer(
+        &[transfer_instruction],
+        Some(&payer.pubkey()),
+        &[payer],
+        latest_blockhash(&_setup).await,
+    );
+    assert!(process(transaction, &_setup).await.is_ok());
+}
+
+#[tokio::test]
+async fn test_compliance_officer_freezes_holder() {
+    let _setup: Setup = setup().await;
+    let program_id = _setup.program_id;
+    let (minted, _) = setup_minted_index(10, &_setup).await;
+    let officer = funded_wallet(&_setup).await;
+
+    let transaction = freeze_holder_transaction(
+        &officer,
+        program_id,
+        minted.index_id,
+        minted.controller_id,
+        minted.token_account,
+        latest_blockhash(&_setup).await,
+    );
+    assert_eq!(
+        process(transaction, &_setup).await.unwrap_err(),
+        custom_error(ProtocolError::MissingControllerRole)
+    );
+
+    let transaction = grant_controller_role_transaction(
+        &_setup.payer,
+        program_id,
+        minted.controller_id,
+        officer.pubkey(),
+        ControllerRole::ComplianceOfficer,
+        latest_blockhash(&_setup).await,
+    );
+    assert!(process(transaction, &_setup).await.is_ok());
+
+    let transaction = freeze_holder_transaction(
+        &officer,
+        program_id,
+        minted.index_id,
+        minted.controller_id,
+        minted.token_account,
+        latest_blockhash(&_setup).await,
+    );
+    assert!(process(transaction, &_setup).await.is_ok());
+    assert_eq!(
+        token_state(minted.token_account, &_setup).await,
+        AccountState::Frozen
+    );
+
+    let transaction = thaw_holder_transaction(
+        &officer,
+        program_id,
+        minted.index_id,
+        minted.controller_id,
+        minted.token_account,
+        latest_blockhash(&_setup).await,
+    );
+    assert!(process(transaction, &_setup).await.is_ok());
+    assert_eq!(
+        token_state(minted.token_account, &_setup).await,
+        AccountState::Initialized
+    );
+}
+
+#[tokio::test]
+async fn test_permissionless_index_cannot_freeze() {
+    let _setup: Setup = setup().await;
+    let program_id = _setup.program_id;
+    let payer = &_setup.payer;
+
+    let ProcessControllerGlobalConfigResult { result } =
+        process_controller_global_config(10, &_setup).await;
+    assert!(result.is_ok());
+    let ProcessInitControllerResult { controller_id, .. } = process_init_controller(&_setup).await;
+
+    let controller_pda = find_controller_address(&program_id, controller_id).0;
+    let index_id = 1;
+    let index_mint = find_index_mint_address(&program_id, &controller_pda, index_id).0;
+    let instruction = without_freeze_authority(create_index_instruction(
+        program_id,
+        payer.pubkey(),
+        payer.pubkey(),
+        payer.pubkey(),
+        find_index_address(&program_id, &controller_pda, index_id).0,
+        index_mint,
+        controller_pda,
+        find_controller_global_config_address(&program_id).0,
+        find_controller_config_address(&program_id, &controller_pda).0,
+    ));
+    let transaction = Transaction::new_signed_with_payer(
+        &[instruction],
+        Some(&payer.pubkey()),
+        &[payer],
+        latest_blockhash(&_setup).await,
+    );
+    assert!(process(transaction, &_setup).await.is_ok());
+
+    let account = _setup
+        .banks_client
+        .get_account(index_mint)
+        .await
+        .unwrap()
+        .unwrap();
+    assert!(Mint::unpack(&account.data)
+        .unwrap()
+        .freeze_authority
+        .is_none());
+
+    let transaction = create_token_account_transaction(
+        payer,
+        payer.pubkey(),
+        payer.pubkey(),
+        index_mint,
+        latest_blockhash(&_setup).await,
+    );
+    assert!(process(transaction, &_setup).await.is_ok());
+
+    let transaction = freeze_holder_transaction(
+        payer,
+        program_id,
+        index_id,
+        controller_id,
+        get_associated_token_address(&payer.pubkey(), &index_mint),
+        latest_blockhash(&_setup).await,
+    );
+    assert_eq!(
+        process(transaction, &_setup).await.unwrap_err(),
+        custom_error(ProtocolError::IndexNotFreezable)
+    );
+}
@@ -0,0 +1,151 @@
+use borsh::BorshDeserialize;
+use openindex::state::{Controller, ControllerCounter, Index, Protocol};
+use openindex_sdk::openindex::{
+    instruction::create_index_instruction,
+    pda::{
+        controller_counter_shard, find_controller_config_address, find_controller_counter_address,
+        find_controller_global_config_address, find_creator_controller_address, find_index_address,
+        find_index_mint_address, find_protocol_address,
+    },
+    transaction::init_controller_with_nonce_transaction,
+};
+use solana_program_test::tokio;
+use solana_sdk::{
+    instruction::InstructionError,
+    signature::Signer,
+    transaction::{Transaction, TransactionError},
+};
+
+use crate::{process_controller_global_config, setup, ProcessControllerGlobalConfigResult, Setup};
+
+async fn next_controller_id(_setup: &Setup) -> u64 {
+    let protocol_pda = find_protocol_address(&_setup.program_id).0;
+    let account = _setup
+        .banks_client
+        .get_account(protocol_pda)
+        .await
+        .unwrap()
+        .unwrap();
+    Protocol::try_from_slice(&account.data)
+        .unwrap()
+        .get_next_controller_id()
+}
+
+#[tokio::test]
+async fn test_init_controller_with_nonce() {
+    let _setup: Setup = setup().await;
+
+    let ProcessControllerGlobalConfigResult { result } =
+        process_controller_global_config(10, &_setup).await;
+    assert!(result.is_ok());
+
+    let next_controller_id_before = next_controller_id(&_setup).await;
+
+    for nonce in 0..2 {
+        let transaction = init_controller_with_nonce_transaction(
+            &_setup.payer,
+            &_setup.payer,
+            _setup.program_id,
+            nonce,
+            _setup.recent_blockhashes().await,
+        );
+        let result = _setup.banks_client.process_transaction(transaction).await;
+        assert!(result.is_ok());
+
+        let controller_pda =
+            find_creator_controller_address(&_setup.program_id, &_setup.payer.pubkey(), nonce).0;
+        let account = _setup
+            .banks_client
+            .get_account(controller_pda)
+            .await
+            .unwrap()
+            .unwrap();
+        let controller = Controller::try_from_slice(&account.data).unwrap();
+        assert_eq!(controller.owner, _setup.payer.pubkey());
+        assert_eq!(controller.id, 0);
+    }
+
+    // the protocol account is never written
+    assert_eq!(next_controller_id(&_setup).await, next_controller_id_before);
+
+    let shard = controller_counter_shard(&_setup.payer.pubkey());
+    let controller_counter_pda = find_controller_counter_address(&_setup.program_id, shard).0;
+    let account = _setup
+        .banks_client
+        .get_account(controller_counter_pda)
+        .await
+        .unwrap()
+        .unwrap();
+    let controller_counter = ControllerCounter::try_from_slice(&account.data).unwrap();
+    assert_eq!(controller_counter.shard, shard);
+    assert_eq!(controller_counter.count, 2);
+
+    // a fresh blockhash keeps the retry from being rejected as a duplicate
+    let latest_blockhash = _setup.banks_client.get_latest_blockhash().await.unwrap();
+    let transaction = init_controller_with_nonce_transaction(
+        &_setup.payer,
+        &_setup.payer,
+        _setup.program_id,
+        0,
+        latest_blockhash,
+    );
+    let result = _setup.banks_client.process_transaction(transaction).await;
+    assert_eq!(
+        result.unwrap_err().unwrap(),
+        TransactionError::InstructionError(0, InstructionError::AccountAlreadyInitialized)
+    );
+}
+
+#[tokio::test]
+async fn test_create_index_under_nonce_controller() {
+    let _setup: Setup = setup().await;
+
+    let ProcessControllerGlobalConfigResult { result } =
+        process_controller_global_config(10, &_setup).await;
+    assert!(result.is_ok());
+
+    let nonce = 7;
+    let transaction = init_controller_with_nonce_transaction(
+        &_setup.payer,
+        &_setup.payer,
+        _setup.program_id,
+        nonce,
+        _setup.recent_blockhashes().await,
+    );
+    let result = _setup.banks_client.process_transaction(transaction).await;
+    assert!(result.is_ok());
+
+    let program_id = _setup.program_id;
+    let controller_pda =
+        find_creator_controller_address(&program_id, &_setup.payer.pubkey(), nonce).0;
+    let index_id = 1;
+    let index_pda = find_index_address(&program_id, &controller_pda, index_id).0;
+    let instruction = create_index_instruction(
+        program_id,
+        _setup.payer.pubkey(),
+        _setup.payer.pubkey(),
+        _setup.payer.pubkey(),
+        index_pda,
+        find_index_mint_address(&program_id, &controller_pda, index_id).0,
+        controller_pda,
+        find_controller_global_config_address(&program_id).0,
+        find_controller_config_address(&program_id, &controller_pda).0,
+    );
+    let transaction = Transaction::new_signed_with_payer(
+        &[instruction],
+        Some(&_setup.payer.pubkey()),
+        &[&_setup.payer],
+        _setup.recent_blockhashes().await,
+    );
+    let result = _setup.banks_client.process_transaction(transaction).await;
+    assert!(result.is_ok());
+
+    let account = _setup
+        .banks_client
+        .get_account(index_pda)
+        .await
+        .unwrap()
+        .unwrap();
+    let index = Index::try_from_slice(&account.data).unwrap();
+    assert_eq!(index.manager, _setup.payer.pubkey());
+}
@@ -2,7 +2,13 @@ use crate::{process_init_protocol, setup, ProcessInitProtocolResult, Setup};
 use borsh::BorshDeserialize;
 use openindex::state::Protocol;
 use openindex_sdk::openindex::{
-    pda::find_protocol_address, transaction::init_protocol_transaction,
+    error::ProtocolError, instruction::init_protocol_instruction, pda::find_protocol_address,
+    transaction::init_protocol_transaction,
+};
+use solana_sdk::{
+    instruction::InstructionError,
+    signature::Keypair,
+    transaction::{Transaction, TransactionError},
 };
 use {solana_program_test::tokio, solana_sdk::signature::Signer};
 
@@ -26,3 +32,34 @@ async fn test_init_protocol() {
     assert_eq!(protocol.next_controller_id, 1);
     assert_eq!(protocol.owner, _setup.payer.pubkey());
 }
+
+#[tokio::test]
+async fn test_init_protocol_rejects_non_upgrade_authority() {
+    let _setup: Setup = setup().await;
+    let attacker = Keypair::new();
+
+    let protocol_pda = find_protocol_address(&_setup.program_id).0;
+    let instruction = init_protocol_instruction(_setup.program_id, attacker.pubkey(), protocol_pda);
+    let transaction = Transaction::new_signed_with_payer(
+        &[instruction],
+        Some(&_setup.payer.pubkey()),
+        &[&_setup.payer, &attacker],
+        _setup.recent_blockhashes().await,
+    );
+
+    let result = _setup.banks_client.process_transaction(transaction).await;
+    assert_eq!(
+        result.unwrap_err().unwrap(),
+        TransactionError::InstructionError(
+            0,
+            InstructionError::Custom(ProtocolError::OnlyUpgradeAuthority as u32)
+        )
+    );
+
+    let protocol_account = _setup
+        .banks_client
+        .get_account(protocol_pda)
+        .await
+        .unwrap();
+    assert!(protocol_account.is_none());
+}
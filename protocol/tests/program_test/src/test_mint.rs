@@ -1,5 +1,5 @@
 use borsh::BorshDeserialize;
-use openindex::state::Component;
+use openindex::state::{Component, Index};
 use openindex_sdk::{
     openindex::{
         pda::{
@@ -12,15 +12,18 @@ use openindex_sdk::{
     },
 };
 use solana_program_test::tokio;
-use solana_sdk::{program_pack::Pack, pubkey::Pubkey, signature::Keypair, signer::Signer};
+use solana_sdk::{
+    account::Account as SolanaAccount, program_pack::Pack, pubkey::Pubkey, signature::Keypair,
+    signer::Signer,
+};
 use spl_associated_token_account::get_associated_token_address_with_program_id;
 use spl_token::{instruction::mint_to_checked, state::Account};
 
 use crate::{
     process_add_index_components, process_controller_global_config, process_create_index,
-    process_init_controller, process_init_module, process_init_protocol, process_mint, setup,
-    ProcessAddIndexComponentsResult, ProcessCreateIndexResult, ProcessInitControllerResult,
-    ProcessMintResult, Setup,
+    process_init_controller, process_init_module, process_init_protocol, process_mint,
+    process_redeem, setup, ProcessAddIndexComponentsResult, ProcessCreateIndexResult,
+    ProcessInitControllerResult, ProcessMintResult, ProcessRedeemResult, Setup,
 };
 use spl_token::state::Account as TokenAccount;
 
@@ -130,3 +133,86 @@ async fn test_mint() {
     let token_account = TokenAccount::unpack(&account.data).unwrap();
     assert_eq!(token_account.amount, mint_amount);
 }
+
+#[tokio::test]
+async fn test_mint_and_redeem_legacy_index() {
+    let mut _setup: Setup = setup().await;
+    let program_id = _setup.program_id;
+    let manager = Keypair::new();
+
+    let _ = process_controller_global_config(10, &_setup).await;
+
+    let ProcessInitControllerResult { controller_id, .. } = process_init_controller(&_setup).await;
+
+    let ProcessCreateIndexResult {
+        index_id,
+        controller_pda,
+        result,
+    } = process_create_index(controller_id, manager.pubkey(), &_setup).await;
+    assert!(result.is_ok());
+
+    let ProcessAddIndexComponentsResult { mints, result, .. } = process_add_index_components(
+        index_id,
+        controller_id,
+        manager.pubkey(),
+        2,
+        vec![1, 2],
+        &_setup,
+    )
+    .await;
+    assert!(result.is_ok());
+
+    // install the layout written before the index PDA bumps were cached
+    let index_pda = find_index_address(&program_id, &controller_pda, index_id).0;
+    let account = _setup
+        .banks_client
+        .get_account(index_pda)
+        .await
+        .unwrap()
+        .unwrap();
+    let legacy_account = SolanaAccount {
+        lamports: _setup.rent.minimum_balance(Index::LEGACY_LEN),
+        data: account.data[..Index::LEGACY_LEN].to_vec(),
+        owner: account.owner,
+        executable: false,
+        rent_epoch: account.rent_epoch,
+    };
+    _setup.set_account(&index_pda, legacy_account);
+
+    let account = _setup
+        .banks_client
+        .get_account(index_pda)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(account.data.len(), Index::LEGACY_LEN);
+    assert_eq!(Index::unpack(&account.data).unwrap().mint_bump, 0);
+
+    let ProcessMintResult {
+        token_account,
+        token_accounts,
+        result,
+        ..
+    } = process_mint(10, 100, controller_id, index_id, mints.clone(), &_setup).await;
+    assert!(result.is_ok());
+
+    let ProcessRedeemResult { result } = process_redeem(
+        4,
+        index_id,
+        controller_id,
+        token_account,
+        mints,
+        token_accounts,
+        &_setup,
+    )
+    .await;
+    assert!(result.is_ok());
+
+    let account = _setup
+        .banks_client
+        .get_account(token_account)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(TokenAccount::unpack(&account.data).unwrap().amount, 6);
+}
@@ -0,0 +1,256 @@
+use borsh::BorshDeserialize;
+use openindex::state::Index;
+use openindex_sdk::openindex::{
+    error::ProtocolError,
+    instruction::{mint_instruction_with_guard, redeem_instruction_with_guard},
+    pda::{
+        find_controller_address, find_index_address, find_index_mint_address,
+        find_index_mint_authority_address, find_index_mints_data_address,
+    },
+    transaction::{execute_composition_change_transaction, queue_composition_change_transaction},
+};
+use solana_program_test::tokio;
+
+use solana_sdk::{
+    instruction::Instruction,
+    pubkey::Pubkey,
+    signature::Signer,
+    transaction::{Transaction, TransactionError},
+};
+
+use crate::{
+    custom_error, latest_blockhash, process, process_add_index_components,
+    process_controller_global_config, process_create_index, process_init_controller, process_mint,
+    setup, ProcessAddIndexComponentsResult, ProcessControllerGlobalConfigResult,
+    ProcessCreateIndexResult, ProcessInitControllerResult, ProcessMintResult, Setup,
+};
+
+async fn process_instruction(
+    instruction: Instruction,
+    _setup: &Setup,
+) -> Result<(), TransactionError> {
+    let transaction = Transaction::new_signed_with_payer(
+        &[instruction],
+        Some(&_setup.payer.pubkey()),
+        &[&_setup.payer],
+        latest_blockhash(_setup).await,
+    );
+    process(transaction, _setup).await
+}
+
+struct Guarded {
+    index_id: u64,
+    controller_id: u64,
+    mints: Vec<Pubkey>,
+    token_account: Pubkey,
+    token_accounts: Vec<Pubkey>,
+}
+
+impl Guarded {
+    fn mint(
+        &self,
+        _setup: &Setup,
+        amount: u64,
+        expected_version: Option<u64>,
+        max_amounts_in: Option<Vec<u64>>,
+    ) -> Instruction {
+        let program_id = _setup.program_id;
+        let controller_pda = find_controller_address(&program_id, self.controller_id).0;
+        mint_instruction_with_guard(
+            _setup.payer.pubkey(),
+            program_id,
+            controller_pda,
+            find_index_mint_address(&program_id, &controller_pda, self.index_id).0,
+            find_index_mint_authority_address(&program_id, &controller_pda, self.index_id).0,
+            find_index_address(&program_id, &controller_pda, self.index_id).0,
+            find_index_mints_data_address(&program_id, &controller_pda, self.index_id).0,
+            self.token_account,
+            spl_token::ID,
+            self.mints.clone(),
+            self.token_accounts.clone(),
+            self.index_id,
+            amount,
+            expected_version,
+            max_amounts_in,
+        )
+    }
+
+    fn redeem(
+        &self,
+        _setup: &Setup,
+        amount: u64,
+        expected_version: Option<u64>,
+        min_amounts_out: Option<Vec<u64>>,
+    ) -> Instruction {
+        let program_id = _setup.program_id;
+        let controller_pda = find_controller_address(&program_id, self.controller_id).0;
+        redeem_instruction_with_guard(
+            _setup.payer.pubkey(),
+            program_id,
+            controller_pda,
+            find_index_mint_address(&program_id, &controller_pda, self.index_id).0,
+            find_index_mint_authority_address(&program_id, &controller_pda, self.index_id).0,
+            find_index_address(&program_id, &controller_pda, self.index_id).0,
+            find_index_mints_data_address(&program_id, &controller_pda, self.index_id).0,
+            self.token_account,
+            spl_token::ID,
+            self.mints.clone(),
+            self.token_accounts.clone(),
+            self.index_id,
+            amount,
+            expected_version,
+            min_amounts_out,
+        )
+    }
+}
+
+#[tokio::test]
+async fn test_mint_redeem_guard() {
+    let _setup: Setup = setup().await;
+
+    let ProcessControllerGlobalConfigResult { result } =
+        process_controller_global_config(10, &_setup).await;
+    assert!(result.is_ok());
+
+    let ProcessInitControllerResult {
+        controller_id,
+        controller_pda,
+        result,
+    } = process_init_controller(&_setup).await;
+    assert!(result.is_ok());
+
+    let ProcessCreateIndexResult {
+        index_id, result, ..
+    } = process_create_index(controller_id, _setup.payer.pubkey(), &_setup).await;
+    assert!(result.is_ok());
+
+    let ProcessAddIndexComponentsResult { mints, result, .. } = process_add_index_components(
+        index_id,
+        controller_id,
+        _setup.payer.pubkey(),
+        2,
+        vec![10, 20],
+        &_setup,
+    )
+    .await;
+    assert!(result.is_ok());
+
+    let index_pda = find_index_address(&_setup.program_id, &controller_pda, index_id).0;
+    let account = _setup
+        .banks_client
+        .get_account(index_pda)
+        .await
+        .unwrap()
+        .unwrap();
+    let index = Index::try_from_slice(&account.data).unwrap();
+    assert_eq!(index.composition_version, 1);
+
+    // unguarded mint also creates the token accounts used below
+    let ProcessMintResult {
+        token_account,
+        token_accounts,
+        result,
+        ..
+    } = process_mint(1, 1_000, controller_id, index_id, mints.clone(), &_setup).await;
+    assert!(result.is_ok());
+
+    let guarded = Guarded {
+        index_id,
+        controller_id,
+        mints: mints.clone(),
+        token_account,
+        token_accounts,
+    };
+
+    assert_eq!(
+        process_instruction(guarded.mint(&_setup, 2, Some(2), None), &_setup).await,
+        Err(custom_error(ProtocolError::CompositionVersionMismatch))
+    );
+    assert_eq!(
+        process_instruction(
+            guarded.mint(&_setup, 2, Some(1), Some(vec![20, 39])),
+            &_setup
+        )
+        .await,
+        Err(custom_error(ProtocolError::MaxAmountInExceeded))
+    );
+    assert_eq!(
+        process_instruction(guarded.mint(&_setup, 2, None, Some(vec![20])), &_setup).await,
+        Err(custom_error(ProtocolError::MintsAmountsLenMismatch))
+    );
+    assert!(process_instruction(
+        guarded.mint(&_setup, 2, Some(1), Some(vec![20, 40])),
+        &_setup
+    )
+    .await
+    .is_ok());
+
+    assert_eq!(
+        process_instruction(
+            guarded.redeem(&_setup, 1, Some(1), Some(vec![10, 21])),
+            &_setup
+        )
+        .await,
+        Err(custom_error(ProtocolError::MinAmountOutNotMet))
+    );
+    assert!(process_instruction(
+        guarded.redeem(&_setup, 1, Some(1), Some(vec![10, 20])),
+        &_setup
+    )
+    .await
+    .is_ok());
+
+    // the recipe only changes once the supply is redeemed
+    assert!(process_instruction(
+        guarded.redeem(&_setup, 2, Some(1), Some(vec![20, 40])),
+        &_setup
+    )
+    .await
+    .is_ok());
+
+    // a composition change moves the version, so quotes built before it fail
+    let transaction = queue_composition_change_transaction(
+        &_setup.payer,
+        _setup.program_id,
+        index_id,
+        controller_id,
+        latest_blockhash(&_setup).await,
+        mints.clone(),
+        vec![1, 2],
+    );
+    let result = _setup.banks_client.process_transaction(transaction).await;
+    assert!(result.is_ok());
+
+    let transaction = execute_composition_change_transaction(
+        &_setup.payer,
+        _setup.program_id,
+        index_id,
+        controller_id,
+        _setup.payer.pubkey(),
+        &mints,
+        latest_blockhash(&_setup).await,
+    );
+    let result = _setup.banks_client.process_transaction(transaction).await;
+    assert!(result.is_ok());
+
+    assert_eq!(
+        process_instruction(guarded.mint(&_setup, 1, Some(1), None), &_setup).await,
+        Err(custom_error(ProtocolError::CompositionVersionMismatch))
+    );
+    assert!(
+        process_instruction(guarded.mint(&_setup, 1, Some(2), Some(vec![1, 2])), &_setup)
+            .await
+            .is_ok()
+    );
+
+    assert_eq!(
+        process_instruction(guarded.redeem(&_setup, 1, Some(1), None), &_setup).await,
+        Err(custom_error(ProtocolError::CompositionVersionMismatch))
+    );
+    assert!(process_instruction(
+        guarded.redeem(&_setup, 1, Some(2), Some(vec![1, 2])),
+        &_setup
+    )
+    .await
+    .is_ok());
+}
@@ -0,0 +1,192 @@
+use openindex_sdk::openindex::{
+    error::ProtocolError,
+    pda::{find_controller_address, find_index_mint_address},
+    transaction::{
+        create_token_account_transaction, mint_transaction, mint_with_recipient_transaction,
+    },
+};
+use solana_program_test::tokio;
+
+use solana_sdk::{
+    hash::Hash,
+    native_token::LAMPORTS_PER_SOL,
+    signature::{Keypair, Signer},
+    system_instruction,
+    transaction::Transaction,
+};
+use spl_associated_token_account::get_associated_token_address_with_program_id;
+
+use crate::{
+    custom_error, latest_blockhash, new_blockhash, process, setup, setup_minted_index,
+    token_balance, ProcessMintResult, Setup,
+};
+
+#[tokio::test]
+async fn test_mint_to_recipient() {
+    let _setup: Setup = setup().await;
+    let program_id = _setup.program_id;
+    let (
+        ProcessMintResult {
+            index_id,
+            controller_id,
+            token_accounts,
+            ..
+        },
+        mints,
+    ) = setup_minted_index(1, &_setup).await;
+
+    let controller_pda = find_controller_address(&program_id, controller_id).0;
+    let index_mint = find_index_mint_address(&program_id, &controller_pda, index_id).0;
+
+    let recipient = Keypair::new();
+    let transaction = create_token_account_transaction(
+        &_setup.payer,
+        _setup.payer.pubkey(),
+        recipient.pubkey(),
+        index_mint,
+        _setup.recent_blockhashes().await,
+    );
+    assert!(process(transaction, &_setup).await.is_ok());
+    let recipient_token_account = get_associated_token_address_with_program_id(
+        &recipient.pubkey(),
+        &index_mint,
+        &spl_token::ID,
+    );
+
+    // without an explicit recipient the index tokens must go to the signer
+    let transaction = mint_transaction(
+        3,
+        &_setup.payer,
+        program_id,
+        index_id,
+        controller_id,
+        recipient_token_account,
+        latest_blockhash(&_setup).await,
+        mints.clone(),
+        token_accounts.clone(),
+    );
+    assert_eq!(
+        process(transaction, &_setup).await.unwrap_err(),
+        custom_error(ProtocolError::IncorrectRecipient)
+    );
+
+    let transaction = mint_with_recipient_transaction(
+        3,
+        &_setup.payer,
+        program_id,
+        index_id,
+        controller_id,
+        recipient.pubkey(),
+        recipient_token_account,
+        latest_blockhash(&_setup).await,
+        mints,
+        token_accounts.clone(),
+    );
+    assert!(process(transaction, &_setup).await.is_ok());
+
+    assert_eq!(token_balance(recipient_token_account, &_setup).await, 3);
+    assert_eq!(token_balance(token_accounts[0], &_setup).await, 96);
+    assert_eq!(token_balance(token_accounts[1], &_setup).await, 92);
+}
+
+#[tokio::test]
+async fn test_mint_from_delegate() {
+    let _setup: Setup = setup().await;
+    let program_id = _setup.program_id;
+    let (
+        ProcessMintResult {
+            index_id,
+            controller_id,
+            token_account,
+            token_accounts,
+            ..
+        },
+        mints,
+    ) = setup_minted_index(1, &_setup).await;
+
+    // the delegate only pays fees; the components stay with the setup payer
+    let delegate = Keypair::new();
+    let transaction = Transaction::new_signed_with_payer(
+        &[system_instruction::transfer(
+            &_setup.payer.pubkey(),
+            &delegate.pubkey(),
+            LAMPORTS_PER_SOL,
+        )],
+        Some(&_setup.payer.pubkey()),
+        &[&_setup.payer],
+        _setup.recent_blockhashes().await,
+    );
+    assert!(process(transaction, &_setup).await.is_ok());
+
+    let approve = |allowances: [u64; 2], blockhash: Hash| {
+        let instructions: Vec<_> = token_accounts
+            .iter()
+            .zip(allowances)
+            .map(|(component_token_account, allowance)| {
+                spl_token::instruction::approve(
+                    &spl_token::ID,
+                    component_token_account,
+                    &delegate.pubkey(),
+                    &_setup.payer.pubkey(),
+                    &[],
+                    allowance,
+                )
+                .unwrap()
+            })
+            .collect();
+        Transaction::new_signed_with_payer(
+            &instructions,
+            Some(&_setup.payer.pubkey()),
+            &[&_setup.payer],
+            blockhash,
+        )
+    };
+    let mint = |blockhash: Hash| {
+        mint_with_recipient_transaction(
+            2,
+            &delegate,
+            program_id,
+            index_id,
+            controller_id,
+            _setup.payer.pubkey(),
+            token_account,
+            blockhash,
+            mints.clone(),
+            token_accounts.clone(),
+        )
+    };
+
+    let mint_blockhash = latest_blockhash(&_setup).await;
+    assert_eq!(
+        process(mint(mint_blockhash), &_setup).await.unwrap_err(),
+        custom_error(ProtocolError::InvalidTokenAccountAuthority)
+    );
+
+    let approve_blockhash = latest_blockhash(&_setup).await;
+    assert!(process(approve([2, 3], approve_blockhash), &_setup)
+        .await
+        .is_ok());
+    let mint_blockhash = new_blockhash(&_setup, &mint_blockhash).await;
+    assert_eq!(
+        process(mint(mint_blockhash), &_setup).await.unwrap_err(),
+        custom_error(ProtocolError::InsufficientDelegatedAmount)
+    );
+
+    let approve_blockhash = new_blockhash(&_setup, &approve_blockhash).await;
+    assert!(process(approve([2, 4], approve_blockhash), &_setup)
+        .await
+        .is_ok());
+    let mint_blockhash = new_blockhash(&_setup, &mint_blockhash).await;
+    assert!(process(mint(mint_blockhash), &_setup).await.is_ok());
+
+    assert_eq!(token_balance(token_account, &_setup).await, 3);
+    assert_eq!(token_balance(token_accounts[0], &_setup).await, 97);
+    assert_eq!(token_balance(token_accounts[1], &_setup).await, 94);
+
+    // the transfers used up the allowance and cleared the delegate
+    let mint_blockhash = new_blockhash(&_setup, &mint_blockhash).await;
+    assert_eq!(
+        process(mint(mint_blockhash), &_setup).await.unwrap_err(),
+        custom_error(ProtocolError::InvalidTokenAccountAuthority)
+    );
+}
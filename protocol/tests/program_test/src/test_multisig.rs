@@ -0,0 +1,499 @@
+use borsh::BorshDeserialize;
+use openindex::state::{Controller, ControllerGlobalConfig, Multisig, Protocol};
+use openindex_sdk::openindex::{
+    error::ProtocolError,
+    instruction::{
+        cancel_composition_change_instruction, create_index_instruction,
+        init_controller_global_config_instruction, queue_composition_change_instruction,
+        set_controller_creator_instruction, transfer_ownership_instruction, with_multisig_signers,
+    },
+    pda::{
+        find_controller_config_address, find_controller_global_config_address, find_index_address,
+        find_index_mint_address, find_multisig_address, find_pending_composition_change_address,
+        find_protocol_address,
+    },
+    transaction::{create_multisig_transaction, transfer_ownership_transaction},
+};
+use solana_program_test::tokio;
+
+use solana_sdk::{
+    hash::Hash,
+    instruction::Instruction,
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+    transaction::{Transaction, TransactionError},
+};
+
+use crate::{
+    custom_error, latest_blockhash, new_blockhash, process_add_index_components,
+    process_controller_global_config, process_create_index, process_init_controller,
+    process_init_protocol, setup, ProcessAddIndexComponentsResult,
+    ProcessControllerGlobalConfigResult, ProcessCreateIndexResult, ProcessInitControllerResult,
+    ProcessInitProtocolResult, Setup,
+};
+
+async fn create_multisig(
+    members: &[Keypair],
+    threshold: u8,
+    _setup: &Setup,
+) -> (Pubkey, Result<(), TransactionError>) {
+    let create_key = Keypair::new();
+    let transaction = create_multisig_transaction(
+        &_setup.payer,
+        _setup.program_id,
+        &create_key,
+        members.iter().map(|member| member.pubkey()).collect(),
+        threshold,
+        latest_blockhash(_setup).await,
+    );
+    let result = _setup
+        .banks_client
+        .process_transaction(transaction)
+        .await
+        .map_err(|error| error.unwrap());
+    (
+        find_multisig_address(&_setup.program_id, &create_key.pubkey()).0,
+        result,
+    )
+}
+
+/// Sends `instruction` signed by the payer and `members`, approving it on
+/// behalf of `multisig` when one is given.
+async fn process_as(
+    instruction: Instruction,
+    multisig: Option<Pubkey>,
+    members: &[&Keypair],
+    recent_blockhash: Hash,
+    _setup: &Setup,
+) -> Result<(), TransactionError> {
+    let instruction = match multisig {
+        Some(multisig) => {
+            let keys: Vec<Pubkey> = members.iter().map(|member| member.pubkey()).collect();
+            with_multisig_signers(instruction, multisig, &keys)
+        }
+        None => instruction,
+    };
+    let mut signers = vec![&_setup.payer];
+    signers.extend(members);
+    let transaction = Transaction::new_signed_with_payer(
+        &[instruction],
+        Some(&_setup.payer.pubkey()),
+        &signers,
+        recent_blockhash,
+    );
+    _setup
+        .banks_client
+        .process_transaction(transaction)
+        .await
+        .map_err(|error| error.unwrap())
+}
+
+#[tokio::test]
+async fn test_create_multisig_validation() {
+    let _setup: Setup = setup().await;
+    let members = [Keypair::new(), Keypair::new()];
+
+    let (_, result) = create_multisig(&[], 1, &_setup).await;
+    assert_eq!(
+        result,
+        Err(custom_error(ProtocolError::InvalidMultisigMembers))
+    );
+
+    let duplicate = [members[0].insecure_clone(), members[0].insecure_clone()];
+    let (_, result) = create_multisig(&duplicate, 1, &_setup).await;
+    assert_eq!(
+        result,
+        Err(custom_error(ProtocolError::InvalidMultisigMembers))
+    );
+
+    let (_, result) = create_multisig(&members, 3, &_setup).await;
+    assert_eq!(
+        result,
+        Err(custom_error(ProtocolError::InvalidMultisigThreshold))
+    );
+
+    let (multisig, result) = create_multisig(&members, 2, &_setup).await;
+    assert!(result.is_ok());
+
+    let account = _setup
+        .banks_client
+        .get_account(multisig)
+        .await
+        .unwrap()
+        .unwrap();
+    let multisig = Multisig::try_from_slice(&account.data).unwrap();
+    assert_eq!(multisig.threshold, 2);
+    assert_eq!(
+        multisig.members,
+        vec![members[0].pubkey(), members[1].pubkey()]
+    );
+}
+
+#[tokio::test]
+async fn test_multisig_protocol_owner() {
+    let _setup: Setup = setup().await;
+
+    let ProcessControllerGlobalConfigResult { result } =
+        process_controller_global_config(10, &_setup).await;
+    assert!(result.is_ok());
+
+    let members = [Keypair::new(), Keypair::new(), Keypair::new()];
+    let (multisig, result) = create_multisig(&members, 2, &_setup).await;
+    assert!(result.is_ok());
+
+    let protocol_pda = find_protocol_address(&_setup.program_id).0;
+    let transaction = transfer_ownership_transaction(
+        &_setup.payer,
+        _setup.program_id,
+        protocol_pda,
+        multisig,
+        latest_blockhash(&_setup).await,
+    );
+    let result = _setup.banks_client.process_transaction(transaction).await;
+    assert!(result.is_ok());
+
+    let creator = Pubkey::new_unique();
+    let set_creator = set_controller_creator_instruction(
+        _setup.program_id,
+        _setup.payer.pubkey(),
+        protocol_pda,
+        creator,
+        true,
+    );
+
+    // the former owner alone is no longer enough
+    assert_eq!(
+        process_as(
+            set_creator.clone(),
+            None,
+            &[],
+            latest_blockhash(&_setup).await,
+            &_setup
+        )
+        .await,
+        Err(custom_error(ProtocolError::OnlyProtocolOwner))
+    );
+
+    let denied_blockhash = latest_blockhash(&_setup).await;
+    assert_eq!(
+        process_as(
+            set_creator.clone(),
+            Some(multisig),
+            &[&members[0]],
+            denied_blockhash,
+            &_setup
+        )
+        .await,
+        Err(custom_error(ProtocolError::MultisigThresholdNotMet))
+    );
+
+    // approvals only count for the multisig that owns the protocol
+    let outsider = Keypair::new();
+    assert_eq!(
+        process_as(
+            set_creator.clone(),
+            Some(outsider.pubkey()),
+            &[&members[0], &members[1]],
+            latest_blockhash(&_setup).await,
+            &_setup
+        )
+        .await,
+        Err(custom_error(ProtocolError::OnlyProtocolOwner))
+    );
+
+    assert!(process_as(
+        set_creator,
+        Some(multisig),
+        &[&members[0], &members[2]],
+        new_blockhash(&_setup, &denied_blockhash).await,
+        &_setup
+    )
+    .await
+    .is_ok());
+
+    // the multisig hands ownership back to the payer
+    let transfer_back = transfer_ownership_instruction(
+        _setup.program_id,
+        _setup.payer.pubkey(),
+        protocol_pda,
+        _setup.payer.pubkey(),
+    );
+    assert!(process_as(
+        transfer_back,
+        Some(multisig),
+        &[&members[1], &members[2]],
+        latest_blockhash(&_setup).await,
+        &_setup
+    )
+    .await
+    .is_ok());
+
+    let account = _setup
+        .banks_client
+        .get_account(protocol_pda)
+        .await
+        .unwrap()
+        .unwrap();
+    let protocol = Protocol::try_from_slice(&account.data).unwrap();
+    assert_eq!(protocol.owner, _setup.payer.pubkey());
+}
+
+#[tokio::test]
+async fn test_multisig_controller_owner() {
+    let _setup: Setup = setup().await;
+
+    let ProcessControllerGlobalConfigResult { result } =
+        process_controller_global_config(10, &_setup).await;
+    assert!(result.is_ok());
+
+    let ProcessInitControllerResult {
+        controller_pda,
+        result,
+        ..
+    } = process_init_controller(&_setup).await;
+    assert!(result.is_ok());
+
+    let members = [Keypair::new(), Keypair::new()];
+    let (multisig, result) = create_multisig(&members, 2, &_setup).await;
+    assert!(result.is_ok());
+
+    let transaction = transfer_ownership_transaction(
+        &_setup.payer,
+        _setup.program_id,
+        controller_pda,
+        multisig,
+        latest_blockhash(&_setup).await,
+    );
+    let result = _setup.banks_client.process_transaction(transaction).await;
+    assert!(result.is_ok());
+
+    let account = _setup
+        .banks_client
+        .get_account(controller_pda)
+        .await
+        .unwrap()
+        .unwrap();
+    let controller = Controller::try_from_slice(&account.data).unwrap();
+    assert_eq!(controller.owner, multisig);
+
+    let index_id = 1;
+    let create_index = create_index_instruction(
+        _setup.program_id,
+        _setup.payer.pubkey(),
+        _setup.payer.pubkey(),
+        _setup.payer.pubkey(),
+        find_index_address(&_setup.program_id, &controller_pda, index_id).0,
+        find_index_mint_address(&_setup.program_id, &controller_pda, index_id).0,
+        controller_pda,
+        find_controller_global_config_address(&_setup.program_id).0,
+        find_controller_config_address(&_setup.program_id, &controller_pda).0,
+    );
+
+    // without approvals the payer falls back to the role registry
+    assert_eq!(
+        process_as(
+            create_index.clone(),
+            None,
+            &[],
+            latest_blockhash(&_setup).await,
+            &_setup
+        )
+        .await,
+        Err(custom_error(ProtocolError::MissingControllerRole))
+    );
+
+    assert!(process_as(
+        create_index,
+        Some(multisig),
+        &[&members[0], &members[1]],
+        latest_blockhash(&_setup).await,
+        &_setup
+    )
+    .await
+    .is_ok());
+}
+
+#[tokio::test]
+async fn test_multisig_protocol_owner_inits_controller_global_config() {
+    let _setup: Setup = setup().await;
+
+    let ProcessInitProtocolResult { result } = process_init_protocol(&_setup).await;
+    assert!(result.is_ok());
+
+    let members = [Keypair::new(), Keypair::new()];
+    let (multisig, result) = create_multisig(&members, 2, &_setup).await;
+    assert!(result.is_ok());
+
+    let protocol_pda = find_protocol_address(&_setup.program_id).0;
+    let transaction = transfer_ownership_transaction(
+        &_setup.payer,
+        _setup.program_id,
+        protocol_pda,
+        multisig,
+        latest_blockhash(&_setup).await,
+    );
+    let result = _setup.banks_client.process_transaction(transaction).await;
+    assert!(result.is_ok());
+
+    let controller_global_config_pda = find_controller_global_config_address(&_setup.program_id).0;
+    let init_config = init_controller_global_config_instruction(
+        _setup.program_id,
+        _setup.payer.pubkey(),
+        protocol_pda,
+        controller_global_config_pda,
+        10,
+    );
+
+    assert_eq!(
+        process_as(
+            init_config.clone(),
+            None,
+            &[],
+            latest_blockhash(&_setup).await,
+            &_setup
+        )
+        .await,
+        Err(custom_error(ProtocolError::OnlyProtocolOwner))
+    );
+
+    assert!(process_as(
+        init_config,
+        Some(multisig),
+        &[&members[0], &members[1]],
+        latest_blockhash(&_setup).await,
+        &_setup
+    )
+    .await
+    .is_ok());
+
+    let account = _setup
+        .banks_client
+        .get_account(controller_global_config_pda)
+        .await
+        .unwrap()
+        .unwrap();
+    let config = ControllerGlobalConfig::unpack(&account.data).unwrap();
+    assert_eq!(config.max_index_components, 10);
+}
+
+#[tokio::test]
+async fn test_multisig_index_manager() {
+    let _setup: Setup = setup().await;
+
+    let ProcessControllerGlobalConfigResult { result } =
+        process_controller_global_config(10, &_setup).await;
+    assert!(result.is_ok());
+
+    let ProcessInitControllerResult {
+        controller_id,
+        controller_pda,
+        result,
+    } = process_init_controller(&_setup).await;
+    assert!(result.is_ok());
+
+    let members = [Keypair::new(), Keypair::new()];
+    let (multisig, result) = create_multisig(&members, 2, &_setup).await;
+    assert!(result.is_ok());
+
+    let ProcessCreateIndexResult {
+        index_id, result, ..
+    } = process_create_index(controller_id, multisig, &_setup).await;
+    assert!(result.is_ok());
+
+    let ProcessAddIndexComponentsResult { mints, result, .. } = process_add_index_components(
+        index_id,
+        controller_id,
+        _setup.payer.pubkey(),
+        2,
+        vec![10, 20],
+        &_setup,
+    )
+    .await;
+    assert!(result.is_ok());
+
+    let index_pda = find_index_address(&_setup.program_id, &controller_pda, index_id).0;
+    let queue = queue_composition_change_instruction(
+        _setup.program_id,
+        _setup.payer.pubkey(),
+        index_pda,
+        controller_pda,
+        find_controller_global_config_address(&_setup.program_id).0,
+        find_controller_config_address(&_setup.program_id, &controller_pda).0,
+        vec![mints[1]],
+        vec![5],
+    );
+
+    // the payer owns the index but only the manager may queue a change
+    assert_eq!(
+        process_as(
+            queue.clone(),
+            None,
+            &[],
+            latest_blockhash(&_setup).await,
+            &_setup
+        )
+        .await,
+        Err(custom_error(ProtocolError::OnlyIndexManager))
+    );
+
+    assert_eq!(
+        process_as(
+            queue.clone(),
+            Some(multisig),
+            &[&members[0]],
+            latest_blockhash(&_setup).await,
+            &_setup
+        )
+        .await,
+        Err(custom_error(ProtocolError::MultisigThresholdNotMet))
+    );
+
+    assert!(process_as(
+        queue,
+        Some(multisig),
+        &[&members[0], &members[1]],
+        latest_blockhash(&_setup).await,
+        &_setup
+    )
+    .await
+    .is_ok());
+
+    // a member alone is neither the manager nor the owner
+    let cancel = cancel_composition_change_instruction(
+        _setup.program_id,
+        members[0].pubkey(),
+        index_pda,
+        controller_pda,
+        _setup.payer.pubkey(),
+    );
+    assert_eq!(
+        process_as(
+            cancel.clone(),
+            None,
+            &[&members[0]],
+            latest_blockhash(&_setup).await,
+            &_setup
+        )
+        .await,
+        Err(custom_error(ProtocolError::OnlyIndexManager))
+    );
+
+    assert!(process_as(
+        cancel,
+        Some(multisig),
+        &[&members[0], &members[1]],
+        latest_blockhash(&_setup).await,
+        &_setup
+    )
+    .await
+    .is_ok());
+
+    let pending_change_pda =
+        find_pending_composition_change_address(&_setup.program_id, &index_pda).0;
+    assert!(_setup
+        .banks_client
+        .get_account(pending_change_pda)
+        .await
+        .unwrap()
+        .is_none());
+}
@@ -0,0 +1,244 @@
+use openindex_sdk::openindex::{
+    error::ProtocolError,
+    instruction::ProtocolInstruction,
+    pda::{
+        find_component_vault_address, find_controller_address, find_index_address,
+        find_index_mint_address,
+    },
+    transaction::{
+        add_index_components_transaction, create_mint_acccount_transaction,
+        create_token_account_transaction, mint_to_transaction, mint_transaction,
+        redeem_transaction,
+    },
+};
+use solana_program_test::tokio;
+use solana_sdk::{
+    instruction::InstructionError,
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+    transaction::{Transaction, TransactionError},
+};
+use spl_associated_token_account::get_associated_token_address;
+use spl_token::native_mint;
+
+use crate::{
+    latest_blockhash, process, process_controller_global_config, process_create_index,
+    process_init_controller, setup, token_balance, ProcessControllerGlobalConfigResult,
+    ProcessCreateIndexResult, ProcessInitControllerResult, Setup,
+};
+
+const NATIVE_UNITS: u64 = 1_000_000;
+
+struct NativeIndex {
+    index_id: u64,
+    controller_id: u64,
+    mints: Vec<Pubkey>,
+    token_account: Pubkey,
+    token_accounts: Vec<Pubkey>,
+}
+
+async fn lamports(address: Pubkey, _setup: &Setup) -> u64 {
+    _setup.banks_client.get_balance(address).await.unwrap()
+}
+
+/// Creates an index of wrapped SOL and one SPL token with units
+/// `[NATIVE_UNITS, 2]`. Only the SPL component is funded in a token account;
+/// the wrapped SOL component is paid in lamports.
+async fn setup_native_index(_setup: &Setup) -> NativeIndex {
+    let payer = &_setup.payer;
+
+    let ProcessControllerGlobalConfigResult { result } =
+        process_controller_global_config(10, _setup).await;
+    assert!(result.is_ok());
+
+    let ProcessInitControllerResult { controller_id, .. } = process_init_controller(_setup).await;
+
+    let ProcessCreateIndexResult {
+        index_id,
+        controller_pda,
+        result,
+    } = process_create_index(controller_id, payer.pubkey(), _setup).await;
+    assert!(result.is_ok());
+
+    let mint = Keypair::new();
+    let transaction = create_mint_acccount_transaction(
+        payer,
+        &mint,
+        _setup.recent_blockhashes().await,
+        &_setup.rent,
+    );
+    assert!(process(transaction, _setup).await.is_ok());
+
+    let mints = vec![native_mint::ID, mint.pubkey()];
+    let transaction = add_index_components_transaction(
+        payer,
+        payer,
+        _setup.program_id,
+        index_id,
+        controller_id,
+        _setup.recent_blockhashes().await,
+        mints.clone(),
+        vec![NATIVE_UNITS, 2],
+    );
+    assert!(process(transaction, _setup).await.is_ok());
+
+    let index_mint = find_index_mint_address(&_setup.program_id, &controller_pda, index_id).0;
+    for mint in [mint.pubkey(), index_mint] {
+        let transaction = create_token_account_transaction(
+            payer,
+            payer.pubkey(),
+            payer.pubkey(),
+            mint,
+            _setup.recent_blockhashes().await,
+        );
+        assert!(process(transaction, _setup).await.is_ok());
+    }
+
+    let component_token_account = get_associated_token_address(&payer.pubkey(), &mint.pubkey());
+    let transaction = mint_to_transaction(
+        payer,
+        100,
+        mint.pubkey(),
+        component_token_account,
+        _setup.recent_blockhashes().await,
+    )
+    .unwrap();
+    assert!(process(transaction, _setup).await.is_ok());
+
+    NativeIndex {
+        index_id,
+        controller_id,
+        mints,
+        token_account: get_associated_token_address(&payer.pubkey(), &index_mint),
+        // the helpers pick the wrapped SOL account themselves
+        token_accounts: vec![Pubkey::default(), component_token_account],
+    }
+}
+
+#[tokio::test]
+async fn test_mint_and_redeem_native_sol() {
+    let _setup: Setup = setup().await;
+    let program_id = _setup.program_id;
+    let payer = &_setup.payer;
+    let NativeIndex {
+        index_id,
+        controller_id,
+        mints,
+        token_account,
+        token_accounts,
+    } = setup_native_index(&_setup).await;
+
+    let controller_pda = find_controller_address(&program_id, controller_id).0;
+    let index_pda = find_index_address(&program_id, &controller_pda, index_id).0;
+    let vault_pda = find_component_vault_address(&program_id, &index_pda, &native_mint::ID).0;
+    let vault_ata = get_associated_token_address(&vault_pda, &native_mint::ID);
+    let wrapped_account = get_associated_token_address(&payer.pubkey(), &native_mint::ID);
+
+    let lamports_before = lamports(payer.pubkey(), &_setup).await;
+    let transaction = mint_transaction(
+        5,
+        payer,
+        program_id,
+        index_id,
+        controller_id,
+        token_account,
+        latest_blockhash(&_setup).await,
+        mints.clone(),
+        token_accounts.clone(),
+    );
+    assert!(process(transaction, &_setup).await.is_ok());
+
+    assert_eq!(token_balance(token_account, &_setup).await, 5);
+    assert_eq!(token_balance(vault_ata, &_setup).await, 5 * NATIVE_UNITS);
+    assert_eq!(token_balance(token_accounts[1], &_setup).await, 90);
+    assert!(lamports(payer.pubkey(), &_setup).await <= lamports_before - 5 * NATIVE_UNITS);
+
+    let lamports_before = lamports(payer.pubkey(), &_setup).await;
+    let transaction = redeem_transaction(
+        3,
+        payer,
+        program_id,
+        index_id,
+        controller_id,
+        token_account,
+        latest_blockhash(&_setup).await,
+        mints,
+        token_accounts.clone(),
+    );
+    assert!(process(transaction, &_setup).await.is_ok());
+
+    assert_eq!(token_balance(token_account, &_setup).await, 2);
+    assert_eq!(token_balance(vault_ata, &_setup).await, 2 * NATIVE_UNITS);
+    assert_eq!(token_balance(token_accounts[1], &_setup).await, 96);
+
+    // the wrapped SOL account is created for the transfer and closed again,
+    // so the payer only gives up the transaction fee
+    let account = _setup
+        .banks_client
+        .get_account(wrapped_account)
+        .await
+        .unwrap();
+    assert!(account.is_none());
+    let lamports_after = lamports(payer.pubkey(), &_setup).await;
+    assert!(lamports_after > lamports_before);
+    assert!(lamports_after <= lamports_before + 3 * NATIVE_UNITS);
+}
+
+#[tokio::test]
+async fn test_unwrap_requires_signer_recipient() {
+    let _setup: Setup = setup().await;
+    let payer = &_setup.payer;
+    let NativeIndex {
+        index_id,
+        controller_id,
+        mints,
+        token_account,
+        token_accounts,
+    } = setup_native_index(&_setup).await;
+
+    let transaction = mint_transaction(
+        5,
+        payer,
+        _setup.program_id,
+        index_id,
+        controller_id,
+        token_account,
+        latest_blockhash(&_setup).await,
+        mints.clone(),
+        token_accounts.clone(),
+    );
+    assert!(process(transaction, &_setup).await.is_ok());
+
+    let transaction = redeem_transaction(
+        1,
+        payer,
+        _setup.program_id,
+        index_id,
+        controller_id,
+        token_account,
+        latest_blockhash(&_setup).await,
+        mints,
+        token_accounts,
+    );
+    let mut message = transaction.message;
+    message.instructions[0].data = borsh::to_vec(&ProtocolInstruction::Redeem {
+        index_id,
+        amount: 1,
+        expected_version: None,
+        min_amounts_out: None,
+        recipient: Some(Pubkey::new_unique()),
+        create_destination_accounts: true,
+        unwrap_native: true,
+    })
+    .unwrap();
+    let blockhash = message.recent_blockhash;
+    let transaction = Transaction::new(&[payer], message, blockhash);
+
+    assert_eq!(
+        process(transaction, &_setup).await.unwrap_err(),
+        TransactionError::InstructionError(
+            0,
+            InstructionError::Custom(ProtocolError::InvalidUnwrapRecipient as u32)
+        )
+    );
+}
@@ -0,0 +1,219 @@
+use openindex::state::Component;
+use openindex_sdk::openindex::{
+    error::ProtocolError,
+    instruction::UnderlyingIndex,
+    pda::{
+        find_component_address, find_component_vault_address, find_controller_address,
+        find_index_address, find_index_mint_address,
+    },
+    transaction::{
+        add_nested_index_components_transaction, create_mint_acccount_transaction,
+        create_token_account_transaction, mint_to_transaction, mint_transaction,
+        redeem_underlying_transaction,
+    },
+};
+use solana_program_test::tokio;
+use solana_sdk::{
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+};
+use spl_associated_token_account::get_associated_token_address;
+
+use crate::{
+    custom_error, latest_blockhash, process, process_add_index_components,
+    process_controller_global_config, process_create_index, process_init_controller, process_mint,
+    setup, token_balance, ProcessAddIndexComponentsResult, ProcessControllerGlobalConfigResult,
+    ProcessCreateIndexResult, ProcessInitControllerResult, ProcessMintResult, Setup,
+};
+
+/// Creates a controller with an inner index of two components with units
+/// `[1, 2]`, mints ten inner index tokens to the setup payer and creates an
+/// empty outer index. Returns the controller, the inner index result and its
+/// component mints, and the outer index id.
+async fn setup_indexes(_setup: &Setup) -> (u64, ProcessMintResult, Vec<Pubkey>, u64) {
+    let ProcessControllerGlobalConfigResult { result } =
+        process_controller_global_config(10, _setup).await;
+    assert!(result.is_ok());
+
+    let ProcessInitControllerResult { controller_id, .. } = process_init_controller(_setup).await;
+
+    let ProcessCreateIndexResult {
+        index_id, result, ..
+    } = process_create_index(controller_id, _setup.payer.pubkey(), _setup).await;
+    assert!(result.is_ok());
+
+    let ProcessAddIndexComponentsResult { mints, result, .. } = process_add_index_components(
+        index_id,
+        controller_id,
+        _setup.payer.pubkey(),
+        2,
+        vec![1, 2],
+        _setup,
+    )
+    .await;
+    assert!(result.is_ok());
+
+    let inner = process_mint(10, 100, controller_id, index_id, mints.clone(), _setup).await;
+    assert!(inner.result.is_ok());
+
+    let ProcessCreateIndexResult {
+        index_id: outer_index_id,
+        result,
+        ..
+    } = process_create_index(controller_id, _setup.payer.pubkey(), _setup).await;
+    assert!(result.is_ok());
+
+    (controller_id, inner, mints, outer_index_id)
+}
+
+/// Creates a mint and funds the setup payer's token account with 100 tokens.
+async fn create_funded_mint(_setup: &Setup) -> (Pubkey, Pubkey) {
+    let payer = &_setup.payer;
+    let mint = Keypair::new();
+    let transaction = create_mint_acccount_transaction(
+        payer,
+        &mint,
+        _setup.recent_blockhashes().await,
+        &_setup.rent,
+    );
+    assert!(process(transaction, _setup).await.is_ok());
+
+    let transaction = create_token_account_transaction(
+        payer,
+        payer.pubkey(),
+        payer.pubkey(),
+        mint.pubkey(),
+        _setup.recent_blockhashes().await,
+    );
+    assert!(process(transaction, _setup).await.is_ok());
+
+    let token_account = get_associated_token_address(&payer.pubkey(), &mint.pubkey());
+    let transaction = mint_to_transaction(
+        payer,
+        100,
+        mint.pubkey(),
+        token_account,
+        _setup.recent_blockhashes().await,
+    )
+    .unwrap();
+    assert!(process(transaction, _setup).await.is_ok());
+
+    (mint.pubkey(), token_account)
+}
+
+#[tokio::test]
+async fn test_redeem_underlying() {
+    let _setup: Setup = setup().await;
+    let program_id = _setup.program_id;
+    let payer = &_setup.payer;
+    let (controller_id, inner, inner_mints, outer_index_id) = setup_indexes(&_setup).await;
+    let (plain_mint, plain_token_account) = create_funded_mint(&_setup).await;
+
+    let controller_pda = find_controller_address(&program_id, controller_id).0;
+    let inner_index = find_index_address(&program_id, &controller_pda, inner.index_id).0;
+    let inner_mint = find_index_mint_address(&program_id, &controller_pda, inner.index_id).0;
+    let outer_index = find_index_address(&program_id, &controller_pda, outer_index_id).0;
+    let outer_mint = find_index_mint_address(&program_id, &controller_pda, outer_index_id).0;
+
+    let outer_mints = vec![inner_mint, plain_mint];
+    let transaction = add_nested_index_components_transaction(
+        payer,
+        payer,
+        program_id,
+        outer_index_id,
+        controller_id,
+        _setup.recent_blockhashes().await,
+        outer_mints.clone(),
+        vec![2, 3],
+        vec![Some((controller_id, inner.index_id)), None],
+    );
+    assert!(process(transaction, &_setup).await.is_ok());
+
+    let component_pda = find_component_address(&program_id, &outer_index, &inner_mint).0;
+    let account = _setup
+        .banks_client
+        .get_account(component_pda)
+        .await
+        .unwrap()
+        .unwrap();
+    let component = Component::unpack(&account.data).unwrap();
+    assert_eq!(component.underlying_index, inner_index);
+
+    let transaction = create_token_account_transaction(
+        payer,
+        payer.pubkey(),
+        payer.pubkey(),
+        outer_mint,
+        _setup.recent_blockhashes().await,
+    );
+    assert!(process(transaction, &_setup).await.is_ok());
+    let outer_token_account = get_associated_token_address(&payer.pubkey(), &outer_mint);
+
+    // nested components are deposited like any other SPL token
+    let transaction = mint_transaction(
+        2,
+        payer,
+        program_id,
+        outer_index_id,
+        controller_id,
+        outer_token_account,
+        latest_blockhash(&_setup).await,
+        outer_mints.clone(),
+        vec![inner.token_account, plain_token_account],
+    );
+    assert!(process(transaction, &_setup).await.is_ok());
+    assert_eq!(token_balance(inner.token_account, &_setup).await, 6);
+    assert_eq!(token_balance(plain_token_account, &_setup).await, 94);
+
+    let transaction = redeem_underlying_transaction(
+        1,
+        payer,
+        program_id,
+        outer_index_id,
+        controller_id,
+        latest_blockhash(&_setup).await,
+        outer_mints,
+        vec![UnderlyingIndex {
+            controller_account: controller_pda,
+            index_id: inner.index_id,
+            mints: inner_mints,
+            underlying: vec![],
+        }],
+    );
+    assert!(process(transaction, &_setup).await.is_ok());
+
+    assert_eq!(token_balance(outer_token_account, &_setup).await, 1);
+    assert_eq!(token_balance(plain_token_account, &_setup).await, 97);
+
+    // the two inner index tokens paid out were redeemed on the spot
+    let inner_vault = find_component_vault_address(&program_id, &outer_index, &inner_mint).0;
+    let inner_vault_ata = get_associated_token_address(&inner_vault, &inner_mint);
+    assert_eq!(token_balance(inner_vault_ata, &_setup).await, 2);
+    assert_eq!(token_balance(inner.token_account, &_setup).await, 6);
+    assert_eq!(token_balance(inner.token_accounts[0], &_setup).await, 92);
+    assert_eq!(token_balance(inner.token_accounts[1], &_setup).await, 84);
+}
+
+#[tokio::test]
+async fn test_nested_component_must_be_underlying_index_mint() {
+    let _setup: Setup = setup().await;
+    let payer = &_setup.payer;
+    let (controller_id, inner, _, outer_index_id) = setup_indexes(&_setup).await;
+    let (plain_mint, _) = create_funded_mint(&_setup).await;
+
+    let transaction = add_nested_index_components_transaction(
+        payer,
+        payer,
+        _setup.program_id,
+        outer_index_id,
+        controller_id,
+        _setup.recent_blockhashes().await,
+        vec![plain_mint],
+        vec![1],
+        vec![Some((controller_id, inner.index_id))],
+    );
+    assert_eq!(
+        process(transaction, &_setup).await.unwrap_err(),
+        custom_error(ProtocolError::InvalidUnderlyingIndex)
+    );
+}
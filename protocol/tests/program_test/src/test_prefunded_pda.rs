@@ -0,0 +1,235 @@
+use borsh::BorshDeserialize;
+use openindex::state::{Component, Controller, Index, IndexMints, Module, Protocol};
+use openindex_sdk::openindex::{
+    pda::{
+        find_component_address, find_component_vault_address, find_controller_address,
+        find_controller_global_config_address, find_index_address, find_index_mint_address,
+        find_index_mints_data_address, find_mint_request_address, find_module_signer_address,
+        find_protocol_address, find_redeem_request_address, find_registered_module_address,
+    },
+    transaction::{
+        add_index_components_transaction, begin_mint_transaction, begin_redeem_transaction,
+        create_mint_acccount_transaction,
+    },
+};
+use solana_program_test::tokio;
+use solana_sdk::{
+    native_token::LAMPORTS_PER_SOL, program_pack::IsInitialized, pubkey::Pubkey,
+    signature::Keypair, signer::Signer, system_instruction, transaction::Transaction,
+};
+use spl_associated_token_account::get_associated_token_address;
+
+use crate::{
+    process_controller_global_config, process_create_index, process_init_controller,
+    process_init_module, process_init_protocol, process_mint, setup, ProcessCreateIndexResult,
+    ProcessInitControllerResult, ProcessInitModuleResult, ProcessMintResult, Setup,
+};
+
+/// Sends `lamports` to every address in `addresses`, as anyone could do for
+/// a predictable PDA before the program creates it.
+async fn prefund(_setup: &Setup, addresses: &[Pubkey], lamports: u64) {
+    let instructions: Vec<_> = addresses
+        .iter()
+        .map(|address| system_instruction::transfer(&_setup.payer.pubkey(), address, lamports))
+        .collect();
+
+    let transaction = Transaction::new_signed_with_payer(
+        &instructions,
+        Some(&_setup.payer.pubkey()),
+        &[&_setup.payer],
+        _setup.recent_blockhashes().await,
+    );
+
+    let result = _setup.banks_client.process_transaction(transaction).await;
+    assert!(result.is_ok());
+}
+
+async fn get_account_data(_setup: &Setup, address: Pubkey) -> Vec<u8> {
+    let account = _setup
+        .banks_client
+        .get_account(address)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(account.owner, _setup.program_id);
+    account.data
+}
+
+#[tokio::test]
+async fn test_prefunded_pda() {
+    let _setup: Setup = setup().await;
+    let manager = Keypair::new();
+    let program_id = _setup.program_id;
+
+    // below the rent exempt minimum of the created accounts: needs a top-up
+    let dust = _setup.rent.minimum_balance(0);
+
+    let protocol_pda = find_protocol_address(&program_id).0;
+    // above the rent exempt minimum: no top-up needed
+    prefund(&_setup, &[protocol_pda], LAMPORTS_PER_SOL).await;
+
+    let result = process_init_protocol(&_setup).await.result;
+    assert!(result.is_ok());
+    let protocol =
+        Protocol::try_from_slice(&get_account_data(&_setup, protocol_pda).await).unwrap();
+    assert!(protocol.is_initialized());
+
+    let controller_global_config_pda = find_controller_global_config_address(&program_id).0;
+    prefund(&_setup, &[controller_global_config_pda], dust).await;
+
+    let result = process_controller_global_config(10, &_setup).await.result;
+    assert!(result.is_ok());
+
+    let controller_pda = find_controller_address(&program_id, protocol.next_controller_id).0;
+    prefund(&_setup, &[controller_pda], dust).await;
+
+    let ProcessInitControllerResult {
+        controller_id,
+        controller_pda: _,
+        result,
+    } = process_init_controller(&_setup).await;
+    assert!(result.is_ok());
+    let controller =
+        Controller::try_from_slice(&get_account_data(&_setup, controller_pda).await).unwrap();
+
+    let index_id = controller.next_index_id;
+    let index_pda = find_index_address(&program_id, &controller_pda, index_id).0;
+    let index_mint_pda = find_index_mint_address(&program_id, &controller_pda, index_id).0;
+    prefund(&_setup, &[index_pda, index_mint_pda], dust).await;
+
+    let ProcessCreateIndexResult {
+        index_id,
+        controller_pda: _,
+        result,
+    } = process_create_index(controller_id, manager.pubkey(), &_setup).await;
+    assert!(result.is_ok());
+    let index = Index::try_from_slice(&get_account_data(&_setup, index_pda).await).unwrap();
+    assert!(index.is_initialized());
+
+    let mut mints = vec![];
+    for _ in 0..2 {
+        let mint = Keypair::new();
+        let create_mint_tx = create_mint_acccount_transaction(
+            &_setup.payer,
+            &mint,
+            _setup.recent_blockhashes().await,
+            &_setup.rent,
+        );
+        let result = _setup
+            .banks_client
+            .process_transaction(create_mint_tx)
+            .await;
+        assert!(result.is_ok());
+        mints.push(mint.pubkey());
+    }
+
+    let mut component_addresses = vec![];
+    for mint in mints.iter() {
+        let component_pda = find_component_address(&program_id, &index_pda, mint).0;
+        let vault_pda = find_component_vault_address(&program_id, &index_pda, mint).0;
+        component_addresses.push(component_pda);
+        component_addresses.push(get_associated_token_address(&vault_pda, mint));
+    }
+    component_addresses
+        .push(find_index_mints_data_address(&program_id, &controller_pda, index_id).0);
+    prefund(&_setup, &component_addresses, dust).await;
+
+    let add_components_tx = add_index_components_transaction(
+        &_setup.payer,
+        &_setup.payer,
+        program_id,
+        index_id,
+        controller_id,
+        _setup.recent_blockhashes().await,
+        mints.clone(),
+        vec![1, 2],
+    );
+    let result = _setup
+        .banks_client
+        .process_transaction(add_components_tx)
+        .await;
+    assert!(result.is_ok());
+
+    for mint in mints.iter() {
+        let component_pda = find_component_address(&program_id, &index_pda, mint).0;
+        let component =
+            Component::try_from_slice(&get_account_data(&_setup, component_pda).await).unwrap();
+        assert!(component.is_initialized());
+    }
+    let index_mints_pda = find_index_mints_data_address(&program_id, &controller_pda, index_id).0;
+    let index_mints =
+        IndexMints::try_from_slice(&get_account_data(&_setup, index_mints_pda).await).unwrap();
+    assert_eq!(index_mints.mints, mints);
+
+    let ProcessMintResult {
+        index_id,
+        controller_id,
+        token_account,
+        token_accounts: _,
+        result,
+    } = process_mint(10, 1_000, controller_id, index_id, mints.clone(), &_setup).await;
+    assert!(result.is_ok());
+
+    let mint_request_pda =
+        find_mint_request_address(&program_id, &index_pda, &_setup.payer.pubkey()).0;
+    let redeem_request_pda =
+        find_redeem_request_address(&program_id, &index_pda, &_setup.payer.pubkey()).0;
+    prefund(&_setup, &[mint_request_pda, redeem_request_pda], dust).await;
+
+    let begin_mint_tx = begin_mint_transaction(
+        1,
+        &_setup.payer,
+        program_id,
+        index_id,
+        controller_id,
+        _setup.recent_blockhashes().await,
+    );
+    let result = _setup.banks_client.process_transaction(begin_mint_tx).await;
+    assert!(result.is_ok());
+    get_account_data(&_setup, mint_request_pda).await;
+
+    let begin_redeem_tx = begin_redeem_transaction(
+        1,
+        &_setup.payer,
+        program_id,
+        index_id,
+        controller_id,
+        token_account,
+        _setup.recent_blockhashes().await,
+        mints.clone(),
+    );
+    let result = _setup
+        .banks_client
+        .process_transaction(begin_redeem_tx)
+        .await;
+    assert!(result.is_ok());
+    get_account_data(&_setup, redeem_request_pda).await;
+}
+
+#[tokio::test]
+async fn test_prefunded_module_pda() {
+    let _setup: Setup = setup().await;
+
+    let _ = process_init_protocol(&_setup).await;
+
+    let module_signer_pda = find_module_signer_address(&_setup.issuance_program_id).0;
+    let registered_module_pda =
+        find_registered_module_address(&_setup.program_id, &module_signer_pda).0;
+    prefund(
+        &_setup,
+        &[registered_module_pda],
+        _setup.rent.minimum_balance(0),
+    )
+    .await;
+
+    let ProcessInitModuleResult {
+        registered_module_pda,
+        module_signer_pda: _,
+        result,
+    } = process_init_module(_setup.issuance_program_id, &_setup).await;
+    assert!(result.is_ok());
+
+    let module =
+        Module::try_from_slice(&get_account_data(&_setup, registered_module_pda).await).unwrap();
+    assert!(module.is_initialized());
+}
@@ -0,0 +1,186 @@
+use openindex_sdk::openindex::{
+    error::ProtocolError,
+    transaction::{redeem_transaction, redeem_with_recipient_transaction},
+};
+use solana_program_test::tokio;
+
+use solana_sdk::{
+    hash::Hash,
+    instruction::InstructionError,
+    native_token::LAMPORTS_PER_SOL,
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+    system_instruction,
+    transaction::{Transaction, TransactionError},
+};
+use spl_associated_token_account::get_associated_token_address_with_program_id;
+
+use crate::{
+    custom_error, latest_blockhash, new_blockhash, process, setup, setup_minted_index,
+    token_balance, ProcessMintResult, Setup,
+};
+
+#[tokio::test]
+async fn test_redeem_to_recipient() {
+    let _setup: Setup = setup().await;
+    let program_id = _setup.program_id;
+    let (
+        ProcessMintResult {
+            index_id,
+            controller_id,
+            token_account,
+            token_accounts,
+            ..
+        },
+        mints,
+    ) = setup_minted_index(5, &_setup).await;
+
+    // destinations must be the recipient's ATAs for the matching mint
+    let transaction = redeem_transaction(
+        1,
+        &_setup.payer,
+        program_id,
+        index_id,
+        controller_id,
+        token_account,
+        latest_blockhash(&_setup).await,
+        mints.clone(),
+        vec![token_accounts[1], token_accounts[0]],
+    );
+    assert_eq!(
+        process(transaction, &_setup).await.unwrap_err(),
+        custom_error(ProtocolError::IncorrectDestinationAccount)
+    );
+
+    let recipient = Keypair::new();
+    let redeem = |create_destination_accounts: bool, blockhash: Hash| {
+        redeem_with_recipient_transaction(
+            2,
+            &_setup.payer,
+            program_id,
+            index_id,
+            controller_id,
+            token_account,
+            recipient.pubkey(),
+            create_destination_accounts,
+            blockhash,
+            mints.clone(),
+        )
+    };
+
+    let blockhash = latest_blockhash(&_setup).await;
+    assert_eq!(
+        process(redeem(false, blockhash), &_setup)
+            .await
+            .unwrap_err(),
+        TransactionError::InstructionError(0, InstructionError::InvalidAccountOwner)
+    );
+
+    assert!(process(redeem(true, blockhash), &_setup).await.is_ok());
+
+    // existing destinations are reused
+    let blockhash = new_blockhash(&_setup, &blockhash).await;
+    assert!(process(redeem(true, blockhash), &_setup).await.is_ok());
+
+    let recipient_token_accounts: Vec<Pubkey> = mints
+        .iter()
+        .map(|mint| {
+            get_associated_token_address_with_program_id(&recipient.pubkey(), mint, &spl_token::ID)
+        })
+        .collect();
+    assert_eq!(token_balance(recipient_token_accounts[0], &_setup).await, 4);
+    assert_eq!(token_balance(recipient_token_accounts[1], &_setup).await, 8);
+    assert_eq!(token_balance(token_account, &_setup).await, 1);
+}
+
+#[tokio::test]
+async fn test_redeem_from_delegate() {
+    let _setup: Setup = setup().await;
+    let program_id = _setup.program_id;
+    let (
+        ProcessMintResult {
+            index_id,
+            controller_id,
+            token_account,
+            token_accounts,
+            ..
+        },
+        mints,
+    ) = setup_minted_index(5, &_setup).await;
+
+    // the delegate burns the setup payer's index tokens and pays fees only
+    let delegate = Keypair::new();
+    let transaction = Transaction::new_signed_with_payer(
+        &[system_instruction::transfer(
+            &_setup.payer.pubkey(),
+            &delegate.pubkey(),
+            LAMPORTS_PER_SOL,
+        )],
+        Some(&_setup.payer.pubkey()),
+        &[&_setup.payer],
+        _setup.recent_blockhashes().await,
+    );
+    assert!(process(transaction, &_setup).await.is_ok());
+
+    let approve = |allowance: u64, blockhash: Hash| {
+        Transaction::new_signed_with_payer(
+            &[spl_token::instruction::approve(
+                &spl_token::ID,
+                &token_account,
+                &delegate.pubkey(),
+                &_setup.payer.pubkey(),
+                &[],
+                allowance,
+            )
+            .unwrap()],
+            Some(&_setup.payer.pubkey()),
+            &[&_setup.payer],
+            blockhash,
+        )
+    };
+    let redeem = |blockhash: Hash| {
+        redeem_with_recipient_transaction(
+            2,
+            &delegate,
+            program_id,
+            index_id,
+            controller_id,
+            token_account,
+            _setup.payer.pubkey(),
+            false,
+            blockhash,
+            mints.clone(),
+        )
+    };
+
+    let redeem_blockhash = latest_blockhash(&_setup).await;
+    assert_eq!(
+        process(redeem(redeem_blockhash), &_setup)
+            .await
+            .unwrap_err(),
+        custom_error(ProtocolError::InvalidTokenAccountAuthority)
+    );
+
+    let approve_blockhash = latest_blockhash(&_setup).await;
+    assert!(process(approve(1, approve_blockhash), &_setup)
+        .await
+        .is_ok());
+    let redeem_blockhash = new_blockhash(&_setup, &redeem_blockhash).await;
+    assert_eq!(
+        process(redeem(redeem_blockhash), &_setup)
+            .await
+            .unwrap_err(),
+        custom_error(ProtocolError::InsufficientDelegatedAmount)
+    );
+
+    let approve_blockhash = new_blockhash(&_setup, &approve_blockhash).await;
+    assert!(process(approve(2, approve_blockhash), &_setup)
+        .await
+        .is_ok());
+    let redeem_blockhash = new_blockhash(&_setup, &redeem_blockhash).await;
+    assert!(process(redeem(redeem_blockhash), &_setup).await.is_ok());
+
+    assert_eq!(token_balance(token_account, &_setup).await, 3);
+    assert_eq!(token_balance(token_accounts[0], &_setup).await, 97);
+    assert_eq!(token_balance(token_accounts[1], &_setup).await, 94);
+}
@@ -0,0 +1,174 @@
+use borsh::BorshDeserialize;
+use openindex::state::{Component, Controller, Index, IndexMints, Protocol};
+use openindex_sdk::openindex::{
+    instruction::init_controller_instruction,
+    pda::{
+        find_component_address, find_controller_address, find_index_address,
+        find_index_mints_data_address, find_protocol_address,
+    },
+    transaction::{
+        add_index_components_transaction, create_index_transaction,
+        create_mint_acccount_transaction, init_controller_transaction,
+    },
+};
+use solana_program_test::tokio;
+use solana_sdk::{
+    instruction::InstructionError,
+    signature::{Keypair, Signer},
+    transaction::{Transaction, TransactionError},
+};
+
+use crate::{process_controller_global_config, setup, ProcessControllerGlobalConfigResult, Setup};
+
+async fn next_controller_id(_setup: &Setup) -> u64 {
+    let protocol_pda = find_protocol_address(&_setup.program_id).0;
+    let account = _setup
+        .banks_client
+        .get_account(protocol_pda)
+        .await
+        .unwrap()
+        .unwrap();
+    Protocol::try_from_slice(&account.data)
+        .unwrap()
+        .get_next_controller_id()
+}
+
+#[tokio::test]
+async fn test_unfunded_authority_with_separate_payer() {
+    let _setup: Setup = setup().await;
+    let program_id = _setup.program_id;
+
+    let ProcessControllerGlobalConfigResult { result } =
+        process_controller_global_config(10, &_setup).await;
+    assert!(result.is_ok());
+
+    // the authority never holds lamports; the setup payer funds everything
+    let authority = Keypair::new();
+
+    let controller_id = next_controller_id(&_setup).await;
+    let transaction = init_controller_transaction(
+        &_setup.payer,
+        &authority,
+        program_id,
+        controller_id,
+        _setup.recent_blockhashes().await,
+    );
+    let result = _setup.banks_client.process_transaction(transaction).await;
+    assert!(result.is_ok());
+
+    let controller_pda = find_controller_address(&program_id, controller_id).0;
+    let account = _setup
+        .banks_client
+        .get_account(controller_pda)
+        .await
+        .unwrap()
+        .unwrap();
+    let controller = Controller::try_from_slice(&account.data).unwrap();
+    assert_eq!(controller.owner, authority.pubkey());
+
+    let index_id = controller.next_index_id;
+    let transaction = create_index_transaction(
+        &_setup.payer,
+        &authority,
+        program_id,
+        index_id,
+        controller_id,
+        authority.pubkey(),
+        _setup.recent_blockhashes().await,
+    );
+    let result = _setup.banks_client.process_transaction(transaction).await;
+    assert!(result.is_ok());
+
+    let index_pda = find_index_address(&program_id, &controller_pda, index_id).0;
+    let account = _setup
+        .banks_client
+        .get_account(index_pda)
+        .await
+        .unwrap()
+        .unwrap();
+    let index = Index::unpack(&account.data).unwrap();
+    assert_eq!(index.owner, authority.pubkey());
+
+    let mint = Keypair::new();
+    let transaction = create_mint_acccount_transaction(
+        &_setup.payer,
+        &mint,
+        _setup.recent_blockhashes().await,
+        &_setup.rent,
+    );
+    let result = _setup.banks_client.process_transaction(transaction).await;
+    assert!(result.is_ok());
+
+    let transaction = add_index_components_transaction(
+        &_setup.payer,
+        &authority,
+        program_id,
+        index_id,
+        controller_id,
+        _setup.recent_blockhashes().await,
+        vec![mint.pubkey()],
+        vec![7],
+    );
+    let result = _setup.banks_client.process_transaction(transaction).await;
+    assert!(result.is_ok());
+
+    let index_mints_pda = find_index_mints_data_address(&program_id, &controller_pda, index_id).0;
+    let account = _setup
+        .banks_client
+        .get_account(index_mints_pda)
+        .await
+        .unwrap()
+        .unwrap();
+    let index_mints = IndexMints::try_from_slice(&account.data).unwrap();
+    assert_eq!(index_mints.mints, vec![mint.pubkey()]);
+
+    let component_pda = find_component_address(&program_id, &index_pda, &mint.pubkey()).0;
+    let account = _setup
+        .banks_client
+        .get_account(component_pda)
+        .await
+        .unwrap()
+        .unwrap();
+    let component = Component::try_from_slice(&account.data).unwrap();
+    assert_eq!(component.uints, 7);
+
+    let balance = _setup
+        .banks_client
+        .get_balance(authority.pubkey())
+        .await
+        .unwrap();
+    assert_eq!(balance, 0);
+}
+
+#[tokio::test]
+async fn test_payer_must_sign() {
+    let _setup: Setup = setup().await;
+    let program_id = _setup.program_id;
+
+    let ProcessControllerGlobalConfigResult { result } =
+        process_controller_global_config(10, &_setup).await;
+    assert!(result.is_ok());
+
+    let controller_id = next_controller_id(&_setup).await;
+    let payer = Keypair::new();
+    let mut instruction = init_controller_instruction(
+        program_id,
+        _setup.payer.pubkey(),
+        payer.pubkey(),
+        find_protocol_address(&program_id).0,
+        find_controller_address(&program_id, controller_id).0,
+    );
+    instruction.accounts[1].is_signer = false;
+
+    let transaction = Transaction::new_signed_with_payer(
+        &[instruction],
+        Some(&_setup.payer.pubkey()),
+        &[&_setup.payer],
+        _setup.recent_blockhashes().await,
+    );
+    let result = _setup.banks_client.process_transaction(transaction).await;
+    assert_eq!(
+        result.unwrap_err().unwrap(),
+        TransactionError::InstructionError(0, InstructionError::MissingRequiredSignature)
+    );
+}
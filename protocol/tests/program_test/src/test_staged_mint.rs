@@ -0,0 +1,519 @@
+use openindex_sdk::openindex::{
+    error::ProtocolError,
+    pda::{find_controller_address, find_index_address, find_mint_request_address},
+    transaction::{
+        begin_mint_transaction, cancel_mint_transaction, deposit_components_transaction,
+        execute_composition_change_transaction, finalize_mint_transaction,
+        queue_composition_change_transaction,
+    },
+};
+use solana_program_test::tokio;
+use solana_sdk::{
+    instruction::InstructionError, signature::Keypair, signer::Signer,
+    transaction::TransactionError,
+};
+
+use crate::{
+    process_add_index_components, process_controller_global_config, process_create_index,
+    process_init_controller, process_init_protocol, process_mint, process_redeem, setup,
+    token_balance, ProcessAddIndexComponentsResult, ProcessCreateIndexResult,
+    ProcessInitControllerResult, ProcessMintResult, ProcessRedeemResult, Setup,
+};
+
+#[tokio::test]
+async fn test_staged_mint() {
+    let _setup: Setup = setup().await;
+    let manager = Keypair::new();
+
+    let _ = process_init_protocol(&_setup).await;
+
+    let _ = process_controller_global_config(10, &_setup).await;
+
+    let ProcessInitControllerResult {
+        controller_id,
+        controller_pda: _,
+        result: _,
+    } = process_init_controller(&_setup).await;
+
+    let ProcessCreateIndexResult {
+        index_id,
+        controller_pda: _,
+        result: _,
+    } = process_create_index(controller_id, manager.pubkey(), &_setup).await;
+
+    let ProcessAddIndexComponentsResult {
+        index_id,
+        controller_id,
+        mints,
+        units,
+        result,
+    } = process_add_index_components(
+        index_id,
+        controller_id,
+        manager.pubkey(),
+        4,
+        vec![1, 2, 3, 4],
+        &_setup,
+    )
+    .await;
+    assert!(result.is_ok());
+
+    // regular mint creates and funds the user's token accounts
+    let ProcessMintResult {
+        index_id,
+        controller_id,
+        token_account,
+        token_accounts,
+        result,
+    } = process_mint(
+        10,
+        100_000_000,
+        controller_id,
+        index_id,
+        mints.clone(),
+        &_setup,
+    )
+    .await;
+    assert!(result.is_ok());
+
+    let controller_pda = find_controller_address(&_setup.program_id, controller_id).0;
+    let index_pda = find_index_address(&_setup.program_id, &controller_pda, index_id).0;
+    let mint_request_pda =
+        find_mint_request_address(&_setup.program_id, &index_pda, &_setup.payer.pubkey()).0;
+
+    let mut balances_before = vec![];
+    for token_account in token_accounts.iter() {
+        balances_before.push(token_balance(*token_account, &_setup).await);
+    }
+
+    let begin_tx = begin_mint_transaction(
+        5,
+        &_setup.payer,
+        _setup.program_id,
+        index_id,
+        controller_id,
+        _setup.recent_blockhashes().await,
+    );
+    let result = _setup.banks_client.process_transaction(begin_tx).await;
+    assert!(result.is_ok());
+
+    // first batch of components
+    let deposit_tx = deposit_components_transaction(
+        &_setup.payer,
+        _setup.program_id,
+        index_id,
+        controller_id,
+        _setup.recent_blockhashes().await,
+        mints[..2].to_vec(),
+        token_accounts[..2].to_vec(),
+    );
+    let result = _setup.banks_client.process_transaction(deposit_tx).await;
+    assert!(result.is_ok());
+
+    // finalizing before every component is deposited must fail
+    // simulated and metadata transactions are checked against the working
+    // bank, whose blockhash queue may have moved past the genesis blockhash
+    let latest_blockhash = _setup.banks_client.get_latest_blockhash().await.unwrap();
+    let finalize_tx = finalize_mint_transaction(
+        &_setup.payer,
+        _setup.program_id,
+        index_id,
+        controller_id,
+        token_account,
+        latest_blockhash,
+    );
+    let simulation = _setup
+        .banks_client
+        .simulate_transaction(finalize_tx)
+        .await
+        .unwrap();
+    assert_eq!(
+        simulation.result.unwrap().unwrap_err(),
+        TransactionError::InstructionError(
+            0,
+            InstructionError::Custom(ProtocolError::MintRequestIncomplete as u32)
+        )
+    );
+
+    // depositing the same component twice must fail
+    let latest_blockhash = _setup.banks_client.get_latest_blockhash().await.unwrap();
+    let deposit_again_tx = deposit_components_transaction(
+        &_setup.payer,
+        _setup.program_id,
+        index_id,
+        controller_id,
+        latest_blockhash,
+        mints[..1].to_vec(),
+        token_accounts[..1].to_vec(),
+    );
+    let simulation = _setup
+        .banks_client
+        .simulate_transaction(deposit_again_tx)
+        .await
+        .unwrap();
+    assert_eq!(
+        simulation.result.unwrap().unwrap_err(),
+        TransactionError::InstructionError(
+            0,
+            InstructionError::Custom(ProtocolError::ComponentAlreadyDeposited as u32)
+        )
+    );
+
+    // second batch of components
+    let deposit_tx = deposit_components_transaction(
+        &_setup.payer,
+        _setup.program_id,
+        index_id,
+        controller_id,
+        _setup.recent_blockhashes().await,
+        mints[2..].to_vec(),
+        token_accounts[2..].to_vec(),
+    );
+    let result = _setup.banks_client.process_transaction(deposit_tx).await;
+    assert!(result.is_ok());
+
+    let finalize_tx = finalize_mint_transaction(
+        &_setup.payer,
+        _setup.program_id,
+        index_id,
+        controller_id,
+        token_account,
+        _setup.recent_blockhashes().await,
+    );
+    let result = _setup.banks_client.process_transaction(finalize_tx).await;
+    assert!(result.is_ok());
+
+    assert_eq!(token_balance(token_account, &_setup).await, 15);
+    for (i, token_account) in token_accounts.iter().enumerate() {
+        assert_eq!(
+            token_balance(*token_account, &_setup).await,
+            balances_before[i] - 5 * units[i]
+        );
+    }
+
+    let mint_request = _setup
+        .banks_client
+        .get_account(mint_request_pda)
+        .await
+        .unwrap();
+    assert!(mint_request.is_none());
+}
+
+#[tokio::test]
+async fn test_staged_mint_cancel() {
+    let _setup: Setup = setup().await;
+    let manager = Keypair::new();
+
+    let _ = process_init_protocol(&_setup).await;
+
+    let _ = process_controller_global_config(10, &_setup).await;
+
+    let ProcessInitControllerResult {
+        controller_id,
+        controller_pda: _,
+        result: _,
+    } = process_init_controller(&_setup).await;
+
+    let ProcessCreateIndexResult {
+        index_id,
+        controller_pda: _,
+        result: _,
+    } = process_create_index(controller_id, manager.pubkey(), &_setup).await;
+
+    let ProcessAddIndexComponentsResult {
+        index_id,
+        controller_id,
+        mints,
+        units: _,
+        result,
+    } = process_add_index_components(
+        index_id,
+        controller_id,
+        manager.pubkey(),
+        3,
+        vec![1, 2, 3],
+        &_setup,
+    )
+    .await;
+    assert!(result.is_ok());
+
+    let ProcessMintResult {
+        index_id,
+        controller_id,
+        token_account: _,
+        token_accounts,
+        result,
+    } = process_mint(
+        10,
+        100_000_000,
+        controller_id,
+        index_id,
+        mints.clone(),
+        &_setup,
+    )
+    .await;
+    assert!(result.is_ok());
+
+    let controller_pda = find_controller_address(&_setup.program_id, controller_id).0;
+    let index_pda = find_index_address(&_setup.program_id, &controller_pda, index_id).0;
+    let mint_request_pda =
+        find_mint_request_address(&_setup.program_id, &index_pda, &_setup.payer.pubkey()).0;
+
+    let mut balances_before = vec![];
+    for token_account in token_accounts.iter() {
+        balances_before.push(token_balance(*token_account, &_setup).await);
+    }
+
+    let begin_tx = begin_mint_transaction(
+        7,
+        &_setup.payer,
+        _setup.program_id,
+        index_id,
+        controller_id,
+        _setup.recent_blockhashes().await,
+    );
+    let result = _setup.banks_client.process_transaction(begin_tx).await;
+    assert!(result.is_ok());
+
+    let deposit_tx = deposit_components_transaction(
+        &_setup.payer,
+        _setup.program_id,
+        index_id,
+        controller_id,
+        _setup.recent_blockhashes().await,
+        mints[..2].to_vec(),
+        token_accounts[..2].to_vec(),
+    );
+    let result = _setup.banks_client.process_transaction(deposit_tx).await;
+    assert!(result.is_ok());
+
+    // refunding a component that was never deposited must fail
+    let latest_blockhash = _setup.banks_client.get_latest_blockhash().await.unwrap();
+    let cancel_tx = cancel_mint_transaction(
+        &_setup.payer,
+        _setup.program_id,
+        index_id,
+        controller_id,
+        latest_blockhash,
+        mints[2..].to_vec(),
+        token_accounts[2..].to_vec(),
+    );
+    let simulation = _setup
+        .banks_client
+        .simulate_transaction(cancel_tx)
+        .await
+        .unwrap();
+    assert_eq!(
+        simulation.result.unwrap().unwrap_err(),
+        TransactionError::InstructionError(
+            0,
+            InstructionError::Custom(ProtocolError::ComponentNotDeposited as u32)
+        )
+    );
+
+    // partial refund keeps the request open
+    let cancel_tx = cancel_mint_transaction(
+        &_setup.payer,
+        _setup.program_id,
+        index_id,
+        controller_id,
+        _setup.recent_blockhashes().await,
+        mints[..1].to_vec(),
+        token_accounts[..1].to_vec(),
+    );
+    let result = _setup.banks_client.process_transaction(cancel_tx).await;
+    assert!(result.is_ok());
+
+    let mint_request = _setup
+        .banks_client
+        .get_account(mint_request_pda)
+        .await
+        .unwrap();
+    assert!(mint_request.is_some());
+
+    // refunding the remaining deposit closes the request
+    let cancel_tx = cancel_mint_transaction(
+        &_setup.payer,
+        _setup.program_id,
+        index_id,
+        controller_id,
+        _setup.recent_blockhashes().await,
+        mints[1..2].to_vec(),
+        token_accounts[1..2].to_vec(),
+    );
+    let result = _setup.banks_client.process_transaction(cancel_tx).await;
+    assert!(result.is_ok());
+
+    let mint_request = _setup
+        .banks_client
+        .get_account(mint_request_pda)
+        .await
+        .unwrap();
+    assert!(mint_request.is_none());
+
+    for (i, token_account) in token_accounts.iter().enumerate() {
+        assert_eq!(
+            token_balance(*token_account, &_setup).await,
+            balances_before[i]
+        );
+    }
+}
+
+#[tokio::test]
+async fn test_staged_mint_after_composition_change() {
+    let _setup: Setup = setup().await;
+
+    let _ = process_init_protocol(&_setup).await;
+
+    let _ = process_controller_global_config(10, &_setup).await;
+
+    let ProcessInitControllerResult {
+        controller_id,
+        controller_pda: _,
+        result: _,
+    } = process_init_controller(&_setup).await;
+
+    let ProcessCreateIndexResult {
+        index_id,
+        controller_pda: _,
+        result: _,
+    } = process_create_index(controller_id, _setup.payer.pubkey(), &_setup).await;
+
+    let ProcessAddIndexComponentsResult {
+        index_id,
+        controller_id,
+        mints,
+        units: _,
+        result,
+    } = process_add_index_components(
+        index_id,
+        controller_id,
+        _setup.payer.pubkey(),
+        2,
+        vec![1, 2],
+        &_setup,
+    )
+    .await;
+    assert!(result.is_ok());
+
+    let ProcessMintResult {
+        index_id,
+        controller_id,
+        token_account,
+        token_accounts,
+        result,
+    } = process_mint(
+        10,
+        100_000_000,
+        controller_id,
+        index_id,
+        mints.clone(),
+        &_setup,
+    )
+    .await;
+    assert!(result.is_ok());
+
+    // the composition can only change while no index tokens are outstanding
+    let ProcessRedeemResult { result } = process_redeem(
+        10,
+        index_id,
+        controller_id,
+        token_account,
+        mints.clone(),
+        token_accounts.clone(),
+        &_setup,
+    )
+    .await;
+    assert!(result.is_ok());
+
+    let mut balances_before = vec![];
+    for token_account in token_accounts.iter() {
+        balances_before.push(token_balance(*token_account, &_setup).await);
+    }
+
+    let begin_tx = begin_mint_transaction(
+        7,
+        &_setup.payer,
+        _setup.program_id,
+        index_id,
+        controller_id,
+        _setup.recent_blockhashes().await,
+    );
+    let result = _setup.banks_client.process_transaction(begin_tx).await;
+    assert!(result.is_ok());
+
+    let deposit_tx = deposit_components_transaction(
+        &_setup.payer,
+        _setup.program_id,
+        index_id,
+        controller_id,
+        _setup.recent_blockhashes().await,
+        mints.clone(),
+        token_accounts.clone(),
+    );
+    let result = _setup.banks_client.process_transaction(deposit_tx).await;
+    assert!(result.is_ok());
+
+    // same components with new units, executable right away
+    let queue_tx = queue_composition_change_transaction(
+        &_setup.payer,
+        _setup.program_id,
+        index_id,
+        controller_id,
+        _setup.recent_blockhashes().await,
+        mints.clone(),
+        vec![5, 9],
+    );
+    let result = _setup.banks_client.process_transaction(queue_tx).await;
+    assert!(result.is_ok());
+
+    let execute_tx = execute_composition_change_transaction(
+        &_setup.payer,
+        _setup.program_id,
+        index_id,
+        controller_id,
+        _setup.payer.pubkey(),
+        &mints,
+        _setup.recent_blockhashes().await,
+    );
+    let result = _setup.banks_client.process_transaction(execute_tx).await;
+    assert!(result.is_ok());
+
+    // the deposits were priced at the old units
+    let finalize_tx = finalize_mint_transaction(
+        &_setup.payer,
+        _setup.program_id,
+        index_id,
+        controller_id,
+        token_account,
+        _setup.recent_blockhashes().await,
+    );
+    let result = _setup.banks_client.process_transaction(finalize_tx).await;
+    assert_eq!(
+        result.unwrap_err().unwrap(),
+        TransactionError::InstructionError(
+            0,
+            InstructionError::Custom(ProtocolError::CompositionVersionMismatch as u32)
+        )
+    );
+
+    // cancelling refunds exactly what was deposited
+    let cancel_tx = cancel_mint_transaction(
+        &_setup.payer,
+        _setup.program_id,
+        index_id,
+        controller_id,
+        _setup.recent_blockhashes().await,
+        mints.clone(),
+        token_accounts.clone(),
+    );
+    let result = _setup.banks_client.process_transaction(cancel_tx).await;
+    assert!(result.is_ok());
+
+    for (i, token_account) in token_accounts.iter().enumerate() {
+        assert_eq!(
+            token_balance(*token_account, &_setup).await,
+            balances_before[i]
+        );
+    }
+}
@@ -0,0 +1,571 @@
+use openindex::state::Index;
+use openindex_sdk::openindex::{
+    error::ProtocolError,
+    instruction::{
+        begin_redeem_instruction, create_index_instruction, finalize_mint_instruction,
+        flash_mint_instruction, freeze_holder_instruction, mint_instruction_with_dynamic_accounts,
+        redeem_instruction_with_dynamic_accounts, set_transfer_allowlist_instruction,
+        settle_flash_mint_instruction, thaw_holder_instruction, with_token_2022,
+        with_transfer_hook,
+    },
+    pda::{
+        find_controller_address, find_controller_config_address,
+        find_controller_global_config_address, find_index_address, find_index_mint_address,
+        find_index_mint_authority_address, find_index_mints_data_address,
+        find_transfer_allowlist_address,
+    },
+    transaction::{
+        begin_mint_transaction, create_token_account_transaction, deposit_components_transaction,
+        mint_to_transaction, set_transfer_allowlist_transaction,
+    },
+    transfer_hook::transfer_hook_extra_accounts,
+};
+use openindex_transfer_hook::error::TransferRestrictionError;
+use solana_program_test::tokio;
+use solana_sdk::{
+    instruction::{Instruction, InstructionError},
+    native_token::LAMPORTS_PER_SOL,
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+    system_instruction,
+    transaction::{Transaction, TransactionError},
+};
+use spl_associated_token_account::{
+    get_associated_token_address, get_associated_token_address_with_program_id,
+    instruction::create_associated_token_account,
+};
+use spl_token_2022::{
+    extension::StateWithExtensions,
+    instruction::transfer_checked,
+    state::{Account, AccountState},
+};
+
+use crate::{
+    custom_error, latest_blockhash, process, process_add_index_components,
+    process_controller_global_config, process_create_index, process_init_controller, setup,
+    token_balance, ProcessAddIndexComponentsResult, ProcessControllerGlobalConfigResult,
+    ProcessCreateIndexResult, ProcessInitControllerResult, Setup,
+};
+
+fn restriction_error(error: TransferRestrictionError) -> TransactionError {
+    TransactionError::InstructionError(0, InstructionError::Custom(error as u32))
+}
+
+async fn process_instruction(
+    instruction: Instruction,
+    signers: &[&Keypair],
+    _setup: &Setup,
+) -> Result<(), TransactionError> {
+    let transaction = Transaction::new_signed_with_payer(
+        &[instruction],
+        Some(&_setup.payer.pubkey()),
+        signers,
+        latest_blockhash(_setup).await,
+    );
+    process(transaction, _setup).await
+}
+
+async fn token_account_state(token_account: Pubkey, _setup: &Setup) -> Account {
+    let account = _setup
+        .banks_client
+        .get_account(token_account)
+        .await
+        .unwrap()
+        .unwrap();
+    StateWithExtensions::<Account>::unpack(&account.data)
+        .unwrap()
+        .base
+}
+
+/// Creates a controller with an index of two components with units `[1, 2]`
+/// whose mint is a Token-2022 mint with the transfer hook, and funds the
+/// setup payer with 100 of each component. Returns the controller id, the
+/// index id, the component mints and the payer's component token accounts.
+async fn setup_hooked_index(_setup: &Setup) -> (u64, u64, Vec<Pubkey>, Vec<Pubkey>) {
+    let program_id = _setup.program_id;
+    let payer = &_setup.payer;
+
+    let ProcessControllerGlobalConfigResult { result } =
+        process_controller_global_config(10, _setup).await;
+    assert!(result.is_ok());
+
+    let ProcessInitControllerResult { controller_id, .. } = process_init_controller(_setup).await;
+
+    let controller_pda = find_controller_address(&program_id, controller_id).0;
+    let index_id = 1;
+    let instruction = with_transfer_hook(
+        create_index_instruction(
+            program_id,
+            payer.pubkey(),
+            payer.pubkey(),
+            payer.pubkey(),
+            find_index_address(&program_id, &controller_pda, index_id).0,
+            find_index_mint_address(&program_id, &controller_pda, index_id).0,
+            controller_pda,
+            find_controller_global_config_address(&program_id).0,
+            find_controller_config_address(&program_id, &controller_pda).0,
+        ),
+        _setup.transfer_hook_program_id,
+        find_index_mint_authority_address(&program_id, &controller_pda, index_id).0,
+    );
+    assert!(process_instruction(instruction, &[payer], _setup)
+        .await
+        .is_ok());
+
+    let ProcessAddIndexComponentsResult { mints, result, .. } = process_add_index_components(
+        index_id,
+        controller_id,
+        payer.pubkey(),
+        2,
+        vec![1, 2],
+        _setup,
+    )
+    .await;
+    assert!(result.is_ok());
+
+    let mut token_accounts = vec![];
+    for mint in mints.iter() {
+        let transaction = create_token_account_transaction(
+            payer,
+            payer.pubkey(),
+            payer.pubkey(),
+            *mint,
+            latest_blockhash(_setup).await,
+        );
+        assert!(process(transaction, _setup).await.is_ok());
+
+        let token_account = get_associated_token_address(&payer.pubkey(), mint);
+        let transaction = mint_to_transaction(
+            payer,
+            100,
+            *mint,
+            token_account,
+            latest_blockhash(_setup).await,
+        )
+        .unwrap();
+        assert!(process(transaction, _setup).await.is_ok());
+        token_accounts.push(token_account);
+    }
+
+    (controller_id, index_id, mints, token_accounts)
+}
+
+/// Creates the Token-2022 index token account of `wallet`.
+async fn create_index_token_account(wallet: Pubkey, mint: Pubkey, _setup: &Setup) -> Pubkey {
+    let payer = &_setup.payer;
+    let instruction =
+        create_associated_token_account(&payer.pubkey(), &wallet, &mint, &spl_token_2022::ID);
+    assert!(process_instruction(instruction, &[payer], _setup)
+        .await
+        .is_ok());
+    get_associated_token_address_with_program_id(&wallet, &mint, &spl_token_2022::ID)
+}
+
+/// Mints `amount` index tokens to the setup payer through the staged flow.
+async fn staged_mint(
+    amount: u64,
+    controller_id: u64,
+    index_id: u64,
+    mints: Vec<Pubkey>,
+    token_accounts: Vec<Pubkey>,
+    token_account: Pubkey,
+    _setup: &Setup,
+) {
+    let program_id = _setup.program_id;
+    let payer = &_setup.payer;
+
+    let transaction = begin_mint_transaction(
+        amount,
+        payer,
+        program_id,
+        index_id,
+        controller_id,
+        latest_blockhash(_setup).await,
+    );
+    assert!(process(transaction, _setup).await.is_ok());
+
+    let transaction = deposit_components_transaction(
+        payer,
+        program_id,
+        index_id,
+        controller_id,
+        latest_blockhash(_setup).await,
+        mints,
+        token_accounts,
+    );
+    assert!(process(transaction, _setup).await.is_ok());
+
+    let controller_pda = find_controller_address(&program_id, controller_id).0;
+    let instruction = with_token_2022(finalize_mint_instruction(
+        payer.pubkey(),
+        program_id,
+        controller_pda,
+        find_index_mint_address(&program_id, &controller_pda, index_id).0,
+        find_index_mint_authority_address(&program_id, &controller_pda, index_id).0,
+        find_index_address(&program_id, &controller_pda, index_id).0,
+        token_account,
+        index_id,
+    ));
+    assert!(process_instruction(instruction, &[payer], _setup)
+        .await
+        .is_ok());
+}
+
+#[tokio::test]
+async fn test_transfer_restricted_to_allowlist() {
+    let _setup: Setup = setup().await;
+    let program_id = _setup.program_id;
+    let transfer_hook_program_id = _setup.transfer_hook_program_id;
+    let payer = &_setup.payer;
+    let (controller_id, index_id, mints, token_accounts) = setup_hooked_index(&_setup).await;
+
+    let controller_pda = find_controller_address(&program_id, controller_id).0;
+    let index_pda = find_index_address(&program_id, &controller_pda, index_id).0;
+    let index_mint = find_index_mint_address(&program_id, &controller_pda, index_id).0;
+
+    let account = _setup
+        .banks_client
+        .get_account(index_pda)
+        .await
+        .unwrap()
+        .unwrap();
+    let index = Index::unpack(&account.data).unwrap();
+    assert_eq!(index.transfer_hook_program, transfer_hook_program_id);
+
+    let token_account = create_index_token_account(payer.pubkey(), index_mint, &_setup).await;
+    staged_mint(
+        10,
+        controller_id,
+        index_id,
+        mints.clone(),
+        token_accounts,
+        token_account,
+        &_setup,
+    )
+    .await;
+    assert_eq!(token_balance(token_account, &_setup).await, 10);
+
+    let recipient = Keypair::new();
+    let recipient_token_account =
+        create_index_token_account(recipient.pubkey(), index_mint, &_setup).await;
+
+    // every attempt moves a different amount, so that a rejected transfer
+    // resent within the same blockhash is not an already processed transaction
+    let transfer = |amount| {
+        let mut instruction = transfer_checked(
+            &spl_token_2022::ID,
+            &token_account,
+            &index_mint,
+            &recipient_token_account,
+            &payer.pubkey(),
+            &[],
+            amount,
+            9,
+        )
+        .unwrap();
+        instruction.accounts.extend(transfer_hook_extra_accounts(
+            &program_id,
+            &transfer_hook_program_id,
+            &index_mint,
+            &payer.pubkey(),
+            &recipient.pubkey(),
+        ));
+        instruction
+    };
+
+    assert_eq!(
+        process_instruction(transfer(1), &[payer], &_setup)
+            .await
+            .unwrap_err(),
+        restriction_error(TransferRestrictionError::SourceNotAllowlisted)
+    );
+
+    let transaction = set_transfer_allowlist_transaction(
+        payer,
+        program_id,
+        index_id,
+        controller_id,
+        payer.pubkey(),
+        true,
+        latest_blockhash(&_setup).await,
+    );
+    assert!(process(transaction, &_setup).await.is_ok());
+
+    assert_eq!(
+        process_instruction(transfer(2), &[payer], &_setup)
+            .await
+            .unwrap_err(),
+        restriction_error(TransferRestrictionError::DestinationNotAllowlisted)
+    );
+
+    let transaction = set_transfer_allowlist_transaction(
+        payer,
+        program_id,
+        index_id,
+        controller_id,
+        recipient.pubkey(),
+        true,
+        latest_blockhash(&_setup).await,
+    );
+    assert!(process(transaction, &_setup).await.is_ok());
+
+    assert!(process_instruction(transfer(4), &[payer], &_setup)
+        .await
+        .is_ok());
+    assert_eq!(token_balance(token_account, &_setup).await, 6);
+    assert_eq!(token_balance(recipient_token_account, &_setup).await, 4);
+
+    // removing the recipient closes its entry and blocks it again
+    let transaction = set_transfer_allowlist_transaction(
+        payer,
+        program_id,
+        index_id,
+        controller_id,
+        recipient.pubkey(),
+        false,
+        latest_blockhash(&_setup).await,
+    );
+    assert!(process(transaction, &_setup).await.is_ok());
+
+    let entry = find_transfer_allowlist_address(&program_id, &index_mint, &recipient.pubkey()).0;
+    assert!(_setup
+        .banks_client
+        .get_account(entry)
+        .await
+        .unwrap()
+        .is_none());
+
+    assert_eq!(
+        process_instruction(transfer(3), &[payer], &_setup)
+            .await
+            .unwrap_err(),
+        restriction_error(TransferRestrictionError::DestinationNotAllowlisted)
+    );
+
+    // Token-2022 index tokens redeem through the staged flow
+    let instruction = with_token_2022(begin_redeem_instruction(
+        payer.pubkey(),
+        program_id,
+        controller_pda,
+        index_mint,
+        index_pda,
+        find_index_mints_data_address(&program_id, &controller_pda, index_id).0,
+        token_account,
+        mints,
+        index_id,
+        2,
+    ));
+    assert!(process_instruction(instruction, &[payer], &_setup)
+        .await
+        .is_ok());
+    assert_eq!(token_balance(token_account, &_setup).await, 4);
+}
+
+#[tokio::test]
+async fn test_set_transfer_allowlist_authority() {
+    let _setup: Setup = setup().await;
+    let program_id = _setup.program_id;
+    let payer = &_setup.payer;
+    let (controller_id, index_id, _, _) = setup_hooked_index(&_setup).await;
+    let controller_pda = find_controller_address(&program_id, controller_id).0;
+
+    let stranger = Keypair::new();
+    let instruction =
+        system_instruction::transfer(&payer.pubkey(), &stranger.pubkey(), LAMPORTS_PER_SOL);
+    assert!(process_instruction(instruction, &[payer], &_setup)
+        .await
+        .is_ok());
+
+    let instruction = set_transfer_allowlist_instruction(
+        stranger.pubkey(),
+        program_id,
+        controller_pda,
+        index_id,
+        stranger.pubkey(),
+        true,
+    );
+    assert_eq!(
+        process_instruction(instruction, &[payer, &stranger], &_setup)
+            .await
+            .unwrap_err(),
+        custom_error(ProtocolError::MissingControllerRole)
+    );
+
+    // indexes without a transfer hook have no allowlist
+    let ProcessCreateIndexResult {
+        index_id: plain_index_id,
+        result,
+        ..
+    } = process_create_index(controller_id, payer.pubkey(), &_setup).await;
+    assert!(result.is_ok());
+
+    let transaction = set_transfer_allowlist_transaction(
+        payer,
+        program_id,
+        plain_index_id,
+        controller_id,
+        payer.pubkey(),
+        true,
+        latest_blockhash(&_setup).await,
+    );
+    assert_eq!(
+        process(transaction, &_setup).await.unwrap_err(),
+        custom_error(ProtocolError::TransferHookNotEnabled)
+    );
+}
+
+#[tokio::test]
+async fn test_create_index_rejects_other_hook_program() {
+    let _setup: Setup = setup().await;
+    let program_id = _setup.program_id;
+    let payer = &_setup.payer;
+
+    let ProcessControllerGlobalConfigResult { result } =
+        process_controller_global_config(10, &_setup).await;
+    assert!(result.is_ok());
+
+    let ProcessInitControllerResult { controller_id, .. } = process_init_controller(&_setup).await;
+
+    // the hook is registered with the mint authority seeds, so only the
+    // workspace hook program may be given
+    let controller_pda = find_controller_address(&program_id, controller_id).0;
+    let index_id = 1;
+    let instruction = with_transfer_hook(
+        create_index_instruction(
+            program_id,
+            payer.pubkey(),
+            payer.pubkey(),
+            payer.pubkey(),
+            find_index_address(&program_id, &controller_pda, index_id).0,
+            find_index_mint_address(&program_id, &controller_pda, index_id).0,
+            controller_pda,
+            find_controller_global_config_address(&program_id).0,
+            find_controller_config_address(&program_id, &controller_pda).0,
+        ),
+        Pubkey::new_unique(),
+        find_index_mint_authority_address(&program_id, &controller_pda, index_id).0,
+    );
+    assert_eq!(
+        process_instruction(instruction, &[payer], &_setup)
+            .await
+            .unwrap_err(),
+        custom_error(ProtocolError::UnsupportedTransferHookProgram)
+    );
+}
+
+#[tokio::test]
+async fn test_token_2022_index_mint_redeem_and_freeze() {
+    let _setup: Setup = setup().await;
+    let program_id = _setup.program_id;
+    let payer = &_setup.payer;
+    let (controller_id, index_id, mints, token_accounts) = setup_hooked_index(&_setup).await;
+
+    let controller_pda = find_controller_address(&program_id, controller_id).0;
+    let index_pda = find_index_address(&program_id, &controller_pda, index_id).0;
+    let index_mint = find_index_mint_address(&program_id, &controller_pda, index_id).0;
+    let index_mint_authority =
+        find_index_mint_authority_address(&program_id, &controller_pda, index_id).0;
+    let index_mints_data = find_index_mints_data_address(&program_id, &controller_pda, index_id).0;
+    let token_account = create_index_token_account(payer.pubkey(), index_mint, &_setup).await;
+
+    // minting and burning the index tokens need no allowlist entry
+    let instruction = with_token_2022(mint_instruction_with_dynamic_accounts(
+        payer.pubkey(),
+        program_id,
+        controller_pda,
+        index_mint,
+        index_mint_authority,
+        index_pda,
+        index_mints_data,
+        token_account,
+        spl_token::ID,
+        mints.clone(),
+        token_accounts.clone(),
+        index_id,
+        10,
+    ));
+    assert!(process_instruction(instruction, &[payer], &_setup)
+        .await
+        .is_ok());
+    assert_eq!(token_balance(token_account, &_setup).await, 10);
+    assert_eq!(token_balance(token_accounts[0], &_setup).await, 90);
+    assert_eq!(token_balance(token_accounts[1], &_setup).await, 80);
+
+    let instruction = with_token_2022(redeem_instruction_with_dynamic_accounts(
+        payer.pubkey(),
+        program_id,
+        controller_pda,
+        index_mint,
+        index_mint_authority,
+        index_pda,
+        index_mints_data,
+        token_account,
+        spl_token::ID,
+        mints.clone(),
+        token_accounts.clone(),
+        index_id,
+        4,
+    ));
+    assert!(process_instruction(instruction, &[payer], &_setup)
+        .await
+        .is_ok());
+    assert_eq!(token_balance(token_account, &_setup).await, 6);
+    assert_eq!(token_balance(token_accounts[0], &_setup).await, 94);
+    assert_eq!(token_balance(token_accounts[1], &_setup).await, 88);
+
+    let transaction = Transaction::new_signed_with_payer(
+        &[
+            with_token_2022(flash_mint_instruction(
+                payer.pubkey(),
+                program_id,
+                controller_pda,
+                token_account,
+                index_id,
+                5,
+            )),
+            settle_flash_mint_instruction(
+                payer.pubkey(),
+                program_id,
+                controller_pda,
+                mints,
+                token_accounts,
+                index_id,
+            ),
+        ],
+        Some(&payer.pubkey()),
+        &[payer],
+        latest_blockhash(&_setup).await,
+    );
+    assert!(process(transaction, &_setup).await.is_ok());
+    assert_eq!(token_balance(token_account, &_setup).await, 11);
+
+    let instruction = with_token_2022(freeze_holder_instruction(
+        payer.pubkey(),
+        program_id,
+        controller_pda,
+        token_account,
+        index_id,
+    ));
+    assert!(process_instruction(instruction, &[payer], &_setup)
+        .await
+        .is_ok());
+    assert_eq!(
+        token_account_state(token_account, &_setup).await.state,
+        AccountState::Frozen
+    );
+
+    let instruction = with_token_2022(thaw_holder_instruction(
+        payer.pubkey(),
+        program_id,
+        controller_pda,
+        token_account,
+        index_id,
+    ));
+    assert!(process_instruction(instruction, &[payer], &_setup)
+        .await
+        .is_ok());
+    assert_eq!(
+        token_account_state(token_account, &_setup).await.state,
+        AccountState::Initialized
+    );
+}
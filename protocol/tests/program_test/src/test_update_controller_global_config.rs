@@ -0,0 +1,223 @@
+use crate::{
+    custom_error, process_add_index_components, process_controller_global_config,
+    process_create_index, process_init_controller, setup, ProcessAddIndexComponentsResult,
+    ProcessControllerGlobalConfigResult, ProcessCreateIndexResult, ProcessInitControllerResult,
+    Setup,
+};
+use openindex::state::ControllerGlobalConfig;
+use openindex_sdk::openindex::{
+    error::ProtocolError,
+    instruction::update_controller_global_config_instruction,
+    pda::{find_controller_global_config_address, find_protocol_address},
+    transaction::update_controller_global_config_transaction,
+};
+use solana_program_test::tokio;
+use solana_sdk::{
+    account::Account,
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+    transaction::Transaction,
+};
+
+async fn get_controller_global_config(_setup: &Setup) -> (Account, ControllerGlobalConfig) {
+    let controller_global_pda = find_controller_global_config_address(&_setup.program_id).0;
+    let account = _setup
+        .banks_client
+        .get_account(controller_global_pda)
+        .await
+        .unwrap()
+        .unwrap();
+    let controller_global_config = ControllerGlobalConfig::unpack(&account.data).unwrap();
+    (account, controller_global_config)
+}
+
+#[tokio::test]
+async fn test_update_controller_global_config() {
+    let _setup: Setup = setup().await;
+    let manager = Keypair::new();
+
+    let ProcessControllerGlobalConfigResult { result } =
+        process_controller_global_config(10, &_setup).await;
+    assert!(result.is_ok());
+
+    // only the protocol owner can update the limits
+    let outsider = Keypair::new();
+    let instruction = update_controller_global_config_instruction(
+        _setup.program_id,
+        outsider.pubkey(),
+        find_protocol_address(&_setup.program_id).0,
+        find_controller_global_config_address(&_setup.program_id).0,
+        Some(20),
+        false,
+        None,
+        None,
+        None,
+        None,
+        None,
+    );
+    let transaction = Transaction::new_signed_with_payer(
+        &[instruction],
+        Some(&_setup.payer.pubkey()),
+        &[&_setup.payer, &outsider],
+        _setup.recent_blockhashes().await,
+    );
+    let result = _setup.banks_client.process_transaction(transaction).await;
+    assert_eq!(
+        result.unwrap_err().unwrap(),
+        custom_error(ProtocolError::OnlyProtocolOwner)
+    );
+
+    let transaction = update_controller_global_config_transaction(
+        &_setup.payer,
+        _setup.program_id,
+        Some(20),
+        true,
+        None,
+        None,
+        None,
+        None,
+        None,
+        _setup.recent_blockhashes().await,
+    );
+    let result = _setup.banks_client.process_transaction(transaction).await;
+    assert!(result.is_ok());
+    let (_, controller_global_config) = get_controller_global_config(&_setup).await;
+    assert_eq!(controller_global_config.max_index_components, 20);
+    assert_eq!(controller_global_config.largest_index_components, 0);
+
+    let ProcessInitControllerResult {
+        controller_id,
+        controller_pda: _,
+        result: _,
+    } = process_init_controller(&_setup).await;
+
+    let ProcessCreateIndexResult {
+        index_id,
+        controller_pda: _,
+        result: _,
+    } = process_create_index(controller_id, manager.pubkey(), &_setup).await;
+
+    let ProcessAddIndexComponentsResult {
+        index_id: _,
+        controller_id: _,
+        mints: _,
+        units: _,
+        result,
+    } = process_add_index_components(
+        index_id,
+        controller_id,
+        manager.pubkey(),
+        4,
+        vec![1, 2, 3, 4],
+        &_setup,
+    )
+    .await;
+    assert!(result.is_ok());
+    let (_, controller_global_config) = get_controller_global_config(&_setup).await;
+    assert_eq!(controller_global_config.largest_index_components, 4);
+
+    // the safety flag refuses a cap below the largest index
+    let transaction = update_controller_global_config_transaction(
+        &_setup.payer,
+        _setup.program_id,
+        Some(3),
+        true,
+        None,
+        None,
+        None,
+        None,
+        None,
+        _setup.recent_blockhashes().await,
+    );
+    let result = _setup.banks_client.process_transaction(transaction).await;
+    assert_eq!(
+        result.unwrap_err().unwrap(),
+        custom_error(ProtocolError::MaxIndexComponentsBelowLargestIndex)
+    );
+
+    let transaction = update_controller_global_config_transaction(
+        &_setup.payer,
+        _setup.program_id,
+        Some(0),
+        false,
+        None,
+        None,
+        None,
+        None,
+        None,
+        _setup.recent_blockhashes().await,
+    );
+    let result = _setup.banks_client.process_transaction(transaction).await;
+    assert_eq!(
+        result.unwrap_err().unwrap(),
+        custom_error(ProtocolError::InvalidMaxIndexComponents)
+    );
+
+    // without the flag the cap can be lowered freely
+    let transaction = update_controller_global_config_transaction(
+        &_setup.payer,
+        _setup.program_id,
+        Some(3),
+        false,
+        None,
+        None,
+        None,
+        None,
+        None,
+        _setup.recent_blockhashes().await,
+    );
+    let result = _setup.banks_client.process_transaction(transaction).await;
+    assert!(result.is_ok());
+    let (_, controller_global_config) = get_controller_global_config(&_setup).await;
+    assert_eq!(controller_global_config.max_index_components, 3);
+    assert_eq!(controller_global_config.largest_index_components, 4);
+}
+
+#[tokio::test]
+async fn test_update_controller_global_config_realloc() {
+    let mut _setup: Setup = setup().await;
+
+    let ProcessControllerGlobalConfigResult { result } =
+        process_controller_global_config(10, &_setup).await;
+    assert!(result.is_ok());
+
+    // install the layout written before `largest_index_components` existed
+    let (account, _) = get_controller_global_config(&_setup).await;
+    let legacy_len = 1 + 4 + 1 + 1;
+    let legacy_account = Account {
+        lamports: _setup.rent.minimum_balance(legacy_len),
+        data: account.data[..legacy_len].to_vec(),
+        owner: account.owner,
+        executable: false,
+        rent_epoch: account.rent_epoch,
+    };
+    let controller_global_pda: Pubkey =
+        find_controller_global_config_address(&_setup.program_id).0;
+    _setup.set_account(&controller_global_pda, legacy_account);
+
+    let (account, controller_global_config) = get_controller_global_config(&_setup).await;
+    assert_eq!(account.data.len(), legacy_len);
+    assert_eq!(controller_global_config.max_index_components, 10);
+
+    let transaction = update_controller_global_config_transaction(
+        &_setup.payer,
+        _setup.program_id,
+        None,
+        false,
+        None,
+        None,
+        None,
+        None,
+        None,
+        _setup.recent_blockhashes().await,
+    );
+    let result = _setup.banks_client.process_transaction(transaction).await;
+    assert!(result.is_ok());
+
+    let (account, controller_global_config) = get_controller_global_config(&_setup).await;
+    assert_eq!(account.data.len(), ControllerGlobalConfig::LEN);
+    assert!(account.lamports >= _setup.rent.minimum_balance(ControllerGlobalConfig::LEN));
+    assert_eq!(controller_global_config.max_index_components, 10);
+    assert_eq!(controller_global_config.largest_index_components, 0);
+    assert!(controller_global_config.initialized);
+}
@@ -4,6 +4,7 @@ mod process_mint;
 mod read_keys;
 mod setup;
 mod test_add_index_components;
+mod test_upgrade_authority;
 
 pub use process_mint::*;
 pub use read_keys::*;
@@ -78,8 +78,13 @@ async fn test_add_index_components() -> Result<()> {
     let controller_id = 1; //protocol_data.get_next_controller_id();
     let controller_address = find_controller_address(&program_id, controller_id).0;
     if (protocol_data.get_next_controller_id() == controller_id) {
-        let init_controller_tx =
-            init_controller_transaction(&payer, program_id, controller_id, recent_blockhashes);
+        let init_controller_tx = init_controller_transaction(
+            &payer,
+            &payer,
+            program_id,
+            controller_id,
+            recent_blockhashes,
+        );
         _context
             .client
             .send_and_confirm_transaction(&init_controller_tx)
@@ -98,6 +103,7 @@ async fn test_add_index_components() -> Result<()> {
     let mint = find_index_mint_address(&program_id, &controller_address, index_id).0;
     let manager = Keypair::new().pubkey();
     let create_index_tx = create_index_transaction(
+        &payer,
         &payer,
         program_id,
         index_id,
@@ -130,6 +136,7 @@ async fn test_add_index_components() -> Result<()> {
     }
 
     let transaction = add_index_components_transaction(
+        &payer,
         &payer,
         program_id,
         index_id,
@@ -0,0 +1,140 @@
+use crate::setup;
+use anyhow::Result;
+use openindex_sdk::openindex::{
+    error::ProtocolError,
+    instruction::{init_controller_global_config_instruction, init_protocol_instruction},
+    pda::{find_controller_global_config_address, find_protocol_address},
+    transaction::{init_controller_global_config_transaction, init_protocol_transaction},
+};
+use solana_client::rpc_client::RpcClient;
+use solana_sdk::{
+    instruction::{Instruction, InstructionError},
+    native_token::LAMPORTS_PER_SOL,
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+    transaction::{Transaction, TransactionError},
+};
+
+fn custom_error(error: ProtocolError) -> TransactionError {
+    TransactionError::InstructionError(0, InstructionError::Custom(error as u32))
+}
+
+/// Sends `instruction` signed by `payer` and `signer`, returning the transaction error.
+fn send_signed_by(
+    client: &RpcClient,
+    payer: &Keypair,
+    signer: &Keypair,
+    instruction: Instruction,
+) -> Option<TransactionError> {
+    let transaction = Transaction::new_signed_with_payer(
+        &[instruction],
+        Some(&payer.pubkey()),
+        &[payer, signer],
+        client.get_latest_blockhash().unwrap(),
+    );
+    client
+        .send_and_confirm_transaction(&transaction)
+        .err()
+        .and_then(|error| error.get_transaction_error())
+}
+
+fn account_exists(client: &RpcClient, address: &Pubkey) -> bool {
+    client.get_account(address).is_ok()
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_init_protocol_upgrade_authority() -> Result<()> {
+    let _context = setup();
+    let payer = &_context.payer;
+    let program_id = _context.openindex_program_id;
+    let client = &_context.client;
+    let protocol_address = find_protocol_address(&program_id).0;
+
+    // reject: the check runs before the protocol account is inspected
+    let attacker = Keypair::new();
+    let error = send_signed_by(
+        client,
+        payer,
+        &attacker,
+        init_protocol_instruction(program_id, attacker.pubkey(), protocol_address),
+    );
+    assert_eq!(error, Some(custom_error(ProtocolError::OnlyUpgradeAuthority)));
+
+    // accept: the payer deployed the program and is its upgrade authority
+    let already_initialized = account_exists(client, &protocol_address);
+    let transaction = init_protocol_transaction(payer, program_id, client.get_latest_blockhash()?);
+    let result = client.send_and_confirm_transaction(&transaction);
+    if already_initialized {
+        let error = result.unwrap_err().get_transaction_error();
+        assert_eq!(
+            error,
+            Some(TransactionError::InstructionError(
+                0,
+                InstructionError::AccountAlreadyInitialized
+            ))
+        );
+    } else {
+        assert!(result.is_ok());
+    }
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_init_controller_global_config_protocol_owner() -> Result<()> {
+    let _context = setup();
+    let payer = &_context.payer;
+    let program_id = _context.openindex_program_id;
+    let client = &_context.client;
+    let protocol_address = find_protocol_address(&program_id).0;
+    let controller_global_config_address = find_controller_global_config_address(&program_id).0;
+
+    if !account_exists(client, &protocol_address) {
+        let transaction =
+            init_protocol_transaction(payer, program_id, client.get_latest_blockhash()?);
+        client.send_and_confirm_transaction(&transaction)?;
+    }
+
+    // reject: only the protocol owner, set by the upgrade authority, may call it
+    let outsider = Keypair::new();
+    let signature = client.request_airdrop(&outsider.pubkey(), LAMPORTS_PER_SOL)?;
+    client.confirm_transaction(&signature)?;
+
+    let error = send_signed_by(
+        client,
+        payer,
+        &outsider,
+        init_controller_global_config_instruction(
+            program_id,
+            outsider.pubkey(),
+            protocol_address,
+            controller_global_config_address,
+            10,
+        ),
+    );
+    assert_eq!(error, Some(custom_error(ProtocolError::OnlyProtocolOwner)));
+
+    // accept
+    let already_initialized = account_exists(client, &controller_global_config_address);
+    let transaction = init_controller_global_config_transaction(
+        payer,
+        program_id,
+        10,
+        client.get_latest_blockhash()?,
+    );
+    let result = client.send_and_confirm_transaction(&transaction);
+    if already_initialized {
+        let error = result.unwrap_err().get_transaction_error();
+        assert_eq!(
+            error,
+            Some(TransactionError::InstructionError(
+                0,
+                InstructionError::AccountAlreadyInitialized
+            ))
+        );
+    } else {
+        assert!(result.is_ok());
+    }
+
+    Ok(())
+}